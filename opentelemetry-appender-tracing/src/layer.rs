@@ -1,7 +1,10 @@
 use opentelemetry::{
+    exception,
     logs::{AnyValue, LogRecord, Logger, LoggerProvider, Severity},
-    Key,
+    Key, Value,
 };
+use std::backtrace::Backtrace;
+use std::collections::HashSet;
 use tracing_core::Level;
 #[cfg(feature = "experimental_metadata_attributes")]
 use tracing_core::Metadata;
@@ -12,6 +15,10 @@ use tracing_subscriber::{registry::LookupSpan, Layer};
 /// Visitor to record the fields from the event record.
 struct EventVisitor<'a, LR: LogRecord> {
     log_record: &'a mut LR,
+    /// Keys of the span fields taking precedence over the event fields.
+    shadowed_keys: &'a [Key],
+    /// Whether a backtrace is captured for error fields.
+    error_backtraces: bool,
 }
 
 /// Logs from the log crate have duplicated attributes that we removed here.
@@ -35,8 +42,23 @@ fn get_filename(filepath: &str) -> &str {
 }
 
 impl<'a, LR: LogRecord> EventVisitor<'a, LR> {
-    fn new(log_record: &'a mut LR) -> Self {
-        EventVisitor { log_record }
+    fn new(log_record: &'a mut LR, shadowed_keys: &'a [Key], error_backtraces: bool) -> Self {
+        EventVisitor {
+            log_record,
+            shadowed_keys,
+            error_backtraces,
+        }
+    }
+
+    fn add_field_attribute(&mut self, field: &tracing::field::Field, value: AnyValue) {
+        if self
+            .shadowed_keys
+            .iter()
+            .any(|key| key.as_str() == field.name())
+        {
+            return;
+        }
+        self.log_record.add_attribute(Key::new(field.name()), value);
     }
 
     #[cfg(feature = "experimental_metadata_attributes")]
@@ -75,8 +97,7 @@ impl<LR: LogRecord> tracing::field::Visit for EventVisitor<'_, LR> {
         if field.name() == "message" {
             self.log_record.set_body(format!("{value:?}").into());
         } else {
-            self.log_record
-                .add_attribute(Key::new(field.name()), AnyValue::from(format!("{value:?}")));
+            self.add_field_attribute(field, AnyValue::from(format!("{value:?}")));
         }
     }
 
@@ -85,16 +106,20 @@ impl<LR: LogRecord> tracing::field::Visit for EventVisitor<'_, LR> {
         _field: &tracing_core::Field,
         value: &(dyn std::error::Error + 'static),
     ) {
-        self.log_record.add_attribute(
-            Key::new("exception.message"),
-            AnyValue::from(value.to_string()),
-        );
-        // No ability to get exception.stacktrace or exception.type from the error today.
+        // Same attributes as `Span::record_exception`, the concrete type of the
+        // error, hence exception.type, is not known here.
+        let backtrace = self.error_backtraces.then(Backtrace::force_capture);
+        for attribute in exception::attributes(value, backtrace.as_ref()) {
+            let value = match attribute.value {
+                Value::String(value) => AnyValue::from(value),
+                value => AnyValue::from(value.to_string()),
+            };
+            self.log_record.add_attribute(attribute.key, value);
+        }
     }
 
     fn record_bytes(&mut self, field: &tracing_core::Field, value: &[u8]) {
-        self.log_record
-            .add_attribute(Key::new(field.name()), AnyValue::from(value));
+        self.add_field_attribute(field, AnyValue::from(value));
     }
 
     fn record_str(&mut self, field: &tracing_core::Field, value: &str) {
@@ -107,19 +132,16 @@ impl<LR: LogRecord> tracing::field::Visit for EventVisitor<'_, LR> {
         if field.name() == "message" {
             self.log_record.set_body(AnyValue::from(value.to_owned()));
         } else {
-            self.log_record
-                .add_attribute(Key::new(field.name()), AnyValue::from(value.to_owned()));
+            self.add_field_attribute(field, AnyValue::from(value.to_owned()));
         }
     }
 
     fn record_bool(&mut self, field: &tracing_core::Field, value: bool) {
-        self.log_record
-            .add_attribute(Key::new(field.name()), AnyValue::from(value));
+        self.add_field_attribute(field, AnyValue::from(value));
     }
 
     fn record_f64(&mut self, field: &tracing::field::Field, value: f64) {
-        self.log_record
-            .add_attribute(Key::new(field.name()), AnyValue::from(value));
+        self.add_field_attribute(field, AnyValue::from(value));
     }
 
     fn record_i64(&mut self, field: &tracing::field::Field, value: i64) {
@@ -127,8 +149,7 @@ impl<LR: LogRecord> tracing::field::Visit for EventVisitor<'_, LR> {
         if is_duplicated_metadata(field.name()) {
             return;
         }
-        self.log_record
-            .add_attribute(Key::new(field.name()), AnyValue::from(value));
+        self.add_field_attribute(field, AnyValue::from(value));
     }
 
     // TODO: We might need to do similar for record_i128,record_u128 too
@@ -139,11 +160,9 @@ impl<LR: LogRecord> tracing::field::Visit for EventVisitor<'_, LR> {
             return;
         }
         if let Ok(signed) = i64::try_from(value) {
-            self.log_record
-                .add_attribute(Key::new(field.name()), AnyValue::from(signed));
+            self.add_field_attribute(field, AnyValue::from(signed));
         } else {
-            self.log_record
-                .add_attribute(Key::new(field.name()), AnyValue::from(format!("{value:?}")));
+            self.add_field_attribute(field, AnyValue::from(format!("{value:?}")));
         }
     }
 
@@ -153,11 +172,9 @@ impl<LR: LogRecord> tracing::field::Visit for EventVisitor<'_, LR> {
             return;
         }
         if let Ok(signed) = i64::try_from(value) {
-            self.log_record
-                .add_attribute(Key::new(field.name()), AnyValue::from(signed));
+            self.add_field_attribute(field, AnyValue::from(signed));
         } else {
-            self.log_record
-                .add_attribute(Key::new(field.name()), AnyValue::from(format!("{value:?}")));
+            self.add_field_attribute(field, AnyValue::from(format!("{value:?}")));
         }
     }
 
@@ -167,23 +184,175 @@ impl<LR: LogRecord> tracing::field::Visit for EventVisitor<'_, LR> {
             return;
         }
         if let Ok(signed) = i64::try_from(value) {
-            self.log_record
-                .add_attribute(Key::new(field.name()), AnyValue::from(signed));
+            self.add_field_attribute(field, AnyValue::from(signed));
         } else {
-            self.log_record
-                .add_attribute(Key::new(field.name()), AnyValue::from(format!("{value:?}")));
+            self.add_field_attribute(field, AnyValue::from(format!("{value:?}")));
         }
     }
 
     // TODO: Remaining field types from AnyValue : Bytes, ListAny, Boolean
 }
 
+/// Selects the fields of the enclosing `tracing` spans added as attributes to
+/// the log records, see [`OpenTelemetryTracingBridge::with_span_fields`].
+#[derive(Clone, Debug)]
+pub struct SpanFields {
+    allow_list: Option<HashSet<String>>,
+    prefix: Option<String>,
+    precedence: SpanFieldPrecedence,
+}
+
+impl SpanFields {
+    /// Captures all the fields of the enclosing spans.
+    pub fn all() -> Self {
+        SpanFields {
+            allow_list: None,
+            prefix: None,
+            precedence: SpanFieldPrecedence::default(),
+        }
+    }
+
+    /// Captures only the span fields with the given names.
+    pub fn allow_list<I, T>(fields: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        SpanFields {
+            allow_list: Some(fields.into_iter().map(Into::into).collect()),
+            ..SpanFields::all()
+        }
+    }
+
+    /// Prefixes the attribute keys of the span fields with `prefix`, e.g.
+    /// `span.` records the `request_id` span field as `span.request_id`.
+    pub fn with_prefix<T: Into<String>>(self, prefix: T) -> Self {
+        SpanFields {
+            prefix: Some(prefix.into()),
+            ..self
+        }
+    }
+
+    /// Specify which of the event or span field is kept when they have the
+    /// same attribute key. Defaults to [`SpanFieldPrecedence::Event`].
+    pub fn with_precedence(self, precedence: SpanFieldPrecedence) -> Self {
+        SpanFields { precedence, ..self }
+    }
+
+    fn key(&self, field: &tracing::field::Field) -> Option<Key> {
+        if let Some(allow_list) = &self.allow_list {
+            if !allow_list.contains(field.name()) {
+                return None;
+            }
+        }
+        Some(match &self.prefix {
+            Some(prefix) => Key::new(format!("{prefix}{}", field.name())),
+            None => Key::new(field.name()),
+        })
+    }
+}
+
+/// The field kept when an event field and a span field, after prefixing, have
+/// the same attribute key.
+///
+/// Fields of inner spans always take precedence over fields of outer spans.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SpanFieldPrecedence {
+    /// The event field is kept.
+    #[default]
+    Event,
+    /// The span field is kept.
+    Span,
+}
+
+/// The captured fields of a span, stored in its extensions.
+#[derive(Debug, Default)]
+struct SpanFieldValues(Vec<(Key, AnyValue)>);
+
+/// Visitor to record the selected fields of a span.
+struct SpanFieldVisitor<'a> {
+    config: &'a SpanFields,
+    values: &'a mut SpanFieldValues,
+}
+
+impl SpanFieldVisitor<'_> {
+    fn record(&mut self, field: &tracing::field::Field, value: AnyValue) {
+        let Some(key) = self.config.key(field) else {
+            return;
+        };
+        // Fields recorded later with `Span::record` replace the initial value.
+        match self.values.0.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, existing)) => *existing = value,
+            None => self.values.0.push((key, value)),
+        }
+    }
+
+    fn record_int<T>(&mut self, field: &tracing::field::Field, value: T)
+    where
+        T: TryInto<i64> + std::fmt::Debug + Copy,
+    {
+        match value.try_into() {
+            Ok(signed) => self.record(field, AnyValue::from(signed)),
+            Err(_) => self.record(field, AnyValue::from(format!("{value:?}"))),
+        }
+    }
+}
+
+impl tracing::field::Visit for SpanFieldVisitor<'_> {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        self.record(field, AnyValue::from(format!("{value:?}")));
+    }
+
+    fn record_error(
+        &mut self,
+        field: &tracing::field::Field,
+        value: &(dyn std::error::Error + 'static),
+    ) {
+        self.record(field, AnyValue::from(value.to_string()));
+    }
+
+    fn record_bytes(&mut self, field: &tracing::field::Field, value: &[u8]) {
+        self.record(field, AnyValue::from(value));
+    }
+
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        self.record(field, AnyValue::from(value.to_owned()));
+    }
+
+    fn record_bool(&mut self, field: &tracing::field::Field, value: bool) {
+        self.record(field, AnyValue::from(value));
+    }
+
+    fn record_f64(&mut self, field: &tracing::field::Field, value: f64) {
+        self.record(field, AnyValue::from(value));
+    }
+
+    fn record_i64(&mut self, field: &tracing::field::Field, value: i64) {
+        self.record(field, AnyValue::from(value));
+    }
+
+    fn record_u64(&mut self, field: &tracing::field::Field, value: u64) {
+        self.record_int(field, value);
+    }
+
+    fn record_i128(&mut self, field: &tracing::field::Field, value: i128) {
+        self.record_int(field, value);
+    }
+
+    fn record_u128(&mut self, field: &tracing::field::Field, value: u128) {
+        self.record_int(field, value);
+    }
+}
+
 pub struct OpenTelemetryTracingBridge<P, L>
 where
     P: LoggerProvider<Logger = L> + Send + Sync,
     L: Logger + Send + Sync,
 {
     logger: L,
+    span_fields: Option<SpanFields>,
+    error_backtraces: bool,
     _phantom: std::marker::PhantomData<P>, // P is not used.
 }
 
@@ -200,9 +369,78 @@ where
             // defined for the same.
             // See https://github.com/open-telemetry/semantic-conventions/issues/1550
             logger: provider.logger(""),
+            span_fields: None,
+            error_backtraces: false,
             _phantom: Default::default(),
         }
     }
+
+    /// Adds the fields of the `tracing` spans enclosing an event, selected by
+    /// `span_fields`, as attributes of its log record.
+    ///
+    /// This captures fields such as a `request_id` recorded once on a parent
+    /// span, independently of any active OpenTelemetry span. Spans are looked
+    /// up from the innermost to the outermost, and fields of inner spans take
+    /// precedence over fields of outer spans with the same key.
+    ///
+    /// ```
+    /// # use opentelemetry_sdk::logs::SdkLoggerProvider;
+    /// use opentelemetry_appender_tracing::layer::{OpenTelemetryTracingBridge, SpanFields};
+    /// # let provider = SdkLoggerProvider::builder().build();
+    ///
+    /// let otel_layer = OpenTelemetryTracingBridge::new(&provider)
+    ///     .with_span_fields(SpanFields::allow_list(["request_id", "user_id"]).with_prefix("span."));
+    /// ```
+    pub fn with_span_fields(self, span_fields: SpanFields) -> Self {
+        OpenTelemetryTracingBridge {
+            span_fields: Some(span_fields),
+            ..self
+        }
+    }
+
+    /// Appends a backtrace to the `exception.stacktrace` attribute of the
+    /// events recording an error field. Disabled by default.
+    ///
+    /// The backtrace is the one of the logging call site, not of the creation
+    /// of the error, and capturing it for every such event is expensive.
+    /// Without it, `exception.stacktrace` holds the source chain of the error.
+    pub fn with_error_backtraces(self, enabled: bool) -> Self {
+        OpenTelemetryTracingBridge {
+            error_backtraces: enabled,
+            ..self
+        }
+    }
+
+    /// Returns the captured fields of the spans enclosing `event`, from the
+    /// innermost span, without duplicated keys.
+    fn enclosing_span_fields<S>(
+        &self,
+        config: &SpanFields,
+        event: &tracing::Event<'_>,
+        ctx: &tracing_subscriber::layer::Context<'_, S>,
+    ) -> Vec<(Key, AnyValue)>
+    where
+        S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+    {
+        let mut attributes: Vec<(Key, AnyValue)> = Vec::new();
+        let Some(scope) = ctx.event_scope(event) else {
+            return attributes;
+        };
+        for span in scope {
+            if let Some(values) = span.extensions().get::<SpanFieldValues>() {
+                for (key, value) in &values.0 {
+                    if !attributes.iter().any(|(k, _)| k == key) {
+                        attributes.push((key.clone(), value.clone()));
+                    }
+                }
+            }
+        }
+        if config.precedence == SpanFieldPrecedence::Event {
+            let event_fields = event.metadata().fields();
+            attributes.retain(|(key, _)| event_fields.field(key.as_str()).is_none());
+        }
+        attributes
+    }
 }
 
 impl<S, P, L> Layer<S> for OpenTelemetryTracingBridge<P, L>
@@ -211,11 +449,45 @@ where
     P: LoggerProvider<Logger = L> + Send + Sync + 'static,
     L: Logger + Send + Sync + 'static,
 {
-    fn on_event(
+    fn on_new_span(
         &self,
-        event: &tracing::Event<'_>,
-        _ctx: tracing_subscriber::layer::Context<'_, S>,
+        attrs: &tracing::span::Attributes<'_>,
+        id: &tracing::span::Id,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let Some(config) = &self.span_fields else {
+            return;
+        };
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut values = SpanFieldValues::default();
+        attrs.record(&mut SpanFieldVisitor {
+            config,
+            values: &mut values,
+        });
+        span.extensions_mut().insert(values);
+    }
+
+    fn on_record(
+        &self,
+        id: &tracing::span::Id,
+        record: &tracing::span::Record<'_>,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
     ) {
+        let Some(config) = &self.span_fields else {
+            return;
+        };
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(values) = extensions.get_mut::<SpanFieldValues>() {
+            record.record(&mut SpanFieldVisitor { config, values });
+        }
+    }
+
+    fn on_event(&self, event: &tracing::Event<'_>, ctx: tracing_subscriber::layer::Context<'_, S>) {
         let metadata = event.metadata();
         let severity = severity_of_level(metadata.level());
         let target = metadata.target();
@@ -237,11 +509,23 @@ where
         log_record.set_event_name(name);
         log_record.set_severity_number(severity);
         log_record.set_severity_text(metadata.level().as_str());
-        let mut visitor = EventVisitor::new(&mut log_record);
+        let span_attributes = self
+            .span_fields
+            .as_ref()
+            .map(|config| self.enclosing_span_fields(config, event, &ctx))
+            .unwrap_or_default();
+        let shadowed_keys: Vec<Key> = match &self.span_fields {
+            Some(config) if config.precedence == SpanFieldPrecedence::Span => {
+                span_attributes.iter().map(|(key, _)| key.clone()).collect()
+            }
+            _ => Vec::new(),
+        };
+        let mut visitor = EventVisitor::new(&mut log_record, &shadowed_keys, self.error_backtraces);
         #[cfg(feature = "experimental_metadata_attributes")]
         visitor.visit_experimental_metadata(meta);
         // Visit fields.
         event.record(&mut visitor);
+        log_record.add_attributes(span_attributes);
 
         //emit record
         self.logger.emit(log_record);
@@ -262,6 +546,7 @@ const fn severity_of_level(level: &Level) -> Severity {
 mod tests {
     use crate::layer;
     use opentelemetry::logs::Severity;
+    use opentelemetry::testing::TestError;
     use opentelemetry::trace::TracerProvider;
     use opentelemetry::trace::{TraceContextExt, TraceFlags, Tracer};
     use opentelemetry::InstrumentationScope;
@@ -471,6 +756,195 @@ mod tests {
             .contains("event opentelemetry-appender-tracing"),);
     }
 
+    #[test]
+    fn tracing_appender_error_source_chain() {
+        // Arrange
+        let exporter: InMemoryLogExporter = InMemoryLogExporter::default();
+        let logger_provider = SdkLoggerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+
+        let subscriber = create_tracing_subscriber(&logger_provider);
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        // Act
+        let err = TestError::new("request failed").with_source(TestError::new("timed out"));
+        error!(error = &err as &dyn std::error::Error, "request failed");
+        assert!(logger_provider.force_flush().is_ok());
+
+        // Assert
+        let exported_logs = exporter
+            .get_emitted_logs()
+            .expect("Logs are expected to be exported.");
+        assert_eq!(exported_logs.len(), 1);
+        let log = &exported_logs[0];
+        assert!(attributes_contains(
+            &log.record,
+            &Key::new("exception.message"),
+            &AnyValue::String("request failed".into())
+        ));
+        let stacktrace = log
+            .record
+            .attributes_iter()
+            .find(|(key, _)| key.as_str() == "exception.stacktrace")
+            .map(|(_, value)| value.clone());
+        let Some(AnyValue::String(stacktrace)) = stacktrace else {
+            panic!("exception.stacktrace is expected");
+        };
+        assert_eq!(
+            stacktrace.as_str(),
+            "request failed\n\nCaused by:\n    0: timed out"
+        );
+    }
+
+    #[test]
+    fn tracing_appender_error_backtraces() {
+        // Arrange
+        let exporter: InMemoryLogExporter = InMemoryLogExporter::default();
+        let logger_provider = SdkLoggerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry().with(
+            layer::OpenTelemetryTracingBridge::new(&logger_provider).with_error_backtraces(true),
+        );
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        // Act
+        let err = TestError::new("request failed");
+        error!(error = &err as &dyn std::error::Error, "request failed");
+        assert!(logger_provider.force_flush().is_ok());
+
+        // Assert
+        let exported_logs = exporter
+            .get_emitted_logs()
+            .expect("Logs are expected to be exported.");
+        let stacktrace = exported_logs[0]
+            .record
+            .attributes_iter()
+            .find(|(key, _)| key.as_str() == "exception.stacktrace")
+            .map(|(_, value)| value.clone());
+        let Some(AnyValue::String(stacktrace)) = stacktrace else {
+            panic!("exception.stacktrace is expected");
+        };
+        assert!(stacktrace
+            .as_str()
+            .starts_with("request failed\n\nStack backtrace:\n"));
+    }
+
+    // The code.* attributes added with the experimental_metadata_attributes feature.
+    const METADATA_ATTRIBUTES: usize = if cfg!(feature = "experimental_metadata_attributes") {
+        4
+    } else {
+        0
+    };
+
+    fn emit_with_span_fields(span_fields: Option<layer::SpanFields>) -> SdkLogRecord {
+        let exporter: InMemoryLogExporter = InMemoryLogExporter::default();
+        let logger_provider = SdkLoggerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let mut layer = layer::OpenTelemetryTracingBridge::new(&logger_provider);
+        if let Some(span_fields) = span_fields {
+            layer = layer.with_span_fields(span_fields);
+        }
+        let subscriber = tracing_subscriber::registry().with(layer);
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let outer =
+            tracing::error_span!("outer", request_id = "r-1", user_id = 7, shared = "outer");
+        let _outer = outer.enter();
+        let inner = tracing::error_span!("inner", shared = "inner", status = tracing::field::Empty);
+        let _inner = inner.enter();
+        inner.record("status", "pending");
+        error!(status = "event", "message");
+        assert!(logger_provider.force_flush().is_ok());
+
+        let exported_logs = exporter
+            .get_emitted_logs()
+            .expect("Logs are expected to be exported.");
+        assert_eq!(exported_logs.len(), 1);
+        exported_logs[0].record.clone()
+    }
+
+    #[test]
+    fn tracing_appender_span_fields_disabled_by_default() {
+        let record = emit_with_span_fields(None);
+        assert_eq!(record.attributes_iter().count(), 1 + METADATA_ATTRIBUTES);
+        assert!(attributes_contains(
+            &record,
+            &Key::new("status"),
+            &AnyValue::String("event".into())
+        ));
+    }
+
+    #[test]
+    fn tracing_appender_all_span_fields() {
+        let record = emit_with_span_fields(Some(layer::SpanFields::all()));
+
+        assert_eq!(record.attributes_iter().count(), 4 + METADATA_ATTRIBUTES);
+        assert!(attributes_contains(
+            &record,
+            &Key::new("request_id"),
+            &AnyValue::String("r-1".into())
+        ));
+        assert!(attributes_contains(
+            &record,
+            &Key::new("user_id"),
+            &AnyValue::Int(7)
+        ));
+        // inner spans take precedence over outer spans
+        assert!(attributes_contains(
+            &record,
+            &Key::new("shared"),
+            &AnyValue::String("inner".into())
+        ));
+        // the event takes precedence over spans by default
+        assert!(attributes_contains(
+            &record,
+            &Key::new("status"),
+            &AnyValue::String("event".into())
+        ));
+    }
+
+    #[test]
+    fn tracing_appender_span_fields_precedence() {
+        let record = emit_with_span_fields(Some(
+            layer::SpanFields::all().with_precedence(layer::SpanFieldPrecedence::Span),
+        ));
+
+        assert_eq!(record.attributes_iter().count(), 4 + METADATA_ATTRIBUTES);
+        // recorded after the span creation
+        assert!(attributes_contains(
+            &record,
+            &Key::new("status"),
+            &AnyValue::String("pending".into())
+        ));
+    }
+
+    #[test]
+    fn tracing_appender_allow_listed_span_fields_with_prefix() {
+        let record = emit_with_span_fields(Some(
+            layer::SpanFields::allow_list(["request_id", "status"]).with_prefix("span."),
+        ));
+
+        assert_eq!(record.attributes_iter().count(), 3 + METADATA_ATTRIBUTES);
+        assert!(attributes_contains(
+            &record,
+            &Key::new("span.request_id"),
+            &AnyValue::String("r-1".into())
+        ));
+        assert!(attributes_contains(
+            &record,
+            &Key::new("span.status"),
+            &AnyValue::String("pending".into())
+        ));
+        assert!(attributes_contains(
+            &record,
+            &Key::new("status"),
+            &AnyValue::String("event".into())
+        ));
+    }
+
     #[test]
     fn tracing_appender_inside_tracing_context() {
         // Arrange
@@ -105,6 +105,7 @@
 //! | level of the event    | `Severity`, `SeverityText` |                                                                                         |
 //! | Fields                | `Attributes`            | Converted into OpenTelemetry log attributes. Field with "message" as key is specially treated and stored as `LogRecord::Body` |
 //! | Message               | `Body`                  | The body/message of the log. This is done only if body was not already populated from "message" field above |
+//! | Fields of enclosing spans | `Attributes`        | Opt-in with `OpenTelemetryTracingBridge::with_span_fields`, selecting all or allow-listed fields, optionally prefixed |
 //!
 //! ### Data Type Mapping
 //!
@@ -123,7 +124,8 @@
 //!
 //! In future, additional types may be supported.
 //!
-//! > **Note:** This crate does not support `tracing` Spans. One may use [`tracing-opentelemetry`](https://docs.rs/tracing-opentelemetry/latest/tracing_opentelemetry/) to
+//! > **Note:** This crate does not convert `tracing` Spans, only their fields can be captured
+//! > on the log records of the events they enclose. One may use [`tracing-opentelemetry`](https://docs.rs/tracing-opentelemetry/latest/tracing_opentelemetry/) to
 //! > convert `tracing` spans into OpenTelemetry spans. This is a third-party crate
 //! > that is not maintained by the OpenTelemetry project.
 //! > `tracing-opentelemetry`:
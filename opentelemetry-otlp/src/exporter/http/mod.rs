@@ -376,6 +376,15 @@ pub(crate) struct OtlpHttpClient {
 }
 
 impl OtlpHttpClient {
+    /// Whether the client sends JSON encoded payloads.
+    #[cfg(feature = "experimental-self-observability")]
+    pub(crate) fn is_json(&self) -> bool {
+        #[cfg(feature = "http-json")]
+        return self.protocol == Protocol::HttpJson;
+        #[cfg(not(feature = "http-json"))]
+        false
+    }
+
     /// Shared HTTP export logic used by all exporters with retry support
     async fn export_http_with_retry<F, T>(
         &self,
@@ -392,6 +392,10 @@ pub use crate::metric::{
     OTEL_EXPORTER_OTLP_METRICS_TIMEOUT,
 };
 
+#[cfg(feature = "experimental-metrics-histogram-aggregation")]
+#[cfg(any(feature = "http-proto", feature = "http-json", feature = "grpc-tonic"))]
+pub use crate::metric::OTEL_EXPORTER_OTLP_METRICS_DEFAULT_HISTOGRAM_AGGREGATION;
+
 #[cfg(feature = "logs")]
 #[cfg(any(feature = "http-proto", feature = "http-json", feature = "grpc-tonic"))]
 pub use crate::logs::{
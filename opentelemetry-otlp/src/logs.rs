@@ -4,6 +4,8 @@
 
 #[cfg(feature = "grpc-tonic")]
 use opentelemetry::otel_debug;
+#[cfg(feature = "experimental-self-observability")]
+use opentelemetry_sdk::self_observability::ExporterMetrics;
 use opentelemetry_sdk::{error::OTelSdkResult, logs::LogBatch};
 use std::fmt::Debug;
 use std::time;
@@ -113,6 +115,8 @@ impl HasHttpConfig for LogExporterBuilder<HttpExporterBuilderSet> {
 #[derive(Debug)]
 pub struct LogExporter {
     client: SupportedTransportClient,
+    #[cfg(feature = "experimental-self-observability")]
+    metrics: Option<ExporterMetrics>,
 }
 
 #[derive(Debug)]
@@ -133,6 +137,8 @@ impl LogExporter {
     pub(crate) fn from_http(client: crate::exporter::http::OtlpHttpClient) -> Self {
         LogExporter {
             client: SupportedTransportClient::Http(client),
+            #[cfg(feature = "experimental-self-observability")]
+            metrics: None,
         }
     }
 
@@ -140,18 +146,33 @@ impl LogExporter {
     pub(crate) fn from_tonic(client: crate::exporter::tonic::logs::TonicLogsClient) -> Self {
         LogExporter {
             client: SupportedTransportClient::Tonic(client),
+            #[cfg(feature = "experimental-self-observability")]
+            metrics: None,
         }
     }
 }
 
 impl opentelemetry_sdk::logs::LogExporter for LogExporter {
     async fn export(&self, batch: LogBatch<'_>) -> OTelSdkResult {
-        match &self.client {
+        #[cfg(feature = "experimental-self-observability")]
+        let count = batch.iter().count();
+        #[cfg(feature = "experimental-self-observability")]
+        if let Some(metrics) = &self.metrics {
+            metrics.export_started(count);
+        }
+
+        let result = match &self.client {
             #[cfg(feature = "grpc-tonic")]
             SupportedTransportClient::Tonic(client) => client.export(batch).await,
             #[cfg(any(feature = "http-proto", feature = "http-json"))]
             SupportedTransportClient::Http(client) => client.export(batch).await,
+        };
+
+        #[cfg(feature = "experimental-self-observability")]
+        if let Some(metrics) = &self.metrics {
+            metrics.export_finished(count, &result);
         }
+        result
     }
 
     fn set_resource(&mut self, resource: &opentelemetry_sdk::Resource) {
@@ -163,6 +184,21 @@ impl opentelemetry_sdk::logs::LogExporter for LogExporter {
         }
     }
 
+    #[cfg(feature = "experimental-self-observability")]
+    fn set_meter(&mut self, meter: &opentelemetry::metrics::Meter) {
+        let component_type = match &self.client {
+            #[cfg(feature = "grpc-tonic")]
+            SupportedTransportClient::Tonic(_) => "otlp_grpc_log_exporter",
+            #[cfg(any(feature = "http-proto", feature = "http-json"))]
+            SupportedTransportClient::Http(client) if client.is_json() => {
+                "otlp_http_json_log_exporter"
+            }
+            #[cfg(any(feature = "http-proto", feature = "http-json"))]
+            SupportedTransportClient::Http(_) => "otlp_http_log_exporter",
+        };
+        self.metrics = Some(ExporterMetrics::logs(meter, component_type));
+    }
+
     fn shutdown_with_timeout(&self, _timeout: time::Duration) -> OTelSdkResult {
         match &self.client {
             #[cfg(feature = "grpc-tonic")]
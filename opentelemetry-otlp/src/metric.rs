@@ -17,9 +17,15 @@ use crate::{ExporterBuildError, NoExporterBuilderSet};
 use core::fmt;
 use opentelemetry_sdk::error::OTelSdkResult;
 
+#[cfg(feature = "experimental-self-observability")]
+use opentelemetry_sdk::metrics::data::{AggregatedMetrics, MetricData};
 use opentelemetry_sdk::metrics::{
     data::ResourceMetrics, exporter::PushMetricExporter, Temporality,
 };
+#[cfg(feature = "experimental-metrics-histogram-aggregation")]
+use opentelemetry_sdk::metrics::{Aggregation, InstrumentKind};
+#[cfg(feature = "experimental-self-observability")]
+use opentelemetry_sdk::self_observability::ExporterMetrics;
 use std::fmt::{Debug, Formatter};
 use std::time::Duration;
 
@@ -36,12 +42,20 @@ pub const OTEL_EXPORTER_OTLP_METRICS_COMPRESSION: &str = "OTEL_EXPORTER_OTLP_MET
 /// Example: `k1=v1,k2=v2`
 /// Note: this is only supported for HTTP.
 pub const OTEL_EXPORTER_OTLP_METRICS_HEADERS: &str = "OTEL_EXPORTER_OTLP_METRICS_HEADERS";
+/// The default aggregation to use for histogram instruments, either
+/// `explicit_bucket_histogram` or `base2_exponential_bucket_histogram`.
+/// Defaults to `explicit_bucket_histogram`.
+#[cfg(feature = "experimental-metrics-histogram-aggregation")]
+pub const OTEL_EXPORTER_OTLP_METRICS_DEFAULT_HISTOGRAM_AGGREGATION: &str =
+    "OTEL_EXPORTER_OTLP_METRICS_DEFAULT_HISTOGRAM_AGGREGATION";
 
 /// A builder for creating a new [MetricExporter].
 #[derive(Debug, Default, Clone)]
 pub struct MetricExporterBuilder<C> {
     client: C,
     temporality: Temporality,
+    #[cfg(feature = "experimental-metrics-histogram-aggregation")]
+    histogram_aggregation: Option<Aggregation>,
 }
 
 impl MetricExporterBuilder<NoExporterBuilderSet> {
@@ -58,6 +72,8 @@ impl<C> MetricExporterBuilder<C> {
         MetricExporterBuilder {
             client: TonicExporterBuilderSet(TonicExporterBuilder::default()),
             temporality: self.temporality,
+            #[cfg(feature = "experimental-metrics-histogram-aggregation")]
+            histogram_aggregation: self.histogram_aggregation,
         }
     }
 
@@ -67,6 +83,8 @@ impl<C> MetricExporterBuilder<C> {
         MetricExporterBuilder {
             client: HttpExporterBuilderSet(HttpExporterBuilder::default()),
             temporality: self.temporality,
+            #[cfg(feature = "experimental-metrics-histogram-aggregation")]
+            histogram_aggregation: self.histogram_aggregation,
         }
     }
 
@@ -75,8 +93,55 @@ impl<C> MetricExporterBuilder<C> {
         MetricExporterBuilder {
             client: self.client,
             temporality,
+            #[cfg(feature = "experimental-metrics-histogram-aggregation")]
+            histogram_aggregation: self.histogram_aggregation,
         }
     }
+
+    /// Set the default aggregation for histogram instruments.
+    ///
+    /// It is used for histograms whose view does not set an aggregation. If
+    /// this is not set, the aggregation is read from the
+    /// `OTEL_EXPORTER_OTLP_METRICS_DEFAULT_HISTOGRAM_AGGREGATION` environment
+    /// variable, defaulting to explicit bucket histograms.
+    #[cfg(feature = "experimental-metrics-histogram-aggregation")]
+    pub fn with_default_histogram_aggregation(
+        mut self,
+        aggregation: Aggregation,
+    ) -> MetricExporterBuilder<C> {
+        self.histogram_aggregation = Some(aggregation);
+        self
+    }
+}
+
+/// Resolves the default histogram aggregation, programmatic configuration
+/// overrides the environment variable.
+#[cfg(feature = "experimental-metrics-histogram-aggregation")]
+fn resolve_histogram_aggregation(provided: Option<Aggregation>) -> Aggregation {
+    if let Some(aggregation) = provided {
+        return aggregation;
+    }
+    match std::env::var(OTEL_EXPORTER_OTLP_METRICS_DEFAULT_HISTOGRAM_AGGREGATION) {
+        Ok(value) => match value.as_str() {
+            "explicit_bucket_histogram" => Aggregation::Default,
+            "base2_exponential_bucket_histogram" => Aggregation::Base2ExponentialHistogram {
+                max_size: 160,
+                max_scale: 20,
+                record_min_max: true,
+            },
+            other => {
+                opentelemetry::otel_warn!(
+                    name: "MetricExporter.InvalidDefaultHistogramAggregation",
+                    message = format!(
+                        "Unrecognized default histogram aggregation '{}' in OTEL_EXPORTER_OTLP_METRICS_DEFAULT_HISTOGRAM_AGGREGATION environment variable. Valid values are: explicit_bucket_histogram, base2_exponential_bucket_histogram. Using explicit_bucket_histogram",
+                        other
+                    ),
+                );
+                Aggregation::Default
+            }
+        },
+        Err(_) => Aggregation::Default,
+    }
 }
 
 #[cfg(feature = "grpc-tonic")]
@@ -84,6 +149,11 @@ impl MetricExporterBuilder<TonicExporterBuilderSet> {
     /// Build the [MetricExporter] with the gRPC Tonic transport.
     pub fn build(self) -> Result<MetricExporter, ExporterBuildError> {
         let exporter = self.client.0.build_metrics_exporter(self.temporality)?;
+        #[cfg(feature = "experimental-metrics-histogram-aggregation")]
+        let exporter = MetricExporter {
+            histogram_aggregation: resolve_histogram_aggregation(self.histogram_aggregation),
+            ..exporter
+        };
         opentelemetry::otel_debug!(name: "MetricExporterBuilt");
         Ok(exporter)
     }
@@ -94,6 +164,11 @@ impl MetricExporterBuilder<HttpExporterBuilderSet> {
     /// Build the [MetricExporter] with the HTTP transport.
     pub fn build(self) -> Result<MetricExporter, ExporterBuildError> {
         let exporter = self.client.0.build_metrics_exporter(self.temporality)?;
+        #[cfg(feature = "experimental-metrics-histogram-aggregation")]
+        let exporter = MetricExporter {
+            histogram_aggregation: resolve_histogram_aggregation(self.histogram_aggregation),
+            ..exporter
+        };
         Ok(exporter)
     }
 }
@@ -139,6 +214,10 @@ pub(crate) trait MetricsClient: fmt::Debug + Send + Sync + 'static {
 pub struct MetricExporter {
     client: SupportedTransportClient,
     temporality: Temporality,
+    #[cfg(feature = "experimental-metrics-histogram-aggregation")]
+    histogram_aggregation: Aggregation,
+    #[cfg(feature = "experimental-self-observability")]
+    metrics: std::sync::OnceLock<ExporterMetrics>,
 }
 
 #[derive(Debug)]
@@ -157,12 +236,25 @@ impl Debug for MetricExporter {
 
 impl PushMetricExporter for MetricExporter {
     async fn export(&self, metrics: &ResourceMetrics) -> OTelSdkResult {
-        match &self.client {
+        #[cfg(feature = "experimental-self-observability")]
+        let count = data_point_count(metrics);
+        #[cfg(feature = "experimental-self-observability")]
+        if let Some(exporter_metrics) = self.metrics.get() {
+            exporter_metrics.export_started(count);
+        }
+
+        let result = match &self.client {
             #[cfg(feature = "grpc-tonic")]
             SupportedTransportClient::Tonic(client) => client.export(metrics).await,
             #[cfg(any(feature = "http-proto", feature = "http-json"))]
             SupportedTransportClient::Http(client) => client.export(metrics).await,
+        };
+
+        #[cfg(feature = "experimental-self-observability")]
+        if let Some(exporter_metrics) = self.metrics.get() {
+            exporter_metrics.export_finished(count, &result);
         }
+        result
     }
 
     fn force_flush(&self) -> OTelSdkResult {
@@ -186,6 +278,54 @@ impl PushMetricExporter for MetricExporter {
     fn temporality(&self) -> Temporality {
         self.temporality
     }
+
+    #[cfg(feature = "experimental-metrics-histogram-aggregation")]
+    fn aggregation(&self, kind: InstrumentKind) -> Aggregation {
+        match kind {
+            InstrumentKind::Histogram => self.histogram_aggregation.clone(),
+            _ => Aggregation::Default,
+        }
+    }
+
+    #[cfg(feature = "experimental-self-observability")]
+    fn set_meter(&self, meter: &opentelemetry::metrics::Meter) {
+        let component_type = match &self.client {
+            #[cfg(feature = "grpc-tonic")]
+            SupportedTransportClient::Tonic(_) => "otlp_grpc_metric_exporter",
+            #[cfg(any(feature = "http-proto", feature = "http-json"))]
+            SupportedTransportClient::Http(client) if client.is_json() => {
+                "otlp_http_json_metric_exporter"
+            }
+            #[cfg(any(feature = "http-proto", feature = "http-json"))]
+            SupportedTransportClient::Http(_) => "otlp_http_metric_exporter",
+        };
+        let _ = self
+            .metrics
+            .set(ExporterMetrics::metric_data_points(meter, component_type));
+    }
+}
+
+/// Returns the number of data points across all metrics of `metrics`.
+#[cfg(feature = "experimental-self-observability")]
+fn data_point_count(metrics: &ResourceMetrics) -> usize {
+    fn count<T>(data: &MetricData<T>) -> usize {
+        match data {
+            MetricData::Gauge(gauge) => gauge.data_points().count(),
+            MetricData::Sum(sum) => sum.data_points().count(),
+            MetricData::Histogram(histogram) => histogram.data_points().count(),
+            MetricData::ExponentialHistogram(histogram) => histogram.data_points().count(),
+        }
+    }
+
+    metrics
+        .scope_metrics()
+        .flat_map(|scope_metrics| scope_metrics.metrics())
+        .map(|metric| match metric.data() {
+            AggregatedMetrics::F64(data) => count(data),
+            AggregatedMetrics::U64(data) => count(data),
+            AggregatedMetrics::I64(data) => count(data),
+        })
+        .sum()
 }
 
 impl MetricExporter {
@@ -202,6 +342,10 @@ impl MetricExporter {
         Self {
             client: SupportedTransportClient::Tonic(client),
             temporality,
+            #[cfg(feature = "experimental-metrics-histogram-aggregation")]
+            histogram_aggregation: Aggregation::Default,
+            #[cfg(feature = "experimental-self-observability")]
+            metrics: std::sync::OnceLock::new(),
         }
     }
 
@@ -213,6 +357,48 @@ impl MetricExporter {
         Self {
             client: SupportedTransportClient::Http(client),
             temporality,
+            #[cfg(feature = "experimental-metrics-histogram-aggregation")]
+            histogram_aggregation: Aggregation::Default,
+            #[cfg(feature = "experimental-self-observability")]
+            metrics: std::sync::OnceLock::new(),
         }
     }
 }
+
+#[cfg(test)]
+#[cfg(feature = "experimental-metrics-histogram-aggregation")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_histogram_aggregation_from_env() {
+        temp_env::with_var_unset(
+            OTEL_EXPORTER_OTLP_METRICS_DEFAULT_HISTOGRAM_AGGREGATION,
+            || {
+                assert_eq!(resolve_histogram_aggregation(None), Aggregation::Default);
+            },
+        );
+        temp_env::with_var(
+            OTEL_EXPORTER_OTLP_METRICS_DEFAULT_HISTOGRAM_AGGREGATION,
+            Some("base2_exponential_bucket_histogram"),
+            || {
+                assert!(matches!(
+                    resolve_histogram_aggregation(None),
+                    Aggregation::Base2ExponentialHistogram { .. }
+                ));
+                // Programmatic configuration overrides the environment variable.
+                assert_eq!(
+                    resolve_histogram_aggregation(Some(Aggregation::Sum)),
+                    Aggregation::Sum
+                );
+            },
+        );
+        temp_env::with_var(
+            OTEL_EXPORTER_OTLP_METRICS_DEFAULT_HISTOGRAM_AGGREGATION,
+            Some("invalid"),
+            || {
+                assert_eq!(resolve_histogram_aggregation(None), Aggregation::Default);
+            },
+        );
+    }
+}
@@ -5,6 +5,8 @@
 use std::fmt::Debug;
 
 use opentelemetry_sdk::error::OTelSdkResult;
+#[cfg(feature = "experimental-self-observability")]
+use opentelemetry_sdk::self_observability::ExporterMetrics;
 use opentelemetry_sdk::trace::SpanData;
 
 use crate::ExporterBuildError;
@@ -116,6 +118,8 @@ impl HasHttpConfig for SpanExporterBuilder<HttpExporterBuilderSet> {
 #[derive(Debug)]
 pub struct SpanExporter {
     client: SupportedTransportClient,
+    #[cfg(feature = "experimental-self-observability")]
+    metrics: Option<ExporterMetrics>,
 }
 
 #[derive(Debug)]
@@ -136,6 +140,8 @@ impl SpanExporter {
     pub(crate) fn from_http(client: crate::exporter::http::OtlpHttpClient) -> Self {
         SpanExporter {
             client: SupportedTransportClient::Http(client),
+            #[cfg(feature = "experimental-self-observability")]
+            metrics: None,
         }
     }
 
@@ -143,18 +149,33 @@ impl SpanExporter {
     pub(crate) fn from_tonic(client: crate::exporter::tonic::trace::TonicTracesClient) -> Self {
         SpanExporter {
             client: SupportedTransportClient::Tonic(client),
+            #[cfg(feature = "experimental-self-observability")]
+            metrics: None,
         }
     }
 }
 
 impl opentelemetry_sdk::trace::SpanExporter for SpanExporter {
     async fn export(&self, batch: Vec<SpanData>) -> OTelSdkResult {
-        match &self.client {
+        #[cfg(feature = "experimental-self-observability")]
+        let count = batch.len();
+        #[cfg(feature = "experimental-self-observability")]
+        if let Some(metrics) = &self.metrics {
+            metrics.export_started(count);
+        }
+
+        let result = match &self.client {
             #[cfg(feature = "grpc-tonic")]
             SupportedTransportClient::Tonic(client) => client.export(batch).await,
             #[cfg(any(feature = "http-proto", feature = "http-json"))]
             SupportedTransportClient::Http(client) => client.export(batch).await,
+        };
+
+        #[cfg(feature = "experimental-self-observability")]
+        if let Some(metrics) = &self.metrics {
+            metrics.export_finished(count, &result);
         }
+        result
     }
 
     fn set_resource(&mut self, resource: &opentelemetry_sdk::Resource) {
@@ -165,4 +186,19 @@ impl opentelemetry_sdk::trace::SpanExporter for SpanExporter {
             SupportedTransportClient::Http(client) => client.set_resource(resource),
         }
     }
+
+    #[cfg(feature = "experimental-self-observability")]
+    fn set_meter(&mut self, meter: &opentelemetry::metrics::Meter) {
+        let component_type = match &self.client {
+            #[cfg(feature = "grpc-tonic")]
+            SupportedTransportClient::Tonic(_) => "otlp_grpc_span_exporter",
+            #[cfg(any(feature = "http-proto", feature = "http-json"))]
+            SupportedTransportClient::Http(client) if client.is_json() => {
+                "otlp_http_json_span_exporter"
+            }
+            #[cfg(any(feature = "http-proto", feature = "http-json"))]
+            SupportedTransportClient::Http(_) => "otlp_http_span_exporter",
+        };
+        self.metrics = Some(ExporterMetrics::spans(meter, component_type));
+    }
 }
@@ -108,7 +108,7 @@ pub mod tonic {
                     .map(Into::into)
                     .unwrap_or_default(),
                 body: log_record.body().cloned().map(Into::into),
-                dropped_attributes_count: 0,
+                dropped_attributes_count: log_record.dropped_attributes_count(),
                 flags: trace_context
                     .map(|ctx| {
                         ctx.trace_flags
@@ -315,4 +315,23 @@ mod tests {
         assert_eq!(scope_logs_1.log_records.len(), 1);
         assert_eq!(scope_logs_2.log_records.len(), 1);
     }
+
+    #[test]
+    fn test_dropped_attributes_count() {
+        let logger = SdkLoggerProvider::builder()
+            .with_log_processor(MockProcessor {})
+            .with_max_attributes_per_log_record(1)
+            .build()
+            .logger("test");
+        let mut log_record = logger.create_log_record();
+        log_record.set_observed_timestamp(now());
+        log_record.add_attribute("k1", "v1");
+        log_record.add_attribute("k2", "v2");
+        log_record.add_attribute("k3", "v3");
+
+        let proto_record: crate::proto::tonic::logs::v1::LogRecord = (&log_record).into();
+
+        assert_eq!(proto_record.attributes.len(), 1);
+        assert_eq!(proto_record.dropped_attributes_count, 2);
+    }
 }
@@ -215,7 +215,9 @@ pub mod tonic {
                     .data_points()
                     .map(|dp| TonicHistogramDataPoint {
                         attributes: dp.attributes().map(Into::into).collect(),
-                        start_time_unix_nano: to_nanos(hist.start_time()),
+                        start_time_unix_nano: to_nanos(
+                            dp.start_time().unwrap_or(hist.start_time()),
+                        ),
                         time_unix_nano: to_nanos(hist.time()),
                         count: dp.count(),
                         sum: Some(dp.sum().into_f64()),
@@ -242,7 +244,9 @@ pub mod tonic {
                     .data_points()
                     .map(|dp| TonicExponentialHistogramDataPoint {
                         attributes: dp.attributes().map(Into::into).collect(),
-                        start_time_unix_nano: to_nanos(hist.start_time()),
+                        start_time_unix_nano: to_nanos(
+                            dp.start_time().unwrap_or(hist.start_time()),
+                        ),
                         time_unix_nano: to_nanos(hist.time()),
                         count: dp.count() as u64,
                         sum: Some(dp.sum().into_f64()),
@@ -278,7 +282,7 @@ pub mod tonic {
                     .data_points()
                     .map(|dp| TonicNumberDataPoint {
                         attributes: dp.attributes().map(Into::into).collect(),
-                        start_time_unix_nano: to_nanos(sum.start_time()),
+                        start_time_unix_nano: to_nanos(dp.start_time().unwrap_or(sum.start_time())),
                         time_unix_nano: to_nanos(sum.time()),
                         exemplars: dp.exemplars().map(Into::into).collect(),
                         flags: TonicDataPointFlags::default() as u32,
@@ -301,7 +305,11 @@ pub mod tonic {
                     .data_points()
                     .map(|dp| TonicNumberDataPoint {
                         attributes: dp.attributes().map(Into::into).collect(),
-                        start_time_unix_nano: gauge.start_time().map(to_nanos).unwrap_or_default(),
+                        start_time_unix_nano: dp
+                            .start_time()
+                            .or(gauge.start_time())
+                            .map(to_nanos)
+                            .unwrap_or_default(),
                         time_unix_nano: to_nanos(gauge.time()),
                         exemplars: dp.exemplars().map(Into::into).collect(),
                         flags: TonicDataPointFlags::default() as u32,
@@ -1,6 +1,6 @@
 use criterion::{criterion_group, criterion_main, Bencher, Criterion};
 use opentelemetry::{
-    metrics::{Counter, Histogram, MeterProvider as _},
+    metrics::{BoundAttributes, Counter, Histogram, MeterProvider as _},
     Key, KeyValue,
 };
 use opentelemetry_sdk::{
@@ -37,6 +37,10 @@ impl MetricReader for SharedReader {
     fn temporality(&self, kind: InstrumentKind) -> Temporality {
         self.0.temporality(kind)
     }
+
+    fn aggregation(&self, kind: InstrumentKind) -> Aggregation {
+        self.0.aggregation(kind)
+    }
 }
 
 // * Summary *
@@ -345,12 +349,60 @@ fn benchmark_collect_histogram(b: &mut Bencher, n: usize) {
     })
 }
 
+fn bound_attributes(c: &mut Criterion) {
+    let r = SharedReader(Arc::new(ManualReader::default()));
+    let mtr = SdkMeterProvider::builder()
+        .with_reader(r.clone())
+        .build()
+        .meter("sdk/metric/bench/bound_attributes");
+    let counters: Vec<Counter<u64>> = (0..4)
+        .map(|i| mtr.u64_counter(format!("counter_{i}")).build())
+        .collect();
+    let histograms: Vec<Histogram<f64>> = (0..4)
+        .map(|i| mtr.f64_histogram(format!("histogram_{i}")).build())
+        .collect();
+    let attributes = [
+        KeyValue::new("http.request.method", "GET"),
+        KeyValue::new("http.route", "/users/{id}"),
+        KeyValue::new("http.response.status_code", 200),
+        KeyValue::new("server.address", "example.com"),
+        KeyValue::new("url.scheme", "https"),
+    ];
+    let bound = BoundAttributes::new(&attributes);
+
+    let mut group = c.benchmark_group("BoundAttributes");
+    group.bench_function("CounterAdd", |b| b.iter(|| counters[0].add(1, &attributes)));
+    group.bench_function("CounterAddBound", |b| {
+        b.iter(|| counters[0].add_bound(1, &bound))
+    });
+    group.bench_function("RecordEightInstruments", |b| {
+        b.iter(|| {
+            for counter in &counters {
+                counter.add(1, &attributes);
+            }
+            for histogram in &histograms {
+                histogram.record(1.5, &attributes);
+            }
+        })
+    });
+    group.bench_function("RecordEightInstrumentsBound", |b| {
+        b.iter(|| {
+            for counter in &counters {
+                counter.add_bound(1, &bound);
+            }
+            for histogram in &histograms {
+                histogram.record_bound(1.5, &bound);
+            }
+        })
+    });
+}
+
 criterion_group! {
     name = benches;
     config = Criterion::default()
         .warm_up_time(std::time::Duration::from_secs(1))
         .measurement_time(std::time::Duration::from_secs(2));
-    targets = counters, histograms
+    targets = counters, histograms, bound_attributes
 }
 
 criterion_main!(benches);
@@ -83,6 +83,7 @@
 //! For `trace` the following feature flags are available:
 //!
 //! * `jaeger_remote_sampler`: Enables the [Jaeger remote sampler](https://www.jaegertracing.io/docs/1.53/sampling/).
+//! * `xray_remote_sampler`: Enables the [AWS X-Ray remote sampler](https://docs.aws.amazon.com/xray/latest/devguide/xray-console-sampling.html).
 //!
 //!
 //! Support for recording and exporting telemetry asynchronously and perform
@@ -127,6 +128,9 @@ pub mod propagation;
 pub mod resource;
 #[cfg(feature = "experimental_async_runtime")]
 pub mod runtime;
+#[cfg(feature = "experimental_self_observability")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental_self_observability")))]
+pub mod self_observability;
 #[cfg(any(feature = "testing", test))]
 #[cfg_attr(docsrs, doc(cfg(any(feature = "testing", test))))]
 pub mod testing;
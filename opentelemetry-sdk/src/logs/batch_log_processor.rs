@@ -17,11 +17,17 @@
 
 use crate::error::{OTelSdkError, OTelSdkResult};
 use crate::logs::log_processor::LogProcessor;
+#[cfg(feature = "experimental_self_observability")]
+use crate::self_observability::{ProcessorMetrics, Signal};
 use crate::{
     logs::{LogBatch, LogExporter, SdkLogRecord},
     Resource,
 };
+#[cfg(feature = "experimental_self_observability")]
+use opentelemetry::metrics::Meter;
 use std::sync::mpsc::{self, RecvTimeoutError, SyncSender};
+#[cfg(feature = "experimental_self_observability")]
+use std::sync::OnceLock;
 
 use opentelemetry::{otel_debug, otel_error, otel_warn, Context, InstrumentationScope};
 
@@ -67,6 +73,9 @@ enum BatchMessage {
     Shutdown(mpsc::SyncSender<OTelSdkResult>),
     /// Set the resource for the exporter.
     SetResource(Arc<Resource>),
+    /// Set the meter for the exporter.
+    #[cfg(feature = "experimental_self_observability")]
+    SetMeter(Meter),
 }
 
 type LogsData = Box<(SdkLogRecord, InstrumentationScope)>;
@@ -139,6 +148,10 @@ pub struct BatchLogProcessor {
 
     // Track the maximum queue size that was configured for this processor
     max_queue_size: usize,
+
+    // Self-observability metrics, shared with the worker thread
+    #[cfg(feature = "experimental_self_observability")]
+    metrics: Arc<OnceLock<ProcessorMetrics>>,
 }
 
 impl Debug for BatchLogProcessor {
@@ -199,6 +212,10 @@ impl LogProcessor for BatchLogProcessor {
                 }
             }
             Err(mpsc::TrySendError::Full(_)) => {
+                #[cfg(feature = "experimental_self_observability")]
+                if let Some(metrics) = self.metrics.get() {
+                    metrics.dropped();
+                }
                 // Increment dropped logs count. The first time we have to drop
                 // a log, emit a warning.
                 if self.dropped_logs_count.fetch_add(1, Ordering::Relaxed) == 0 {
@@ -326,6 +343,20 @@ impl LogProcessor for BatchLogProcessor {
             .message_sender
             .try_send(BatchMessage::SetResource(resource));
     }
+
+    #[cfg(feature = "experimental_self_observability")]
+    fn set_meter(&mut self, meter: &Meter) {
+        let _ = self.metrics.set(ProcessorMetrics::new(
+            meter,
+            Signal::Log,
+            "batching_log_processor",
+            Arc::clone(&self.current_batch_size),
+            self.max_queue_size,
+        ));
+        let _ = self
+            .message_sender
+            .try_send(BatchMessage::SetMeter(meter.clone()));
+    }
 }
 
 impl BatchLogProcessor {
@@ -339,6 +370,10 @@ impl BatchLogProcessor {
         let max_export_batch_size = config.max_export_batch_size;
         let current_batch_size = Arc::new(AtomicUsize::new(0));
         let current_batch_size_for_thread = current_batch_size.clone();
+        #[cfg(feature = "experimental_self_observability")]
+        let metrics = Arc::new(OnceLock::new());
+        #[cfg(feature = "experimental_self_observability")]
+        let metrics_for_thread = Arc::clone(&metrics);
 
         let handle = thread::Builder::new()
             .name("OpenTelemetry.Logs.BatchProcessor".to_string())
@@ -353,6 +388,8 @@ impl BatchLogProcessor {
                 let mut last_export_time = Instant::now();
                 let mut logs = Vec::with_capacity(config.max_export_batch_size);
                 let current_batch_size = current_batch_size_for_thread;
+                #[cfg(feature = "experimental_self_observability")]
+                let metrics = metrics_for_thread;
 
                 // This method gets up to `max_export_batch_size` amount of logs from the channel and exports them.
                 // It returns the result of the export operation.
@@ -365,6 +402,9 @@ impl BatchLogProcessor {
                     last_export_time: &mut Instant,
                     current_batch_size: &AtomicUsize,
                     max_export_size: usize,
+                    #[cfg(feature = "experimental_self_observability")] metrics: &OnceLock<
+                        ProcessorMetrics,
+                    >,
                 ) -> OTelSdkResult
                 where
                     E: LogExporter + Send + Sync + 'static,
@@ -388,6 +428,10 @@ impl BatchLogProcessor {
                         result = export_batch_sync(exporter, logs, last_export_time); // This method clears the logs vec after exporting
 
                         current_batch_size.fetch_sub(count_of_logs, Ordering::Relaxed);
+                        #[cfg(feature = "experimental_self_observability")]
+                        if let Some(metrics) = metrics.get() {
+                            metrics.processed(count_of_logs);
+                        }
                     }
                     result
                 }
@@ -414,6 +458,8 @@ impl BatchLogProcessor {
                                 &mut last_export_time,
                                 &current_batch_size,
                                 max_export_batch_size,
+                                #[cfg(feature = "experimental_self_observability")]
+                                &metrics,
                             );
                         }
                         Ok(BatchMessage::ForceFlush(sender)) => {
@@ -425,6 +471,8 @@ impl BatchLogProcessor {
                                 &mut last_export_time,
                                 &current_batch_size,
                                 max_export_batch_size,
+                                #[cfg(feature = "experimental_self_observability")]
+                                &metrics,
                             );
                             let _ = sender.send(result);
                         }
@@ -437,6 +485,8 @@ impl BatchLogProcessor {
                                 &mut last_export_time,
                                 &current_batch_size,
                                 max_export_batch_size,
+                                #[cfg(feature = "experimental_self_observability")]
+                                &metrics,
                             );
                             let _ = exporter.shutdown();
                             let _ = sender.send(result);
@@ -453,6 +503,10 @@ impl BatchLogProcessor {
                         Ok(BatchMessage::SetResource(resource)) => {
                             exporter.set_resource(&resource);
                         }
+                        #[cfg(feature = "experimental_self_observability")]
+                        Ok(BatchMessage::SetMeter(meter)) => {
+                            exporter.set_meter(&meter);
+                        }
                         Err(RecvTimeoutError::Timeout) => {
                             otel_debug!(
                                 name: "BatchLogProcessor.ExportingDueToTimer",
@@ -465,6 +519,8 @@ impl BatchLogProcessor {
                                 &mut last_export_time,
                                 &current_batch_size,
                                 max_export_batch_size,
+                                #[cfg(feature = "experimental_self_observability")]
+                                &metrics,
                             );
                         }
                         Err(RecvTimeoutError::Disconnected) => {
@@ -495,6 +551,8 @@ impl BatchLogProcessor {
             export_log_message_sent: Arc::new(AtomicBool::new(false)),
             current_batch_size,
             max_export_batch_size,
+            #[cfg(feature = "experimental_self_observability")]
+            metrics,
         }
     }
 
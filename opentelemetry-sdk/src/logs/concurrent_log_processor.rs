@@ -61,4 +61,9 @@ impl<T: LogExporter> LogProcessor for SimpleConcurrentLogProcessor<T> {
     fn set_resource(&mut self, resource: &Resource) {
         self.exporter.set_resource(resource);
     }
+
+    #[cfg(feature = "experimental_self_observability")]
+    fn set_meter(&mut self, meter: &opentelemetry::metrics::Meter) {
+        self.exporter.set_meter(meter);
+    }
 }
@@ -150,4 +150,9 @@ pub trait LogExporter: Send + Sync + Debug {
     }
     /// Set the resource for the exporter.
     fn set_resource(&mut self, _resource: &Resource) {}
+    /// Set the meter the exporter reports its
+    /// [self-observability](crate::self_observability) metrics with, see
+    /// [ExporterMetrics](crate::self_observability::ExporterMetrics).
+    #[cfg(feature = "experimental_self_observability")]
+    fn set_meter(&mut self, _meter: &opentelemetry::metrics::Meter) {}
 }
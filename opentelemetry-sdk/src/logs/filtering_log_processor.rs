@@ -0,0 +1,433 @@
+use std::cell::RefCell;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock, Weak};
+use std::time::Duration;
+
+use opentelemetry::logs::Severity;
+use opentelemetry::InstrumentationScope;
+
+use crate::error::OTelSdkResult;
+use crate::logs::{LogProcessor, SdkLogRecord};
+use crate::Resource;
+
+/// A set of directives enabling logs by target, event name and severity,
+/// similar to the `EnvFilter` of `tracing-subscriber`.
+///
+/// Directives are comma separated, each in one of the forms:
+///
+/// - `level`, the default level of all logs, e.g. `info`.
+/// - `target=level`, the level of the logs whose target starts with `target`,
+///   e.g. `hyper=warn`.
+/// - `target[name]=level` or `[name]=level`, the level of the events named
+///   `name`, e.g. `my_crate[checkout]=debug`.
+///
+/// Levels are `trace`, `debug`, `info`, `warn`, `error`, `fatal` and `off`.
+/// A log is enabled if its severity is at least the level of the most specific
+/// matching directive: directives with an event name first, then the ones with
+/// the longest target. Logs matching no directive are disabled.
+///
+/// ```
+/// use opentelemetry_sdk::logs::LogFilter;
+///
+/// let filter: LogFilter = "info,my_crate=debug,hyper=warn".parse().unwrap();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LogFilter {
+    /// Sorted from the most to the least specific.
+    directives: Vec<Directive>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Directive {
+    target: String,
+    name: Option<String>,
+    /// `None` disables the matching logs.
+    level: Option<Severity>,
+}
+
+impl Directive {
+    fn matches(&self, target: &str, name: Option<&str>) -> bool {
+        target.starts_with(&self.target)
+            && self
+                .name
+                .as_deref()
+                .map_or(true, |directive_name| name == Some(directive_name))
+    }
+
+    fn parse(directive: &str) -> Option<Self> {
+        let (selector, level) = match directive.rsplit_once('=') {
+            Some((selector, level)) => (selector.trim(), parse_level(level.trim())?),
+            None => ("", parse_level(directive)?),
+        };
+        let (target, name) = match selector.split_once('[') {
+            Some((target, name)) => {
+                let name = name.strip_suffix(']')?;
+                if name.is_empty() {
+                    return None;
+                }
+                (target, Some(name.to_owned()))
+            }
+            None => (selector, None),
+        };
+        if target.contains([']', '=']) {
+            return None;
+        }
+        Some(Directive {
+            target: target.to_owned(),
+            name,
+            level,
+        })
+    }
+}
+
+fn parse_level(level: &str) -> Option<Option<Severity>> {
+    match level.to_ascii_lowercase().as_str() {
+        "trace" => Some(Some(Severity::Trace)),
+        "debug" => Some(Some(Severity::Debug)),
+        "info" => Some(Some(Severity::Info)),
+        "warn" => Some(Some(Severity::Warn)),
+        "error" => Some(Some(Severity::Error)),
+        "fatal" => Some(Some(Severity::Fatal)),
+        "off" => Some(None),
+        _ => None,
+    }
+}
+
+impl LogFilter {
+    /// Returns `true` if logs of the given severity, target and event name are
+    /// enabled.
+    pub fn enabled(&self, level: Severity, target: &str, name: Option<&str>) -> bool {
+        self.directives
+            .iter()
+            .find(|directive| directive.matches(target, name))
+            .and_then(|directive| directive.level)
+            .is_some_and(|min_level| level >= min_level)
+    }
+}
+
+/// Error returned when a [`LogFilter`] directive cannot be parsed.
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+#[error("invalid log filter directive `{0}`")]
+pub struct ParseLogFilterError(String);
+
+impl FromStr for LogFilter {
+    type Err = ParseLogFilterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut directives = s
+            .split(',')
+            .map(str::trim)
+            .filter(|directive| !directive.is_empty())
+            .map(|directive| {
+                Directive::parse(directive).ok_or_else(|| ParseLogFilterError(directive.to_owned()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // Stable sort, the first of equally specific directives wins.
+        directives.sort_by(|a, b| {
+            (b.name.is_some(), b.target.len()).cmp(&(a.name.is_some(), a.target.len()))
+        });
+        Ok(LogFilter { directives })
+    }
+}
+
+/// A handle to replace the [`LogFilter`] of a [`FilteringLogProcessor`] at
+/// runtime.
+#[derive(Clone, Debug)]
+pub struct LogFilterHandle {
+    shared: Arc<SharedFilter>,
+}
+
+#[derive(Debug)]
+struct SharedFilter {
+    /// Incremented on every reload, while holding the write lock of `filter`.
+    epoch: AtomicU64,
+    filter: RwLock<Arc<LogFilter>>,
+}
+
+/// A copy of the filter of a [`SharedFilter`] as of `epoch`.
+struct CachedFilter {
+    shared: Weak<SharedFilter>,
+    epoch: u64,
+    filter: Arc<LogFilter>,
+}
+
+thread_local! {
+    /// The filters last used on this thread, one per live [`SharedFilter`], so
+    /// that checking a log does not lock the filter until it is reloaded.
+    static CACHED_FILTERS: RefCell<Vec<CachedFilter>> = const { RefCell::new(Vec::new()) };
+}
+
+impl LogFilterHandle {
+    fn new(filter: LogFilter) -> Self {
+        LogFilterHandle {
+            shared: Arc::new(SharedFilter {
+                epoch: AtomicU64::new(0),
+                filter: RwLock::new(Arc::new(filter)),
+            }),
+        }
+    }
+
+    /// Replaces the filter, applied to the logs emitted from now on.
+    pub fn reload(&self, filter: LogFilter) {
+        let mut current = self
+            .shared
+            .filter
+            .write()
+            .unwrap_or_else(|err| err.into_inner());
+        *current = Arc::new(filter);
+        self.shared.epoch.fetch_add(1, Ordering::Release);
+    }
+
+    /// Returns the current filter.
+    pub fn current(&self) -> Arc<LogFilter> {
+        self.current_with_epoch().1
+    }
+
+    fn current_with_epoch(&self) -> (u64, Arc<LogFilter>) {
+        let filter = self
+            .shared
+            .filter
+            .read()
+            .unwrap_or_else(|err| err.into_inner());
+        // The epoch only changes under the write lock.
+        (self.shared.epoch.load(Ordering::Acquire), filter.clone())
+    }
+
+    /// Calls `f` with the current filter, using the copy cached on this thread
+    /// unless the filter was reloaded since.
+    fn with_filter<R>(&self, f: impl Fn(&LogFilter) -> R) -> R {
+        let epoch = self.shared.epoch.load(Ordering::Acquire);
+        CACHED_FILTERS
+            .try_with(|cache| {
+                let mut cache = cache.borrow_mut();
+                // Compared by address, the cached `Weak` keeps it from being reused.
+                let index = match cache
+                    .iter()
+                    .position(|cached| Weak::as_ptr(&cached.shared) == Arc::as_ptr(&self.shared))
+                {
+                    Some(index) if cache[index].epoch == epoch => index,
+                    Some(index) => {
+                        (cache[index].epoch, cache[index].filter) = self.current_with_epoch();
+                        index
+                    }
+                    None => {
+                        cache.retain(|cached| cached.shared.strong_count() > 0);
+                        let (epoch, filter) = self.current_with_epoch();
+                        cache.push(CachedFilter {
+                            shared: Arc::downgrade(&self.shared),
+                            epoch,
+                            filter,
+                        });
+                        cache.len() - 1
+                    }
+                };
+                f(&cache[index].filter)
+            })
+            // The thread local is being destroyed.
+            .unwrap_or_else(|_| f(&self.current()))
+    }
+}
+
+/// A [`LogProcessor`] forwarding to `inner` only the logs enabled by a
+/// [`LogFilter`].
+///
+/// The filter is also applied in [`LogProcessor::event_enabled`], so that
+/// appenders checking `Logger::event_enabled` skip building disabled logs.
+/// Records without a severity are handled as the lowest severity.
+///
+/// ```
+/// use opentelemetry_sdk::logs::{
+///     FilteringLogProcessor, LogExporter, SdkLoggerProvider, SimpleLogProcessor,
+/// };
+///
+/// fn init_logs(exporter: impl LogExporter + 'static) {
+///     let processor = FilteringLogProcessor::new(
+///         SimpleLogProcessor::new(exporter),
+///         "info,hyper=warn".parse().unwrap(),
+///     );
+///     let handle = processor.handle();
+///     let _provider = SdkLoggerProvider::builder()
+///         .with_log_processor(processor)
+///         .build();
+///
+///     // later, e.g. on a configuration change
+///     handle.reload("debug".parse().unwrap());
+/// }
+/// ```
+#[derive(Debug)]
+pub struct FilteringLogProcessor<P> {
+    inner: P,
+    filter: LogFilterHandle,
+}
+
+impl<P: LogProcessor> FilteringLogProcessor<P> {
+    /// Creates a processor forwarding the logs enabled by `filter` to `inner`.
+    pub fn new(inner: P, filter: LogFilter) -> Self {
+        FilteringLogProcessor {
+            inner,
+            filter: LogFilterHandle::new(filter),
+        }
+    }
+
+    /// Returns a handle to replace the filter at runtime.
+    pub fn handle(&self) -> LogFilterHandle {
+        self.filter.clone()
+    }
+
+    fn enabled(&self, level: Severity, target: &str, name: Option<&str>) -> bool {
+        self.filter
+            .with_filter(|filter| filter.enabled(level, target, name))
+    }
+}
+
+impl<P: LogProcessor> LogProcessor for FilteringLogProcessor<P> {
+    fn emit(&self, data: &mut SdkLogRecord, instrumentation: &InstrumentationScope) {
+        let target = data
+            .target()
+            .map(|target| target.as_ref())
+            .unwrap_or_else(|| instrumentation.name());
+        if self.enabled(
+            data.severity_number().unwrap_or(Severity::Trace),
+            target,
+            data.event_name(),
+        ) {
+            self.inner.emit(data, instrumentation);
+        }
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.inner.force_flush()
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        self.inner.shutdown_with_timeout(timeout)
+    }
+
+    #[inline]
+    fn event_enabled(&self, level: Severity, target: &str, name: Option<&str>) -> bool {
+        self.enabled(level, target, name) && self.inner.event_enabled(level, target, name)
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource);
+    }
+
+    #[cfg(feature = "experimental_self_observability")]
+    fn set_meter(&mut self, meter: &opentelemetry::metrics::Meter) {
+        self.inner.set_meter(meter);
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::logs::{InMemoryLogExporter, SdkLoggerProvider, SimpleLogProcessor};
+    use opentelemetry::logs::{LogRecord, Logger, LoggerProvider};
+
+    #[test]
+    fn parse_directives() {
+        let filter: LogFilter =
+            " info, my_crate=debug ,hyper=WARN,my_crate[checkout]=trace,[audit]=off"
+                .parse()
+                .unwrap();
+
+        assert!(filter.enabled(Severity::Info, "app", None));
+        assert!(!filter.enabled(Severity::Debug, "app", None));
+        assert!(filter.enabled(Severity::Debug, "my_crate::db", None));
+        assert!(!filter.enabled(Severity::Trace, "my_crate::db", None));
+        assert!(filter.enabled(Severity::Trace, "my_crate::db", Some("checkout")));
+        assert!(!filter.enabled(Severity::Trace, "other", Some("checkout")));
+        assert!(!filter.enabled(Severity::Info, "hyper::client", None));
+        assert!(filter.enabled(Severity::Error, "hyper::client", None));
+        assert!(!filter.enabled(Severity::Fatal, "app", Some("audit")));
+
+        for invalid in [
+            "verbose",
+            "hyper=",
+            "my_crate[=info",
+            "my_crate[]=info",
+            "a=b=info",
+        ] {
+            assert_eq!(
+                invalid.parse::<LogFilter>(),
+                Err(ParseLogFilterError(invalid.to_owned())),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn unmatched_logs_are_disabled() {
+        let filter: LogFilter = "hyper=info".parse().unwrap();
+        assert!(!filter.enabled(Severity::Fatal, "app", None));
+        assert!(!LogFilter::default().enabled(Severity::Fatal, "app", None));
+    }
+
+    #[test]
+    fn filters_emitted_logs_and_reloads() {
+        let exporter = InMemoryLogExporter::default();
+        let processor = FilteringLogProcessor::new(
+            SimpleLogProcessor::new(exporter.clone()),
+            "warn,my_crate=debug".parse().unwrap(),
+        );
+        let handle = processor.handle();
+        let provider = SdkLoggerProvider::builder()
+            .with_log_processor(processor)
+            .build();
+        let logger = provider.logger("test");
+
+        let emit = |target: &'static str, severity: Severity| {
+            let mut record = logger.create_log_record();
+            record.set_target(target);
+            record.set_severity_number(severity);
+            logger.emit(record);
+        };
+
+        assert!(logger.event_enabled(Severity::Debug, "my_crate", None));
+        assert!(!logger.event_enabled(Severity::Info, "hyper", None));
+        emit("my_crate", Severity::Debug);
+        emit("hyper", Severity::Info);
+        emit("hyper", Severity::Warn);
+        assert_eq!(exporter.get_emitted_logs().unwrap().len(), 2);
+
+        handle.reload("hyper=info".parse().unwrap());
+        assert_eq!(handle.current().as_ref(), &"hyper=info".parse().unwrap());
+        assert!(!logger.event_enabled(Severity::Debug, "my_crate", None));
+        assert!(logger.event_enabled(Severity::Info, "hyper", None));
+        emit("my_crate", Severity::Debug);
+        emit("hyper", Severity::Info);
+        let emitted = exporter.get_emitted_logs().unwrap();
+        assert_eq!(emitted.len(), 3);
+        assert_eq!(emitted[2].record.target().unwrap(), "hyper");
+    }
+
+    #[test]
+    fn filters_are_cached_per_processor_and_thread() {
+        let new_processor = |filter: &str| {
+            FilteringLogProcessor::new(
+                SimpleLogProcessor::new(InMemoryLogExporter::default()),
+                filter.parse().unwrap(),
+            )
+        };
+        let info = new_processor("info");
+        let warn = new_processor("warn");
+
+        assert!(info.enabled(Severity::Info, "app", None));
+        assert!(!warn.enabled(Severity::Info, "app", None));
+
+        // A reload on another thread is seen by the copy cached on this one.
+        let handle = warn.handle();
+        std::thread::spawn(move || handle.reload("debug".parse().unwrap()))
+            .join()
+            .unwrap();
+        assert!(warn.enabled(Severity::Debug, "app", None));
+        assert!(!info.enabled(Severity::Debug, "app", None));
+
+        // The copies of dropped processors are removed.
+        drop(info);
+        let error = new_processor("error");
+        assert!(!error.enabled(Severity::Warn, "app", None));
+        CACHED_FILTERS.with(|cache| assert_eq!(cache.borrow().len(), 2));
+    }
+}
@@ -87,6 +87,11 @@ pub trait LogProcessor: Send + Sync + Debug {
 
     /// Set the resource for the log processor.
     fn set_resource(&mut self, _resource: &Resource) {}
+
+    /// Set the meter the log processor and its exporter report their
+    /// [self-observability](crate::self_observability) metrics with.
+    #[cfg(feature = "experimental_self_observability")]
+    fn set_meter(&mut self, _meter: &opentelemetry::metrics::Meter) {}
 }
 
 #[cfg(all(test, feature = "testing", feature = "logs"))]
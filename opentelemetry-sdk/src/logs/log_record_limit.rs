@@ -0,0 +1,33 @@
+/// # Log record limit
+/// Erroneous code can add unintended attributes to a log record. If they are
+/// unbounded, they can quickly exhaust available memory.
+///
+/// To protect against those errors, users can use log record limits to configure
+///  - Maximum allowed attribute count per log record
+///  - Maximum allowed length of string attribute values
+///
+/// If the attribute count limit has been breached, the attributes added later are
+/// dropped. String values longer than the allowed length are truncated.
+pub(crate) const DEFAULT_MAX_ATTRIBUTES_PER_LOG_RECORD: u32 = 128;
+
+/// Log record limit configuration to keep the attributes of a log record in a
+/// reasonable number and size.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LogRecordLimits {
+    /// The max attributes that can be added to a log record.
+    pub max_attributes_per_log_record: u32,
+    /// The max length, in characters, of string attribute values, including
+    /// the strings nested in lists and maps. Longer values are truncated.
+    ///
+    /// `None` means values are not truncated.
+    pub max_attribute_value_length: Option<u32>,
+}
+
+impl Default for LogRecordLimits {
+    fn default() -> Self {
+        LogRecordLimits {
+            max_attributes_per_log_record: DEFAULT_MAX_ATTRIBUTES_PER_LOG_RECORD,
+            max_attribute_value_length: None,
+        }
+    }
+}
@@ -27,7 +27,7 @@ impl opentelemetry::logs::Logger for SdkLogger {
     type LogRecord = SdkLogRecord;
 
     fn create_log_record(&self) -> Self::LogRecord {
-        SdkLogRecord::new()
+        SdkLogRecord::with_limits(self.provider.log_record_limits())
     }
 
     /// Emit a `LogRecord`.
@@ -51,6 +51,11 @@ impl opentelemetry::logs::Logger for SdkLogger {
             record.observed_timestamp = Some(now());
         }
 
+        #[cfg(feature = "experimental_self_observability")]
+        if let Some(metrics) = provider.metrics() {
+            metrics.log_created();
+        }
+
         for p in processors {
             p.emit(&mut record, &self.scope);
         }
@@ -1,8 +1,15 @@
 use super::{BatchLogProcessor, LogProcessor, SdkLogger, SimpleLogProcessor};
 use crate::error::{OTelSdkError, OTelSdkResult};
-use crate::logs::LogExporter;
+use crate::logs::{LogExporter, LogRecordLimits};
+#[cfg(feature = "experimental_self_observability")]
+use crate::self_observability::{self, LoggerMetrics};
+use crate::util::attribute_value_length_limit_from_env;
 use crate::Resource;
+#[cfg(feature = "experimental_self_observability")]
+use opentelemetry::metrics::{Meter, MeterProvider};
 use opentelemetry::{otel_debug, otel_info, InstrumentationScope};
+use std::env;
+use std::str::FromStr;
 use std::time::Duration;
 use std::{
     borrow::Cow,
@@ -21,7 +28,10 @@ fn noop_logger_provider() -> &'static SdkLoggerProvider {
     NOOP_LOGGER_PROVIDER.get_or_init(|| SdkLoggerProvider {
         inner: Arc::new(LoggerProviderInner {
             processors: Vec::new(),
+            log_record_limits: LogRecordLimits::default(),
             is_shutdown: AtomicBool::new(true),
+            #[cfg(feature = "experimental_self_observability")]
+            metrics: None,
         }),
     })
 }
@@ -82,6 +92,15 @@ impl SdkLoggerProvider {
         &self.inner.processors
     }
 
+    pub(crate) fn log_record_limits(&self) -> LogRecordLimits {
+        self.inner.log_record_limits
+    }
+
+    #[cfg(feature = "experimental_self_observability")]
+    pub(crate) fn metrics(&self) -> Option<&LoggerMetrics> {
+        self.inner.metrics.as_ref()
+    }
+
     /// Force flush all remaining logs in log processors and return results.
     pub fn force_flush(&self) -> OTelSdkResult {
         let result: Vec<_> = self
@@ -134,7 +153,10 @@ impl SdkLoggerProvider {
 #[derive(Debug)]
 struct LoggerProviderInner {
     processors: Vec<Box<dyn LogProcessor>>,
+    log_record_limits: LogRecordLimits,
     is_shutdown: AtomicBool,
+    #[cfg(feature = "experimental_self_observability")]
+    metrics: Option<LoggerMetrics>,
 }
 
 impl LoggerProviderInner {
@@ -184,6 +206,12 @@ impl Drop for LoggerProviderInner {
 pub struct LoggerProviderBuilder {
     processors: Vec<Box<dyn LogProcessor>>,
     resource: Option<Resource>,
+    max_attributes_per_log_record: Option<u32>,
+    /// `Some(None)` if the length of attribute values was explicitly left
+    /// unlimited, which is not overridden by the environment.
+    max_attribute_value_length: Option<Option<u32>>,
+    #[cfg(feature = "experimental_self_observability")]
+    meter: Option<Meter>,
 }
 
 impl LoggerProviderBuilder {
@@ -259,6 +287,58 @@ impl LoggerProviderBuilder {
         LoggerProviderBuilder { resource, ..self }
     }
 
+    /// Specify the [`LogRecordLimits`] of the log records created by the
+    /// provider, replacing any previously set limit.
+    pub fn with_log_record_limits(self, limits: LogRecordLimits) -> Self {
+        LoggerProviderBuilder {
+            max_attributes_per_log_record: Some(limits.max_attributes_per_log_record),
+            max_attribute_value_length: Some(limits.max_attribute_value_length),
+            ..self
+        }
+    }
+
+    /// Specify the max number of attributes of a log record. Attributes added
+    /// beyond the limit are dropped and counted in
+    /// [`SdkLogRecord::dropped_attributes_count`].
+    ///
+    /// If not set, the limit is read from the
+    /// `OTEL_LOGRECORD_ATTRIBUTE_COUNT_LIMIT` environment variable, and
+    /// defaults to 128.
+    ///
+    /// [`SdkLogRecord::dropped_attributes_count`]: crate::logs::SdkLogRecord::dropped_attributes_count
+    pub fn with_max_attributes_per_log_record(self, max_attributes: u32) -> Self {
+        LoggerProviderBuilder {
+            max_attributes_per_log_record: Some(max_attributes),
+            ..self
+        }
+    }
+
+    /// Specify the max length, in characters, of string attribute values.
+    /// Longer values, including strings nested in lists and maps, are
+    /// truncated.
+    ///
+    /// If not set, the limit is read from the
+    /// `OTEL_LOGRECORD_ATTRIBUTE_VALUE_LENGTH_LIMIT` environment variable,
+    /// falling back to `OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT`. By default values
+    /// are not truncated.
+    pub fn with_max_attribute_value_length(self, max_length: u32) -> Self {
+        LoggerProviderBuilder {
+            max_attribute_value_length: Some(Some(max_length)),
+            ..self
+        }
+    }
+
+    /// Reports the [self-observability](crate::self_observability) metrics of
+    /// the provider, e.g. `otel.sdk.log.created`, and of its log processors
+    /// and exporters through `meter_provider`.
+    #[cfg(feature = "experimental_self_observability")]
+    pub fn with_self_observability(self, meter_provider: &impl MeterProvider) -> Self {
+        LoggerProviderBuilder {
+            meter: Some(self_observability::meter(meter_provider)),
+            ..self
+        }
+    }
+
     /// Create a new provider from this configuration.
     pub fn build(self) -> SdkLoggerProvider {
         let resource = self.resource.unwrap_or(Resource::builder().build());
@@ -267,10 +347,35 @@ impl LoggerProviderBuilder {
             processor.set_resource(&resource);
         }
 
+        #[cfg(feature = "experimental_self_observability")]
+        let metrics = self.meter.map(|meter| {
+            for processor in &mut processors {
+                processor.set_meter(&meter);
+            }
+            LoggerMetrics::new(&meter)
+        });
+
+        let log_record_limits = LogRecordLimits {
+            max_attributes_per_log_record: self
+                .max_attributes_per_log_record
+                .or_else(|| {
+                    env::var("OTEL_LOGRECORD_ATTRIBUTE_COUNT_LIMIT")
+                        .ok()
+                        .and_then(|count_limit| u32::from_str(&count_limit).ok())
+                })
+                .unwrap_or(LogRecordLimits::default().max_attributes_per_log_record),
+            max_attribute_value_length: self.max_attribute_value_length.unwrap_or_else(|| {
+                attribute_value_length_limit_from_env("OTEL_LOGRECORD_ATTRIBUTE_VALUE_LENGTH_LIMIT")
+            }),
+        };
+
         let logger_provider = SdkLoggerProvider {
             inner: Arc::new(LoggerProviderInner {
                 processors,
+                log_record_limits,
                 is_shutdown: AtomicBool::new(false),
+                #[cfg(feature = "experimental_self_observability")]
+                metrics,
             }),
         };
 
@@ -664,6 +769,168 @@ mod tests {
         });
     }
 
+    #[test]
+    fn attribute_value_length_limit_test() {
+        let exporter = InMemoryLogExporter::default();
+        let logger_provider = SdkLoggerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .with_max_attribute_value_length(3)
+            .build();
+        let logger = logger_provider.logger("test-logger");
+
+        let mut record = logger.create_log_record();
+        record.set_body(AnyValue::String("body is not truncated".into()));
+        record.add_attribute("short", "abc");
+        record.add_attribute("long", "abcdef");
+        record.add_attribute("multibyte", "日本語です");
+        record.add_attribute(
+            "list",
+            AnyValue::ListAny(Box::new(vec![
+                AnyValue::String("a".into()),
+                AnyValue::String("abcd".into()),
+            ])),
+        );
+        logger.emit(record);
+
+        let emitted = exporter.get_emitted_logs().unwrap();
+        let record = &emitted[0].record;
+        assert_eq!(record.truncated_attributes_count(), 3);
+        assert_eq!(
+            record.body(),
+            Some(&AnyValue::String("body is not truncated".into()))
+        );
+        assert!(record.attributes_contains(&Key::new("short"), &AnyValue::String("abc".into())));
+        assert!(record.attributes_contains(&Key::new("long"), &AnyValue::String("abc".into())));
+        assert!(
+            record.attributes_contains(&Key::new("multibyte"), &AnyValue::String("日本語".into()))
+        );
+        assert!(record.attributes_contains(
+            &Key::new("list"),
+            &AnyValue::ListAny(Box::new(vec![
+                AnyValue::String("a".into()),
+                AnyValue::String("abc".into()),
+            ]))
+        ));
+    }
+
+    #[test]
+    fn attribute_count_limit_test() {
+        let exporter = InMemoryLogExporter::default();
+        let logger_provider = SdkLoggerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .with_max_attributes_per_log_record(2)
+            .build();
+        let logger = logger_provider.logger("test-logger");
+
+        let mut record = logger.create_log_record();
+        record.add_attribute("k1", "v1");
+        record.add_attributes([("k2", "v2"), ("k3", "v3"), ("k4", "v4")]);
+        logger.emit(record);
+
+        let emitted = exporter.get_emitted_logs().unwrap();
+        let record = &emitted[0].record;
+        assert_eq!(record.attributes_iter().count(), 2);
+        assert_eq!(record.dropped_attributes_count(), 2);
+        assert!(record.attributes_contains(&Key::new("k1"), &AnyValue::String("v1".into())));
+        assert!(record.attributes_contains(&Key::new("k2"), &AnyValue::String("v2".into())));
+    }
+
+    #[test]
+    fn attribute_count_limit_from_env_test() {
+        temp_env::with_var("OTEL_LOGRECORD_ATTRIBUTE_COUNT_LIMIT", Some("10"), || {
+            let logger_provider = SdkLoggerProvider::builder().build();
+            assert_eq!(
+                logger_provider
+                    .log_record_limits()
+                    .max_attributes_per_log_record,
+                10
+            );
+
+            let logger_provider = SdkLoggerProvider::builder()
+                .with_log_record_limits(LogRecordLimits {
+                    max_attributes_per_log_record: 4,
+                    max_attribute_value_length: Some(8),
+                })
+                .build();
+            assert_eq!(
+                logger_provider.log_record_limits(),
+                LogRecordLimits {
+                    max_attributes_per_log_record: 4,
+                    max_attribute_value_length: Some(8),
+                }
+            );
+        });
+
+        temp_env::with_var_unset("OTEL_LOGRECORD_ATTRIBUTE_COUNT_LIMIT", || {
+            let logger_provider = SdkLoggerProvider::builder().build();
+            assert_eq!(
+                logger_provider
+                    .log_record_limits()
+                    .max_attributes_per_log_record,
+                128
+            );
+        });
+    }
+
+    #[test]
+    fn attribute_value_length_limit_from_env_test() {
+        temp_env::with_vars(
+            [
+                ("OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT", Some("16")),
+                ("OTEL_LOGRECORD_ATTRIBUTE_VALUE_LENGTH_LIMIT", Some("8")),
+            ],
+            || {
+                let logger_provider = SdkLoggerProvider::builder().build();
+                assert_eq!(
+                    logger_provider
+                        .log_record_limits()
+                        .max_attribute_value_length,
+                    Some(8)
+                );
+
+                let logger_provider = SdkLoggerProvider::builder()
+                    .with_max_attribute_value_length(4)
+                    .build();
+                assert_eq!(
+                    logger_provider
+                        .log_record_limits()
+                        .max_attribute_value_length,
+                    Some(4)
+                );
+
+                // An explicitly unlimited length is not overridden either.
+                let logger_provider = SdkLoggerProvider::builder()
+                    .with_log_record_limits(LogRecordLimits {
+                        max_attributes_per_log_record: 4,
+                        max_attribute_value_length: None,
+                    })
+                    .build();
+                assert_eq!(
+                    logger_provider
+                        .log_record_limits()
+                        .max_attribute_value_length,
+                    None
+                );
+            },
+        );
+
+        temp_env::with_vars_unset(
+            [
+                "OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT",
+                "OTEL_LOGRECORD_ATTRIBUTE_VALUE_LENGTH_LIMIT",
+            ],
+            || {
+                let logger_provider = SdkLoggerProvider::builder().build();
+                assert_eq!(
+                    logger_provider
+                        .log_record_limits()
+                        .max_attribute_value_length,
+                    None
+                );
+            },
+        );
+    }
+
     #[test]
     fn shutdown_test() {
         let counter = Arc::new(AtomicU64::new(0));
@@ -752,7 +1019,10 @@ mod tests {
                     shutdown_called.clone(),
                     flush_called.clone(),
                 ))],
+                log_record_limits: LogRecordLimits::default(),
                 is_shutdown: AtomicBool::new(false),
+                #[cfg(feature = "experimental_self_observability")]
+                metrics: None,
             });
 
             {
@@ -792,7 +1062,10 @@ mod tests {
                 shutdown_called.clone(),
                 flush_called.clone(),
             ))],
+            log_record_limits: LogRecordLimits::default(),
             is_shutdown: AtomicBool::new(false),
+            #[cfg(feature = "experimental_self_observability")]
+            metrics: None,
         });
 
         // Create a scope to test behavior when providers are dropped
@@ -1,11 +1,17 @@
 //! # OpenTelemetry Log SDK
 mod batch_log_processor;
 mod export;
+mod filtering_log_processor;
 mod log_processor;
+mod log_record_limit;
 mod logger;
 mod logger_provider;
 pub(crate) mod record;
+#[cfg(feature = "trace")]
+mod sampling_log_processor;
 mod simple_log_processor;
+#[cfg(feature = "trace")]
+mod span_event_log_processor;
 
 /// In-Memory log exporter for testing purpose.
 #[cfg(any(feature = "testing", test))]
@@ -19,11 +25,19 @@ pub use batch_log_processor::{
     BatchConfig, BatchConfigBuilder, BatchLogProcessor, BatchLogProcessorBuilder,
 };
 pub use export::{LogBatch, LogExporter};
+pub use filtering_log_processor::{
+    FilteringLogProcessor, LogFilter, LogFilterHandle, ParseLogFilterError,
+};
 pub use log_processor::LogProcessor;
+pub use log_record_limit::LogRecordLimits;
 pub use logger::SdkLogger;
 pub use logger_provider::{LoggerProviderBuilder, SdkLoggerProvider};
 pub use record::{SdkLogRecord, TraceContext};
+#[cfg(feature = "trace")]
+pub use sampling_log_processor::SamplingLogProcessor;
 pub use simple_log_processor::SimpleLogProcessor;
+#[cfg(feature = "trace")]
+pub use span_event_log_processor::SpanEventLogProcessor;
 
 #[cfg(feature = "experimental_logs_concurrent_log_processor")]
 /// Module for ConcurrentLogProcessor.
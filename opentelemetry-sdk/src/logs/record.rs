@@ -1,4 +1,6 @@
 use crate::growable_array::GrowableArray;
+use crate::logs::LogRecordLimits;
+use crate::util::truncate_str;
 #[cfg(feature = "trace")]
 use opentelemetry::trace::SpanContext;
 use opentelemetry::{
@@ -50,6 +52,17 @@ pub struct SdkLogRecord {
 
     /// Additional attributes associated with this record
     pub(crate) attributes: LogRecordAttributes,
+
+    /// The number of attributes that were above the configured limit, and thus
+    /// dropped.
+    pub(crate) dropped_attributes_count: u32,
+
+    /// The number of attribute values that were longer than the configured
+    /// limit, and thus truncated.
+    pub(crate) truncated_attributes_count: u32,
+
+    /// The limits applied to the attributes added to this record.
+    pub(crate) limits: LogRecordLimits,
 }
 
 impl opentelemetry::logs::LogRecord for SdkLogRecord {
@@ -101,7 +114,17 @@ impl opentelemetry::logs::LogRecord for SdkLogRecord {
         K: Into<Key>,
         V: Into<AnyValue>,
     {
-        self.attributes.push(Some((key.into(), value.into())));
+        if self.attributes.len() >= self.limits.max_attributes_per_log_record as usize {
+            self.dropped_attributes_count += 1;
+            return;
+        }
+        let mut value = value.into();
+        if let Some(limit) = self.limits.max_attribute_value_length {
+            if truncate_any_value(&mut value, limit as usize) {
+                self.truncated_attributes_count += 1;
+            }
+        }
+        self.attributes.push(Some((key.into(), value)));
     }
 
     fn set_trace_context(
@@ -120,7 +143,13 @@ impl opentelemetry::logs::LogRecord for SdkLogRecord {
 
 impl SdkLogRecord {
     /// Crate only default constructor
+    #[cfg(test)]
     pub(crate) fn new() -> Self {
+        Self::with_limits(LogRecordLimits::default())
+    }
+
+    /// Crate only constructor of a record enforcing `limits`
+    pub(crate) fn with_limits(limits: LogRecordLimits) -> Self {
         SdkLogRecord {
             event_name: None,
             target: None,
@@ -131,6 +160,9 @@ impl SdkLogRecord {
             severity_number: None,
             body: None,
             attributes: LogRecordAttributes::default(),
+            dropped_attributes_count: 0,
+            truncated_attributes_count: 0,
+            limits,
         }
     }
 
@@ -202,6 +234,41 @@ impl SdkLogRecord {
             .flatten()
             .any(|(k, v)| k == key && v == value)
     }
+
+    /// Returns the number of attributes that were dropped because the record
+    /// exceeded the configured max attribute count.
+    #[inline]
+    pub fn dropped_attributes_count(&self) -> u32 {
+        self.dropped_attributes_count
+    }
+
+    /// Returns the number of attribute values that were truncated because
+    /// they exceeded the configured max attribute value length.
+    #[inline]
+    pub fn truncated_attributes_count(&self) -> u32 {
+        self.truncated_attributes_count
+    }
+}
+
+/// Truncates the strings in `value` to `limit` characters. Returns `true` if
+/// anything was truncated.
+fn truncate_any_value(value: &mut AnyValue, limit: usize) -> bool {
+    match value {
+        AnyValue::String(s) => match truncate_str(s.as_str(), limit) {
+            Some(truncated) => {
+                *s = truncated.to_owned().into();
+                true
+            }
+            None => false,
+        },
+        AnyValue::ListAny(values) => values.iter_mut().fold(false, |truncated, v| {
+            truncate_any_value(v, limit) | truncated
+        }),
+        AnyValue::Map(map) => map.values_mut().fold(false, |truncated, v| {
+            truncate_any_value(v, limit) | truncated
+        }),
+        _ => false,
+    }
 }
 
 /// TraceContext stores the trace context for logs that have an associated
@@ -339,6 +406,9 @@ mod tests {
             severity_number: Some(Severity::Error),
             body: Some(AnyValue::String("Test body".into())),
             attributes: LogRecordAttributes::new(),
+            dropped_attributes_count: 0,
+            truncated_attributes_count: 0,
+            limits: LogRecordLimits::default(),
             trace_context: Some(TraceContext {
                 trace_id: TraceId::from(1),
                 span_id: SpanId::from(1),
@@ -0,0 +1,305 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use opentelemetry::logs::{LogRecord, Severity};
+use opentelemetry::trace::TraceContextExt;
+use opentelemetry::{Context, InstrumentationScope, Key};
+
+use crate::error::OTelSdkResult;
+use crate::logs::{LogProcessor, SdkLogRecord};
+use crate::Resource;
+
+/// The number of records each record kept by the probabilistic sampling
+/// stands for, `1 / ratio`.
+const ADJUSTED_COUNT: Key = Key::from_static_str("sampling.adjusted_count");
+/// The number of records dropped by the rate limit since the previous record
+/// kept with the same severity.
+const DROPPED_COUNT: Key = Key::from_static_str("sampling.dropped_count");
+
+/// The number of severity levels, `Trace` to `Fatal`, each spanning four
+/// severity numbers.
+const LEVELS: usize = 6;
+
+/// A [`LogProcessor`] forwarding to `inner` only the sampled logs.
+///
+/// Logs emitted within a trace follow the trace sampling decision: they are
+/// dropped when the trace flags of their [`TraceContext`] are not sampled and
+/// kept otherwise. The other logs are sampled with a fixed probability, then
+/// rate limited per severity level, e.g. [`Severity::Debug`] limiting the logs
+/// from `Debug` to `Debug4`.
+///
+/// So that backends can extrapolate the actual counts, the logs kept by the
+/// probabilistic sampling carry a `sampling.adjusted_count` attribute, and the
+/// first log kept after some were dropped by the rate limit carries their
+/// number as `sampling.dropped_count`.
+///
+/// [`TraceContext`]: crate::logs::TraceContext
+///
+/// ```
+/// use opentelemetry::logs::Severity;
+/// use opentelemetry_sdk::logs::{
+///     BatchLogProcessor, LogExporter, SamplingLogProcessor, SdkLoggerProvider,
+/// };
+///
+/// fn init_logs(exporter: impl LogExporter + 'static) {
+///     let processor = SamplingLogProcessor::new(BatchLogProcessor::builder(exporter).build())
+///         .with_ratio(0.5)
+///         .with_rate_limit(Severity::Debug, 100);
+///     let _provider = SdkLoggerProvider::builder()
+///         .with_log_processor(processor)
+///         .build();
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SamplingLogProcessor<P> {
+    inner: P,
+    ratio: f64,
+    rate_limits: [Option<Mutex<RateLimiter>>; LEVELS],
+}
+
+impl<P: LogProcessor> SamplingLogProcessor<P> {
+    /// Creates a processor forwarding to `inner` the logs of sampled traces
+    /// and all the logs emitted outside of a trace.
+    pub fn new(inner: P) -> Self {
+        SamplingLogProcessor {
+            inner,
+            ratio: 1.0,
+            rate_limits: Default::default(),
+        }
+    }
+
+    /// Keeps the logs emitted outside of a trace with the given probability,
+    /// between `0.0` and `1.0`.
+    pub fn with_ratio(mut self, ratio: f64) -> Self {
+        self.ratio = ratio.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Keeps at most `records_per_second` logs emitted outside of a trace with
+    /// the severity level of `severity`.
+    pub fn with_rate_limit(mut self, severity: Severity, records_per_second: u32) -> Self {
+        self.rate_limits[level(severity)] = Some(Mutex::new(RateLimiter::new(records_per_second)));
+        self
+    }
+
+    /// Returns `false` if the record is dropped, adding the sampling
+    /// attributes to the kept ones.
+    fn sample(&self, record: &mut SdkLogRecord) -> bool {
+        if let Some(trace_flags) = record
+            .trace_context()
+            .and_then(|trace_context| trace_context.trace_flags)
+        {
+            return trace_flags.is_sampled();
+        }
+
+        if self.ratio < 1.0 {
+            if rand::random::<f64>() >= self.ratio {
+                return false;
+            }
+            record.add_attribute(ADJUSTED_COUNT, 1.0 / self.ratio);
+        }
+
+        let severity = record.severity_number().unwrap_or(Severity::Trace);
+        if let Some(rate_limit) = &self.rate_limits[level(severity)] {
+            let mut rate_limit = rate_limit.lock().unwrap_or_else(|err| err.into_inner());
+            if !rate_limit.try_acquire() {
+                return false;
+            }
+            let dropped = std::mem::take(&mut rate_limit.dropped);
+            drop(rate_limit);
+            if dropped > 0 {
+                record.add_attribute(DROPPED_COUNT, dropped as i64);
+            }
+        }
+        true
+    }
+}
+
+fn level(severity: Severity) -> usize {
+    (severity as usize - 1) / 4
+}
+
+/// Limits the number of records kept per one second window.
+#[derive(Debug)]
+struct RateLimiter {
+    records_per_second: u32,
+    window_start: Option<Instant>,
+    records_in_window: u32,
+    /// Records dropped since the last kept one.
+    dropped: u64,
+}
+
+impl RateLimiter {
+    fn new(records_per_second: u32) -> Self {
+        RateLimiter {
+            records_per_second,
+            window_start: None,
+            records_in_window: 0,
+            dropped: 0,
+        }
+    }
+
+    fn try_acquire(&mut self) -> bool {
+        let now = Instant::now();
+        match self.window_start {
+            Some(start) if now.duration_since(start) < Duration::from_secs(1) => {}
+            _ => {
+                self.window_start = Some(now);
+                self.records_in_window = 0;
+            }
+        }
+        if self.records_in_window < self.records_per_second {
+            self.records_in_window += 1;
+            true
+        } else {
+            self.dropped += 1;
+            false
+        }
+    }
+}
+
+impl<P: LogProcessor> LogProcessor for SamplingLogProcessor<P> {
+    fn emit(&self, data: &mut SdkLogRecord, instrumentation: &InstrumentationScope) {
+        if self.sample(data) {
+            self.inner.emit(data, instrumentation);
+        }
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.inner.force_flush()
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        self.inner.shutdown_with_timeout(timeout)
+    }
+
+    #[inline]
+    fn event_enabled(&self, level: Severity, target: &str, name: Option<&str>) -> bool {
+        // Logs emitted in an unsampled span are dropped, whatever their content.
+        let in_unsampled_span = Context::map_current(|cx| {
+            cx.has_active_span() && !cx.span().span_context().is_sampled()
+        });
+        !in_unsampled_span && self.inner.event_enabled(level, target, name)
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource);
+    }
+
+    #[cfg(feature = "experimental_self_observability")]
+    fn set_meter(&mut self, meter: &opentelemetry::metrics::Meter) {
+        self.inner.set_meter(meter);
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::logs::{InMemoryLogExporter, SdkLoggerProvider, SimpleLogProcessor};
+    use crate::trace::{Sampler, SdkTracerProvider};
+    use opentelemetry::logs::{AnyValue, Logger, LoggerProvider};
+    use opentelemetry::trace::{Tracer, TracerProvider};
+
+    fn emit(logger: &impl Logger, severity: Severity) {
+        let mut record = logger.create_log_record();
+        record.set_severity_number(severity);
+        logger.emit(record);
+    }
+
+    #[test]
+    fn follows_trace_sampling() {
+        let exporter = InMemoryLogExporter::default();
+        let logger_provider = SdkLoggerProvider::builder()
+            .with_log_processor(
+                SamplingLogProcessor::new(SimpleLogProcessor::new(exporter.clone()))
+                    .with_ratio(0.0),
+            )
+            .build();
+        let logger = logger_provider.logger("test-logger");
+
+        let sampled = SdkTracerProvider::builder().build();
+        sampled.tracer("test").in_span("sampled", |_cx| {
+            assert!(logger.event_enabled(Severity::Debug, "test", None));
+            emit(&logger, Severity::Debug);
+        });
+        let unsampled = SdkTracerProvider::builder()
+            .with_sampler(Sampler::AlwaysOff)
+            .build();
+        unsampled.tracer("test").in_span("unsampled", |_cx| {
+            assert!(!logger.event_enabled(Severity::Debug, "test", None));
+            emit(&logger, Severity::Debug);
+        });
+        // Outside of a trace, dropped by the ratio.
+        emit(&logger, Severity::Debug);
+
+        let emitted = exporter.get_emitted_logs().unwrap();
+        assert_eq!(emitted.len(), 1);
+        assert!(emitted[0].record.trace_context().is_some());
+        assert_eq!(emitted[0].record.attributes_len(), 0);
+    }
+
+    #[test]
+    fn rate_limits_per_severity_level() {
+        let exporter = InMemoryLogExporter::default();
+        let processor = SamplingLogProcessor::new(SimpleLogProcessor::new(exporter.clone()))
+            .with_rate_limit(Severity::Debug, 2);
+        let logger_provider = SdkLoggerProvider::builder()
+            .with_log_processor(processor)
+            .build();
+        let logger = logger_provider.logger("test-logger");
+
+        for severity in [Severity::Debug, Severity::Debug2, Severity::Debug3] {
+            emit(&logger, severity);
+        }
+        for _ in 0..3 {
+            emit(&logger, Severity::Info);
+        }
+        assert_eq!(exporter.get_emitted_logs().unwrap().len(), 5);
+
+        let mut limiter = RateLimiter::new(1);
+        assert!(limiter.try_acquire());
+        assert!(!limiter.try_acquire());
+        assert!(!limiter.try_acquire());
+        assert_eq!(limiter.dropped, 2);
+    }
+
+    #[test]
+    fn adds_sampling_attributes() {
+        // Keeps virtually all records, while still adding the adjusted count.
+        const RATIO: f64 = 1.0 - f64::EPSILON;
+        let exporter = InMemoryLogExporter::default();
+        let processor = SamplingLogProcessor::new(SimpleLogProcessor::new(exporter.clone()))
+            .with_ratio(RATIO)
+            .with_rate_limit(Severity::Info, 10);
+        processor.rate_limits[level(Severity::Info)]
+            .as_ref()
+            .unwrap()
+            .lock()
+            .unwrap()
+            .dropped = 7;
+        let logger_provider = SdkLoggerProvider::builder()
+            .with_log_processor(processor)
+            .build();
+        let logger = logger_provider.logger("test-logger");
+
+        emit(&logger, Severity::Info);
+        emit(&logger, Severity::Info);
+
+        let emitted = exporter.get_emitted_logs().unwrap();
+        assert_eq!(emitted.len(), 2);
+        let attributes = |index: usize| -> Vec<(Key, AnyValue)> {
+            emitted[index].record.attributes_iter().cloned().collect()
+        };
+        assert_eq!(
+            attributes(0),
+            vec![
+                (ADJUSTED_COUNT, AnyValue::Double(1.0 / RATIO)),
+                (DROPPED_COUNT, AnyValue::Int(7)),
+            ]
+        );
+        assert_eq!(
+            attributes(1),
+            vec![(ADJUSTED_COUNT, AnyValue::Double(1.0 / RATIO))]
+        );
+    }
+}
@@ -135,6 +135,13 @@ impl<T: LogExporter> LogProcessor for SimpleLogProcessor<T> {
         }
     }
 
+    #[cfg(feature = "experimental_self_observability")]
+    fn set_meter(&mut self, meter: &opentelemetry::metrics::Meter) {
+        if let Ok(mut exporter) = self.exporter.lock() {
+            exporter.set_meter(meter);
+        }
+    }
+
     #[inline]
     fn event_enabled(
         &self,
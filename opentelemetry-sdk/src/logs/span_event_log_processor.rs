@@ -0,0 +1,408 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use opentelemetry::logs::{AnyValue, Severity};
+use opentelemetry::trace::TraceContextExt;
+use opentelemetry::{Array, Context, InstrumentationScope, Key, KeyValue, Value};
+
+use crate::error::OTelSdkResult;
+use crate::logs::{LogProcessor, SdkLogRecord};
+use crate::Resource;
+
+/// The name of the span events created from logs without an event name.
+const DEFAULT_EVENT_NAME: &str = "log";
+const MESSAGE: Key = Key::from_static_str("message");
+const SEVERITY_TEXT: Key = Key::from_static_str("severity_text");
+const SEVERITY_NUMBER: Key = Key::from_static_str("severity_number");
+
+/// A [`LogProcessor`] attaching logs to the span they were emitted in, as span
+/// events, for backends ingesting only traces.
+///
+/// A log is attached when its severity is at least the configured threshold
+/// and its [`TraceContext`] refers to the current span, still recording. The
+/// event is named after the event name of the log, `log` by default. It holds
+/// the body as `message`, the attributes of the log and its severity as
+/// `severity_text` and `severity_number`.
+///
+/// All logs are forwarded to `inner` unless
+/// [`with_attached_logs_exported(false)`] is set, in which case only the logs
+/// not attached to a span are.
+///
+/// [`TraceContext`]: crate::logs::TraceContext
+/// [`with_attached_logs_exported(false)`]: SpanEventLogProcessor::with_attached_logs_exported
+///
+/// ```
+/// use opentelemetry::logs::Severity;
+/// use opentelemetry_sdk::logs::{
+///     LogExporter, SdkLoggerProvider, SimpleLogProcessor, SpanEventLogProcessor,
+/// };
+///
+/// fn init_logs(exporter: impl LogExporter + 'static) {
+///     let processor = SpanEventLogProcessor::new(SimpleLogProcessor::new(exporter))
+///         .with_min_severity(Severity::Info)
+///         .with_attached_logs_exported(false);
+///     let _provider = SdkLoggerProvider::builder()
+///         .with_log_processor(processor)
+///         .build();
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SpanEventLogProcessor<P> {
+    inner: P,
+    min_severity: Severity,
+    export_attached_logs: bool,
+}
+
+impl<P: LogProcessor> SpanEventLogProcessor<P> {
+    /// Creates a processor attaching all logs to their span and forwarding
+    /// them to `inner`.
+    pub fn new(inner: P) -> Self {
+        SpanEventLogProcessor {
+            inner,
+            min_severity: Severity::Trace,
+            export_attached_logs: true,
+        }
+    }
+
+    /// Attaches only the logs with at least the given severity. Records
+    /// without a severity are handled as the lowest severity.
+    pub fn with_min_severity(mut self, min_severity: Severity) -> Self {
+        self.min_severity = min_severity;
+        self
+    }
+
+    /// Whether the logs attached to a span are also forwarded to the inner
+    /// processor, `true` by default.
+    pub fn with_attached_logs_exported(mut self, export_attached_logs: bool) -> Self {
+        self.export_attached_logs = export_attached_logs;
+        self
+    }
+
+    /// Adds `record` as an event of the current span, returning `false` if it
+    /// is not the span of the record or is no longer recording.
+    fn attach(&self, record: &SdkLogRecord) -> bool {
+        let Some(trace_context) = record.trace_context() else {
+            return false;
+        };
+        if record.severity_number().unwrap_or(Severity::Trace) < self.min_severity {
+            return false;
+        }
+        Context::map_current(|cx| {
+            let span = cx.span();
+            let span_context = span.span_context();
+            if !span.is_recording()
+                || span_context.trace_id() != trace_context.trace_id
+                || span_context.span_id() != trace_context.span_id
+            {
+                return false;
+            }
+            let timestamp = record
+                .timestamp()
+                .or_else(|| record.observed_timestamp())
+                .unwrap_or_else(opentelemetry::time::now);
+            span.add_event_with_timestamp(
+                record.event_name().unwrap_or(DEFAULT_EVENT_NAME),
+                timestamp,
+                event_attributes(record),
+            );
+            true
+        })
+    }
+}
+
+fn event_attributes(record: &SdkLogRecord) -> Vec<KeyValue> {
+    let mut attributes = Vec::with_capacity(record.attributes_len() + 3);
+    if let Some(body) = record.body() {
+        attributes.push(KeyValue::new(MESSAGE, to_value(body)));
+    }
+    attributes.extend(
+        record
+            .attributes_iter()
+            .map(|(key, value)| KeyValue::new(key.clone(), to_value(value))),
+    );
+    if let Some(severity_text) = record.severity_text() {
+        attributes.push(KeyValue::new(SEVERITY_TEXT, severity_text));
+    }
+    if let Some(severity_number) = record.severity_number() {
+        attributes.push(KeyValue::new(SEVERITY_NUMBER, severity_number as i64));
+    }
+    attributes
+}
+
+/// Lists of integers, doubles, booleans or strings are recorded as arrays.
+/// Span attributes cannot hold bytes, empty lists, lists of mixed types or
+/// maps, those are recorded as JSON, with bytes encoded in base64 and the keys
+/// of maps sorted.
+fn to_value(value: &AnyValue) -> Value {
+    match value {
+        AnyValue::Int(i) => Value::I64(*i),
+        AnyValue::Double(d) => Value::F64(*d),
+        AnyValue::String(s) => Value::String(s.clone()),
+        AnyValue::Boolean(b) => Value::Bool(*b),
+        AnyValue::ListAny(list) => match to_array(list) {
+            Some(array) => Value::Array(array),
+            None => Value::String(to_json(value).into()),
+        },
+        other => Value::String(to_json(other).into()),
+    }
+}
+
+fn to_json(value: &AnyValue) -> String {
+    let mut json = String::new();
+    write_json(value, &mut json);
+    json
+}
+
+fn write_json(value: &AnyValue, json: &mut String) {
+    match value {
+        AnyValue::Int(i) => {
+            let _ = write!(json, "{i}");
+        }
+        // JSON has no representation for NaN and infinities.
+        AnyValue::Double(d) if d.is_finite() => {
+            let _ = write!(json, "{d}");
+        }
+        AnyValue::Double(d) => write_json_string(&d.to_string(), json),
+        AnyValue::String(s) => write_json_string(s.as_str(), json),
+        AnyValue::Boolean(b) => {
+            let _ = write!(json, "{b}");
+        }
+        AnyValue::Bytes(bytes) => write_json_string(&to_base64(bytes), json),
+        AnyValue::ListAny(list) => {
+            json.push('[');
+            for (i, value) in list.iter().enumerate() {
+                if i > 0 {
+                    json.push(',');
+                }
+                write_json(value, json);
+            }
+            json.push(']');
+        }
+        AnyValue::Map(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_unstable_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+            json.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    json.push(',');
+                }
+                write_json_string(key.as_str(), json);
+                json.push(':');
+                write_json(value, json);
+            }
+            json.push('}');
+        }
+        _ => json.push_str("null"),
+    }
+}
+
+fn write_json_string(s: &str, json: &mut String) {
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+fn to_base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | u32::from(*b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i)) as usize & 0x3f] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Converts `list` to an array if all its values are of the same primitive
+/// type, the type of its first value.
+fn to_array(list: &[AnyValue]) -> Option<Array> {
+    fn collect<T>(list: &[AnyValue], f: impl Fn(&AnyValue) -> Option<T>) -> Option<Vec<T>> {
+        list.iter().map(f).collect()
+    }
+
+    match list.first()? {
+        AnyValue::Int(_) => collect(list, |v| match v {
+            AnyValue::Int(i) => Some(*i),
+            _ => None,
+        })
+        .map(Array::I64),
+        AnyValue::Double(_) => collect(list, |v| match v {
+            AnyValue::Double(d) => Some(*d),
+            _ => None,
+        })
+        .map(Array::F64),
+        AnyValue::Boolean(_) => collect(list, |v| match v {
+            AnyValue::Boolean(b) => Some(*b),
+            _ => None,
+        })
+        .map(Array::Bool),
+        AnyValue::String(_) => collect(list, |v| match v {
+            AnyValue::String(s) => Some(s.clone()),
+            _ => None,
+        })
+        .map(Array::String),
+        _ => None,
+    }
+}
+
+impl<P: LogProcessor> LogProcessor for SpanEventLogProcessor<P> {
+    fn emit(&self, data: &mut SdkLogRecord, instrumentation: &InstrumentationScope) {
+        if !self.attach(data) || self.export_attached_logs {
+            self.inner.emit(data, instrumentation);
+        }
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.inner.force_flush()
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        self.inner.shutdown_with_timeout(timeout)
+    }
+
+    #[inline]
+    fn event_enabled(&self, level: Severity, target: &str, name: Option<&str>) -> bool {
+        level >= self.min_severity || self.inner.event_enabled(level, target, name)
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource);
+    }
+
+    #[cfg(feature = "experimental_self_observability")]
+    fn set_meter(&mut self, meter: &opentelemetry::metrics::Meter) {
+        self.inner.set_meter(meter);
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::logs::{InMemoryLogExporter, SdkLoggerProvider, SimpleLogProcessor};
+    use crate::trace::{InMemorySpanExporter, SdkTracerProvider};
+    use opentelemetry::logs::{LogRecord, Logger, LoggerProvider};
+    use opentelemetry::trace::{Tracer, TracerProvider};
+
+    fn emit_logs(logger_provider: &SdkLoggerProvider, tracer_provider: &SdkTracerProvider) {
+        let logger = logger_provider.logger("test-logger");
+        let emit = |severity: Severity| {
+            let mut record = logger.create_log_record();
+            record.set_event_name("checkout");
+            record.set_severity_number(severity);
+            record.set_severity_text(severity.name());
+            record.set_body("order placed".into());
+            record.add_attribute("order.id", 42);
+            record.add_attribute(
+                "items",
+                AnyValue::ListAny(Box::new(vec![1.into(), 2.into()])),
+            );
+            record.add_attribute(
+                "tags",
+                AnyValue::ListAny(Box::new(vec![1.into(), "new".into()])),
+            );
+            record.add_attribute(
+                "details",
+                AnyValue::Map(Box::new(
+                    [
+                        ("status".into(), "new".into()),
+                        ("payload".into(), AnyValue::from(&b"otel"[..])),
+                        ("note".into(), "say \"hi\"\n".into()),
+                    ]
+                    .into(),
+                )),
+            );
+            logger.emit(record);
+        };
+
+        emit(Severity::Error);
+        tracer_provider
+            .tracer("test-tracer")
+            .in_span("span", |_cx| {
+                emit(Severity::Debug);
+                emit(Severity::Error);
+            });
+    }
+
+    #[test]
+    fn attaches_logs_to_current_span() {
+        let log_exporter = InMemoryLogExporter::default();
+        let span_exporter = InMemorySpanExporter::default();
+        let tracer_provider = SdkTracerProvider::builder()
+            .with_simple_exporter(span_exporter.clone())
+            .build();
+        let logger_provider = SdkLoggerProvider::builder()
+            .with_log_processor(
+                SpanEventLogProcessor::new(SimpleLogProcessor::new(log_exporter.clone()))
+                    .with_min_severity(Severity::Info),
+            )
+            .build();
+        emit_logs(&logger_provider, &tracer_provider);
+
+        let spans = span_exporter.get_finished_spans().unwrap();
+        assert_eq!(spans.len(), 1);
+        let events = &spans[0].events.events;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].name, "checkout");
+        assert_eq!(
+            events[0].attributes,
+            vec![
+                KeyValue::new("message", "order placed"),
+                KeyValue::new("order.id", 42),
+                KeyValue::new("items", Value::Array(vec![1, 2].into())),
+                KeyValue::new("tags", r#"[1,"new"]"#),
+                KeyValue::new(
+                    "details",
+                    r#"{"note":"say \"hi\"\n","payload":"b3RlbA==","status":"new"}"#
+                ),
+                KeyValue::new("severity_text", "ERROR"),
+                KeyValue::new("severity_number", 17),
+            ]
+        );
+        assert_eq!(log_exporter.get_emitted_logs().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn exports_only_unattached_logs() {
+        let log_exporter = InMemoryLogExporter::default();
+        let span_exporter = InMemorySpanExporter::default();
+        let tracer_provider = SdkTracerProvider::builder()
+            .with_simple_exporter(span_exporter.clone())
+            .build();
+        let logger_provider = SdkLoggerProvider::builder()
+            .with_log_processor(
+                SpanEventLogProcessor::new(SimpleLogProcessor::new(log_exporter.clone()))
+                    .with_min_severity(Severity::Info)
+                    .with_attached_logs_exported(false),
+            )
+            .build();
+        emit_logs(&logger_provider, &tracer_provider);
+
+        let spans = span_exporter.get_finished_spans().unwrap();
+        assert_eq!(spans[0].events.len(), 1);
+        let emitted = log_exporter.get_emitted_logs().unwrap();
+        assert_eq!(emitted.len(), 2);
+        assert!(emitted[0].record.trace_context().is_none());
+        assert_eq!(emitted[1].record.severity_number(), Some(Severity::Debug));
+    }
+}
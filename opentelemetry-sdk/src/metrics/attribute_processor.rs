@@ -0,0 +1,224 @@
+use std::{fmt, sync::Arc};
+
+use opentelemetry::{Key, KeyValue, Value};
+
+type ValueMapper = Arc<dyn Fn(&Value) -> Value + Send + Sync>;
+type ValuePredicate = Arc<dyn Fn(&Value) -> bool + Send + Sync>;
+
+/// Rewrites the attributes of the measurements of a metric stream before they
+/// are aggregated.
+///
+/// Rewriting happens before the attribute set is looked up in the aggregation,
+/// so replacing high-cardinality values with a coarser value reduces the
+/// number of data points the stream keeps in memory. Operations are applied in
+/// the order they were added, after the attributes not allowed by
+/// [StreamBuilder::with_allowed_attribute_keys] were removed.
+///
+/// # Example
+///
+/// ```
+/// use opentelemetry::{KeyValue, Value};
+/// use opentelemetry_sdk::metrics::{AttributeProcessor, Instrument, SdkMeterProvider, Stream};
+///
+/// let view = |i: &Instrument| {
+///     if i.name() == "http.server.request.duration" {
+///         let processor = AttributeProcessor::new()
+///             // Replace user ids in routes with a placeholder.
+///             .map_value("http.route", |route| {
+///                 let route = route.as_str();
+///                 match route.strip_prefix("/users/") {
+///                     Some(_) => Value::from("/users/{id}"),
+///                     None => Value::from(route.into_owned()),
+///                 }
+///             })
+///             .rename_key("status", "http.response.status_code")
+///             .drop_if("user_agent", |_| true)
+///             .insert(KeyValue::new("deployment.environment", "production"));
+///         Stream::builder()
+///             .with_attribute_processor(processor)
+///             .build()
+///             .ok()
+///     } else {
+///         None
+///     }
+/// };
+///
+/// let provider = SdkMeterProvider::builder().with_view(view).build();
+/// # drop(provider);
+/// ```
+///
+/// [StreamBuilder::with_allowed_attribute_keys]: crate::metrics::StreamBuilder::with_allowed_attribute_keys
+#[derive(Clone, Default)]
+pub struct AttributeProcessor {
+    operations: Vec<Operation>,
+}
+
+#[derive(Clone)]
+enum Operation {
+    Rename { from: Key, to: Key },
+    MapValue { key: Key, map: ValueMapper },
+    DropIf { key: Key, predicate: ValuePredicate },
+    Insert(KeyValue),
+}
+
+impl AttributeProcessor {
+    /// Create a processor that leaves attributes unchanged.
+    pub fn new() -> Self {
+        AttributeProcessor::default()
+    }
+
+    /// Renames the attribute `from` to `to`.
+    ///
+    /// An attribute already recorded with the key `to` is replaced.
+    pub fn rename_key(mut self, from: impl Into<Key>, to: impl Into<Key>) -> Self {
+        self.operations.push(Operation::Rename {
+            from: from.into(),
+            to: to.into(),
+        });
+        self
+    }
+
+    /// Replaces the value of the attribute `key` with the value returned by
+    /// `map`, e.g. to group high-cardinality values into buckets.
+    pub fn map_value<F>(mut self, key: impl Into<Key>, map: F) -> Self
+    where
+        F: Fn(&Value) -> Value + Send + Sync + 'static,
+    {
+        self.operations.push(Operation::MapValue {
+            key: key.into(),
+            map: Arc::new(map),
+        });
+        self
+    }
+
+    /// Removes the attribute `key` if `predicate` returns `true` for its value.
+    ///
+    /// The measurement itself is still recorded.
+    pub fn drop_if<F>(mut self, key: impl Into<Key>, predicate: F) -> Self
+    where
+        F: Fn(&Value) -> bool + Send + Sync + 'static,
+    {
+        self.operations.push(Operation::DropIf {
+            key: key.into(),
+            predicate: Arc::new(predicate),
+        });
+        self
+    }
+
+    /// Adds `attribute` to every measurement of the stream, replacing a
+    /// recorded attribute with the same key.
+    pub fn insert(mut self, attribute: KeyValue) -> Self {
+        self.operations.push(Operation::Insert(attribute));
+        self
+    }
+
+    /// Applies the operations of the processor to `attrs`.
+    pub(crate) fn process(&self, attrs: &mut Vec<KeyValue>) {
+        for operation in &self.operations {
+            match operation {
+                Operation::Rename { from, to } => {
+                    if let Some(index) = attrs.iter().position(|kv| &kv.key == from) {
+                        let value = attrs.remove(index).value;
+                        upsert(attrs, KeyValue::new(to.clone(), value));
+                    }
+                }
+                Operation::MapValue { key, map } => {
+                    for kv in attrs.iter_mut().filter(|kv| &kv.key == key) {
+                        kv.value = map(&kv.value);
+                    }
+                }
+                Operation::DropIf { key, predicate } => {
+                    attrs.retain(|kv| &kv.key != key || !predicate(&kv.value));
+                }
+                Operation::Insert(attribute) => upsert(attrs, attribute.clone()),
+            }
+        }
+    }
+}
+
+/// Sets `attribute`, replacing any attribute with the same key.
+fn upsert(attrs: &mut Vec<KeyValue>, attribute: KeyValue) {
+    match attrs.iter_mut().find(|kv| kv.key == attribute.key) {
+        Some(kv) => kv.value = attribute.value,
+        None => attrs.push(attribute),
+    }
+}
+
+impl fmt::Debug for AttributeProcessor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.operations).finish()
+    }
+}
+
+impl fmt::Debug for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operation::Rename { from, to } => f
+                .debug_struct("Rename")
+                .field("from", from)
+                .field("to", to)
+                .finish(),
+            Operation::MapValue { key, .. } => {
+                f.debug_struct("MapValue").field("key", key).finish()
+            }
+            Operation::DropIf { key, .. } => f.debug_struct("DropIf").field("key", key).finish(),
+            Operation::Insert(attribute) => f.debug_tuple("Insert").field(attribute).finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operations_apply_in_order() {
+        let processor = AttributeProcessor::new()
+            .rename_key("status", "http.response.status_code")
+            .map_value("http.route", |route| {
+                if route.as_str().starts_with("/users/") {
+                    "/users/{id}".into()
+                } else {
+                    route.clone()
+                }
+            })
+            .drop_if("http.response.status_code", |code| code.as_str() == "200")
+            .insert(KeyValue::new("env", "prod"));
+
+        let mut attrs = vec![
+            KeyValue::new("http.route", "/users/42"),
+            KeyValue::new("status", "200"),
+            KeyValue::new("env", "dev"),
+        ];
+        processor.process(&mut attrs);
+        assert_eq!(
+            attrs,
+            vec![
+                KeyValue::new("http.route", "/users/{id}"),
+                KeyValue::new("env", "prod"),
+            ]
+        );
+
+        let mut attrs = vec![
+            KeyValue::new("http.route", "/health"),
+            KeyValue::new("status", "503"),
+        ];
+        processor.process(&mut attrs);
+        assert_eq!(
+            attrs,
+            vec![
+                KeyValue::new("http.route", "/health"),
+                KeyValue::new("http.response.status_code", "503"),
+                KeyValue::new("env", "prod"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rename_replaces_existing_key() {
+        let processor = AttributeProcessor::new().rename_key("a", "b");
+        let mut attrs = vec![KeyValue::new("b", 1), KeyValue::new("a", 2)];
+        processor.process(&mut attrs);
+        assert_eq!(attrs, vec![KeyValue::new("b", 2)]);
+    }
+}
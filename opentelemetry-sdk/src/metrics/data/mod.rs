@@ -168,6 +168,9 @@ pub struct GaugeDataPoint<T> {
     /// Attributes is the set of key value pairs that uniquely identify the
     /// time series.
     pub(crate) attributes: Vec<KeyValue>,
+    /// The time the time series started, when it differs from the start time
+    /// of the metric.
+    pub(crate) start_time: Option<SystemTime>,
     /// The value of this data point.
     pub(crate) value: T,
     /// The sampled [Exemplar]s collected during the time series.
@@ -180,6 +183,15 @@ impl<T> GaugeDataPoint<T> {
         self.attributes.iter()
     }
 
+    /// Returns the time when the time series was started, if it differs from
+    /// the start time of the metric.
+    ///
+    /// This is the case for time series of streams that evict idle attribute
+    /// sets, which start over when their attribute set is recorded again.
+    pub fn start_time(&self) -> Option<SystemTime> {
+        self.start_time
+    }
+
     /// Returns an iterator over the [Exemplar]s in [GaugeDataPoint].
     pub fn exemplars(&self) -> impl Iterator<Item = &Exemplar<T>> {
         self.exemplars.iter()
@@ -227,6 +239,9 @@ pub struct SumDataPoint<T> {
     /// Attributes is the set of key value pairs that uniquely identify the
     /// time series.
     pub(crate) attributes: Vec<KeyValue>,
+    /// The time the time series started, when it differs from the start time
+    /// of the metric.
+    pub(crate) start_time: Option<SystemTime>,
     /// The value of this data point.
     pub(crate) value: T,
     /// The sampled [Exemplar]s collected during the time series.
@@ -239,6 +254,15 @@ impl<T> SumDataPoint<T> {
         self.attributes.iter()
     }
 
+    /// Returns the time when the time series was started, if it differs from
+    /// the start time of the metric.
+    ///
+    /// This is the case for time series of streams that evict idle attribute
+    /// sets, which start over when their attribute set is recorded again.
+    pub fn start_time(&self) -> Option<SystemTime> {
+        self.start_time
+    }
+
     /// Returns an iterator over the [Exemplar]s in [SumDataPoint].
     pub fn exemplars(&self) -> impl Iterator<Item = &Exemplar<T>> {
         self.exemplars.iter()
@@ -338,6 +362,9 @@ impl<T> Histogram<T> {
 pub struct HistogramDataPoint<T> {
     /// The set of key value pairs that uniquely identify the time series.
     pub(crate) attributes: Vec<KeyValue>,
+    /// The time the time series started, when it differs from the start time
+    /// of the metric.
+    pub(crate) start_time: Option<SystemTime>,
     /// The number of updates this histogram has been calculated with.
     pub(crate) count: u64,
     /// The upper bounds of the buckets of the histogram.
@@ -364,6 +391,15 @@ impl<T> HistogramDataPoint<T> {
         self.attributes.iter()
     }
 
+    /// Returns the time when the time series was started, if it differs from
+    /// the start time of the metric.
+    ///
+    /// This is the case for time series of streams that evict idle attribute
+    /// sets, which start over when their attribute set is recorded again.
+    pub fn start_time(&self) -> Option<SystemTime> {
+        self.start_time
+    }
+
     /// Returns an iterator over the exemplars in [HistogramDataPoint].
     pub fn exemplars(&self) -> impl Iterator<Item = &Exemplar<T>> {
         self.exemplars.iter()
@@ -444,6 +480,9 @@ impl<T> ExponentialHistogram<T> {
 pub struct ExponentialHistogramDataPoint<T> {
     /// The set of key value pairs that uniquely identify the time series.
     pub(crate) attributes: Vec<KeyValue>,
+    /// The time the time series started, when it differs from the start time
+    /// of the metric.
+    pub(crate) start_time: Option<SystemTime>,
 
     /// The number of updates this histogram has been calculated with.
     pub(crate) count: usize,
@@ -490,6 +529,15 @@ impl<T> ExponentialHistogramDataPoint<T> {
         self.attributes.iter()
     }
 
+    /// Returns the time when the time series was started, if it differs from
+    /// the start time of the metric.
+    ///
+    /// This is the case for time series of streams that evict idle attribute
+    /// sets, which start over when their attribute set is recorded again.
+    pub fn start_time(&self) -> Option<SystemTime> {
+        self.start_time
+    }
+
     /// Returns an iterator over the exemplars in [ExponentialHistogramDataPoint].
     pub fn exemplars(&self) -> impl Iterator<Item = &Exemplar<T>> {
         self.exemplars.iter()
@@ -622,6 +670,7 @@ mod tests {
     fn validate_cloning_data_points() {
         let data_type = SumDataPoint {
             attributes: vec![KeyValue::new("key", "value")],
+            start_time: None,
             value: 0u32,
             exemplars: vec![Exemplar {
                 filtered_attributes: vec![],
@@ -635,6 +684,7 @@ mod tests {
 
         let histogram_data_point = HistogramDataPoint {
             attributes: vec![KeyValue::new("key", "value")],
+            start_time: None,
             count: 0,
             bounds: vec![],
             bucket_counts: vec![],
@@ -653,6 +703,7 @@ mod tests {
 
         let exponential_histogram_data_point = ExponentialHistogramDataPoint {
             attributes: vec![KeyValue::new("key", "value")],
+            start_time: None,
             count: 0,
             min: None,
             max: None,
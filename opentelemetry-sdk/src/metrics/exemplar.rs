@@ -0,0 +1,349 @@
+//! Exemplar filters and reservoirs.
+//!
+//! Exemplars are sampled measurements that link an aggregated data point back
+//! to the trace that was active when the measurement was recorded. Which
+//! measurements are eligible is decided by the [ExemplarFilter], and which of
+//! the eligible measurements are kept is decided by an exemplar reservoir
+//! attached to each attribute set of a metric stream, see
+//! [ExemplarReservoir].
+use std::cell::RefCell;
+use std::env;
+use std::fmt;
+
+use opentelemetry::{otel_warn, time::now, KeyValue};
+use rand::{rngs, Rng, SeedableRng};
+
+use super::data::Exemplar;
+use super::internal::{AttributeSetFilter, Number};
+
+/// The environment variable used to select the [ExemplarFilter].
+const OTEL_METRICS_EXEMPLAR_FILTER: &str = "OTEL_METRICS_EXEMPLAR_FILTER";
+
+/// Determines which measurements are eligible to become [Exemplar]s.
+///
+/// See the [specification] for details.
+///
+/// [specification]: https://github.com/open-telemetry/opentelemetry-specification/blob/v1.41.0/specification/metrics/sdk.md#exemplarfilter
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ExemplarFilter {
+    /// Makes all measurements eligible for being an exemplar.
+    AlwaysOn,
+    /// Makes no measurements eligible for being an exemplar.
+    ///
+    /// This disables exemplar sampling and its overhead entirely.
+    AlwaysOff,
+    /// Makes measurements recorded in the context of a sampled span eligible
+    /// for being an exemplar.
+    ///
+    /// Requires the `trace` feature, without it no measurement is eligible.
+    #[default]
+    TraceBased,
+}
+
+impl ExemplarFilter {
+    /// Reads the filter from the `OTEL_METRICS_EXEMPLAR_FILTER` environment
+    /// variable, falling back to [ExemplarFilter::TraceBased] if it is unset or
+    /// invalid.
+    pub(crate) fn from_env() -> Self {
+        match env::var(OTEL_METRICS_EXEMPLAR_FILTER) {
+            Ok(value) => match value.as_str() {
+                "always_on" => ExemplarFilter::AlwaysOn,
+                "always_off" => ExemplarFilter::AlwaysOff,
+                "trace_based" => ExemplarFilter::TraceBased,
+                s => {
+                    otel_warn!(
+                        name: "MeterProvider.Config.InvalidExemplarFilter",
+                        message = format!(
+                            "Unrecognized exemplar filter '{}' in OTEL_METRICS_EXEMPLAR_FILTER environment variable. Valid values are: always_on, always_off, trace_based. Using fallback filter: trace_based",
+                            s
+                        ),
+                    );
+                    ExemplarFilter::TraceBased
+                }
+            },
+            Err(_) => ExemplarFilter::TraceBased,
+        }
+    }
+}
+
+/// A measurement that passed the [ExemplarFilter], offered to an
+/// [ExemplarReservoir].
+///
+/// The [Exemplar] is only built, with its timestamp and filtered attributes,
+/// once the reservoir retains the measurement with
+/// [ExemplarMeasurement::into_exemplar].
+pub struct ExemplarMeasurement<'a, T> {
+    value: T,
+    attributes: &'a [KeyValue],
+    attribute_filter: &'a AttributeSetFilter,
+    span_id: [u8; 8],
+    trace_id: [u8; 16],
+}
+
+impl<'a, T: Copy> ExemplarMeasurement<'a, T> {
+    pub(crate) fn new(
+        value: T,
+        attributes: &'a [KeyValue],
+        attribute_filter: &'a AttributeSetFilter,
+        span_id: [u8; 8],
+        trace_id: [u8; 16],
+    ) -> Self {
+        ExemplarMeasurement {
+            value,
+            attributes,
+            attribute_filter,
+            span_id,
+            trace_id,
+        }
+    }
+
+    /// The measured value.
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    /// Builds the [Exemplar] of the measurement, timestamped now.
+    pub fn into_exemplar(self) -> Exemplar<T> {
+        Exemplar {
+            filtered_attributes: self.attribute_filter.dropped(self.attributes),
+            time: now(),
+            value: self.value,
+            span_id: self.span_id,
+            trace_id: self.trace_id,
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ExemplarMeasurement<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExemplarMeasurement")
+            .field("value", &self.value)
+            .field("span_id", &self.span_id)
+            .field("trace_id", &self.trace_id)
+            .finish()
+    }
+}
+
+/// Stores the [Exemplar]s sampled from the measurements of a single attribute
+/// set of a metric stream.
+///
+/// Each attribute set of a stream gets its own reservoir, created by the
+/// [ExemplarReservoirFactory] configured on the stream with
+/// [StreamBuilder::with_exemplar_reservoir]. Reservoirs only see the
+/// measurements that passed the [ExemplarFilter].
+///
+/// [StreamBuilder::with_exemplar_reservoir]: crate::metrics::StreamBuilder::with_exemplar_reservoir
+pub trait ExemplarReservoir<T>: Send + Sync {
+    /// Offers a measurement that passed the [ExemplarFilter], the reservoir
+    /// decides whether it is retained.
+    fn offer(&mut self, measurement: ExemplarMeasurement<'_, T>);
+
+    /// Moves the retained exemplars into `dest` and starts a new sampling
+    /// period.
+    ///
+    /// This is called once per collection cycle.
+    fn collect(&mut self, dest: &mut Vec<Exemplar<T>>);
+}
+
+/// Creates the [ExemplarReservoir]s of a metric stream.
+///
+/// # Examples
+///
+/// A reservoir keeping the largest measurements, e.g. the slowest requests of a
+/// latency histogram:
+///
+/// ```
+/// use opentelemetry_sdk::metrics::data::Exemplar;
+/// use opentelemetry_sdk::metrics::{
+///     ExemplarMeasurement, ExemplarReservoir, ExemplarReservoirFactory,
+/// };
+///
+/// struct Largest<T> {
+///     size: usize,
+///     exemplars: Vec<Exemplar<T>>,
+/// }
+///
+/// impl<T: Copy + PartialOrd + Send + Sync> ExemplarReservoir<T> for Largest<T> {
+///     fn offer(&mut self, measurement: ExemplarMeasurement<'_, T>) {
+///         if self.exemplars.len() == self.size
+///             && self.exemplars.iter().all(|e| e.value >= measurement.value())
+///         {
+///             return;
+///         }
+///         self.exemplars.push(measurement.into_exemplar());
+///         self.exemplars
+///             .sort_by(|a, b| b.value.partial_cmp(&a.value).unwrap_or(std::cmp::Ordering::Equal));
+///         self.exemplars.truncate(self.size);
+///     }
+///
+///     fn collect(&mut self, dest: &mut Vec<Exemplar<T>>) {
+///         dest.append(&mut self.exemplars);
+///     }
+/// }
+///
+/// struct LargestN(usize);
+///
+/// impl ExemplarReservoirFactory for LargestN {
+///     fn create<T>(&self) -> Box<dyn ExemplarReservoir<T>>
+///     where
+///         T: Copy + PartialOrd + std::fmt::Debug + Send + Sync + 'static,
+///     {
+///         Box::new(Largest {
+///             size: self.0,
+///             exemplars: Vec::new(),
+///         })
+///     }
+/// }
+/// ```
+pub trait ExemplarReservoirFactory: Send + Sync + 'static {
+    /// Creates a new, empty reservoir for an attribute set.
+    fn create<T>(&self) -> Box<dyn ExemplarReservoir<T>>
+    where
+        T: Copy + PartialOrd + fmt::Debug + Send + Sync + 'static;
+}
+
+/// A reservoir that keeps a uniformly random sample of at most `size`
+/// measurements per collection cycle, using [reservoir sampling].
+///
+/// [reservoir sampling]: https://en.wikipedia.org/wiki/Reservoir_sampling
+#[derive(Debug)]
+pub(crate) struct SimpleFixedSizeExemplarReservoir<T> {
+    storage: Vec<Exemplar<T>>,
+    size: usize,
+    num_measurements: usize,
+}
+
+impl<T> SimpleFixedSizeExemplarReservoir<T> {
+    /// Creates a reservoir holding up to `size` exemplars.
+    pub(crate) fn new(size: usize) -> Self {
+        SimpleFixedSizeExemplarReservoir {
+            storage: Vec::new(),
+            size,
+            num_measurements: 0,
+        }
+    }
+}
+
+impl<T: Copy + Send + Sync> ExemplarReservoir<T> for SimpleFixedSizeExemplarReservoir<T> {
+    fn offer(&mut self, measurement: ExemplarMeasurement<'_, T>) {
+        if self.storage.len() < self.size {
+            self.storage.push(measurement.into_exemplar());
+        } else {
+            let index =
+                CURRENT_RNG.with(|rng| rng.borrow_mut().random_range(0..=self.num_measurements));
+            if let Some(slot) = self.storage.get_mut(index) {
+                *slot = measurement.into_exemplar();
+            }
+        }
+        self.num_measurements += 1;
+    }
+
+    fn collect(&mut self, dest: &mut Vec<Exemplar<T>>) {
+        dest.append(&mut self.storage);
+        self.num_measurements = 0;
+    }
+}
+
+/// A reservoir that keeps the most recent measurement for each bucket of an
+/// explicit bucket histogram.
+#[derive(Debug)]
+pub(crate) struct AlignedHistogramBucketExemplarReservoir<T> {
+    bounds: Vec<f64>,
+    storage: Vec<Option<Exemplar<T>>>,
+}
+
+impl<T> AlignedHistogramBucketExemplarReservoir<T> {
+    /// Creates a reservoir with one slot per bucket delimited by `bounds`.
+    ///
+    /// `bounds` must be sorted, as they are for the histogram aggregation.
+    pub(crate) fn new(bounds: Vec<f64>) -> Self {
+        let storage = std::iter::repeat_with(|| None)
+            .take(bounds.len() + 1)
+            .collect();
+        AlignedHistogramBucketExemplarReservoir { bounds, storage }
+    }
+}
+
+impl<T: Number> ExemplarReservoir<T> for AlignedHistogramBucketExemplarReservoir<T> {
+    fn offer(&mut self, measurement: ExemplarMeasurement<'_, T>) {
+        let value = measurement.value().into_float();
+        // Same bucket selection as the histogram aggregation itself.
+        let index = self.bounds.partition_point(|&x| x < value);
+        self.storage[index] = Some(measurement.into_exemplar());
+    }
+
+    fn collect(&mut self, dest: &mut Vec<Exemplar<T>>) {
+        dest.extend(self.storage.iter_mut().filter_map(Option::take));
+    }
+}
+
+thread_local! {
+    /// Store random number generator for each thread
+    static CURRENT_RNG: RefCell<rngs::SmallRng> = RefCell::new(rngs::SmallRng::from_os_rng());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measurement(value: f64, filter: &AttributeSetFilter) -> ExemplarMeasurement<'_, f64> {
+        ExemplarMeasurement::new(value, &[], filter, [0; 8], [0; 16])
+    }
+
+    #[test]
+    fn simple_fixed_size_reservoir_is_bounded() {
+        let filter = AttributeSetFilter::new(None);
+        let mut reservoir = SimpleFixedSizeExemplarReservoir::new(2);
+        for v in 0..100 {
+            reservoir.offer(measurement(v as f64, &filter));
+        }
+
+        let mut collected = vec![];
+        reservoir.collect(&mut collected);
+        assert_eq!(collected.len(), 2);
+
+        // Collecting starts a new sampling period.
+        let mut collected = vec![];
+        reservoir.collect(&mut collected);
+        assert!(collected.is_empty());
+
+        reservoir.offer(measurement(7.0, &filter));
+        reservoir.collect(&mut collected);
+        assert_eq!(collected.len(), 1);
+        assert_eq!(collected[0].value, 7.0);
+    }
+
+    #[test]
+    fn aligned_histogram_reservoir_keeps_last_per_bucket() {
+        let filter = AttributeSetFilter::new(None);
+        let mut reservoir = AlignedHistogramBucketExemplarReservoir::new(vec![1.0, 10.0]);
+        for v in [0.5, 0.7, 5.0, 100.0, 200.0] {
+            reservoir.offer(measurement(v, &filter));
+        }
+
+        let mut collected = vec![];
+        reservoir.collect(&mut collected);
+        let values: Vec<f64> = collected.iter().map(|e| e.value).collect();
+        assert_eq!(values, vec![0.7, 5.0, 200.0]);
+
+        let mut collected = vec![];
+        reservoir.collect(&mut collected);
+        assert!(collected.is_empty());
+    }
+
+    #[test]
+    fn exemplar_filter_from_env() {
+        for (value, expected) in [
+            (Some("always_on"), ExemplarFilter::AlwaysOn),
+            (Some("always_off"), ExemplarFilter::AlwaysOff),
+            (Some("trace_based"), ExemplarFilter::TraceBased),
+            (Some("unknown"), ExemplarFilter::TraceBased),
+            (None, ExemplarFilter::TraceBased),
+        ] {
+            temp_env::with_var(OTEL_METRICS_EXEMPLAR_FILTER, value, || {
+                assert_eq!(ExemplarFilter::from_env(), expected);
+            });
+        }
+    }
+}
@@ -1,11 +1,13 @@
 //! Interfaces for exporting metrics
 
 use crate::error::OTelSdkResult;
+#[cfg(feature = "experimental_self_observability")]
+use opentelemetry::metrics::Meter;
 use std::time::Duration;
 
 use crate::metrics::data::ResourceMetrics;
 
-use super::Temporality;
+use super::{aggregation::Aggregation, InstrumentKind, Temporality};
 
 /// Exporter handles the delivery of metric data to external receivers.
 ///
@@ -37,4 +39,23 @@ pub trait PushMetricExporter: Send + Sync + 'static {
 
     /// Access the [Temporality] of the MetricExporter.
     fn temporality(&self) -> Temporality;
+
+    /// The default [Aggregation] of the MetricExporter, a function of
+    /// instrument kind.
+    ///
+    /// See [MetricReader::aggregation] for details.
+    ///
+    /// [MetricReader::aggregation]: crate::metrics::reader::MetricReader::aggregation
+    fn aggregation(&self, _kind: InstrumentKind) -> Aggregation {
+        Aggregation::Default
+    }
+
+    /// Set the meter the exporter reports its
+    /// [self-observability](crate::self_observability) metrics with, e.g.
+    /// using [ExporterMetrics](crate::self_observability::ExporterMetrics).
+    ///
+    /// Called by the reader of the exporter, which may already share it, hence
+    /// the shared reference.
+    #[cfg(feature = "experimental_self_observability")]
+    fn set_meter(&self, _meter: &Meter) {}
 }
@@ -1,11 +1,17 @@
 use std::{borrow::Cow, collections::HashSet, error::Error, sync::Arc};
 
 use opentelemetry::{
-    metrics::{AsyncInstrument, SyncInstrument},
+    metrics::{AsyncInstrument, BoundAttributes, SyncInstrument},
     InstrumentationScope, Key, KeyValue,
 };
 
-use crate::metrics::{aggregation::Aggregation, internal::Measure};
+#[cfg(feature = "spec_unstable_metrics_views")]
+use crate::metrics::attribute_processor::AttributeProcessor;
+use crate::metrics::{
+    aggregation::Aggregation,
+    exemplar::ExemplarReservoirFactory,
+    internal::{Measure, StreamReservoirFactory},
+};
 
 use super::meter::{
     INSTRUMENT_NAME_EMPTY, INSTRUMENT_NAME_FIRST_ALPHABETIC, INSTRUMENT_NAME_INVALID_CHAR,
@@ -129,6 +135,45 @@ impl Instrument {
     }
 }
 
+/// What a metric stream does with measurements of new attribute sets once it
+/// reached its cardinality limit.
+///
+/// See [StreamBuilder::with_cardinality_limit_strategy].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CardinalityLimitStrategy {
+    /// Aggregates the measurements of all new attribute sets into a single
+    /// data point with the `otel.metric.overflow=true` attribute.
+    #[default]
+    Overflow,
+    /// Evicts the least recently updated attribute set to make room for the new
+    /// one. Evictions are done in batches of a tenth of the cardinality limit,
+    /// so the following new attribute sets are admitted without searching the
+    /// stream again.
+    ///
+    /// Evicted attribute sets are still exported at the next collection with
+    /// the measurements they received so far, so no data is lost. Once as many
+    /// attribute sets as the cardinality limit were evicted since the last
+    /// collection, new attribute sets overflow.
+    ///
+    /// Only applies to streams with delta temporality, other streams overflow.
+    EvictLeastRecentlyUpdated,
+    /// Drops the measurements of new attribute sets.
+    ///
+    /// The dropped measurements are counted by the
+    /// `otel.sdk.metric.cardinality_limit.rejected` counter, reported under the
+    /// instrumentation scope of the SDK with the scope name and the stream name
+    /// of the stream as `otel.scope.name` and `otel.metric.name` attributes.
+    Reject,
+    /// Exports only the `k` attribute sets with the largest absolute values,
+    /// the values of all other attribute sets are added to the
+    /// `otel.metric.overflow=true` data point.
+    ///
+    /// Attribute sets beyond the cardinality limit overflow. Only applies to
+    /// sum aggregations with delta temporality, other streams overflow.
+    TopK(usize),
+}
+
 /// A builder for creating Stream objects.
 ///
 /// # Example
@@ -151,7 +196,12 @@ pub struct StreamBuilder {
     unit: Option<Cow<'static, str>>,
     aggregation: Option<Aggregation>,
     allowed_attribute_keys: Option<Arc<HashSet<Key>>>,
+    #[cfg(feature = "spec_unstable_metrics_views")]
+    attribute_processor: Option<Arc<AttributeProcessor>>,
     cardinality_limit: Option<usize>,
+    cardinality_limit_strategy: Option<CardinalityLimitStrategy>,
+    idle_timeout: Option<usize>,
+    exemplar_reservoir: Option<StreamReservoirFactory>,
 }
 
 impl StreamBuilder {
@@ -200,12 +250,56 @@ impl StreamBuilder {
         self
     }
 
+    /// Set the processor rewriting the attributes of the stream.
+    ///
+    /// The processor renames, rewrites, drops and adds attributes before
+    /// measurements are aggregated, after the allowed attribute keys were
+    /// applied. See [AttributeProcessor] for details.
+    #[cfg(feature = "spec_unstable_metrics_views")]
+    pub fn with_attribute_processor(mut self, processor: AttributeProcessor) -> Self {
+        self.attribute_processor = Some(Arc::new(processor));
+        self
+    }
+
     /// Set the stream cardinality limit. If this is not set, the default limit of 2000 will be used.
     pub fn with_cardinality_limit(mut self, limit: usize) -> Self {
         self.cardinality_limit = Some(limit);
         self
     }
 
+    /// Set what the stream does with new attribute sets once it reached its
+    /// cardinality limit. If this is not set, the measurements of new attribute
+    /// sets are aggregated into the `otel.metric.overflow=true` data point.
+    pub fn with_cardinality_limit_strategy(mut self, strategy: CardinalityLimitStrategy) -> Self {
+        self.cardinality_limit_strategy = Some(strategy);
+        self
+    }
+
+    /// Set the number of collections after which an attribute set that was
+    /// not updated is dropped from a cumulative stream.
+    ///
+    /// This bounds the memory of cumulative streams whose attribute values
+    /// churn, e.g. pod names or tenant ids, which are otherwise kept forever.
+    /// An evicted attribute set that is recorded again starts over from zero
+    /// with a new start time. If this is not set, attribute sets are never
+    /// evicted. Delta streams forget attribute sets at each collection and
+    /// ignore this setting.
+    pub fn with_idle_timeout(mut self, collections: usize) -> Self {
+        self.idle_timeout = Some(collections);
+        self
+    }
+
+    /// Set the exemplar reservoir of the stream.
+    ///
+    /// Each attribute set of the stream gets its own reservoir, created by
+    /// `factory`. If this is not set, explicit bucket histograms keep the latest
+    /// exemplar of each bucket and all other aggregations keep a fixed size
+    /// random sample. Observable instruments do not record exemplars.
+    pub fn with_exemplar_reservoir(mut self, factory: impl ExemplarReservoirFactory) -> Self {
+        self.exemplar_reservoir = Some(StreamReservoirFactory::new(factory));
+        self
+    }
+
     /// Build a new Stream instance using the configuration in this builder.
     ///
     /// # Returns
@@ -258,6 +352,16 @@ impl StreamBuilder {
             }
         }
 
+        if let Some(CardinalityLimitStrategy::TopK(0)) = self.cardinality_limit_strategy {
+            return Err(
+                "Top-K cardinality limit strategy must keep at least 1 attribute set".into(),
+            );
+        }
+
+        if self.idle_timeout == Some(0) {
+            return Err("Idle timeout must be at least 1 collection".into());
+        }
+
         // Validate bucket boundaries if using ExplicitBucketHistogram
         if let Some(Aggregation::ExplicitBucketHistogram { boundaries, .. }) = &self.aggregation {
             validate_bucket_boundaries(boundaries)?;
@@ -269,7 +373,12 @@ impl StreamBuilder {
             unit: self.unit,
             aggregation: self.aggregation,
             allowed_attribute_keys: self.allowed_attribute_keys,
+            #[cfg(feature = "spec_unstable_metrics_views")]
+            attribute_processor: self.attribute_processor,
             cardinality_limit: self.cardinality_limit,
+            cardinality_limit_strategy: self.cardinality_limit_strategy,
+            idle_timeout: self.idle_timeout,
+            exemplar_reservoir: self.exemplar_reservoir,
         })
     }
 }
@@ -315,8 +424,23 @@ pub struct Stream {
     /// attributes will be kept.
     pub(crate) allowed_attribute_keys: Option<Arc<HashSet<Key>>>,
 
+    /// Rewrites the attributes of the stream after the allow-list was applied.
+    #[cfg(feature = "spec_unstable_metrics_views")]
+    pub(crate) attribute_processor: Option<Arc<AttributeProcessor>>,
+
     /// Cardinality limit for the stream.
     pub(crate) cardinality_limit: Option<usize>,
+
+    /// What the stream does with new attribute sets beyond its cardinality
+    /// limit.
+    pub(crate) cardinality_limit_strategy: Option<CardinalityLimitStrategy>,
+
+    /// The number of collections after which idle attribute sets are evicted
+    /// from cumulative aggregations.
+    pub(crate) idle_timeout: Option<usize>,
+
+    /// Creates the exemplar reservoir of each attribute set of the stream.
+    pub(crate) exemplar_reservoir: Option<StreamReservoirFactory>,
 }
 
 impl Stream {
@@ -367,6 +491,12 @@ impl<T: Copy + 'static> SyncInstrument<T> for ResolvedMeasures<T> {
             measure.call(val, attrs)
         }
     }
+
+    fn measure_bound(&self, val: T, attrs: &BoundAttributes) {
+        for measure in &self.measures {
+            measure.call_bound(val, attrs)
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -390,7 +520,7 @@ impl<T: Copy + Send + Sync + 'static> AsyncInstrument<T> for Observable<T> {
 
 #[cfg(test)]
 mod tests {
-    use super::StreamBuilder;
+    use super::{CardinalityLimitStrategy, StreamBuilder};
     use crate::metrics::meter::{
         INSTRUMENT_NAME_EMPTY, INSTRUMENT_NAME_FIRST_ALPHABETIC, INSTRUMENT_NAME_INVALID_CHAR,
         INSTRUMENT_NAME_LENGTH, INSTRUMENT_UNIT_INVALID_CHAR, INSTRUMENT_UNIT_LENGTH,
@@ -513,6 +643,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn stream_cardinality_limit_strategy_validation() {
+        let result = StreamBuilder::new()
+            .with_cardinality_limit_strategy(CardinalityLimitStrategy::TopK(0))
+            .build();
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "Top-K cardinality limit strategy must keep at least 1 attribute set",
+        );
+
+        for strategy in [
+            CardinalityLimitStrategy::Overflow,
+            CardinalityLimitStrategy::EvictLeastRecentlyUpdated,
+            CardinalityLimitStrategy::Reject,
+            CardinalityLimitStrategy::TopK(10),
+        ] {
+            let result = StreamBuilder::new()
+                .with_cardinality_limit_strategy(strategy)
+                .build();
+            assert!(result.is_ok(), "Expected successful build for {strategy:?}");
+        }
+    }
+
+    #[test]
+    fn stream_idle_timeout_validation() {
+        let result = StreamBuilder::new().with_idle_timeout(0).build();
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "Idle timeout must be at least 1 collection",
+        );
+
+        let stream = StreamBuilder::new().with_idle_timeout(5).build().unwrap();
+        assert_eq!(stream.idle_timeout, Some(5));
+    }
+
     #[test]
     fn stream_valid_build() {
         // Test with valid configuration
@@ -6,18 +6,26 @@ use std::{
     time::SystemTime,
 };
 
-use crate::metrics::{data::AggregatedMetrics, Temporality};
+#[cfg(feature = "spec_unstable_metrics_views")]
+use crate::metrics::attribute_processor::AttributeProcessor;
+use crate::metrics::{data::AggregatedMetrics, CardinalityLimitStrategy, Temporality};
 use opentelemetry::time::now;
-use opentelemetry::KeyValue;
+use opentelemetry::{metrics::BoundAttributes, KeyValue};
 
 use super::{
-    exponential_histogram::ExpoHistogram, histogram::Histogram, last_value::LastValue,
-    precomputed_sum::PrecomputedSum, sum::Sum, Number,
+    exemplar::ExemplarConfig, exponential_histogram::ExpoHistogram, histogram::Histogram,
+    last_value::LastValue, precomputed_sum::PrecomputedSum, sum::Sum, CardinalityLimit, Number,
+    RejectedMeasurements,
 };
 
 /// Receives measurements to be aggregated.
 pub(crate) trait Measure<T>: Send + Sync + 'static {
     fn call(&self, measurement: T, attrs: &[KeyValue]);
+
+    /// Receives a measurement with a pre-bound attribute set.
+    fn call_bound(&self, measurement: T, attrs: &BoundAttributes) {
+        self.call(measurement, attrs.attributes())
+    }
 }
 
 /// Stores the aggregate of measurements into the aggregation and returns the number
@@ -91,26 +99,82 @@ impl Default for AggregateTimeInitiator {
 
 type Filter = Arc<dyn Fn(&KeyValue) -> bool + Send + Sync>;
 
-/// Applies filter on provided attribute set
-/// No-op, if filter is not set
+/// Applies filter and processor on provided attribute set
+/// No-op, if neither is set
 #[derive(Clone)]
 pub(crate) struct AttributeSetFilter {
     filter: Option<Filter>,
+    #[cfg(feature = "spec_unstable_metrics_views")]
+    processor: Option<Arc<AttributeProcessor>>,
 }
 
 impl AttributeSetFilter {
     pub(crate) fn new(filter: Option<Filter>) -> Self {
-        Self { filter }
+        Self {
+            filter,
+            #[cfg(feature = "spec_unstable_metrics_views")]
+            processor: None,
+        }
+    }
+
+    /// Rewrites the filtered attributes with `processor`.
+    #[cfg(feature = "spec_unstable_metrics_views")]
+    pub(crate) fn with_processor(mut self, processor: Option<Arc<AttributeProcessor>>) -> Self {
+        self.processor = processor;
+        self
+    }
+
+    /// Whether attributes are rewritten by a processor.
+    #[cfg(feature = "spec_unstable_metrics_views")]
+    fn has_processor(&self) -> bool {
+        self.processor.is_some()
+    }
+
+    #[cfg(not(feature = "spec_unstable_metrics_views"))]
+    fn has_processor(&self) -> bool {
+        false
     }
 
     pub(crate) fn apply(&self, attrs: &[KeyValue], run: impl FnOnce(&[KeyValue])) {
-        if let Some(filter) = &self.filter {
-            let filtered_attrs: Vec<KeyValue> =
-                attrs.iter().filter(|kv| filter(kv)).cloned().collect();
-            run(&filtered_attrs);
-        } else {
+        if self.filter.is_none() && !self.has_processor() {
             run(attrs);
+            return;
+        }
+
+        let processed_attrs: Vec<KeyValue> = match &self.filter {
+            Some(filter) => attrs.iter().filter(|kv| filter(kv)).cloned().collect(),
+            None => attrs.to_vec(),
+        };
+        #[cfg(feature = "spec_unstable_metrics_views")]
+        let processed_attrs = match &self.processor {
+            Some(processor) => {
+                let mut processed_attrs = processed_attrs;
+                processor.process(&mut processed_attrs);
+                processed_attrs
+            }
+            None => processed_attrs,
         };
+        run(&processed_attrs);
+    }
+
+    /// Returns the attributes removed or rewritten by the filter and the
+    /// processor, which are kept on exemplars.
+    pub(crate) fn dropped(&self, attrs: &[KeyValue]) -> Vec<KeyValue> {
+        match &self.filter {
+            _ if self.has_processor() => {
+                let mut dropped = vec![];
+                self.apply(attrs, |processed| {
+                    dropped = attrs
+                        .iter()
+                        .filter(|kv| !processed.contains(kv))
+                        .cloned()
+                        .collect();
+                });
+                dropped
+            }
+            Some(filter) => attrs.iter().filter(|kv| !filter(kv)).cloned().collect(),
+            None => vec![],
+        }
     }
 }
 
@@ -124,7 +188,10 @@ pub(crate) struct AggregateBuilder<T> {
     filter: AttributeSetFilter,
 
     /// Cardinality limit for the metric stream
-    cardinality_limit: usize,
+    cardinality_limit: CardinalityLimit,
+
+    /// How the aggregate functions sample exemplars.
+    exemplars: ExemplarConfig<T>,
 
     _marker: marker::PhantomData<T>,
 }
@@ -134,21 +201,74 @@ impl<T: Number> AggregateBuilder<T> {
         temporality: Temporality,
         filter: Option<Filter>,
         cardinality_limit: usize,
+        exemplars: ExemplarConfig<T>,
     ) -> Self {
         AggregateBuilder {
             temporality,
             filter: AttributeSetFilter::new(filter),
-            cardinality_limit,
+            cardinality_limit: cardinality_limit.into(),
+            exemplars,
             _marker: marker::PhantomData,
         }
     }
 
+    /// Rewrites the attributes of measurements with `processor` before they
+    /// are aggregated.
+    #[cfg(feature = "spec_unstable_metrics_views")]
+    pub(crate) fn with_attribute_processor(
+        mut self,
+        processor: Option<Arc<AttributeProcessor>>,
+    ) -> Self {
+        self.filter = self.filter.with_processor(processor);
+        self
+    }
+
+    /// Sets what the aggregate functions do with attribute sets beyond the
+    /// cardinality limit, counting rejected measurements with `rejected`.
+    pub(crate) fn with_cardinality_limit_strategy(
+        mut self,
+        strategy: CardinalityLimitStrategy,
+        rejected: Option<RejectedMeasurements>,
+    ) -> Self {
+        self.cardinality_limit.strategy = strategy;
+        self.cardinality_limit.rejected = rejected;
+        self
+    }
+
+    /// Evicts attribute sets of cumulative aggregations that were not updated
+    /// for `idle_timeout` collections.
+    pub(crate) fn with_idle_timeout(mut self, idle_timeout: Option<usize>) -> Self {
+        self.cardinality_limit.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Returns the cardinality limit for aggregate functions with
+    /// `temporality`.
+    ///
+    /// Evicting attribute sets of cumulative streams would lose their state, and
+    /// the top `k` attribute sets of cumulative streams change between
+    /// collections, which would make monotonic sums decrease, so they overflow
+    /// instead. Idle attribute sets are only evicted from cumulative streams.
+    fn cardinality_limit(&self, temporality: Temporality) -> CardinalityLimit {
+        let mut cardinality_limit = self.cardinality_limit.clone();
+        if temporality == Temporality::Delta {
+            cardinality_limit.idle_timeout = None;
+        } else if matches!(
+            cardinality_limit.strategy,
+            CardinalityLimitStrategy::EvictLeastRecentlyUpdated | CardinalityLimitStrategy::TopK(_)
+        ) {
+            cardinality_limit.strategy = CardinalityLimitStrategy::Overflow;
+        }
+        cardinality_limit
+    }
+
     /// Builds a last-value aggregate function input and output.
     pub(crate) fn last_value(&self, overwrite_temporality: Option<Temporality>) -> AggregateFns<T> {
         LastValue::new(
             overwrite_temporality.unwrap_or(self.temporality),
             self.filter.clone(),
-            self.cardinality_limit,
+            self.cardinality_limit(overwrite_temporality.unwrap_or(self.temporality)),
+            self.exemplars.clone(),
         )
         .into()
     }
@@ -159,7 +279,7 @@ impl<T: Number> AggregateBuilder<T> {
             self.temporality,
             self.filter.clone(),
             monotonic,
-            self.cardinality_limit,
+            self.cardinality_limit(self.temporality),
         )
         .into()
     }
@@ -170,7 +290,8 @@ impl<T: Number> AggregateBuilder<T> {
             self.temporality,
             self.filter.clone(),
             monotonic,
-            self.cardinality_limit,
+            self.cardinality_limit(self.temporality),
+            self.exemplars.clone(),
         )
         .into()
     }
@@ -188,7 +309,8 @@ impl<T: Number> AggregateBuilder<T> {
             boundaries,
             record_min_max,
             record_sum,
-            self.cardinality_limit,
+            self.cardinality_limit(self.temporality),
+            self.exemplars.clone(),
         )
         .into()
     }
@@ -208,7 +330,8 @@ impl<T: Number> AggregateBuilder<T> {
             max_scale,
             record_min_max,
             record_sum,
-            self.cardinality_limit,
+            self.cardinality_limit(self.temporality),
+            self.exemplars.clone(),
         )
         .into()
     }
@@ -220,20 +343,56 @@ mod tests {
         ExponentialBucket, ExponentialHistogram, ExponentialHistogramDataPoint, Gauge,
         GaugeDataPoint, Histogram, HistogramDataPoint, MetricData, Sum, SumDataPoint,
     };
+    use crate::metrics::ExemplarFilter;
     use std::vec;
 
     use super::*;
 
     const CARDINALITY_LIMIT_DEFAULT: usize = 2000;
 
+    #[cfg(feature = "spec_unstable_metrics_views")]
+    #[test]
+    fn dropped_attributes_include_processed_ones() {
+        let attrs = [
+            KeyValue::new("route", "/users/1"),
+            KeyValue::new("statusCode", "200"),
+            KeyValue::new("verb", "Get"),
+            KeyValue::new("user", 1),
+        ];
+        let allowed: Filter = Arc::new(|kv: &KeyValue| kv.key.as_str() != "user");
+
+        let filter = AttributeSetFilter::new(Some(allowed.clone()));
+        assert_eq!(filter.dropped(&attrs), vec![KeyValue::new("user", 1)]);
+
+        let processor = AttributeProcessor::new()
+            .rename_key("statusCode", "http.response.status_code")
+            .map_value("route", |_| "/users/{id}".into())
+            .insert(KeyValue::new("verb", "Get"));
+        let filter =
+            AttributeSetFilter::new(Some(allowed)).with_processor(Some(Arc::new(processor)));
+        assert_eq!(
+            filter.dropped(&attrs),
+            vec![
+                KeyValue::new("route", "/users/1"),
+                KeyValue::new("statusCode", "200"),
+                KeyValue::new("user", 1),
+            ]
+        );
+    }
+
     #[test]
     fn last_value_aggregation() {
-        let AggregateFns { measure, collect } =
-            AggregateBuilder::<u64>::new(Temporality::Cumulative, None, CARDINALITY_LIMIT_DEFAULT)
-                .last_value(None);
+        let AggregateFns { measure, collect } = AggregateBuilder::<u64>::new(
+            Temporality::Cumulative,
+            None,
+            CARDINALITY_LIMIT_DEFAULT,
+            ExemplarFilter::AlwaysOff.into(),
+        )
+        .last_value(None);
         let mut a = MetricData::Gauge(Gauge {
             data_points: vec![GaugeDataPoint {
                 attributes: vec![KeyValue::new("a", 1)],
+                start_time: None,
                 value: 1u64,
                 exemplars: vec![],
             }],
@@ -259,18 +418,24 @@ mod tests {
     #[test]
     fn precomputed_sum_aggregation() {
         for temporality in [Temporality::Delta, Temporality::Cumulative] {
-            let AggregateFns { measure, collect } =
-                AggregateBuilder::<u64>::new(temporality, None, CARDINALITY_LIMIT_DEFAULT)
-                    .precomputed_sum(true);
+            let AggregateFns { measure, collect } = AggregateBuilder::<u64>::new(
+                temporality,
+                None,
+                CARDINALITY_LIMIT_DEFAULT,
+                ExemplarFilter::AlwaysOff.into(),
+            )
+            .precomputed_sum(true);
             let mut a = MetricData::Sum(Sum {
                 data_points: vec![
                     SumDataPoint {
                         attributes: vec![KeyValue::new("a1", 1)],
+                        start_time: None,
                         value: 1u64,
                         exemplars: vec![],
                     },
                     SumDataPoint {
                         attributes: vec![KeyValue::new("a2", 1)],
+                        start_time: None,
                         value: 2u64,
                         exemplars: vec![],
                     },
@@ -306,18 +471,24 @@ mod tests {
     #[test]
     fn sum_aggregation() {
         for temporality in [Temporality::Delta, Temporality::Cumulative] {
-            let AggregateFns { measure, collect } =
-                AggregateBuilder::<u64>::new(temporality, None, CARDINALITY_LIMIT_DEFAULT)
-                    .sum(true);
+            let AggregateFns { measure, collect } = AggregateBuilder::<u64>::new(
+                temporality,
+                None,
+                CARDINALITY_LIMIT_DEFAULT,
+                ExemplarFilter::AlwaysOff.into(),
+            )
+            .sum(true);
             let mut a = MetricData::Sum(Sum {
                 data_points: vec![
                     SumDataPoint {
                         attributes: vec![KeyValue::new("a1", 1)],
+                        start_time: None,
                         value: 1u64,
                         exemplars: vec![],
                     },
                     SumDataPoint {
                         attributes: vec![KeyValue::new("a2", 1)],
+                        start_time: None,
                         value: 2u64,
                         exemplars: vec![],
                     },
@@ -353,12 +524,17 @@ mod tests {
     #[test]
     fn explicit_bucket_histogram_aggregation() {
         for temporality in [Temporality::Delta, Temporality::Cumulative] {
-            let AggregateFns { measure, collect } =
-                AggregateBuilder::<u64>::new(temporality, None, CARDINALITY_LIMIT_DEFAULT)
-                    .explicit_bucket_histogram(vec![1.0], true, true);
+            let AggregateFns { measure, collect } = AggregateBuilder::<u64>::new(
+                temporality,
+                None,
+                CARDINALITY_LIMIT_DEFAULT,
+                ExemplarFilter::AlwaysOff.into(),
+            )
+            .explicit_bucket_histogram(vec![1.0], true, true);
             let mut a = MetricData::Histogram(Histogram {
                 data_points: vec![HistogramDataPoint {
                     attributes: vec![KeyValue::new("a1", 1)],
+                    start_time: None,
                     count: 2,
                     bounds: vec![1.0, 2.0],
                     bucket_counts: vec![0, 1, 1],
@@ -401,12 +577,17 @@ mod tests {
     #[test]
     fn exponential_histogram_aggregation() {
         for temporality in [Temporality::Delta, Temporality::Cumulative] {
-            let AggregateFns { measure, collect } =
-                AggregateBuilder::<u64>::new(temporality, None, CARDINALITY_LIMIT_DEFAULT)
-                    .exponential_bucket_histogram(4, 20, true, true);
+            let AggregateFns { measure, collect } = AggregateBuilder::<u64>::new(
+                temporality,
+                None,
+                CARDINALITY_LIMIT_DEFAULT,
+                ExemplarFilter::AlwaysOff.into(),
+            )
+            .exponential_bucket_histogram(4, 20, true, true);
             let mut a = MetricData::ExponentialHistogram(ExponentialHistogram {
                 data_points: vec![ExponentialHistogramDataPoint {
                     attributes: vec![KeyValue::new("a1", 1)],
+                    start_time: None,
                     count: 2,
                     min: None,
                     max: None,
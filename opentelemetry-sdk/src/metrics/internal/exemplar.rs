@@ -0,0 +1,231 @@
+use std::fmt;
+use std::mem::replace;
+use std::sync::{Arc, Mutex};
+
+use opentelemetry::KeyValue;
+#[cfg(feature = "trace")]
+use opentelemetry::{trace::TraceContextExt, Context};
+
+use crate::metrics::data::Exemplar;
+use crate::metrics::exemplar::{
+    ExemplarFilter, ExemplarMeasurement, ExemplarReservoir, ExemplarReservoirFactory,
+};
+
+use super::aggregate::AttributeSetFilter;
+use super::{Aggregator, Number};
+
+/// Creates a new, empty reservoir for each attribute set of a stream.
+pub(crate) type ReservoirFactory<T> = Arc<dyn Fn() -> Box<dyn ExemplarReservoir<T>> + Send + Sync>;
+
+/// How a metric stream samples exemplars.
+pub(crate) struct ExemplarConfig<T> {
+    /// Decides which measurements are offered to the reservoirs.
+    pub(crate) filter: ExemplarFilter,
+    /// The reservoir configured on the stream, replacing the default reservoir
+    /// of the aggregation.
+    pub(crate) reservoir: Option<ReservoirFactory<T>>,
+}
+
+impl<T> ExemplarConfig<T> {
+    /// Returns the [ReservoirFactory] of the stream, building reservoirs with
+    /// `make` unless one was configured on the stream.
+    ///
+    /// Returns `None` if the filter never lets a measurement through, so no
+    /// reservoir is allocated.
+    pub(crate) fn reservoir_factory<R>(
+        &self,
+        make: impl Fn() -> R + Send + Sync + 'static,
+    ) -> Option<ReservoirFactory<T>>
+    where
+        R: ExemplarReservoir<T> + 'static,
+    {
+        if self.filter == ExemplarFilter::AlwaysOff {
+            None
+        } else if let Some(reservoir) = &self.reservoir {
+            Some(reservoir.clone())
+        } else {
+            Some(Arc::new(move || {
+                Box::new(make()) as Box<dyn ExemplarReservoir<T>>
+            }))
+        }
+    }
+}
+
+impl<T> Clone for ExemplarConfig<T> {
+    fn clone(&self) -> Self {
+        ExemplarConfig {
+            filter: self.filter,
+            reservoir: self.reservoir.clone(),
+        }
+    }
+}
+
+impl<T> From<ExemplarFilter> for ExemplarConfig<T> {
+    fn from(filter: ExemplarFilter) -> Self {
+        ExemplarConfig {
+            filter,
+            reservoir: None,
+        }
+    }
+}
+
+/// A type erased [ExemplarReservoirFactory], creating reservoirs for each of
+/// the supported measurement types.
+pub(crate) trait AnyReservoirFactory: Send + Sync {
+    fn create_u64(&self) -> Box<dyn ExemplarReservoir<u64>>;
+    fn create_i64(&self) -> Box<dyn ExemplarReservoir<i64>>;
+    fn create_f64(&self) -> Box<dyn ExemplarReservoir<f64>>;
+}
+
+impl<F: ExemplarReservoirFactory> AnyReservoirFactory for F {
+    fn create_u64(&self) -> Box<dyn ExemplarReservoir<u64>> {
+        self.create()
+    }
+
+    fn create_i64(&self) -> Box<dyn ExemplarReservoir<i64>> {
+        self.create()
+    }
+
+    fn create_f64(&self) -> Box<dyn ExemplarReservoir<f64>> {
+        self.create()
+    }
+}
+
+/// The [ExemplarReservoirFactory] configured on a stream.
+#[derive(Clone)]
+pub(crate) struct StreamReservoirFactory(Arc<dyn AnyReservoirFactory>);
+
+impl StreamReservoirFactory {
+    pub(crate) fn new(factory: impl ExemplarReservoirFactory) -> Self {
+        StreamReservoirFactory(Arc::new(factory))
+    }
+
+    /// Returns a [ReservoirFactory] for the measurement type of an instrument.
+    pub(crate) fn for_number<T: Number>(&self) -> ReservoirFactory<T> {
+        let factory = self.0.clone();
+        Arc::new(move || T::new_exemplar_reservoir(&*factory))
+    }
+}
+
+impl fmt::Debug for StreamReservoirFactory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("StreamReservoirFactory")
+    }
+}
+
+/// Returns the measurement to offer to the exemplar reservoir if `filter`
+/// makes it eligible in the current context.
+///
+/// Attributes removed by `attribute_filter` are preserved on the exemplar, which
+/// is only built once a reservoir retains the measurement.
+pub(crate) fn sample<'a, T: Number>(
+    filter: ExemplarFilter,
+    value: T,
+    attrs: &'a [KeyValue],
+    attribute_filter: &'a AttributeSetFilter,
+) -> Option<ExemplarMeasurement<'a, T>> {
+    if filter == ExemplarFilter::AlwaysOff {
+        return None;
+    }
+
+    let (trace_id, span_id, sampled) = current_span();
+    if filter == ExemplarFilter::TraceBased && !sampled {
+        return None;
+    }
+
+    Some(ExemplarMeasurement::new(
+        value,
+        attrs,
+        attribute_filter,
+        span_id,
+        trace_id,
+    ))
+}
+
+/// Returns the trace id, span id and sampled flag of the active span.
+#[cfg(feature = "trace")]
+fn current_span() -> ([u8; 16], [u8; 8], bool) {
+    Context::map_current(|cx| {
+        let span = cx.span();
+        let span_context = span.span_context();
+        if span_context.is_valid() {
+            (
+                span_context.trace_id().to_bytes(),
+                span_context.span_id().to_bytes(),
+                span_context.is_sampled(),
+            )
+        } else {
+            ([0; 16], [0; 8], false)
+        }
+    })
+}
+
+#[cfg(not(feature = "trace"))]
+fn current_span() -> ([u8; 16], [u8; 8], bool) {
+    ([0; 16], [0; 8], false)
+}
+
+/// Configuration of [WithExemplars], which is the configuration of the
+/// wrapped aggregator and the reservoir to sample exemplars with.
+pub(crate) struct ExemplarsInit<C, T> {
+    pub(crate) aggregator: C,
+    pub(crate) reservoir: Option<ReservoirFactory<T>>,
+}
+
+/// An [Aggregator] paired with the exemplar reservoir of its attribute set.
+pub(crate) struct WithExemplars<A, T> {
+    pub(crate) aggregator: A,
+    reservoir: Option<Mutex<Box<dyn ExemplarReservoir<T>>>>,
+}
+
+impl<A, T> WithExemplars<A, T> {
+    /// Takes the exemplars sampled since the last collection.
+    pub(crate) fn collect_exemplars(&self) -> Vec<Exemplar<T>> {
+        let mut exemplars = vec![];
+        if let Some(reservoir) = &self.reservoir {
+            reservoir
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .collect(&mut exemplars);
+        }
+        exemplars
+    }
+}
+
+impl<A, T> Aggregator for WithExemplars<A, T>
+where
+    A: Aggregator,
+    T: Number,
+{
+    type InitConfig = ExemplarsInit<A::InitConfig, T>;
+    type PreComputedValue<'a> = (A::PreComputedValue<'a>, Option<ExemplarMeasurement<'a, T>>);
+
+    fn create(init: &Self::InitConfig) -> Self {
+        WithExemplars {
+            aggregator: A::create(&init.aggregator),
+            reservoir: init.reservoir.as_ref().map(|factory| Mutex::new(factory())),
+        }
+    }
+
+    fn update(&self, (value, measurement): Self::PreComputedValue<'_>) {
+        self.aggregator.update(value);
+        if let (Some(measurement), Some(reservoir)) = (measurement, &self.reservoir) {
+            reservoir
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .offer(measurement);
+        }
+    }
+
+    fn clone_and_reset(&self, init: &Self::InitConfig) -> Self {
+        WithExemplars {
+            aggregator: self.aggregator.clone_and_reset(&init.aggregator),
+            reservoir: self.reservoir.as_ref().zip(init.reservoir.as_ref()).map(
+                |(reservoir, factory)| {
+                    let mut current = reservoir.lock().unwrap_or_else(|err| err.into_inner());
+                    Mutex::new(replace(&mut *current, factory()))
+                },
+            ),
+        }
+    }
+}
@@ -1,16 +1,18 @@
 use std::{f64::consts::LOG2_E, mem::replace, ops::DerefMut, sync::Mutex};
 
-use opentelemetry::{otel_debug, KeyValue};
+use opentelemetry::{metrics::BoundAttributes, otel_debug, KeyValue};
 use std::sync::OnceLock;
 
 use crate::metrics::{
     data::{self, AggregatedMetrics, MetricData},
-    Temporality,
+    exemplar::SimpleFixedSizeExemplarReservoir,
+    ExemplarFilter, Temporality,
 };
 
 use super::{
     aggregate::{AggregateTimeInitiator, AttributeSetFilter},
-    Aggregator, ComputeAggregation, Measure, Number, ValueMap,
+    exemplar::{self, ExemplarConfig, ExemplarsInit, WithExemplars},
+    Aggregator, CardinalityLimit, ComputeAggregation, Measure, Number, ValueMap,
 };
 
 pub(crate) const EXPO_MAX_SCALE: i8 = 20;
@@ -319,7 +321,7 @@ where
 {
     type InitConfig = BucketConfig;
 
-    type PreComputedValue = T;
+    type PreComputedValue<'a> = T;
 
     fn create(init: &BucketConfig) -> Self {
         Mutex::new(ExpoHistogramDataPoint::new(init))
@@ -352,16 +354,18 @@ struct BucketConfig {
 /// Each histogram is scoped by attributes and the aggregation cycle the
 /// measurements were made in.
 pub(crate) struct ExpoHistogram<T: Number> {
-    value_map: ValueMap<Mutex<ExpoHistogramDataPoint<T>>>,
+    value_map: ValueMap<WithExemplars<Mutex<ExpoHistogramDataPoint<T>>, T>>,
     init_time: AggregateTimeInitiator,
     temporality: Temporality,
     filter: AttributeSetFilter,
+    exemplar_filter: ExemplarFilter,
     record_sum: bool,
     record_min_max: bool,
 }
 
 impl<T: Number> ExpoHistogram<T> {
     /// Create a new exponential histogram.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         temporality: Temporality,
         filter: AttributeSetFilter,
@@ -369,19 +373,26 @@ impl<T: Number> ExpoHistogram<T> {
         max_scale: i8,
         record_min_max: bool,
         record_sum: bool,
-        cardinality_limit: usize,
+        cardinality_limit: CardinalityLimit,
+        exemplars: ExemplarConfig<T>,
     ) -> Self {
+        // The specification recommends a reservoir no larger than the number
+        // of buckets, capped at 20.
+        let reservoir_size = max_size.clamp(1, 20) as usize;
+        let init = ExemplarsInit {
+            aggregator: BucketConfig {
+                max_size: max_size as i32,
+                max_scale,
+            },
+            reservoir: exemplars
+                .reservoir_factory(move || SimpleFixedSizeExemplarReservoir::new(reservoir_size)),
+        };
         ExpoHistogram {
-            value_map: ValueMap::new(
-                BucketConfig {
-                    max_size: max_size as i32,
-                    max_scale,
-                },
-                cardinality_limit,
-            ),
+            value_map: ValueMap::new(init, cardinality_limit),
             init_time: AggregateTimeInitiator::default(),
             temporality,
             filter,
+            exemplar_filter: exemplars.filter,
             record_sum,
             record_min_max,
         }
@@ -414,9 +425,14 @@ impl<T: Number> ExpoHistogram<T> {
 
         self.value_map
             .collect_and_reset(&mut h.data_points, |attributes, attr| {
-                let b = attr.into_inner().unwrap_or_else(|err| err.into_inner());
+                let exemplars = attr.collect_exemplars();
+                let b = attr
+                    .aggregator
+                    .into_inner()
+                    .unwrap_or_else(|err| err.into_inner());
                 data::ExponentialHistogramDataPoint {
                     attributes,
+                    start_time: None,
                     count: b.count,
                     min: if self.record_min_max {
                         Some(b.min)
@@ -440,7 +456,7 @@ impl<T: Number> ExpoHistogram<T> {
                         counts: b.neg_buckets.counts,
                     },
                     zero_threshold: 0.0,
-                    exemplars: vec![],
+                    exemplars,
                 }
             });
 
@@ -473,10 +489,15 @@ impl<T: Number> ExpoHistogram<T> {
         h.time = time.current;
 
         self.value_map
-            .collect_readonly(&mut h.data_points, |attributes, attr| {
-                let b = attr.lock().unwrap_or_else(|err| err.into_inner());
+            .collect_readonly(&mut h.data_points, |attributes, attr, start_time| {
+                let exemplars = attr.collect_exemplars();
+                let b = attr
+                    .aggregator
+                    .lock()
+                    .unwrap_or_else(|err| err.into_inner());
                 data::ExponentialHistogramDataPoint {
                     attributes,
+                    start_time,
                     count: b.count,
                     min: if self.record_min_max {
                         Some(b.min)
@@ -500,7 +521,7 @@ impl<T: Number> ExpoHistogram<T> {
                         counts: b.neg_buckets.counts.clone(),
                     },
                     zero_threshold: 0.0,
-                    exemplars: vec![],
+                    exemplars,
                 }
             });
 
@@ -520,10 +541,27 @@ where
             return;
         }
 
+        let exemplar = exemplar::sample(self.exemplar_filter, measurement, attrs, &self.filter);
         self.filter.apply(attrs, |filtered| {
-            self.value_map.measure(measurement, filtered);
+            self.value_map.measure((measurement, exemplar), filtered);
         })
     }
+
+    fn call_bound(&self, measurement: T, attrs: &BoundAttributes) {
+        // Ignore NaN and infinity.
+        if !measurement.into_float().is_finite() {
+            return;
+        }
+
+        let exemplar = exemplar::sample(
+            self.exemplar_filter,
+            measurement,
+            attrs.attributes(),
+            &self.filter,
+        );
+        self.value_map
+            .measure_bound((measurement, exemplar), attrs, &self.filter);
+    }
 }
 
 impl<T> ComputeAggregation for ExpoHistogram<T>
@@ -716,12 +754,13 @@ mod tests {
                 20,
                 true,
                 true,
-                CARDINALITY_LIMIT_DEFAULT,
+                CARDINALITY_LIMIT_DEFAULT.into(),
+                ExemplarFilter::AlwaysOff.into(),
             );
             for v in test.values {
                 Measure::call(&h, v, &[]);
             }
-            let dp = h.value_map.no_attribute_tracker.lock().unwrap();
+            let dp = h.value_map.no_attribute_tracker.aggregator.lock().unwrap();
 
             assert_eq!(test.expected.max, dp.max);
             assert_eq!(test.expected.min, dp.min);
@@ -773,12 +812,13 @@ mod tests {
                 20,
                 true,
                 true,
-                CARDINALITY_LIMIT_DEFAULT,
+                CARDINALITY_LIMIT_DEFAULT.into(),
+                ExemplarFilter::AlwaysOff.into(),
             );
             for v in test.values {
                 Measure::call(&h, v, &[]);
             }
-            let dp = h.value_map.no_attribute_tracker.lock().unwrap();
+            let dp = h.value_map.no_attribute_tracker.aggregator.lock().unwrap();
 
             assert_eq!(test.expected.max, dp.max);
             assert_eq!(test.expected.min, dp.min);
@@ -1286,13 +1326,18 @@ mod tests {
             TestCase {
                 name: "Delta Single",
                 build: Box::new(move || {
-                    AggregateBuilder::new(Temporality::Delta, None, CARDINALITY_LIMIT_DEFAULT)
-                        .exponential_bucket_histogram(
-                            max_size,
-                            max_scale,
-                            record_min_max,
-                            record_sum,
-                        )
+                    AggregateBuilder::new(
+                        Temporality::Delta,
+                        None,
+                        CARDINALITY_LIMIT_DEFAULT,
+                        ExemplarFilter::AlwaysOff.into(),
+                    )
+                    .exponential_bucket_histogram(
+                        max_size,
+                        max_scale,
+                        record_min_max,
+                        record_sum,
+                    )
                 }),
                 input: vec![vec![4, 4, 4, 2, 16, 1]
                     .into_iter()
@@ -1302,6 +1347,7 @@ mod tests {
                     temporality: Temporality::Delta,
                     data_points: vec![data::ExponentialHistogramDataPoint {
                         attributes: vec![],
+                        start_time: None,
                         count: 6,
                         min: Some(1.into()),
                         max: Some(16.into()),
@@ -1331,6 +1377,7 @@ mod tests {
                         Temporality::Cumulative,
                         None,
                         CARDINALITY_LIMIT_DEFAULT,
+                        ExemplarFilter::AlwaysOff.into(),
                     )
                     .exponential_bucket_histogram(
                         max_size,
@@ -1347,6 +1394,7 @@ mod tests {
                     temporality: Temporality::Cumulative,
                     data_points: vec![data::ExponentialHistogramDataPoint {
                         attributes: vec![],
+                        start_time: None,
                         count: 6,
                         min: Some(1.into()),
                         max: Some(16.into()),
@@ -1376,6 +1424,7 @@ mod tests {
                         Temporality::Delta,
                         None,
                         CARDINALITY_LIMIT_DEFAULT,
+                        ExemplarFilter::AlwaysOff.into(),
                     )
                     .exponential_bucket_histogram(
                         max_size,
@@ -1395,6 +1444,7 @@ mod tests {
                     temporality: Temporality::Delta,
                     data_points: vec![data::ExponentialHistogramDataPoint {
                         attributes: vec![],
+                        start_time: None,
                         count: 6,
                         min: Some(1.into()),
                         max: Some(16.into()),
@@ -1424,6 +1474,7 @@ mod tests {
                         Temporality::Cumulative,
                         None,
                         CARDINALITY_LIMIT_DEFAULT,
+                        ExemplarFilter::AlwaysOff.into(),
                     )
                     .exponential_bucket_histogram(
                         max_size,
@@ -1442,6 +1493,7 @@ mod tests {
                 want: data::ExponentialHistogram {
                     temporality: Temporality::Cumulative,
                     data_points: vec![data::ExponentialHistogramDataPoint {
+                        start_time: None,
                         count: 9,
                         min: Some(1.into()),
                         max: Some(16.into()),
@@ -4,15 +4,19 @@ use std::sync::Mutex;
 
 use crate::metrics::data::{self, MetricData};
 use crate::metrics::data::{AggregatedMetrics, HistogramDataPoint};
-use crate::metrics::Temporality;
-use opentelemetry::KeyValue;
+use crate::metrics::exemplar::{
+    AlignedHistogramBucketExemplarReservoir, SimpleFixedSizeExemplarReservoir,
+};
+use crate::metrics::{ExemplarFilter, Temporality};
+use opentelemetry::{metrics::BoundAttributes, KeyValue};
 
 use super::aggregate::AggregateTimeInitiator;
 use super::aggregate::AttributeSetFilter;
+use super::exemplar::{self, ExemplarConfig, ExemplarsInit, WithExemplars};
 use super::ComputeAggregation;
 use super::Measure;
-use super::ValueMap;
 use super::{Aggregator, Number};
+use super::{CardinalityLimit, ValueMap};
 
 impl<T> Aggregator for Mutex<Buckets<T>>
 where
@@ -20,7 +24,7 @@ where
 {
     type InitConfig = usize;
     /// Value and bucket index
-    type PreComputedValue = (T, usize);
+    type PreComputedValue<'a> = (T, usize);
 
     fn update(&self, (value, index): (T, usize)) {
         let mut buckets = self.lock().unwrap_or_else(|err| err.into_inner());
@@ -73,10 +77,11 @@ impl<T: Number> Buckets<T> {
 /// Summarizes a set of measurements as a histogram with explicitly defined
 /// buckets.
 pub(crate) struct Histogram<T: Number> {
-    value_map: ValueMap<Mutex<Buckets<T>>>,
+    value_map: ValueMap<WithExemplars<Mutex<Buckets<T>>, T>>,
     init_time: AggregateTimeInitiator,
     temporality: Temporality,
     filter: AttributeSetFilter,
+    exemplar_filter: ExemplarFilter,
     bounds: Vec<f64>,
     record_min_max: bool,
     record_sum: bool,
@@ -90,7 +95,8 @@ impl<T: Number> Histogram<T> {
         mut bounds: Vec<f64>,
         record_min_max: bool,
         record_sum: bool,
-        cardinality_limit: usize,
+        cardinality_limit: CardinalityLimit,
+        exemplars: ExemplarConfig<T>,
     ) -> Self {
         #[cfg(feature = "spec_unstable_metrics_views")]
         {
@@ -105,17 +111,43 @@ impl<T: Number> Histogram<T> {
             bounds.len() + 1
         };
 
+        // Histograms with buckets keep an exemplar per bucket, as recommended
+        // by the specification.
+        let reservoir = if bounds.is_empty() {
+            exemplars.reservoir_factory(|| SimpleFixedSizeExemplarReservoir::new(1))
+        } else {
+            let reservoir_bounds = bounds.clone();
+            exemplars.reservoir_factory(move || {
+                AlignedHistogramBucketExemplarReservoir::new(reservoir_bounds.clone())
+            })
+        };
+        let init = ExemplarsInit {
+            aggregator: buckets_count,
+            reservoir,
+        };
+
         Histogram {
-            value_map: ValueMap::new(buckets_count, cardinality_limit),
+            value_map: ValueMap::new(init, cardinality_limit),
             init_time: AggregateTimeInitiator::default(),
             temporality,
             filter,
+            exemplar_filter: exemplars.filter,
             bounds,
             record_min_max,
             record_sum,
         }
     }
 
+    fn bucket_index(&self, measurement: T) -> usize {
+        let f = measurement.into_float();
+        // This search will return an index in the range `[0, bounds.len()]`, where
+        // it will return `bounds.len()` if value is greater than the last element
+        // of `bounds`. This aligns with the buckets in that the length of buckets
+        // is `bounds.len()+1`, with the last bucket representing:
+        // `(bounds[bounds.len()-1], +∞)`.
+        self.bounds.partition_point(|&x| x < f)
+    }
+
     fn delta(&self, dest: Option<&mut MetricData<T>>) -> (usize, Option<MetricData<T>>) {
         let time = self.init_time.delta();
 
@@ -143,9 +175,14 @@ impl<T: Number> Histogram<T> {
 
         self.value_map
             .collect_and_reset(&mut h.data_points, |attributes, aggr| {
-                let b = aggr.into_inner().unwrap_or_else(|err| err.into_inner());
+                let exemplars = aggr.collect_exemplars();
+                let b = aggr
+                    .aggregator
+                    .into_inner()
+                    .unwrap_or_else(|err| err.into_inner());
                 HistogramDataPoint {
                     attributes,
+                    start_time: None,
                     count: b.count,
                     bounds: self.bounds.clone(),
                     bucket_counts: b.counts,
@@ -164,7 +201,7 @@ impl<T: Number> Histogram<T> {
                     } else {
                         None
                     },
-                    exemplars: vec![],
+                    exemplars,
                 }
             });
 
@@ -196,10 +233,15 @@ impl<T: Number> Histogram<T> {
         h.time = time.current;
 
         self.value_map
-            .collect_readonly(&mut h.data_points, |attributes, aggr| {
-                let b = aggr.lock().unwrap_or_else(|err| err.into_inner());
+            .collect_readonly(&mut h.data_points, |attributes, aggr, start_time| {
+                let exemplars = aggr.collect_exemplars();
+                let b = aggr
+                    .aggregator
+                    .lock()
+                    .unwrap_or_else(|err| err.into_inner());
                 HistogramDataPoint {
                     attributes,
+                    start_time,
                     count: b.count,
                     bounds: self.bounds.clone(),
                     bucket_counts: b.counts.clone(),
@@ -218,7 +260,7 @@ impl<T: Number> Histogram<T> {
                     } else {
                         None
                     },
-                    exemplars: vec![],
+                    exemplars,
                 }
             });
 
@@ -231,18 +273,25 @@ where
     T: Number,
 {
     fn call(&self, measurement: T, attrs: &[KeyValue]) {
-        let f = measurement.into_float();
-        // This search will return an index in the range `[0, bounds.len()]`, where
-        // it will return `bounds.len()` if value is greater than the last element
-        // of `bounds`. This aligns with the buckets in that the length of buckets
-        // is `bounds.len()+1`, with the last bucket representing:
-        // `(bounds[bounds.len()-1], +∞)`.
-        let index = self.bounds.partition_point(|&x| x < f);
-
+        let index = self.bucket_index(measurement);
+        let exemplar = exemplar::sample(self.exemplar_filter, measurement, attrs, &self.filter);
         self.filter.apply(attrs, |filtered| {
-            self.value_map.measure((measurement, index), filtered);
+            self.value_map
+                .measure(((measurement, index), exemplar), filtered);
         })
     }
+
+    fn call_bound(&self, measurement: T, attrs: &BoundAttributes) {
+        let index = self.bucket_index(measurement);
+        let exemplar = exemplar::sample(
+            self.exemplar_filter,
+            measurement,
+            attrs.attributes(),
+            &self.filter,
+        );
+        self.value_map
+            .measure_bound(((measurement, index), exemplar), attrs, &self.filter);
+    }
 }
 
 impl<T> ComputeAggregation for Histogram<T>
@@ -271,7 +320,8 @@ mod tests {
             vec![1.0, 3.0, 6.0],
             false,
             false,
-            2000,
+            2000.into(),
+            ExemplarFilter::AlwaysOff.into(),
         );
         for v in 1..11 {
             Measure::call(&hist, v, &[]);
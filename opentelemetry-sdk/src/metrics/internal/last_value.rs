@@ -1,12 +1,15 @@
 use crate::metrics::{
     data::{self, AggregatedMetrics, GaugeDataPoint, MetricData},
-    Temporality,
+    exemplar::SimpleFixedSizeExemplarReservoir,
+    ExemplarFilter, Temporality,
 };
-use opentelemetry::KeyValue;
+use opentelemetry::{metrics::BoundAttributes, KeyValue};
 
 use super::{
     aggregate::{AggregateTimeInitiator, AttributeSetFilter},
-    Aggregator, AtomicTracker, AtomicallyUpdate, ComputeAggregation, Measure, Number, ValueMap,
+    exemplar::{self, ExemplarConfig, ExemplarsInit, WithExemplars},
+    Aggregator, AtomicTracker, AtomicallyUpdate, CardinalityLimit, ComputeAggregation, Measure,
+    Number, ValueMap,
 };
 
 /// this is reused by PrecomputedSum
@@ -22,7 +25,7 @@ where
     T: Number,
 {
     type InitConfig = ();
-    type PreComputedValue = T;
+    type PreComputedValue<'a> = T;
 
     fn create(_init: &()) -> Self {
         Self {
@@ -43,23 +46,30 @@ where
 
 /// Summarizes a set of measurements as the last one made.
 pub(crate) struct LastValue<T: Number> {
-    value_map: ValueMap<Assign<T>>,
+    value_map: ValueMap<WithExemplars<Assign<T>, T>>,
     init_time: AggregateTimeInitiator,
     temporality: Temporality,
     filter: AttributeSetFilter,
+    exemplar_filter: ExemplarFilter,
 }
 
 impl<T: Number> LastValue<T> {
     pub(crate) fn new(
         temporality: Temporality,
         filter: AttributeSetFilter,
-        cardinality_limit: usize,
+        cardinality_limit: CardinalityLimit,
+        exemplars: ExemplarConfig<T>,
     ) -> Self {
+        let init = ExemplarsInit {
+            aggregator: (),
+            reservoir: exemplars.reservoir_factory(|| SimpleFixedSizeExemplarReservoir::new(1)),
+        };
         LastValue {
-            value_map: ValueMap::new((), cardinality_limit),
+            value_map: ValueMap::new(init, cardinality_limit),
             init_time: AggregateTimeInitiator::default(),
             temporality,
             filter,
+            exemplar_filter: exemplars.filter,
         }
     }
 
@@ -89,8 +99,9 @@ impl<T: Number> LastValue<T> {
         self.value_map
             .collect_and_reset(&mut s_data.data_points, |attributes, aggr| GaugeDataPoint {
                 attributes,
-                value: aggr.value.get_value(),
-                exemplars: vec![],
+                start_time: None,
+                value: aggr.aggregator.value.get_value(),
+                exemplars: aggr.collect_exemplars(),
             });
 
         (s_data.data_points.len(), new_agg.map(Into::into))
@@ -123,10 +134,13 @@ impl<T: Number> LastValue<T> {
         s_data.time = time.current;
 
         self.value_map
-            .collect_readonly(&mut s_data.data_points, |attributes, aggr| GaugeDataPoint {
-                attributes,
-                value: aggr.value.get_value(),
-                exemplars: vec![],
+            .collect_readonly(&mut s_data.data_points, |attributes, aggr, start_time| {
+                GaugeDataPoint {
+                    attributes,
+                    start_time,
+                    value: aggr.aggregator.value.get_value(),
+                    exemplars: aggr.collect_exemplars(),
+                }
             });
 
         (s_data.data_points.len(), new_agg.map(Into::into))
@@ -138,10 +152,22 @@ where
     T: Number,
 {
     fn call(&self, measurement: T, attrs: &[KeyValue]) {
+        let exemplar = exemplar::sample(self.exemplar_filter, measurement, attrs, &self.filter);
         self.filter.apply(attrs, |filtered| {
-            self.value_map.measure(measurement, filtered);
+            self.value_map.measure((measurement, exemplar), filtered);
         })
     }
+
+    fn call_bound(&self, measurement: T, attrs: &BoundAttributes) {
+        let exemplar = exemplar::sample(
+            self.exemplar_filter,
+            measurement,
+            attrs.attributes(),
+            &self.filter,
+        );
+        self.value_map
+            .measure_bound((measurement, exemplar), attrs, &self.filter);
+    }
 }
 
 impl<T> ComputeAggregation for LastValue<T>
@@ -1,4 +1,5 @@
 mod aggregate;
+mod exemplar;
 mod exponential_histogram;
 mod histogram;
 mod last_value;
@@ -6,18 +7,26 @@ mod precomputed_sum;
 mod sum;
 
 use core::fmt;
+use std::borrow::Cow;
 use std::cmp::min;
+use std::cmp::Ordering as CmpOrdering;
 use std::collections::{HashMap, HashSet};
-use std::mem::swap;
-use std::ops::{Add, AddAssign, DerefMut, Sub};
+use std::mem::{swap, take};
+use std::ops::{Add, AddAssign, Deref, DerefMut, Sub};
 use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, AtomicUsize, Ordering};
-use std::sync::{Arc, OnceLock, RwLock};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::SystemTime;
 
-pub(crate) use aggregate::{AggregateBuilder, AggregateFns, ComputeAggregation, Measure};
+pub(crate) use aggregate::{
+    AggregateBuilder, AggregateFns, AttributeSetFilter, ComputeAggregation, Measure,
+};
+pub(crate) use exemplar::{ExemplarConfig, StreamReservoirFactory};
 pub(crate) use exponential_histogram::{EXPO_MAX_SCALE, EXPO_MIN_SCALE};
-use opentelemetry::{otel_warn, KeyValue};
+use opentelemetry::{metrics::BoundAttributes, otel_warn, time::now, KeyValue};
 
-use super::data::{AggregatedMetrics, MetricData};
+use super::data::{AggregatedMetrics, MetricData, SumDataPoint};
+use super::exemplar::ExemplarReservoir;
+use super::instrument::CardinalityLimitStrategy;
 use super::pipeline::DEFAULT_CARDINALITY_LIMIT;
 
 // TODO Replace it with LazyLock once it is stable
@@ -36,18 +45,109 @@ pub(crate) trait Aggregator {
     /// Some aggregators can do some computations before updating aggregator.
     /// This helps to reduce contention for aggregators because it makes
     /// [`Aggregator::update`] as short as possible.
-    type PreComputedValue;
+    type PreComputedValue<'a>;
 
     /// Called everytime a new attribute-set is stored.
     fn create(init: &Self::InitConfig) -> Self;
 
     /// Called for each measurement.
-    fn update(&self, value: Self::PreComputedValue);
+    fn update(&self, value: Self::PreComputedValue<'_>);
 
     /// Return current value and reset this instance
     fn clone_and_reset(&self, init: &Self::InitConfig) -> Self;
 }
 
+/// The limits on the attribute sets of a metric stream.
+#[derive(Clone)]
+pub(crate) struct CardinalityLimit {
+    /// The maximum number of attribute sets of the stream.
+    pub(crate) limit: usize,
+    pub(crate) strategy: CardinalityLimitStrategy,
+    /// Counts the measurements dropped by [CardinalityLimitStrategy::Reject].
+    pub(crate) rejected: Option<RejectedMeasurements>,
+    /// The number of collections after which an attribute set that was not
+    /// updated is evicted, only applied by [ValueMap::collect_readonly].
+    pub(crate) idle_timeout: Option<usize>,
+}
+
+impl CardinalityLimit {
+    fn reject(&self) {
+        if let Some(rejected) = &self.rejected {
+            rejected.counter.call(1, &rejected.attributes);
+        }
+    }
+}
+
+impl From<usize> for CardinalityLimit {
+    fn from(limit: usize) -> Self {
+        CardinalityLimit {
+            limit,
+            strategy: CardinalityLimitStrategy::Overflow,
+            rejected: None,
+            idle_timeout: None,
+        }
+    }
+}
+
+/// The counter of the measurements a stream rejected.
+#[derive(Clone)]
+pub(crate) struct RejectedMeasurements {
+    counter: Arc<dyn Measure<u64>>,
+    attributes: [KeyValue; 2],
+}
+
+impl RejectedMeasurements {
+    pub(crate) fn new(
+        counter: Arc<dyn Measure<u64>>,
+        scope_name: String,
+        stream_name: Cow<'static, str>,
+    ) -> Self {
+        RejectedMeasurements {
+            counter,
+            attributes: [
+                KeyValue::new("otel.scope.name", scope_name),
+                KeyValue::new("otel.metric.name", stream_name),
+            ],
+        }
+    }
+}
+
+/// The aggregator of an attribute set, along with when it was last updated.
+struct Tracker<A> {
+    aggregator: A,
+    /// The [ValueMap::clock] at the last update, only maintained for
+    /// [CardinalityLimitStrategy::EvictLeastRecentlyUpdated]. Only written
+    /// when the clock advanced since.
+    last_update: AtomicU64,
+    /// The [ValueMap::collections] at the last update, only maintained for
+    /// streams evicting idle attribute sets.
+    last_collection: AtomicU64,
+    /// When the tracker was created, only recorded for streams evicting idle
+    /// attribute sets.
+    start_time: Option<SystemTime>,
+}
+
+impl<A> Tracker<A> {
+    fn new(aggregator: A) -> Self {
+        Tracker {
+            aggregator,
+            last_update: AtomicU64::new(0),
+            last_collection: AtomicU64::new(0),
+            start_time: None,
+        }
+    }
+}
+
+impl<A> Deref for Tracker<A> {
+    type Target = A;
+
+    fn deref(&self) -> &A {
+        &self.aggregator
+    }
+}
+
+type Trackers<A> = HashMap<Vec<KeyValue>, Arc<Tracker<A>>>;
+
 /// The storage for sums.
 ///
 /// This structure is parametrized by an `Operation` that indicates how
@@ -57,12 +157,22 @@ where
     A: Aggregator,
 {
     /// Trackers store the values associated with different attribute sets.
-    trackers: RwLock<HashMap<Vec<KeyValue>, Arc<A>>>,
+    trackers: RwLock<Trackers<A>>,
 
     /// Used ONLY by Delta collect. The data type must match the one used in
     /// `trackers` to allow mem::swap. Wrapping the type in `OnceLock` to
     /// avoid this allocation for Cumulative aggregation.
-    trackers_for_collect: OnceLock<RwLock<HashMap<Vec<KeyValue>, Arc<A>>>>,
+    trackers_for_collect: OnceLock<RwLock<Trackers<A>>>,
+
+    /// Trackers evicted from `trackers` since the last collection, keyed by
+    /// their sorted attributes.
+    evicted: Mutex<Trackers<A>>,
+
+    /// Trackers resolved for [BoundAttributes], keyed by their id. Only
+    /// accessed while holding the `trackers` lock, and cleared whenever
+    /// trackers are removed and on each collection. Holds at most as many
+    /// trackers as the cardinality limit.
+    bound: RwLock<HashMap<u64, Arc<Tracker<A>>>>,
 
     /// Number of different attribute set stored in the `trackers` map.
     count: AtomicUsize,
@@ -72,42 +182,160 @@ where
     no_attribute_tracker: A,
     /// Configuration for an Aggregator
     config: A::InitConfig,
-    cardinality_limit: usize,
+    cardinality_limit: CardinalityLimit,
+    /// Orders the updates of the trackers relative to the insertions of new
+    /// trackers, the only times it advances.
+    clock: AtomicU64,
+    /// The number of cumulative collections.
+    collections: AtomicU64,
 }
 
 impl<A> ValueMap<A>
 where
     A: Aggregator,
 {
-    fn new(config: A::InitConfig, cardinality_limit: usize) -> Self {
+    fn new(config: A::InitConfig, cardinality_limit: CardinalityLimit) -> Self {
         ValueMap {
             trackers: RwLock::new(HashMap::with_capacity(
-                1 + min(DEFAULT_CARDINALITY_LIMIT, cardinality_limit),
+                1 + min(DEFAULT_CARDINALITY_LIMIT, cardinality_limit.limit),
             )),
             trackers_for_collect: OnceLock::new(),
+            evicted: Mutex::new(HashMap::new()),
+            bound: RwLock::new(HashMap::new()),
             has_no_attribute_value: AtomicBool::new(false),
             no_attribute_tracker: A::create(&config),
             count: AtomicUsize::new(0),
             config,
             cardinality_limit,
+            clock: AtomicU64::new(0),
+            collections: AtomicU64::new(0),
         }
     }
 
+    fn new_tracker(&self) -> Arc<Tracker<A>> {
+        let mut tracker = Tracker::new(A::create(&self.config));
+        if self.cardinality_limit.idle_timeout.is_some() {
+            // An evicted attribute set that is recorded again starts over.
+            tracker.start_time = Some(now());
+        }
+        Arc::new(tracker)
+    }
+
     #[inline]
-    fn trackers_for_collect(&self) -> &RwLock<HashMap<Vec<KeyValue>, Arc<A>>> {
+    fn trackers_for_collect(&self) -> &RwLock<Trackers<A>> {
         self.trackers_for_collect.get_or_init(|| {
             RwLock::new(HashMap::with_capacity(
-                1 + min(DEFAULT_CARDINALITY_LIMIT, self.cardinality_limit),
+                1 + min(DEFAULT_CARDINALITY_LIMIT, self.cardinality_limit.limit),
             ))
         })
     }
 
+    /// The number of attribute sets exported by [CardinalityLimitStrategy::TopK].
+    fn top_k(&self) -> Option<usize> {
+        match self.cardinality_limit.strategy {
+            CardinalityLimitStrategy::TopK(k) => Some(k),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn evicts_least_recently_updated(&self) -> bool {
+        self.cardinality_limit.strategy == CardinalityLimitStrategy::EvictLeastRecentlyUpdated
+    }
+
+    /// Advances the clock after inserting a tracker, so trackers updated from
+    /// now on are more recent than the ones that were not. Must be called
+    /// while holding the `trackers` write lock.
+    fn tracker_inserted(&self) {
+        if self.evicts_least_recently_updated() {
+            self.clock.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
     /// Checks whether aggregator has hit cardinality limit for metric streams
     fn is_under_cardinality_limit(&self) -> bool {
-        self.count.load(Ordering::SeqCst) < self.cardinality_limit
+        self.count.load(Ordering::SeqCst) < self.cardinality_limit.limit
     }
 
-    fn measure(&self, value: A::PreComputedValue, attributes: &[KeyValue]) {
+    #[inline]
+    fn update(&self, tracker: &Tracker<A>, value: A::PreComputedValue<'_>) {
+        tracker.update(value);
+        if self.evicts_least_recently_updated() {
+            let now = self.clock.load(Ordering::Relaxed);
+            if tracker.last_update.load(Ordering::Relaxed) != now {
+                tracker.last_update.store(now, Ordering::Relaxed);
+            }
+        }
+        if self.cardinality_limit.idle_timeout.is_some() {
+            let collection = self.collections.load(Ordering::Relaxed);
+            tracker.last_collection.store(collection, Ordering::Relaxed);
+        }
+    }
+
+    /// Caches `tracker` as the tracker of the bound attribute set `bound_id`,
+    /// unless the cache is full, in which case the attribute set keeps being
+    /// looked up like unbound attributes.
+    ///
+    /// Must be called while holding the `trackers` lock, so a collection
+    /// cannot remove the tracker in the meantime.
+    #[inline]
+    fn bind(&self, bound_id: Option<u64>, tracker: &Arc<Tracker<A>>) {
+        if let Some(id) = bound_id {
+            if let Ok(mut bound) = self.bound.write() {
+                if bound.len() < self.cardinality_limit.limit {
+                    bound.insert(id, tracker.clone());
+                }
+            }
+        }
+    }
+
+    fn clear_bound(&self) {
+        if let Ok(mut bound) = self.bound.write() {
+            bound.clear();
+        }
+    }
+
+    fn measure(&self, value: A::PreComputedValue<'_>, attributes: &[KeyValue]) {
+        self.measure_and_bind(value, attributes, None)
+    }
+
+    /// Records a measurement of a pre-bound attribute set.
+    ///
+    /// The tracker the attribute set resolves to is cached until the next
+    /// collection, so further measurements skip `filter` as well as sorting
+    /// and hashing the attributes.
+    fn measure_bound(
+        &self,
+        value: A::PreComputedValue<'_>,
+        attributes: &BoundAttributes,
+        filter: &AttributeSetFilter,
+    ) {
+        {
+            let Ok(_trackers) = self.trackers.read() else {
+                return;
+            };
+            let tracker = self
+                .bound
+                .read()
+                .ok()
+                .and_then(|bound| bound.get(&attributes.id()).cloned());
+            if let Some(tracker) = tracker {
+                self.update(&tracker, value);
+                return;
+            }
+        }
+
+        filter.apply(attributes.attributes(), |filtered| {
+            self.measure_and_bind(value, filtered, Some(attributes.id()))
+        });
+    }
+
+    fn measure_and_bind(
+        &self,
+        value: A::PreComputedValue<'_>,
+        attributes: &[KeyValue],
+        bound_id: Option<u64>,
+    ) {
         if attributes.is_empty() {
             self.no_attribute_tracker.update(value);
             self.has_no_attribute_value.store(true, Ordering::Release);
@@ -120,14 +348,16 @@ where
 
         // Try to retrieve and update the tracker with the attributes in the provided order first
         if let Some(tracker) = trackers.get(attributes) {
-            tracker.update(value);
+            self.update(tracker, value);
+            self.bind(bound_id, tracker);
             return;
         }
 
         // Try to retrieve and update the tracker with the attributes sorted.
         let sorted_attrs = sort_and_dedup(attributes);
         if let Some(tracker) = trackers.get(sorted_attrs.as_slice()) {
-            tracker.update(value);
+            self.update(tracker, value);
+            self.bind(bound_id, tracker);
             return;
         }
 
@@ -141,48 +371,197 @@ where
         // Recheck both the provided and sorted orders after acquiring the write lock
         // in case another thread has pushed an update in the meantime.
         if let Some(tracker) = trackers.get(attributes) {
-            tracker.update(value);
+            self.update(tracker, value);
+            self.bind(bound_id, tracker);
         } else if let Some(tracker) = trackers.get(sorted_attrs.as_slice()) {
-            tracker.update(value);
+            self.update(tracker, value);
+            self.bind(bound_id, tracker);
         } else if self.is_under_cardinality_limit() {
-            let new_tracker = Arc::new(A::create(&self.config));
-            new_tracker.update(value);
+            let new_tracker = self.new_tracker();
+            self.update(&new_tracker, value);
+            self.bind(bound_id, &new_tracker);
 
             // Insert tracker with the attributes in the provided and sorted orders
             trackers.insert(attributes.to_vec(), new_tracker.clone());
             trackers.insert(sorted_attrs, new_tracker);
 
             self.count.fetch_add(1, Ordering::SeqCst);
-        } else if let Some(overflow_value) = trackers.get(stream_overflow_attributes().as_slice()) {
-            overflow_value.update(value);
+            self.tracker_inserted();
         } else {
-            let new_tracker = A::create(&self.config);
-            new_tracker.update(value);
-            trackers.insert(stream_overflow_attributes().clone(), Arc::new(new_tracker));
+            match self.cardinality_limit.strategy {
+                CardinalityLimitStrategy::Reject => {
+                    drop(trackers);
+                    self.cardinality_limit.reject();
+                }
+                CardinalityLimitStrategy::EvictLeastRecentlyUpdated => {
+                    match self.evict_least_recently_updated(&mut trackers, attributes, sorted_attrs)
+                    {
+                        Some(tracker) => {
+                            self.update(&tracker, value);
+                            self.bind(bound_id, &tracker);
+                        }
+                        None => self.overflow(&mut trackers, value, bound_id),
+                    }
+                }
+                _ => self.overflow(&mut trackers, value, bound_id),
+            }
         }
     }
 
+    /// Records the measurement of an attribute set beyond the cardinality limit
+    /// in the overflow tracker.
+    fn overflow(
+        &self,
+        trackers: &mut Trackers<A>,
+        value: A::PreComputedValue<'_>,
+        bound_id: Option<u64>,
+    ) {
+        if let Some(overflow_value) = trackers.get(stream_overflow_attributes().as_slice()) {
+            self.update(overflow_value, value);
+            self.bind(bound_id, overflow_value);
+        } else {
+            let new_tracker = self.new_tracker();
+            self.update(&new_tracker, value);
+            self.bind(bound_id, &new_tracker);
+            trackers.insert(stream_overflow_attributes().clone(), new_tracker);
+        }
+    }
+
+    /// Evicts a batch of the least recently updated trackers to make room
+    /// for the tracker of `sorted_attrs`, returning the latter.
+    ///
+    /// Evicting a tenth of the cardinality limit at once amortises the scan
+    /// of the trackers over the following insertions. The evicted trackers are
+    /// kept until the next collection. Returns `None` if the number of evicted
+    /// trackers reached the cardinality limit.
+    fn evict_least_recently_updated(
+        &self,
+        trackers: &mut Trackers<A>,
+        attributes: &[KeyValue],
+        sorted_attrs: Vec<KeyValue>,
+    ) -> Option<Arc<Tracker<A>>> {
+        let mut evicted = self.evicted.lock().ok()?;
+        let reinstated = evicted.remove(&sorted_attrs);
+        let batch = (self.cardinality_limit.limit / 10)
+            .max(1)
+            .min(self.cardinality_limit.limit.saturating_sub(evicted.len()));
+        if reinstated.is_none() && batch == 0 {
+            return None;
+        }
+
+        // Trackers are stored under their provided and sorted attributes, both
+        // entries being adjacent once ordered by last update and address.
+        let overflow_attrs = stream_overflow_attributes();
+        let mut candidates: Vec<_> = trackers
+            .iter()
+            .filter(|(attrs, _)| *attrs != overflow_attrs)
+            .map(|(attrs, tracker)| {
+                let last_update = tracker.last_update.load(Ordering::Relaxed);
+                (last_update, Arc::as_ptr(tracker), attrs)
+            })
+            .collect();
+        candidates.sort_unstable_by_key(|(last_update, ptr, _)| (*last_update, *ptr));
+        candidates.dedup_by_key(|(_, ptr, _)| *ptr);
+        let victims: Vec<_> = candidates
+            .into_iter()
+            .take(batch)
+            .map(|(_, _, attrs)| sort_and_dedup(attrs))
+            .collect();
+        if victims.is_empty() {
+            if let Some(reinstated) = reinstated {
+                evicted.insert(sorted_attrs, reinstated);
+            }
+            return None;
+        }
+
+        for victim_attrs in victims {
+            if let Some(victim) = trackers.remove(&victim_attrs) {
+                trackers.retain(|_, tracker| !Arc::ptr_eq(tracker, &victim));
+                evicted.insert(victim_attrs, victim);
+                self.count.fetch_sub(1, Ordering::SeqCst);
+            }
+        }
+        self.clear_bound();
+
+        // An attribute set evicted earlier in this collection cycle continues
+        // with the measurements it already received.
+        let tracker = reinstated.unwrap_or_else(|| self.new_tracker());
+        trackers.insert(attributes.to_vec(), tracker.clone());
+        trackers.insert(sorted_attrs, tracker.clone());
+        self.count.fetch_add(1, Ordering::SeqCst);
+        self.tracker_inserted();
+        Some(tracker)
+    }
+
     /// Iterate through all attribute sets and populate `DataPoints` in readonly mode.
     /// This is used in Cumulative temporality mode, where [`ValueMap`] is not cleared.
+    ///
+    /// `map_fn` also receives the start time of attribute sets that started
+    /// after the stream. Attribute sets that were not updated for the idle
+    /// timeout of the stream are evicted instead of collected.
     pub(crate) fn collect_readonly<Res, MapFn>(&self, dest: &mut Vec<Res>, mut map_fn: MapFn)
     where
-        MapFn: FnMut(Vec<KeyValue>, &A) -> Res,
+        MapFn: FnMut(Vec<KeyValue>, &A, Option<SystemTime>) -> Res,
     {
         prepare_data(dest, self.count.load(Ordering::SeqCst));
         if self.has_no_attribute_value.load(Ordering::Acquire) {
-            dest.push(map_fn(vec![], &self.no_attribute_tracker));
+            dest.push(map_fn(vec![], &self.no_attribute_tracker, None));
         }
 
         let Ok(trackers) = self.trackers.read() else {
             return;
         };
+        // Bound attribute sets resolve their trackers again, so the ones no
+        // longer in use are released.
+        self.clear_bound();
+
+        let collection = self.collections.fetch_add(1, Ordering::Relaxed);
+        let is_idle = |tracker: &Tracker<A>| {
+            self.cardinality_limit.idle_timeout.is_some_and(|timeout| {
+                let last_collection = tracker.last_collection.load(Ordering::Relaxed);
+                collection.saturating_sub(last_collection) >= timeout as u64
+            })
+        };
 
         let mut seen = HashSet::new();
+        let mut has_idle = false;
         for (attrs, tracker) in trackers.iter() {
-            if seen.insert(Arc::as_ptr(tracker)) {
-                dest.push(map_fn(attrs.clone(), tracker));
+            if !seen.insert(Arc::as_ptr(tracker)) {
+                continue;
+            }
+            if is_idle(tracker) {
+                has_idle = true;
+            } else {
+                dest.push(map_fn(attrs.clone(), tracker, tracker.start_time));
             }
         }
+        drop(trackers);
+
+        if has_idle {
+            self.evict_idle(is_idle);
+        }
+    }
+
+    /// Removes the trackers for which `is_idle` returns `true`.
+    fn evict_idle(&self, is_idle: impl Fn(&Tracker<A>) -> bool) {
+        let Ok(mut trackers) = self.trackers.write() else {
+            return;
+        };
+
+        // Recheck each tracker once, it may have been updated in the meantime.
+        let mut idle = HashMap::new();
+        let mut evicted = 0;
+        trackers.retain(|attrs, tracker| {
+            let ptr = Arc::as_ptr(tracker);
+            let is_new = !idle.contains_key(&ptr);
+            let is_idle = *idle.entry(ptr).or_insert_with(|| is_idle(tracker));
+            if is_new && is_idle && attrs != stream_overflow_attributes() {
+                evicted += 1;
+            }
+            !is_idle
+        });
+        self.count.fetch_sub(evicted, Ordering::SeqCst);
+        self.clear_bound();
     }
 
     /// Iterate through all attribute sets, populate `DataPoints` and reset.
@@ -200,16 +579,21 @@ where
         }
 
         if let Ok(mut trackers_collect) = self.trackers_for_collect().write() {
-            if let Ok(mut trackers_current) = self.trackers.write() {
+            let evicted = if let Ok(mut trackers_current) = self.trackers.write() {
                 swap(trackers_collect.deref_mut(), trackers_current.deref_mut());
                 self.count.store(0, Ordering::SeqCst);
+                self.clear_bound();
+                self.evicted
+                    .lock()
+                    .map(|mut evicted| take(evicted.deref_mut()))
+                    .unwrap_or_default()
             } else {
                 otel_warn!(name: "MeterProvider.InternalError", message = "Metric collection failed. Report this issue in OpenTelemetry repo.", details ="ValueMap trackers lock poisoned");
                 return;
-            }
+            };
 
             let mut seen = HashSet::new();
-            for (attrs, tracker) in trackers_collect.drain() {
+            for (attrs, tracker) in trackers_collect.drain().chain(evicted) {
                 if seen.insert(Arc::as_ptr(&tracker)) {
                     dest.push(map_fn(attrs, tracker.clone_and_reset(&self.config)));
                 }
@@ -239,6 +623,36 @@ fn sort_and_dedup(attributes: &[KeyValue]) -> Vec<KeyValue> {
     sorted
 }
 
+/// Keeps the `k` data points with the largest absolute values, adding the
+/// values of all other data points to the overflow data point.
+fn retain_top_k<T: Number>(data_points: &mut Vec<SumDataPoint<T>>, k: usize) {
+    let overflow_attrs = stream_overflow_attributes();
+    let overflow = data_points
+        .iter()
+        .position(|dp| &dp.attributes == overflow_attrs)
+        .map(|index| data_points.swap_remove(index));
+    if data_points.len() <= k {
+        data_points.extend(overflow);
+        return;
+    }
+
+    data_points.sort_unstable_by(|a, b| {
+        let (a, b) = (a.value.into_float().abs(), b.value.into_float().abs());
+        b.partial_cmp(&a).unwrap_or(CmpOrdering::Equal)
+    });
+    let mut overflow = overflow.unwrap_or_else(|| SumDataPoint {
+        attributes: overflow_attrs.clone(),
+        start_time: None,
+        value: T::default(),
+        exemplars: vec![],
+    });
+    for dp in data_points.drain(k..) {
+        overflow.value += dp.value;
+        overflow.exemplars.extend(dp.exemplars);
+    }
+    data_points.push(overflow);
+}
+
 /// Marks a type that can have a value added and retrieved atomically. Required since
 /// different types have different backing atomic mechanisms
 pub(crate) trait AtomicTracker<T>: Sync + Send + 'static {
@@ -282,6 +696,11 @@ pub(crate) trait Number:
     fn max() -> Self;
 
     fn into_float(self) -> f64;
+
+    /// Creates an exemplar reservoir for this measurement type.
+    fn new_exemplar_reservoir(
+        factory: &dyn exemplar::AnyReservoirFactory,
+    ) -> Box<dyn ExemplarReservoir<Self>>;
 }
 
 impl Number for i64 {
@@ -297,6 +716,12 @@ impl Number for i64 {
         // May have precision loss at high values
         self as f64
     }
+
+    fn new_exemplar_reservoir(
+        factory: &dyn exemplar::AnyReservoirFactory,
+    ) -> Box<dyn ExemplarReservoir<Self>> {
+        factory.create_i64()
+    }
 }
 impl Number for u64 {
     fn min() -> Self {
@@ -311,6 +736,12 @@ impl Number for u64 {
         // May have precision loss at high values
         self as f64
     }
+
+    fn new_exemplar_reservoir(
+        factory: &dyn exemplar::AnyReservoirFactory,
+    ) -> Box<dyn ExemplarReservoir<Self>> {
+        factory.create_u64()
+    }
 }
 impl Number for f64 {
     fn min() -> Self {
@@ -324,6 +755,12 @@ impl Number for f64 {
     fn into_float(self) -> f64 {
         self
     }
+
+    fn new_exemplar_reservoir(
+        factory: &dyn exemplar::AnyReservoirFactory,
+    ) -> Box<dyn ExemplarReservoir<Self>> {
+        factory.create_f64()
+    }
 }
 
 impl AggregatedMetricsAccess for i64 {
@@ -626,13 +1063,71 @@ mod tests {
         assert!(f64::abs(0.0 - value2) < 0.0001, "Incorrect second value");
     }
 
+    #[test]
+    fn evicts_least_recently_updated_in_batches() {
+        let value_map = ValueMap::<Assign<i64>>::new(
+            (),
+            CardinalityLimit {
+                limit: 20,
+                strategy: CardinalityLimitStrategy::EvictLeastRecentlyUpdated,
+                rejected: None,
+                idle_timeout: None,
+            },
+        );
+        let attributes = |i: i64| [KeyValue::new("i", i)];
+        for i in 0..20 {
+            value_map.measure(i, &attributes(i));
+        }
+        value_map.measure(0, &attributes(0));
+
+        // A tenth of the limit is evicted at once, the least recently updated
+        // first.
+        value_map.measure(20, &attributes(20));
+        assert_eq!(value_map.count.load(Ordering::SeqCst), 19);
+        value_map.measure(21, &attributes(21));
+        assert_eq!(value_map.count.load(Ordering::SeqCst), 20);
+        let mut evicted: Vec<_> = value_map.evicted.lock().unwrap().keys().cloned().collect();
+        evicted.sort_by_key(|attrs| attrs[0].value.to_string());
+        assert_eq!(
+            evicted,
+            vec![attributes(1).to_vec(), attributes(2).to_vec()]
+        );
+    }
+
     #[test]
     fn large_cardinality_limit() {
         // This is a regression test for panics that used to occur for large cardinality limits
 
         // Should not panic
-        let value_map = ValueMap::<Assign<i64>>::new((), usize::MAX);
+        let value_map = ValueMap::<Assign<i64>>::new((), usize::MAX.into());
         // Should not panic
         let _ = value_map.trackers_for_collect();
     }
+
+    #[test]
+    fn bound_attributes_cache_is_limited() {
+        let value_map = ValueMap::<Assign<u64>>::new((), 2.into());
+        let filter = AttributeSetFilter::new(None);
+        let attributes: Vec<_> = (0..5)
+            .map(|i| BoundAttributes::new(&[KeyValue::new("key", i)]))
+            .collect();
+
+        for _ in 0..2 {
+            for (i, attributes) in attributes.iter().enumerate() {
+                value_map.measure_bound(i as u64, attributes, &filter);
+            }
+        }
+
+        assert_eq!(value_map.bound.read().unwrap().len(), 2);
+        let overflow = value_map
+            .trackers
+            .read()
+            .unwrap()
+            .get(stream_overflow_attributes().as_slice())
+            .unwrap()
+            .aggregator
+            .value
+            .get_value();
+        assert_eq!(overflow, 4);
+    }
 }
@@ -4,7 +4,7 @@ use crate::metrics::data::{self, AggregatedMetrics, MetricData, SumDataPoint};
 use crate::metrics::Temporality;
 
 use super::aggregate::{AggregateTimeInitiator, AttributeSetFilter};
-use super::{last_value::Assign, AtomicTracker, Number, ValueMap};
+use super::{last_value::Assign, retain_top_k, AtomicTracker, CardinalityLimit, Number, ValueMap};
 use super::{ComputeAggregation, Measure};
 use std::{collections::HashMap, sync::Mutex};
 
@@ -23,7 +23,7 @@ impl<T: Number> PrecomputedSum<T> {
         temporality: Temporality,
         filter: AttributeSetFilter,
         monotonic: bool,
-        cardinality_limit: usize,
+        cardinality_limit: CardinalityLimit,
     ) -> Self {
         PrecomputedSum {
             value_map: ValueMap::new((), cardinality_limit),
@@ -75,6 +75,7 @@ impl<T: Number> PrecomputedSum<T> {
                 let delta = value - *reported.get(&attributes).unwrap_or(&T::default());
                 SumDataPoint {
                     attributes,
+                    start_time: None,
                     value: delta,
                     exemplars: vec![],
                 }
@@ -83,6 +84,10 @@ impl<T: Number> PrecomputedSum<T> {
         *reported = new_reported;
         drop(reported); // drop before values guard is dropped
 
+        if let Some(k) = self.value_map.top_k() {
+            retain_top_k(&mut s_data.data_points, k);
+        }
+
         (s_data.data_points.len(), new_agg.map(Into::into))
     }
 
@@ -117,10 +122,13 @@ impl<T: Number> PrecomputedSum<T> {
         s_data.is_monotonic = self.monotonic;
 
         self.value_map
-            .collect_readonly(&mut s_data.data_points, |attributes, aggr| SumDataPoint {
-                attributes,
-                value: aggr.value.get_value(),
-                exemplars: vec![],
+            .collect_readonly(&mut s_data.data_points, |attributes, aggr, start_time| {
+                SumDataPoint {
+                    attributes,
+                    start_time,
+                    value: aggr.value.get_value(),
+                    exemplars: vec![],
+                }
             });
 
         (s_data.data_points.len(), new_agg.map(Into::into))
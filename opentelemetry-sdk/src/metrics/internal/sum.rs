@@ -1,10 +1,12 @@
 use crate::metrics::data::{self, AggregatedMetrics, MetricData, SumDataPoint};
-use crate::metrics::Temporality;
-use opentelemetry::KeyValue;
+use crate::metrics::exemplar::SimpleFixedSizeExemplarReservoir;
+use crate::metrics::{ExemplarFilter, Temporality};
+use opentelemetry::{metrics::BoundAttributes, KeyValue};
 
 use super::aggregate::{AggregateTimeInitiator, AttributeSetFilter};
+use super::exemplar::{self, ExemplarConfig, ExemplarsInit, WithExemplars};
+use super::{retain_top_k, AtomicallyUpdate, CardinalityLimit, ValueMap};
 use super::{Aggregator, AtomicTracker, ComputeAggregation, Measure, Number};
-use super::{AtomicallyUpdate, ValueMap};
 
 struct Increment<T>
 where
@@ -18,7 +20,7 @@ where
     T: Number,
 {
     type InitConfig = ();
-    type PreComputedValue = T;
+    type PreComputedValue<'a> = T;
 
     fn create(_init: &()) -> Self {
         Self {
@@ -39,10 +41,11 @@ where
 
 /// Summarizes a set of measurements made as their arithmetic sum.
 pub(crate) struct Sum<T: Number> {
-    value_map: ValueMap<Increment<T>>,
+    value_map: ValueMap<WithExemplars<Increment<T>, T>>,
     init_time: AggregateTimeInitiator,
     temporality: Temporality,
     filter: AttributeSetFilter,
+    exemplar_filter: ExemplarFilter,
     monotonic: bool,
 }
 
@@ -56,13 +59,19 @@ impl<T: Number> Sum<T> {
         temporality: Temporality,
         filter: AttributeSetFilter,
         monotonic: bool,
-        cardinality_limit: usize,
+        cardinality_limit: CardinalityLimit,
+        exemplars: ExemplarConfig<T>,
     ) -> Self {
+        let init = ExemplarsInit {
+            aggregator: (),
+            reservoir: exemplars.reservoir_factory(|| SimpleFixedSizeExemplarReservoir::new(1)),
+        };
         Sum {
-            value_map: ValueMap::new((), cardinality_limit),
+            value_map: ValueMap::new(init, cardinality_limit),
             init_time: AggregateTimeInitiator::default(),
             temporality,
             filter,
+            exemplar_filter: exemplars.filter,
             monotonic,
         }
     }
@@ -96,9 +105,13 @@ impl<T: Number> Sum<T> {
         self.value_map
             .collect_and_reset(&mut s_data.data_points, |attributes, aggr| SumDataPoint {
                 attributes,
-                value: aggr.value.get_value(),
-                exemplars: vec![],
+                start_time: None,
+                value: aggr.aggregator.value.get_value(),
+                exemplars: aggr.collect_exemplars(),
             });
+        if let Some(k) = self.value_map.top_k() {
+            retain_top_k(&mut s_data.data_points, k);
+        }
 
         (s_data.data_points.len(), new_agg.map(Into::into))
     }
@@ -134,10 +147,13 @@ impl<T: Number> Sum<T> {
         s_data.is_monotonic = self.monotonic;
 
         self.value_map
-            .collect_readonly(&mut s_data.data_points, |attributes, aggr| SumDataPoint {
-                attributes,
-                value: aggr.value.get_value(),
-                exemplars: vec![],
+            .collect_readonly(&mut s_data.data_points, |attributes, aggr, start_time| {
+                SumDataPoint {
+                    attributes,
+                    start_time,
+                    value: aggr.aggregator.value.get_value(),
+                    exemplars: aggr.collect_exemplars(),
+                }
             });
 
         (s_data.data_points.len(), new_agg.map(Into::into))
@@ -149,10 +165,22 @@ where
     T: Number,
 {
     fn call(&self, measurement: T, attrs: &[KeyValue]) {
+        let exemplar = exemplar::sample(self.exemplar_filter, measurement, attrs, &self.filter);
         self.filter.apply(attrs, |filtered| {
-            self.value_map.measure(measurement, filtered);
+            self.value_map.measure((measurement, exemplar), filtered);
         })
     }
+
+    fn call_bound(&self, measurement: T, attrs: &BoundAttributes) {
+        let exemplar = exemplar::sample(
+            self.exemplar_filter,
+            measurement,
+            attrs.attributes(),
+            &self.filter,
+        );
+        self.value_map
+            .measure_bound((measurement, exemplar), attrs, &self.filter);
+    }
 }
 
 impl<T> ComputeAggregation for Sum<T>
@@ -2,12 +2,12 @@ use opentelemetry::otel_debug;
 use std::time::Duration;
 use std::{
     fmt,
-    sync::{Mutex, Weak},
+    sync::{Arc, Mutex, Weak},
 };
 
 use crate::{
     error::{OTelSdkError, OTelSdkResult},
-    metrics::Temporality,
+    metrics::{aggregation::Aggregation, InstrumentKind, Temporality},
 };
 
 use super::{
@@ -32,8 +32,11 @@ use super::{
 pub struct ManualReader {
     inner: Mutex<ManualReaderInner>,
     temporality: Temporality,
+    aggregation_selector: Option<AggregationSelector>,
 }
 
+type AggregationSelector = Arc<dyn Fn(InstrumentKind) -> Aggregation + Send + Sync>;
+
 impl Default for ManualReader {
     fn default() -> Self {
         ManualReader::builder().build()
@@ -59,13 +62,17 @@ impl ManualReader {
     }
 
     /// A [MetricReader] which is directly called to collect metrics.
-    pub(crate) fn new(temporality: Temporality) -> Self {
+    pub(crate) fn new(
+        temporality: Temporality,
+        aggregation_selector: Option<AggregationSelector>,
+    ) -> Self {
         ManualReader {
             inner: Mutex::new(ManualReaderInner {
                 sdk_producer: None,
                 is_shutdown: false,
             }),
             temporality,
+            aggregation_selector,
         }
     }
 }
@@ -127,15 +134,23 @@ impl MetricReader for ManualReader {
         Ok(())
     }
 
-    fn temporality(&self, kind: super::InstrumentKind) -> Temporality {
+    fn temporality(&self, kind: InstrumentKind) -> Temporality {
         kind.temporality_preference(self.temporality)
     }
+
+    fn aggregation(&self, kind: InstrumentKind) -> Aggregation {
+        match &self.aggregation_selector {
+            Some(selector) => selector(kind),
+            None => Aggregation::Default,
+        }
+    }
 }
 
 /// Configuration for a [ManualReader]
 #[derive(Default)]
 pub struct ManualReaderBuilder {
     temporality: Temporality,
+    aggregation_selector: Option<AggregationSelector>,
 }
 
 impl fmt::Debug for ManualReaderBuilder {
@@ -156,8 +171,42 @@ impl ManualReaderBuilder {
         self
     }
 
+    /// Set the default [Aggregation] of the reader, a function of instrument
+    /// kind.
+    ///
+    /// It is used for instruments whose view does not set an aggregation.
+    /// Returning [Aggregation::Default] uses the default aggregation of the
+    /// instrument kind.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use opentelemetry_sdk::metrics::{Aggregation, InstrumentKind, ManualReader};
+    ///
+    /// // Use exponential histograms for all histogram instruments.
+    /// let reader = ManualReader::builder()
+    ///     .with_aggregation_selector(|kind| match kind {
+    ///         InstrumentKind::Histogram => Aggregation::Base2ExponentialHistogram {
+    ///             max_size: 160,
+    ///             max_scale: 20,
+    ///             record_min_max: true,
+    ///         },
+    ///         _ => Aggregation::Default,
+    ///     })
+    ///     .build();
+    /// # drop(reader)
+    /// ```
+    #[cfg(feature = "spec_unstable_metrics_views")]
+    pub fn with_aggregation_selector(
+        mut self,
+        selector: impl Fn(InstrumentKind) -> Aggregation + Send + Sync + 'static,
+    ) -> Self {
+        self.aggregation_selector = Some(Arc::new(selector));
+        self
+    }
+
     /// Create a new [ManualReader] from this configuration.
     pub fn build(self) -> ManualReader {
-        ManualReader::new(self.temporality)
+        ManualReader::new(self.temporality, self.aggregation_selector)
     }
 }
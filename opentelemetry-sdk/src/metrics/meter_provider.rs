@@ -16,7 +16,7 @@ use crate::error::OTelSdkResult;
 use crate::Resource;
 
 use super::{
-    exporter::PushMetricExporter, meter::SdkMeter, noop::NoopMeter,
+    exemplar::ExemplarFilter, exporter::PushMetricExporter, meter::SdkMeter, noop::NoopMeter,
     periodic_reader::PeriodicReader, pipeline::Pipelines, reader::MetricReader, view::View,
     Instrument, Stream,
 };
@@ -233,6 +233,9 @@ pub struct MeterProviderBuilder {
     resource: Option<Resource>,
     readers: Vec<Box<dyn MetricReader>>,
     views: Vec<Arc<dyn View>>,
+    exemplar_filter: Option<ExemplarFilter>,
+    #[cfg(feature = "experimental_self_observability")]
+    meter: Option<Meter>,
 }
 
 impl MeterProviderBuilder {
@@ -372,6 +375,40 @@ impl MeterProviderBuilder {
         self
     }
 
+    /// Sets the [ExemplarFilter] deciding which measurements are eligible to
+    /// be sampled as exemplars.
+    ///
+    /// If this is not set, the filter is read from the
+    /// `OTEL_METRICS_EXEMPLAR_FILTER` environment variable (`always_on`,
+    /// `always_off` or `trace_based`), defaulting to
+    /// [ExemplarFilter::TraceBased].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use opentelemetry_sdk::metrics::{ExemplarFilter, SdkMeterProvider};
+    ///
+    /// let provider = SdkMeterProvider::builder()
+    ///     .with_exemplar_filter(ExemplarFilter::AlwaysOn)
+    ///     .build();
+    /// ```
+    pub fn with_exemplar_filter(mut self, filter: ExemplarFilter) -> Self {
+        self.exemplar_filter = Some(filter);
+        self
+    }
+
+    /// Reports the [self-observability](crate::self_observability) metrics of
+    /// the readers of the provider, e.g.
+    /// `otel.sdk.metric_reader.collection.duration`, through `meter_provider`.
+    ///
+    /// `meter_provider` should be a different provider than the one being
+    /// built, e.g. one exporting to a different backend.
+    #[cfg(feature = "experimental_self_observability")]
+    pub fn with_self_observability(mut self, meter_provider: &impl MeterProvider) -> Self {
+        self.meter = Some(crate::self_observability::meter(meter_provider));
+        self
+    }
+
     /// Construct a new [MeterProvider] with this configuration.
     pub fn build(self) -> SdkMeterProvider {
         otel_debug!(
@@ -379,12 +416,21 @@ impl MeterProviderBuilder {
             builder = format!("{:?}", &self),
         );
 
+        #[cfg(feature = "experimental_self_observability")]
+        if let Some(meter) = &self.meter {
+            for reader in &self.readers {
+                reader.set_meter(meter);
+            }
+        }
+
         let meter_provider = SdkMeterProvider {
             inner: Arc::new(SdkMeterProviderInner {
                 pipes: Arc::new(Pipelines::new(
                     self.resource.unwrap_or(Resource::builder().build()),
                     self.readers,
                     self.views,
+                    self.exemplar_filter
+                        .unwrap_or_else(ExemplarFilter::from_env),
                 )),
                 meters: Default::default(),
                 shutdown_invoked: AtomicBool::new(false),
@@ -404,6 +450,7 @@ impl fmt::Debug for MeterProviderBuilder {
             .field("resource", &self.resource)
             .field("readers", &self.readers)
             .field("views", &self.views.len())
+            .field("exemplar_filter", &self.exemplar_filter)
             .finish()
     }
 }
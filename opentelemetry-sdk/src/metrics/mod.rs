@@ -42,8 +42,11 @@
 #[allow(unreachable_pub)]
 #[allow(unused)]
 pub(crate) mod aggregation;
+#[cfg(feature = "spec_unstable_metrics_views")]
+pub(crate) mod attribute_processor;
 pub mod data;
 mod error;
+mod exemplar;
 pub mod exporter;
 pub(crate) mod instrument;
 pub(crate) mod internal;
@@ -57,10 +60,13 @@ pub(crate) mod periodic_reader;
 /// Module for periodic reader with async runtime.
 pub mod periodic_reader_with_async_runtime;
 pub(crate) mod pipeline;
+#[cfg(feature = "experimental_metrics_prometheus_reader")]
+pub(crate) mod prometheus;
 #[cfg(feature = "experimental_metrics_custom_reader")]
 pub mod reader;
 #[cfg(not(feature = "experimental_metrics_custom_reader"))]
 pub(crate) mod reader;
+mod temporality_converter;
 pub(crate) mod view;
 
 /// In-Memory metric exporter for testing purpose.
@@ -73,14 +79,23 @@ pub use in_memory_exporter::{InMemoryMetricExporter, InMemoryMetricExporterBuild
 
 #[cfg(feature = "spec_unstable_metrics_views")]
 pub use aggregation::*;
+#[cfg(feature = "spec_unstable_metrics_views")]
+pub use attribute_processor::AttributeProcessor;
 #[cfg(feature = "experimental_metrics_custom_reader")]
 pub use manual_reader::*;
 pub use meter_provider::*;
 pub use periodic_reader::*;
 #[cfg(feature = "experimental_metrics_custom_reader")]
 pub use pipeline::Pipeline;
+#[cfg(feature = "experimental_metrics_prometheus_reader")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental_metrics_prometheus_reader")))]
+pub use prometheus::{ExpositionFormat, PrometheusReader, PrometheusReaderBuilder};
 
-pub use instrument::{Instrument, InstrumentKind, Stream, StreamBuilder};
+pub use exemplar::{
+    ExemplarFilter, ExemplarMeasurement, ExemplarReservoir, ExemplarReservoirFactory,
+};
+pub use instrument::{CardinalityLimitStrategy, Instrument, InstrumentKind, Stream, StreamBuilder};
+pub use temporality_converter::{TemporalityConverter, TemporalityConverterBuilder};
 
 use std::hash::Hash;
 
@@ -110,7 +125,7 @@ pub enum Temporality {
 #[cfg(all(test, feature = "testing"))]
 mod tests {
     use self::data::{HistogramDataPoint, ScopeMetrics, SumDataPoint};
-    use super::data::MetricData;
+    use super::data::{AggregatedMetrics, MetricData};
     use super::internal::Number;
     use super::*;
     use crate::metrics::data::ResourceMetrics;
@@ -118,7 +133,7 @@ mod tests {
     use crate::metrics::InMemoryMetricExporter;
     use crate::metrics::InMemoryMetricExporterBuilder;
     use data::GaugeDataPoint;
-    use opentelemetry::metrics::{Counter, Meter, UpDownCounter};
+    use opentelemetry::metrics::{BoundAttributes, Counter, Meter, UpDownCounter};
     use opentelemetry::InstrumentationScope;
     use opentelemetry::Value;
     use opentelemetry::{metrics::MeterProvider as _, KeyValue};
@@ -377,6 +392,371 @@ mod tests {
         counter_aggregation_overflow_helper_custom_limit(Temporality::Cumulative);
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn counter_cardinality_limit_reject() {
+        // cargo test counter_cardinality_limit_reject --features=testing
+
+        // Arrange
+        let view = |i: &Instrument| {
+            if i.name == "my_counter" {
+                Some(
+                    Stream::builder()
+                        .with_cardinality_limit(3)
+                        .with_cardinality_limit_strategy(CardinalityLimitStrategy::Reject)
+                        .build()
+                        .unwrap(),
+                )
+            } else {
+                None
+            }
+        };
+        let test_context = TestContext::new_with_view(Temporality::Cumulative, view);
+        let counter = test_context.u64_counter("test", "my_counter", None);
+
+        // Act
+        for v in 0..3 {
+            counter.add(10, &[KeyValue::new("A", v.to_string())]);
+        }
+        // Existing attribute sets are still updated once the limit is reached.
+        counter.add(10, &[KeyValue::new("A", "0")]);
+        // New attribute sets are dropped.
+        counter.add(100, &[KeyValue::new("A", "foo")]);
+        counter.add(100, &[KeyValue::new("A", "bar")]);
+        counter.add(100, &[KeyValue::new("A", "foo")]);
+        test_context.flush_metrics();
+
+        // Assert
+        let resource_metrics = test_context
+            .exporter
+            .get_finished_metrics()
+            .expect("metrics are expected to be exported.");
+        let scope_metrics = |name: &str| {
+            resource_metrics[0]
+                .scope_metrics
+                .iter()
+                .find(|scope_metrics| scope_metrics.scope.name() == name)
+                .expect("metrics of the scope expected")
+        };
+
+        let metric = scope_metrics("test")
+            .metrics
+            .iter()
+            .find(|m| m.name == "my_counter")
+            .unwrap();
+        let MetricData::Sum(sum) = u64::extract_metrics_data_ref(&metric.data).unwrap() else {
+            unreachable!()
+        };
+        assert_eq!(sum.data_points.len(), 3);
+        assert!(find_overflow_sum_datapoint(&sum.data_points).is_none());
+        let data_point =
+            find_sum_datapoint_with_key_value(&sum.data_points, "A", "0").expect("point expected");
+        assert_eq!(data_point.value, 20);
+
+        let metric = scope_metrics("opentelemetry_sdk")
+            .metrics
+            .iter()
+            .find(|m| m.name == "otel.sdk.metric.cardinality_limit.rejected")
+            .expect("rejected measurements counter expected");
+        assert_eq!(metric.unit, "{measurement}");
+        let MetricData::Sum(rejected) = u64::extract_metrics_data_ref(&metric.data).unwrap() else {
+            unreachable!()
+        };
+        assert!(rejected.is_monotonic);
+        let data_point = find_sum_datapoint_with_key_value(
+            &rejected.data_points,
+            "otel.metric.name",
+            "my_counter",
+        )
+        .expect("point expected");
+        assert_eq!(data_point.value, 3);
+        assert!(data_point
+            .attributes
+            .iter()
+            .any(|kv| kv.key.as_str() == "otel.scope.name" && kv.value.as_str() == "test"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn counter_cardinality_limit_evict_least_recently_updated() {
+        // cargo test counter_cardinality_limit_evict_least_recently_updated --features=testing
+
+        // Arrange
+        let view = |i: &Instrument| {
+            if i.name == "my_counter" {
+                Some(
+                    Stream::builder()
+                        .with_cardinality_limit(2)
+                        .with_cardinality_limit_strategy(
+                            CardinalityLimitStrategy::EvictLeastRecentlyUpdated,
+                        )
+                        .build()
+                        .unwrap(),
+                )
+            } else {
+                None
+            }
+        };
+        let mut test_context = TestContext::new_with_view(Temporality::Delta, view);
+        let counter = test_context.u64_counter("test", "my_counter", None);
+
+        // Act
+        counter.add(1, &[KeyValue::new("A", "a")]);
+        counter.add(2, &[KeyValue::new("A", "b")]);
+        counter.add(3, &[KeyValue::new("A", "a")]);
+        // "b" is the least recently updated attribute set and makes room for "c".
+        counter.add(4, &[KeyValue::new("A", "c")]);
+        test_context.flush_metrics();
+
+        // Assert
+        let MetricData::Sum(sum) = test_context.get_aggregation::<u64>("my_counter", None) else {
+            unreachable!()
+        };
+        assert_eq!(sum.data_points.len(), 3);
+        assert!(find_overflow_sum_datapoint(&sum.data_points).is_none());
+        let data_point =
+            find_sum_datapoint_with_key_value(&sum.data_points, "A", "a").expect("point expected");
+        assert_eq!(data_point.value, 4);
+        // The evicted attribute set is still exported with its measurements.
+        let data_point =
+            find_sum_datapoint_with_key_value(&sum.data_points, "A", "b").expect("point expected");
+        assert_eq!(data_point.value, 2);
+        let data_point =
+            find_sum_datapoint_with_key_value(&sum.data_points, "A", "c").expect("point expected");
+        assert_eq!(data_point.value, 4);
+
+        // Evicted attribute sets are not exported again.
+        test_context.reset_metrics();
+        counter.add(5, &[KeyValue::new("A", "d")]);
+        test_context.flush_metrics();
+
+        let MetricData::Sum(sum) = test_context.get_aggregation::<u64>("my_counter", None) else {
+            unreachable!()
+        };
+        assert_eq!(sum.data_points.len(), 1);
+        let data_point =
+            find_sum_datapoint_with_key_value(&sum.data_points, "A", "d").expect("point expected");
+        assert_eq!(data_point.value, 5);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn counter_cardinality_limit_top_k() {
+        // cargo test counter_cardinality_limit_top_k --features=testing
+
+        // Arrange
+        let view = |i: &Instrument| {
+            if i.name == "my_counter" {
+                Some(
+                    Stream::builder()
+                        .with_cardinality_limit_strategy(CardinalityLimitStrategy::TopK(2))
+                        .build()
+                        .unwrap(),
+                )
+            } else {
+                None
+            }
+        };
+        let mut test_context = TestContext::new_with_view(Temporality::Delta, view);
+        let counter = test_context.u64_counter("test", "my_counter", None);
+
+        // Act
+        counter.add(1, &[KeyValue::new("A", "a")]);
+        counter.add(5, &[KeyValue::new("A", "b")]);
+        counter.add(3, &[KeyValue::new("A", "c")]);
+        counter.add(10, &[KeyValue::new("A", "d")]);
+        test_context.flush_metrics();
+
+        // Assert
+        let MetricData::Sum(sum) = test_context.get_aggregation::<u64>("my_counter", None) else {
+            unreachable!()
+        };
+        assert_eq!(sum.data_points.len(), 3);
+        let data_point =
+            find_sum_datapoint_with_key_value(&sum.data_points, "A", "d").expect("point expected");
+        assert_eq!(data_point.value, 10);
+        let data_point =
+            find_sum_datapoint_with_key_value(&sum.data_points, "A", "b").expect("point expected");
+        assert_eq!(data_point.value, 5);
+        let data_point =
+            find_overflow_sum_datapoint(&sum.data_points).expect("overflow point expected");
+        assert_eq!(data_point.value, 4);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn counter_cardinality_limit_top_k_cumulative_overflows() {
+        // cargo test counter_cardinality_limit_top_k_cumulative_overflows --features=testing
+
+        // Arrange
+        let view = |i: &Instrument| {
+            if i.name == "my_counter" {
+                Some(
+                    Stream::builder()
+                        .with_cardinality_limit_strategy(CardinalityLimitStrategy::TopK(1))
+                        .build()
+                        .unwrap(),
+                )
+            } else {
+                None
+            }
+        };
+        let mut test_context = TestContext::new_with_view(Temporality::Cumulative, view);
+        let counter = test_context.u64_counter("test", "my_counter", None);
+
+        // Act
+        counter.add(5, &[KeyValue::new("A", "a")]);
+        counter.add(1, &[KeyValue::new("A", "b")]);
+        test_context.flush_metrics();
+        test_context.reset_metrics();
+        counter.add(10, &[KeyValue::new("A", "b")]);
+        test_context.flush_metrics();
+
+        // Assert
+        let MetricData::Sum(sum) = test_context.get_aggregation::<u64>("my_counter", None) else {
+            unreachable!()
+        };
+        assert_eq!(sum.data_points.len(), 2);
+        assert!(find_overflow_sum_datapoint(&sum.data_points).is_none());
+        let data_point =
+            find_sum_datapoint_with_key_value(&sum.data_points, "A", "a").expect("point expected");
+        assert_eq!(data_point.value, 5);
+        let data_point =
+            find_sum_datapoint_with_key_value(&sum.data_points, "A", "b").expect("point expected");
+        assert_eq!(data_point.value, 11);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn counter_idle_attribute_sets_evicted_cumulative() {
+        // cargo test counter_idle_attribute_sets_evicted_cumulative --features=testing
+
+        // Arrange
+        let view = |i: &Instrument| {
+            if i.name == "my_counter" {
+                Some(Stream::builder().with_idle_timeout(1).build().unwrap())
+            } else {
+                None
+            }
+        };
+        let mut test_context = TestContext::new_with_view(Temporality::Cumulative, view);
+        let counter = test_context.u64_counter("test", "my_counter", None);
+
+        // Act
+        counter.add(1, &[KeyValue::new("pod", "a")]);
+        counter.add(2, &[KeyValue::new("pod", "b")]);
+        test_context.flush_metrics();
+
+        // Assert
+        let MetricData::Sum(sum) = test_context.get_aggregation::<u64>("my_counter", None) else {
+            unreachable!()
+        };
+        assert_eq!(sum.data_points.len(), 2);
+        let data_point = find_sum_datapoint_with_key_value(&sum.data_points, "pod", "b")
+            .expect("point expected");
+        assert_eq!(data_point.value, 2);
+        let first_start_time = data_point.start_time().expect("start time expected");
+        assert!(first_start_time >= sum.start_time);
+
+        // "b" is not updated during the next collection cycle and is evicted.
+        test_context.reset_metrics();
+        counter.add(1, &[KeyValue::new("pod", "a")]);
+        test_context.flush_metrics();
+
+        let MetricData::Sum(sum) = test_context.get_aggregation::<u64>("my_counter", None) else {
+            unreachable!()
+        };
+        assert_eq!(sum.data_points.len(), 1);
+        let data_point = find_sum_datapoint_with_key_value(&sum.data_points, "pod", "a")
+            .expect("point expected");
+        assert_eq!(data_point.value, 2);
+
+        // "b" starts over when it is recorded again.
+        test_context.reset_metrics();
+        counter.add(5, &[KeyValue::new("pod", "b")]);
+        test_context.flush_metrics();
+
+        let MetricData::Sum(sum) = test_context.get_aggregation::<u64>("my_counter", None) else {
+            unreachable!()
+        };
+        assert_eq!(sum.data_points.len(), 1);
+        let data_point = find_sum_datapoint_with_key_value(&sum.data_points, "pod", "b")
+            .expect("point expected");
+        assert_eq!(data_point.value, 5);
+        assert!(data_point.start_time().expect("start time expected") > first_start_time);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn histogram_idle_attribute_sets_evicted_cumulative() {
+        // cargo test histogram_idle_attribute_sets_evicted_cumulative --features=testing
+
+        // Arrange
+        let view = |i: &Instrument| {
+            if i.name == "my_histogram" {
+                Some(Stream::builder().with_idle_timeout(2).build().unwrap())
+            } else {
+                None
+            }
+        };
+        let mut test_context = TestContext::new_with_view(Temporality::Cumulative, view);
+        let histogram = test_context.meter().f64_histogram("my_histogram").build();
+
+        // Act
+        histogram.record(1.0, &[KeyValue::new("pod", "a")]);
+        histogram.record(2.0, &[KeyValue::new("pod", "b")]);
+        test_context.flush_metrics();
+        test_context.reset_metrics();
+        histogram.record(3.0, &[KeyValue::new("pod", "a")]);
+        test_context.flush_metrics();
+
+        // Assert
+        // "b" was not updated for one collection, which is below the timeout.
+        let MetricData::Histogram(histogram_data) =
+            test_context.get_aggregation::<f64>("my_histogram", None)
+        else {
+            unreachable!()
+        };
+        assert_eq!(histogram_data.data_points.len(), 2);
+
+        test_context.reset_metrics();
+        histogram.record(4.0, &[KeyValue::new("pod", "a")]);
+        test_context.flush_metrics();
+
+        let MetricData::Histogram(histogram_data) =
+            test_context.get_aggregation::<f64>("my_histogram", None)
+        else {
+            unreachable!()
+        };
+        assert_eq!(histogram_data.data_points.len(), 1);
+        let data_point =
+            find_histogram_datapoint_with_key_value(&histogram_data.data_points, "pod", "a")
+                .expect("point expected");
+        assert_eq!(data_point.count, 3);
+        assert_eq!(data_point.sum, 8.0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn counter_idle_timeout_ignored_delta() {
+        // cargo test counter_idle_timeout_ignored_delta --features=testing
+
+        // Arrange
+        let view = |i: &Instrument| {
+            if i.name == "my_counter" {
+                Some(Stream::builder().with_idle_timeout(1).build().unwrap())
+            } else {
+                None
+            }
+        };
+        let mut test_context = TestContext::new_with_view(Temporality::Delta, view);
+        let counter = test_context.u64_counter("test", "my_counter", None);
+
+        // Act
+        counter.add(1, &[KeyValue::new("pod", "a")]);
+        test_context.flush_metrics();
+
+        // Assert
+        let MetricData::Sum(sum) = test_context.get_aggregation::<u64>("my_counter", None) else {
+            unreachable!()
+        };
+        assert_eq!(sum.data_points.len(), 1);
+        assert_eq!(sum.data_points[0].start_time(), None);
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
     async fn counter_aggregation_attribute_order_sorted_first_delta() {
         // Run this test with stdout enabled to see output.
@@ -385,26 +765,192 @@ mod tests {
     }
 
     #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
-    async fn counter_aggregation_attribute_order_sorted_first_cumulative() {
-        // Run this test with stdout enabled to see output.
-        // cargo test counter_aggregation_attribute_order_sorted_first_cumulative --features=testing -- --nocapture
-        counter_aggregation_attribute_order_helper(Temporality::Cumulative, true);
-    }
+    async fn counter_aggregation_attribute_order_sorted_first_cumulative() {
+        // Run this test with stdout enabled to see output.
+        // cargo test counter_aggregation_attribute_order_sorted_first_cumulative --features=testing -- --nocapture
+        counter_aggregation_attribute_order_helper(Temporality::Cumulative, true);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn counter_aggregation_attribute_order_unsorted_first_delta() {
+        // Run this test with stdout enabled to see output.
+        // cargo test counter_aggregation_attribute_order_unsorted_first_delta --features=testing -- --nocapture
+
+        counter_aggregation_attribute_order_helper(Temporality::Delta, false);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn counter_aggregation_attribute_order_unsorted_first_cumulative() {
+        // Run this test with stdout enabled to see output.
+        // cargo test counter_aggregation_attribute_order_unsorted_first_cumulative --features=testing -- --nocapture
+
+        counter_aggregation_attribute_order_helper(Temporality::Cumulative, false);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn counter_bound_attributes_delta() {
+        // cargo test counter_bound_attributes_delta --features=testing
+        counter_bound_attributes_helper(Temporality::Delta);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn counter_bound_attributes_cumulative() {
+        // cargo test counter_bound_attributes_cumulative --features=testing
+        counter_bound_attributes_helper(Temporality::Cumulative);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn bound_attributes_multiple_instruments() {
+        // cargo test bound_attributes_multiple_instruments --features=testing
+
+        // Arrange
+        let exporter = InMemoryMetricExporter::default();
+        let meter_provider = SdkMeterProvider::builder()
+            .with_periodic_exporter(exporter.clone())
+            .build();
+        let meter = meter_provider.meter("test");
+        let counter = meter.u64_counter("my_counter").build();
+        let up_down_counter = meter.i64_up_down_counter("my_up_down_counter").build();
+        let histogram = meter
+            .f64_histogram("my_histogram")
+            .with_boundaries(vec![1.0, 10.0])
+            .build();
+        let gauge = meter.u64_gauge("my_gauge").build();
+        let attributes = BoundAttributes::new(&[KeyValue::new("key1", "value1")]);
+
+        // Act
+        for i in 0..5 {
+            counter.add_bound(1, &attributes);
+            up_down_counter.add_bound(-1, &attributes);
+            histogram.record_bound(i as f64 * 3.0, &attributes);
+            gauge.record_bound(i, &attributes);
+        }
+        meter_provider.force_flush().unwrap();
+
+        // Assert
+        let resource_metrics = exporter
+            .get_finished_metrics()
+            .expect("metrics are expected to be exported.");
+        let metrics = &resource_metrics[0].scope_metrics[0].metrics;
+        assert_eq!(metrics.len(), 4);
+        for metric in metrics {
+            match (metric.name(), metric.data()) {
+                ("my_counter", AggregatedMetrics::U64(MetricData::Sum(sum))) => {
+                    let data_point =
+                        find_sum_datapoint_with_key_value(&sum.data_points, "key1", "value1")
+                            .expect("datapoint with key1=value1 expected");
+                    assert_eq!(data_point.value, 5);
+                }
+                ("my_up_down_counter", AggregatedMetrics::I64(MetricData::Sum(sum))) => {
+                    assert_eq!(sum.data_points.len(), 1);
+                    assert_eq!(sum.data_points[0].value, -5);
+                }
+                ("my_histogram", AggregatedMetrics::F64(MetricData::Histogram(histogram))) => {
+                    let data_point = find_histogram_datapoint_with_key_value(
+                        &histogram.data_points,
+                        "key1",
+                        "value1",
+                    )
+                    .expect("datapoint with key1=value1 expected");
+                    assert_eq!(data_point.count, 5);
+                    assert_eq!(data_point.sum, 30.0);
+                    assert_eq!(data_point.bucket_counts, vec![1, 3, 1]);
+                }
+                ("my_gauge", AggregatedMetrics::U64(MetricData::Gauge(gauge))) => {
+                    assert_eq!(gauge.data_points.len(), 1);
+                    assert_eq!(gauge.data_points[0].value, 4);
+                }
+                (name, _) => panic!("unexpected metric {name}"),
+            }
+        }
+    }
+
+    #[cfg(feature = "spec_unstable_metrics_views")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn counter_bound_attributes_with_view_filter() {
+        // cargo test counter_bound_attributes_with_view_filter --features=testing
+
+        // Arrange
+        let view = |i: &Instrument| {
+            if i.name == "my_counter" {
+                Some(
+                    Stream::builder()
+                        .with_allowed_attribute_keys(vec!["key1".into()])
+                        .build()
+                        .unwrap(),
+                )
+            } else {
+                None
+            }
+        };
+        let mut test_context = TestContext::new_with_view(Temporality::Cumulative, view);
+        let counter = test_context.u64_counter("test", "my_counter", None);
+        let attributes = BoundAttributes::new(&[
+            KeyValue::new("key1", "value1"),
+            KeyValue::new("key2", "dropped"),
+        ]);
+
+        // Act
+        counter.add_bound(1, &attributes);
+        counter.add_bound(1, &attributes);
+        counter.add(1, &[KeyValue::new("key1", "value1")]);
+        test_context.flush_metrics();
+
+        // Assert
+        let MetricData::Sum(sum) = test_context.get_aggregation::<u64>("my_counter", None) else {
+            unreachable!()
+        };
+        assert_eq!(sum.data_points.len(), 1);
+        assert_eq!(
+            sum.data_points[0].attributes,
+            vec![KeyValue::new("key1", "value1")]
+        );
+        assert_eq!(sum.data_points[0].value, 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn counter_bound_attributes_overflow_delta() {
+        // cargo test counter_bound_attributes_overflow_delta --features=testing
 
-    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
-    async fn counter_aggregation_attribute_order_unsorted_first_delta() {
-        // Run this test with stdout enabled to see output.
-        // cargo test counter_aggregation_attribute_order_unsorted_first_delta --features=testing -- --nocapture
+        // Arrange
+        let view = |i: &Instrument| {
+            if i.name == "my_counter" {
+                Some(Stream::builder().with_cardinality_limit(1).build().unwrap())
+            } else {
+                None
+            }
+        };
+        let mut test_context = TestContext::new_with_view(Temporality::Delta, view);
+        let counter = test_context.u64_counter("test", "my_counter", None);
+        let attributes = BoundAttributes::new(&[KeyValue::new("key1", "value1")]);
 
-        counter_aggregation_attribute_order_helper(Temporality::Delta, false);
-    }
+        // Act
+        counter.add(1, &[KeyValue::new("key1", "other")]);
+        counter.add_bound(10, &attributes);
+        counter.add_bound(10, &attributes);
+        test_context.flush_metrics();
 
-    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
-    async fn counter_aggregation_attribute_order_unsorted_first_cumulative() {
-        // Run this test with stdout enabled to see output.
-        // cargo test counter_aggregation_attribute_order_unsorted_first_cumulative --features=testing -- --nocapture
+        // Assert
+        let MetricData::Sum(sum) = test_context.get_aggregation::<u64>("my_counter", None) else {
+            unreachable!()
+        };
+        let data_point =
+            find_overflow_sum_datapoint(&sum.data_points).expect("overflow point expected");
+        assert_eq!(data_point.value, 20);
 
-        counter_aggregation_attribute_order_helper(Temporality::Cumulative, false);
+        // The collection frees up the cardinality limit, so the bound attribute
+        // set gets its own data point.
+        test_context.reset_metrics();
+        counter.add_bound(10, &attributes);
+        test_context.flush_metrics();
+
+        let MetricData::Sum(sum) = test_context.get_aggregation::<u64>("my_counter", None) else {
+            unreachable!()
+        };
+        assert_eq!(sum.data_points.len(), 1);
+        let data_point = find_sum_datapoint_with_key_value(&sum.data_points, "key1", "value1")
+            .expect("datapoint with key1=value1 expected");
+        assert_eq!(data_point.value, 10);
     }
 
     #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
@@ -1089,6 +1635,94 @@ mod tests {
         assert_eq!(data_point.value, 30);
     }
 
+    #[cfg(feature = "spec_unstable_metrics_views")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn view_attribute_processor_rewrites_attributes() {
+        // cargo test view_attribute_processor_rewrites_attributes --features=testing,spec_unstable_metrics_views
+
+        // Arrange
+        let view = |i: &Instrument| {
+            if i.name == "my_counter" {
+                let processor = AttributeProcessor::new()
+                    .rename_key("statusCode", "http.response.status_code")
+                    .map_value("route", |route| {
+                        if route.as_str().starts_with("/users/") {
+                            "/users/{id}".into()
+                        } else {
+                            route.clone()
+                        }
+                    })
+                    .drop_if("verb", |verb| verb.as_str() == "Get")
+                    .insert(KeyValue::new("env", "test"));
+                Some(
+                    Stream::builder()
+                        .with_allowed_attribute_keys(vec![
+                            "statusCode".into(),
+                            "route".into(),
+                            "verb".into(),
+                        ])
+                        .with_attribute_processor(processor)
+                        .build()
+                        .unwrap(),
+                )
+            } else {
+                None
+            }
+        };
+        let mut test_context = TestContext::new_with_view(Temporality::Cumulative, view);
+        let counter = test_context.u64_counter("test", "my_counter", None);
+
+        // Act
+        for user in 0..10 {
+            counter.add(
+                1,
+                &[
+                    KeyValue::new("route", format!("/users/{user}")),
+                    KeyValue::new("statusCode", "200"),
+                    KeyValue::new("verb", "Get"),
+                    KeyValue::new("user", user),
+                ],
+            );
+        }
+        counter.add(
+            5,
+            &[
+                KeyValue::new("route", "/health"),
+                KeyValue::new("statusCode", "200"),
+                KeyValue::new("verb", "Post"),
+            ],
+        );
+        test_context.flush_metrics();
+
+        // Assert
+        let MetricData::Sum(sum) = test_context.get_aggregation::<u64>("my_counter", None) else {
+            unreachable!()
+        };
+
+        // The user ids collapse into a single route, instead of 10 series.
+        assert_eq!(sum.data_points.len(), 2);
+
+        let data_point =
+            find_sum_datapoint_with_key_value(&sum.data_points, "route", "/users/{id}")
+                .expect("datapoint with route = /users/{id} expected");
+        assert_eq!(data_point.value, 10);
+        let mut attributes = data_point.attributes.clone();
+        attributes.sort_by(|a, b| a.key.cmp(&b.key));
+        assert_eq!(
+            attributes,
+            vec![
+                KeyValue::new("env", "test"),
+                KeyValue::new("http.response.status_code", "200"),
+                KeyValue::new("route", "/users/{id}"),
+            ]
+        );
+
+        let data_point = find_sum_datapoint_with_key_value(&sum.data_points, "route", "/health")
+            .expect("datapoint with route = /health expected");
+        assert_eq!(data_point.value, 5);
+        assert_eq!(data_point.attributes.len(), 4);
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
     async fn no_attr_cumulative_up_down_counter() {
         let mut test_context = TestContext::new(Temporality::Cumulative);
@@ -1276,6 +1910,290 @@ mod tests {
         assert!(resource_metrics.is_empty(), "No metrics should be exported as no new measurements were recorded since last collect.");
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn counter_exemplars_always_on() {
+        let mut test_context =
+            TestContext::new_with_exemplar_filter(Temporality::Delta, ExemplarFilter::AlwaysOn);
+        let counter = test_context.u64_counter("test", "my_counter", None);
+
+        counter.add(5, &[KeyValue::new("key1", "value1")]);
+        test_context.flush_metrics();
+
+        let MetricData::Sum(sum) = test_context.get_aggregation::<u64>("my_counter", None) else {
+            unreachable!()
+        };
+        let data_point = find_sum_datapoint_with_key_value(&sum.data_points, "key1", "value1")
+            .expect("datapoint with key1=value1 expected");
+        assert_eq!(data_point.exemplars.len(), 1);
+        assert_eq!(data_point.exemplars[0].value, 5);
+        assert_eq!(data_point.exemplars[0].trace_id, [0; 16]);
+        assert!(data_point.exemplars[0].filtered_attributes.is_empty());
+
+        // Exemplars are only reported for the collection cycle they were sampled in.
+        test_context.reset_metrics();
+        counter.add(7, &[KeyValue::new("key1", "value1")]);
+        test_context.flush_metrics();
+
+        let MetricData::Sum(sum) = test_context.get_aggregation::<u64>("my_counter", None) else {
+            unreachable!()
+        };
+        let data_point = find_sum_datapoint_with_key_value(&sum.data_points, "key1", "value1")
+            .expect("datapoint with key1=value1 expected");
+        assert_eq!(data_point.exemplars.len(), 1);
+        assert_eq!(data_point.exemplars[0].value, 7);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn counter_exemplars_always_off() {
+        let mut test_context = TestContext::new_with_exemplar_filter(
+            Temporality::Cumulative,
+            ExemplarFilter::AlwaysOff,
+        );
+        let counter = test_context.u64_counter("test", "my_counter", None);
+
+        counter.add(5, &[KeyValue::new("key1", "value1")]);
+        test_context.flush_metrics();
+
+        let MetricData::Sum(sum) = test_context.get_aggregation::<u64>("my_counter", None) else {
+            unreachable!()
+        };
+        assert_eq!(sum.data_points.len(), 1);
+        assert!(sum.data_points[0].exemplars.is_empty());
+    }
+
+    #[cfg(feature = "trace")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn counter_exemplars_trace_based() {
+        use opentelemetry::trace::{
+            SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState,
+        };
+        use opentelemetry::Context;
+
+        let mut test_context =
+            TestContext::new_with_exemplar_filter(Temporality::Delta, ExemplarFilter::TraceBased);
+        let counter = test_context.u64_counter("test", "my_counter", None);
+
+        let trace_id = TraceId::from(42);
+        let span_id = SpanId::from(7);
+        let span_context = |flags| {
+            Context::current().with_remote_span_context(SpanContext::new(
+                trace_id,
+                span_id,
+                flags,
+                true,
+                TraceState::default(),
+            ))
+        };
+
+        // Not sampled: measurements outside of spans or in unsampled spans are
+        // not eligible.
+        counter.add(1, &[]);
+        {
+            let _guard = span_context(TraceFlags::default()).attach();
+            counter.add(2, &[]);
+        }
+        {
+            let _guard = span_context(TraceFlags::SAMPLED).attach();
+            counter.add(3, &[]);
+        }
+        test_context.flush_metrics();
+
+        let MetricData::Sum(sum) = test_context.get_aggregation::<u64>("my_counter", None) else {
+            unreachable!()
+        };
+        let data_point = &sum.data_points[0];
+        assert_eq!(data_point.value, 6);
+        assert_eq!(data_point.exemplars.len(), 1);
+        let exemplar = &data_point.exemplars[0];
+        assert_eq!(exemplar.value, 3);
+        assert_eq!(exemplar.trace_id, trace_id.to_bytes());
+        assert_eq!(exemplar.span_id, span_id.to_bytes());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn histogram_exemplars_aligned_with_buckets() {
+        let mut test_context =
+            TestContext::new_with_exemplar_filter(Temporality::Delta, ExemplarFilter::AlwaysOn);
+        let histogram = test_context
+            .meter()
+            .u64_histogram("my_histogram")
+            .with_boundaries(vec![10.0, 100.0])
+            .build();
+
+        for value in [1, 2, 50, 500, 600] {
+            histogram.record(value, &[]);
+        }
+        test_context.flush_metrics();
+
+        let MetricData::Histogram(histogram_data) =
+            test_context.get_aggregation::<u64>("my_histogram", None)
+        else {
+            unreachable!()
+        };
+        let data_point = &histogram_data.data_points[0];
+        let mut values: Vec<u64> = data_point.exemplars.iter().map(|e| e.value).collect();
+        values.sort();
+        // Latest measurement of each bucket is kept.
+        assert_eq!(values, vec![2, 50, 600]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn histogram_exemplars_custom_reservoir() {
+        use crate::metrics::data::Exemplar;
+
+        // Keeps the largest measurements of each attribute set.
+        struct Largest<T>(Vec<Exemplar<T>>);
+
+        impl<T: Copy + PartialOrd + Send + Sync> ExemplarReservoir<T> for Largest<T> {
+            fn offer(&mut self, measurement: ExemplarMeasurement<'_, T>) {
+                self.0.push(measurement.into_exemplar());
+                self.0
+                    .sort_by(|a, b| b.value.partial_cmp(&a.value).unwrap());
+                self.0.truncate(2);
+            }
+
+            fn collect(&mut self, dest: &mut Vec<Exemplar<T>>) {
+                dest.append(&mut self.0);
+            }
+        }
+
+        struct LargestFactory;
+
+        impl ExemplarReservoirFactory for LargestFactory {
+            fn create<T>(&self) -> Box<dyn ExemplarReservoir<T>>
+            where
+                T: Copy + PartialOrd + std::fmt::Debug + Send + Sync + 'static,
+            {
+                Box::new(Largest(Vec::new()))
+            }
+        }
+
+        let exporter = InMemoryMetricExporterBuilder::new()
+            .with_temporality(Temporality::Delta)
+            .build();
+        let meter_provider = SdkMeterProvider::builder()
+            .with_periodic_exporter(exporter.clone())
+            .with_exemplar_filter(ExemplarFilter::AlwaysOn)
+            .with_view(|i: &Instrument| {
+                if i.name() == "my_histogram" {
+                    Stream::builder()
+                        .with_exemplar_reservoir(LargestFactory)
+                        .build()
+                        .ok()
+                } else {
+                    None
+                }
+            })
+            .build();
+        let mut test_context = TestContext {
+            exporter,
+            meter_provider,
+            resource_metrics: vec![],
+        };
+        let histogram = test_context.meter().f64_histogram("my_histogram").build();
+
+        for value in [3.0, 1.0, 7.0, 5.0] {
+            histogram.record(value, &[KeyValue::new("key1", "value1")]);
+        }
+        histogram.record(2.0, &[KeyValue::new("key1", "value2")]);
+        test_context.flush_metrics();
+
+        let MetricData::Histogram(histogram_data) =
+            test_context.get_aggregation::<f64>("my_histogram", None)
+        else {
+            unreachable!()
+        };
+        let data_point =
+            find_histogram_datapoint_with_key_value(&histogram_data.data_points, "key1", "value1")
+                .expect("datapoint with key1=value1 expected");
+        let values: Vec<f64> = data_point.exemplars.iter().map(|e| e.value).collect();
+        assert_eq!(values, vec![7.0, 5.0]);
+
+        // Each attribute set has its own reservoir.
+        let data_point =
+            find_histogram_datapoint_with_key_value(&histogram_data.data_points, "key1", "value2")
+                .expect("datapoint with key1=value2 expected");
+        let values: Vec<f64> = data_point.exemplars.iter().map(|e| e.value).collect();
+        assert_eq!(values, vec![2.0]);
+    }
+
+    #[test]
+    #[cfg(all(
+        feature = "spec_unstable_metrics_views",
+        feature = "experimental_metrics_custom_reader"
+    ))]
+    fn reader_aggregation_selector() {
+        #[derive(Debug, Clone)]
+        struct SharedReader(Arc<ManualReader>);
+
+        impl reader::MetricReader for SharedReader {
+            fn register_pipeline(&self, pipeline: std::sync::Weak<Pipeline>) {
+                self.0.register_pipeline(pipeline)
+            }
+
+            fn collect(&self, rm: &mut ResourceMetrics) -> crate::error::OTelSdkResult {
+                self.0.collect(rm)
+            }
+
+            fn force_flush(&self) -> crate::error::OTelSdkResult {
+                self.0.force_flush()
+            }
+
+            fn shutdown_with_timeout(&self, timeout: Duration) -> crate::error::OTelSdkResult {
+                self.0.shutdown_with_timeout(timeout)
+            }
+
+            fn temporality(&self, kind: InstrumentKind) -> Temporality {
+                self.0.temporality(kind)
+            }
+
+            fn aggregation(&self, kind: InstrumentKind) -> Aggregation {
+                self.0.aggregation(kind)
+            }
+        }
+
+        let reader = SharedReader(Arc::new(
+            ManualReader::builder()
+                .with_aggregation_selector(|kind| match kind {
+                    InstrumentKind::Histogram => Aggregation::Base2ExponentialHistogram {
+                        max_size: 160,
+                        max_scale: 20,
+                        record_min_max: true,
+                    },
+                    _ => Aggregation::Default,
+                })
+                .build(),
+        ));
+        let meter_provider = SdkMeterProvider::builder()
+            .with_reader(reader.clone())
+            .build();
+        let meter = meter_provider.meter("test");
+
+        // Bucket advice only applies to explicit bucket histograms.
+        let histogram = meter
+            .f64_histogram("my_histogram")
+            .with_boundaries(vec![1.0, 2.0])
+            .build();
+        histogram.record(1.5, &[]);
+        let counter = meter.u64_counter("my_counter").build();
+        counter.add(1, &[]);
+
+        let mut rm = ResourceMetrics::default();
+        reader::MetricReader::collect(&reader, &mut rm).unwrap();
+        let metrics = &rm.scope_metrics[0].metrics;
+
+        let histogram = metrics.iter().find(|m| m.name == "my_histogram").unwrap();
+        assert!(matches!(
+            f64::extract_metrics_data_ref(&histogram.data),
+            Some(MetricData::ExponentialHistogram(_))
+        ));
+        let counter = metrics.iter().find(|m| m.name == "my_counter").unwrap();
+        assert!(matches!(
+            u64::extract_metrics_data_ref(&counter.data),
+            Some(MetricData::Sum(_))
+        ));
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
     async fn counter_multithreaded() {
         // Run this test with stdout enabled to see output.
@@ -3033,6 +3951,46 @@ mod tests {
         assert_eq!(data_point1.value, 6);
     }
 
+    fn counter_bound_attributes_helper(temporality: Temporality) {
+        // Arrange
+        let mut test_context = TestContext::new(temporality);
+        let counter = test_context.u64_counter("test", "my_counter", None);
+        let attributes = BoundAttributes::new(&[KeyValue::new("B", "b"), KeyValue::new("A", "a")]);
+
+        // Act
+        // Bound and unbound measurements of the same attribute set, in any
+        // order, are aggregated together.
+        counter.add_bound(1, &attributes);
+        counter.add(1, &[KeyValue::new("A", "a"), KeyValue::new("B", "b")]);
+        counter.add_bound(1, &attributes);
+        counter.add(1, &[KeyValue::new("B", "b"), KeyValue::new("A", "a")]);
+        counter.add_bound(1, &attributes);
+        test_context.flush_metrics();
+
+        // Assert
+        let MetricData::Sum(sum) = test_context.get_aggregation::<u64>("my_counter", None) else {
+            unreachable!()
+        };
+        assert_eq!(sum.data_points.len(), 1);
+        assert_eq!(sum.data_points[0].value, 5);
+
+        // Measurements after a collection are not lost.
+        test_context.reset_metrics();
+        counter.add_bound(1, &attributes);
+        counter.add_bound(1, &attributes);
+        test_context.flush_metrics();
+
+        let MetricData::Sum(sum) = test_context.get_aggregation::<u64>("my_counter", None) else {
+            unreachable!()
+        };
+        assert_eq!(sum.data_points.len(), 1);
+        if temporality == Temporality::Cumulative {
+            assert_eq!(sum.data_points[0].value, 7);
+        } else {
+            assert_eq!(sum.data_points[0].value, 2);
+        }
+    }
+
     fn updown_counter_aggregation_helper(temporality: Temporality) {
         // Arrange
         let mut test_context = TestContext::new(temporality);
@@ -3208,6 +4166,21 @@ mod tests {
             }
         }
 
+        fn new_with_exemplar_filter(temporality: Temporality, filter: ExemplarFilter) -> Self {
+            let exporter = InMemoryMetricExporterBuilder::new().with_temporality(temporality);
+            let exporter = exporter.build();
+            let meter_provider = SdkMeterProvider::builder()
+                .with_periodic_exporter(exporter.clone())
+                .with_exemplar_filter(filter)
+                .build();
+
+            TestContext {
+                exporter,
+                meter_provider,
+                resource_metrics: vec![],
+            }
+        }
+
         fn new_with_view<T>(temporality: Temporality, view: T) -> Self
         where
             T: Fn(&Instrument) -> Option<Stream> + Send + Sync + 'static,
@@ -8,7 +8,14 @@ use std::{
     time::{Duration, Instant},
 };
 
+#[cfg(feature = "experimental_self_observability")]
+use opentelemetry::metrics::Meter;
 use opentelemetry::{otel_debug, otel_error, otel_info, otel_warn, Context};
+#[cfg(feature = "experimental_self_observability")]
+use std::sync::OnceLock;
+
+#[cfg(feature = "experimental_self_observability")]
+use crate::self_observability::ReaderMetrics;
 
 use crate::{
     error::{OTelSdkError, OTelSdkResult},
@@ -17,8 +24,8 @@ use crate::{
 };
 
 use super::{
-    data::ResourceMetrics, instrument::InstrumentKind, pipeline::Pipeline, reader::MetricReader,
-    Temporality,
+    aggregation::Aggregation, data::ResourceMetrics, instrument::InstrumentKind,
+    pipeline::Pipeline, reader::MetricReader, Temporality,
 };
 
 const DEFAULT_INTERVAL: Duration = Duration::from_secs(60);
@@ -152,6 +159,8 @@ impl<E: PushMetricExporter> PeriodicReader<E> {
                 message_sender,
                 producer: Mutex::new(None),
                 exporter: exporter_arc.clone(),
+                #[cfg(feature = "experimental_self_observability")]
+                metrics: OnceLock::new(),
             }),
         };
         let cloned_reader = reader.clone();
@@ -351,6 +360,8 @@ struct PeriodicReaderInner<E: PushMetricExporter> {
     exporter: Arc<E>,
     message_sender: mpsc::Sender<Message>,
     producer: Mutex<Option<Weak<dyn SdkProducer>>>,
+    #[cfg(feature = "experimental_self_observability")]
+    metrics: OnceLock<ReaderMetrics>,
 }
 
 impl<E: PushMetricExporter> PeriodicReaderInner<E> {
@@ -363,6 +374,10 @@ impl<E: PushMetricExporter> PeriodicReaderInner<E> {
         self.exporter.temporality()
     }
 
+    fn aggregation(&self, kind: InstrumentKind) -> Aggregation {
+        self.exporter.aggregation(kind)
+    }
+
     fn collect(&self, rm: &mut ResourceMetrics) -> OTelSdkResult {
         let producer = self.producer.lock().expect("lock poisoned");
         if let Some(p) = producer.as_ref() {
@@ -387,6 +402,10 @@ impl<E: PushMetricExporter> PeriodicReaderInner<E> {
         let current_time = Instant::now();
         let collect_result = self.collect(rm);
         let time_taken_for_collect = current_time.elapsed();
+        #[cfg(feature = "experimental_self_observability")]
+        if let Some(metrics) = self.metrics.get() {
+            metrics.collected(time_taken_for_collect, &collect_result);
+        }
 
         #[allow(clippy::question_mark)]
         if let Err(e) = collect_result {
@@ -506,6 +525,19 @@ impl<E: PushMetricExporter> MetricReader for PeriodicReader<E> {
     fn temporality(&self, kind: InstrumentKind) -> Temporality {
         kind.temporality_preference(self.inner.temporality(kind))
     }
+
+    fn aggregation(&self, kind: InstrumentKind) -> Aggregation {
+        self.inner.aggregation(kind)
+    }
+
+    #[cfg(feature = "experimental_self_observability")]
+    fn set_meter(&self, meter: &Meter) {
+        let _ = self
+            .inner
+            .metrics
+            .set(ReaderMetrics::new(meter, "periodic_metric_reader"));
+        self.inner.exporter.set_meter(meter);
+    }
 }
 
 #[cfg(all(test, feature = "testing"))]
@@ -437,6 +437,15 @@ impl<E: PushMetricExporter> MetricReader for PeriodicReader<E> {
     fn temporality(&self, kind: InstrumentKind) -> super::Temporality {
         kind.temporality_preference(self.exporter.temporality())
     }
+
+    fn aggregation(&self, kind: InstrumentKind) -> super::Aggregation {
+        self.exporter.aggregation(kind)
+    }
+
+    #[cfg(feature = "experimental_self_observability")]
+    fn set_meter(&self, meter: &opentelemetry::metrics::Meter) {
+        self.exporter.set_meter(meter);
+    }
 }
 
 #[cfg(all(test, feature = "testing"))]
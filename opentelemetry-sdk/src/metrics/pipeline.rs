@@ -13,8 +13,9 @@ use crate::{
         aggregation,
         data::{Metric, ResourceMetrics, ScopeMetrics},
         error::{MetricError, MetricResult},
-        instrument::{Instrument, InstrumentId, InstrumentKind, Stream},
-        internal::{self, AggregateBuilder, Number},
+        exemplar::ExemplarFilter,
+        instrument::{CardinalityLimitStrategy, Instrument, InstrumentId, InstrumentKind, Stream},
+        internal::{self, AggregateBuilder, ExemplarConfig, Number, RejectedMeasurements},
         reader::{MetricReader, SdkProducer},
         view::View,
     },
@@ -38,6 +39,7 @@ pub struct Pipeline {
     pub(crate) resource: Resource,
     reader: Box<dyn MetricReader>,
     views: Vec<Arc<dyn View>>,
+    exemplar_filter: ExemplarFilter,
     inner: Mutex<PipelineInner>,
 }
 
@@ -50,12 +52,23 @@ impl fmt::Debug for Pipeline {
 /// Single or multi-instrument callbacks
 type GenericCallback = Arc<dyn Fn() + Send + Sync>;
 
+type RejectedCounter = Arc<dyn internal::Measure<u64>>;
+
 pub(crate) const DEFAULT_CARDINALITY_LIMIT: usize = 2000;
 
+/// The name of the counter of measurements dropped by
+/// [CardinalityLimitStrategy::Reject].
+const REJECTED_MEASUREMENTS_NAME: &str = "otel.sdk.metric.cardinality_limit.rejected";
+const REJECTED_MEASUREMENTS_DESCRIPTION: &str =
+    "Measurements dropped because their metric stream reached its cardinality limit.";
+
 #[derive(Default)]
 struct PipelineInner {
     aggregations: HashMap<InstrumentationScope, Vec<InstrumentSync>>,
     callbacks: Vec<GenericCallback>,
+    /// The counter of rejected measurements, reported under the scope of the
+    /// SDK.
+    rejected_measurements: Option<RejectedCounter>,
 }
 
 impl fmt::Debug for PipelineInner {
@@ -79,6 +92,35 @@ impl Pipeline {
         });
     }
 
+    /// Returns the counter of the measurements rejected by the streams of the
+    /// pipeline, adding it to the pipeline on first use.
+    fn rejected_measurements(&self) -> Option<RejectedCounter> {
+        let mut inner = self.inner.lock().ok()?;
+        if let Some(counter) = &inner.rejected_measurements {
+            return Some(counter.clone());
+        }
+
+        let AggregateFns { measure, collect } = AggregateBuilder::<u64>::new(
+            self.reader.temporality(InstrumentKind::Counter),
+            None,
+            DEFAULT_CARDINALITY_LIMIT,
+            ExemplarFilter::AlwaysOff.into(),
+        )
+        .sum(true);
+        inner
+            .aggregations
+            .entry(crate::util::sdk_scope())
+            .or_default()
+            .push(InstrumentSync {
+                name: REJECTED_MEASUREMENTS_NAME.into(),
+                description: REJECTED_MEASUREMENTS_DESCRIPTION.into(),
+                unit: "{measurement}".into(),
+                comp_agg: collect,
+            });
+        inner.rejected_measurements = Some(measure.clone());
+        Some(measure)
+    }
+
     /// Registers a single instrument callback to be run when `produce` is called.
     fn add_callback(&self, callback: GenericCallback) {
         let _ = self
@@ -311,15 +353,33 @@ where
             unit: Some(inst.unit),
             aggregation: None,
             allowed_attribute_keys: None,
+            #[cfg(feature = "spec_unstable_metrics_views")]
+            attribute_processor: None,
             cardinality_limit: None,
+            cardinality_limit_strategy: None,
+            idle_timeout: None,
+            exemplar_reservoir: None,
         };
 
-        // Override default histogram boundaries if provided.
+        // Override default histogram boundaries if provided. The advice only
+        // applies to explicit bucket histograms, so it is ignored if the reader
+        // selects another aggregation.
         if let Some(boundaries) = boundaries {
-            stream.aggregation = Some(Aggregation::ExplicitBucketHistogram {
-                boundaries: boundaries.to_vec(),
-                record_min_max: true,
-            });
+            match self.pipeline.reader.aggregation(kind) {
+                Aggregation::Default => {
+                    stream.aggregation = Some(Aggregation::ExplicitBucketHistogram {
+                        boundaries: boundaries.to_vec(),
+                        record_min_max: true,
+                    });
+                }
+                Aggregation::ExplicitBucketHistogram { record_min_max, .. } => {
+                    stream.aggregation = Some(Aggregation::ExplicitBucketHistogram {
+                        boundaries: boundaries.to_vec(),
+                        record_min_max,
+                    });
+                }
+                _ => {}
+            }
         }
 
         match self.cached_aggregator(&inst.scope, kind, stream) {
@@ -362,10 +422,12 @@ where
         // TODO: Create a separate pub (crate) Stream struct for the pipeline,
         // as Stream will not have any optional fields as None at this point and
         // new struct can better reflect this.
-        let mut agg = stream
-            .aggregation
-            .take()
-            .unwrap_or_else(|| default_aggregation_selector(kind));
+        let mut agg = stream.aggregation.take().unwrap_or(Aggregation::Default);
+
+        // Apply the reader aggregation if the stream does not set one
+        if matches!(agg, aggregation::Aggregation::Default) {
+            agg = self.pipeline.reader.aggregation(kind);
+        }
 
         // Apply default if stream or reader aggregation returns default
         if matches!(agg, aggregation::Aggregation::Default) {
@@ -400,11 +462,34 @@ where
             let cardinality_limit = stream
                 .cardinality_limit
                 .unwrap_or(DEFAULT_CARDINALITY_LIMIT);
+            let strategy = stream.cardinality_limit_strategy.unwrap_or_default();
+            let rejected = if strategy == CardinalityLimitStrategy::Reject {
+                self.pipeline.rejected_measurements().map(|counter| {
+                    RejectedMeasurements::new(
+                        counter,
+                        scope.name().to_owned(),
+                        stream.name.clone().unwrap_or_default(),
+                    )
+                })
+            } else {
+                None
+            };
             let b = AggregateBuilder::new(
                 self.pipeline.reader.temporality(kind),
                 filter,
                 cardinality_limit,
-            );
+                ExemplarConfig {
+                    filter: self.pipeline.exemplar_filter,
+                    reservoir: stream
+                        .exemplar_reservoir
+                        .as_ref()
+                        .map(|factory| factory.for_number()),
+                },
+            )
+            .with_cardinality_limit_strategy(strategy, rejected)
+            .with_idle_timeout(stream.idle_timeout);
+            #[cfg(feature = "spec_unstable_metrics_views")]
+            let b = b.with_attribute_processor(stream.attribute_processor.clone());
             let AggregateFns { measure, collect } = match aggregate_fn(b, &agg, kind) {
                 Ok(Some(inst)) => inst,
                 other => return other.map(|fs| fs.map(|inst| inst.measure)), // Drop aggregator or error
@@ -646,6 +731,7 @@ impl Pipelines {
         res: Resource,
         readers: Vec<Box<dyn MetricReader>>,
         views: Vec<Arc<dyn View>>,
+        exemplar_filter: ExemplarFilter,
     ) -> Self {
         let mut pipes = Vec::with_capacity(readers.len());
         for r in readers {
@@ -653,6 +739,7 @@ impl Pipelines {
                 resource: res.clone(),
                 reader: r,
                 views: views.clone(),
+                exemplar_filter,
                 inner: Default::default(),
             });
             p.reader.register_pipeline(Arc::downgrade(&p));
@@ -0,0 +1,777 @@
+//! Rendering of collected metrics in the Prometheus text and OpenMetrics
+//! exposition formats.
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::{Display, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use opentelemetry::{otel_debug, otel_warn, InstrumentationScope, Key, KeyValue, Value};
+
+use crate::metrics::data::{
+    AggregatedMetrics, Exemplar, Gauge, Histogram, Metric, MetricData, ResourceMetrics, Sum,
+};
+use crate::metrics::internal::Number;
+use crate::Resource;
+
+use super::ExpositionFormat;
+
+const TARGET_INFO_NAME: &str = "target_info";
+const TARGET_INFO_DESCRIPTION: &str = "Target metadata";
+const SCOPE_NAME_LABEL: &str = "otel_scope_name";
+const SCOPE_VERSION_LABEL: &str = "otel_scope_version";
+const COUNTER_SUFFIX: &str = "_total";
+/// The maximum number of UTF-8 characters in the label set of an exemplar.
+const MAX_EXEMPLAR_LABELS_LENGTH: usize = 128;
+
+const NON_APPLICABLE_ON_PER_UNIT: [&str; 8] = ["1", "d", "h", "min", "s", "ms", "us", "ns"];
+
+/// Options of the metric and label names.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct EncoderConfig {
+    pub(crate) without_units: bool,
+    pub(crate) without_counter_suffixes: bool,
+    pub(crate) without_target_info: bool,
+    pub(crate) without_scope_info: bool,
+    pub(crate) namespace: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FamilyKind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl FamilyKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            FamilyKind::Counter => "counter",
+            FamilyKind::Gauge => "gauge",
+            FamilyKind::Histogram => "histogram",
+        }
+    }
+}
+
+/// The metrics of all scopes sharing a name.
+#[derive(Debug)]
+struct Family {
+    name: String,
+    kind: FamilyKind,
+    help: String,
+    unit: Option<Cow<'static, str>>,
+    samples: String,
+}
+
+/// Renders the collected metrics in `format`.
+pub(crate) fn encode(
+    rm: &ResourceMetrics,
+    config: &EncoderConfig,
+    format: ExpositionFormat,
+) -> String {
+    let mut families: Vec<Family> = Vec::new();
+    let mut family_index: HashMap<String, usize> = HashMap::new();
+
+    for scope_metrics in rm.scope_metrics() {
+        let scope_labels = scope_labels(scope_metrics.scope(), config);
+        for metric in scope_metrics.metrics() {
+            let Some(kind) = family_kind(metric) else {
+                otel_debug!(
+                    name: "PrometheusReader.UnsupportedAggregation",
+                    metric_name = metric.name().to_string(),
+                    message = "Exponential histograms are not supported by the Prometheus exposition formats; the metric is dropped."
+                );
+                continue;
+            };
+
+            let unit = (!config.without_units)
+                .then(|| unit_suffix(metric.unit(), kind))
+                .flatten();
+            let name = family_name(metric.name(), unit.as_deref(), kind, config, format);
+
+            let index = *family_index.entry(name.clone()).or_insert_with(|| {
+                families.push(Family {
+                    name: name.clone(),
+                    kind,
+                    help: metric.description().to_string(),
+                    unit: unit.clone(),
+                    samples: String::new(),
+                });
+                families.len() - 1
+            });
+            let family = &mut families[index];
+            if family.kind != kind {
+                otel_warn!(
+                    name: "PrometheusReader.ConflictingMetricType",
+                    metric_name = name.clone(),
+                    message = "A metric with the same name but a different type was already rendered; the metric is dropped."
+                );
+                continue;
+            }
+
+            let sample_name = sample_name(&family.name, kind, format);
+            let mut writer = SampleWriter {
+                out: &mut family.samples,
+                name: &sample_name,
+                scope_labels: &scope_labels,
+                format,
+            };
+            match metric.data() {
+                AggregatedMetrics::F64(data) => writer.write_data(data),
+                AggregatedMetrics::U64(data) => writer.write_data(data),
+                AggregatedMetrics::I64(data) => writer.write_data(data),
+            }
+        }
+    }
+
+    let mut out = String::new();
+    if !config.without_target_info && !rm.resource().is_empty() {
+        write_target_info(&mut out, rm.resource(), format);
+    }
+    for family in &families {
+        write_family(&mut out, family, format);
+    }
+    if format == ExpositionFormat::OpenMetrics {
+        out.push_str("# EOF\n");
+    }
+    out
+}
+
+fn family_kind(metric: &Metric) -> Option<FamilyKind> {
+    fn kind<T>(data: &MetricData<T>) -> Option<FamilyKind> {
+        match data {
+            MetricData::Sum(sum) if sum.is_monotonic() => Some(FamilyKind::Counter),
+            MetricData::Sum(_) | MetricData::Gauge(_) => Some(FamilyKind::Gauge),
+            MetricData::Histogram(_) => Some(FamilyKind::Histogram),
+            MetricData::ExponentialHistogram(_) => None,
+        }
+    }
+    match metric.data() {
+        AggregatedMetrics::F64(data) => kind(data),
+        AggregatedMetrics::U64(data) => kind(data),
+        AggregatedMetrics::I64(data) => kind(data),
+    }
+}
+
+/// The family name, including the namespace and unit suffix.
+///
+/// OpenMetrics counter families are named without the `_total` suffix of
+/// their samples.
+fn family_name(
+    metric_name: &str,
+    unit: Option<&str>,
+    kind: FamilyKind,
+    config: &EncoderConfig,
+    format: ExpositionFormat,
+) -> String {
+    let mut name = match &config.namespace {
+        Some(namespace) => sanitize_name(&format!("{namespace}_{metric_name}")).into_owned(),
+        None => sanitize_name(metric_name).into_owned(),
+    };
+
+    let strip_total = kind == FamilyKind::Counter
+        && (format == ExpositionFormat::OpenMetrics || !config.without_counter_suffixes);
+    if strip_total && name.ends_with(COUNTER_SUFFIX) {
+        name.truncate(name.len() - COUNTER_SUFFIX.len());
+    }
+
+    if let Some(unit) = unit {
+        if !name.ends_with(unit) {
+            name.push('_');
+            name.push_str(unit);
+        }
+    }
+
+    if kind == FamilyKind::Counter
+        && format == ExpositionFormat::PrometheusText
+        && !config.without_counter_suffixes
+    {
+        name.push_str(COUNTER_SUFFIX);
+    }
+    name
+}
+
+/// The name of the samples of a family, without the `_bucket`, `_sum` or
+/// `_count` suffixes of histograms.
+fn sample_name(family_name: &str, kind: FamilyKind, format: ExpositionFormat) -> String {
+    if kind == FamilyKind::Counter && format == ExpositionFormat::OpenMetrics {
+        format!("{family_name}{COUNTER_SUFFIX}")
+    } else {
+        family_name.to_string()
+    }
+}
+
+/// The suffix of the metric name for `unit`, e.g. `seconds` for `s`.
+fn unit_suffix(unit: &str, kind: FamilyKind) -> Option<Cow<'static, str>> {
+    // a ratio is only a meaningful suffix for gauges
+    if unit == "1" && kind != FamilyKind::Gauge {
+        return None;
+    }
+    get_unit_suffixes(unit)
+}
+
+pub(crate) fn get_unit_suffixes(unit: &str) -> Option<Cow<'static, str>> {
+    // no unit return early
+    if unit.is_empty() {
+        return None;
+    }
+
+    // direct match with known units
+    if let Some(matched) = get_prom_units(unit) {
+        return Some(Cow::Borrowed(matched));
+    }
+
+    // converting foo/bar to foo_per_bar
+    // e.g
+    // "test/y" => "per_year"
+    // "km/s" => "kilometers_per_second"
+    if let Some((first, second)) = unit.split_once('/') {
+        return match (
+            NON_APPLICABLE_ON_PER_UNIT.contains(&first),
+            get_prom_units(first),
+            get_prom_per_unit(second),
+        ) {
+            (true, _, Some(second_part)) | (false, None, Some(second_part)) => {
+                Some(Cow::Owned(format!("per_{second_part}")))
+            }
+            (false, Some(first_part), Some(second_part)) => {
+                Some(Cow::Owned(format!("{first_part}_per_{second_part}")))
+            }
+            _ => None,
+        };
+    }
+
+    // Unmatched units and annotations are ignored
+    // e.g. "{request}"
+    None
+}
+
+fn get_prom_units(unit: &str) -> Option<&'static str> {
+    match unit {
+        // Time
+        "d" => Some("days"),
+        "h" => Some("hours"),
+        "min" => Some("minutes"),
+        "s" => Some("seconds"),
+        "ms" => Some("milliseconds"),
+        "us" => Some("microseconds"),
+        "ns" => Some("nanoseconds"),
+
+        // Bytes
+        "By" => Some("bytes"),
+        "KiBy" => Some("kibibytes"),
+        "MiBy" => Some("mebibytes"),
+        "GiBy" => Some("gibibytes"),
+        "TiBy" => Some("tibibytes"),
+        "KBy" => Some("kilobytes"),
+        "MBy" => Some("megabytes"),
+        "GBy" => Some("gigabytes"),
+        "TBy" => Some("terabytes"),
+
+        // SI
+        "m" => Some("meters"),
+        "V" => Some("volts"),
+        "A" => Some("amperes"),
+        "J" => Some("joules"),
+        "W" => Some("watts"),
+        "g" => Some("grams"),
+
+        // Misc
+        "Cel" => Some("celsius"),
+        "Hz" => Some("hertz"),
+        "1" => Some("ratio"),
+        "%" => Some("percent"),
+        _ => None,
+    }
+}
+
+fn get_prom_per_unit(unit: &str) -> Option<&'static str> {
+    match unit {
+        "s" => Some("second"),
+        "m" => Some("minute"),
+        "h" => Some("hour"),
+        "d" => Some("day"),
+        "w" => Some("week"),
+        "mo" => Some("month"),
+        "y" => Some("year"),
+        _ => None,
+    }
+}
+
+/// Replaces the characters not allowed in metric names with `_`, and
+/// prefixes names starting with a digit with `_`.
+pub(crate) fn sanitize_name(name: &str) -> Cow<'_, str> {
+    let is_valid = |(i, c): (usize, char)| {
+        c.is_ascii_alphabetic() || c == '_' || c == ':' || (i > 0 && c.is_ascii_digit())
+    };
+    if name.char_indices().all(is_valid) {
+        return Cow::Borrowed(name);
+    }
+
+    let prefix = name
+        .starts_with(|c: char| c.is_ascii_digit())
+        .then_some('_');
+    Cow::Owned(
+        prefix
+            .into_iter()
+            .chain(name.chars().map(|c| {
+                if c.is_ascii_alphanumeric() || c == ':' {
+                    c
+                } else {
+                    '_'
+                }
+            }))
+            .collect(),
+    )
+}
+
+/// Replaces the characters not allowed in label names with `_`, and
+/// prefixes names starting with a digit with `key_`.
+pub(crate) fn sanitize_label_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if sanitized.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("key_{sanitized}")
+    } else {
+        sanitized
+    }
+}
+
+/// Converts attributes to labels, joining the values of attributes whose
+/// sanitized names collide with `;`.
+fn labels<'a>(attributes: impl Iterator<Item = (&'a Key, &'a Value)>) -> Vec<(String, String)> {
+    let mut labels: Vec<(String, String)> = Vec::new();
+    for (key, value) in attributes {
+        let name = sanitize_label_name(key.as_str());
+        let value = value.as_str();
+        match labels.iter_mut().find(|(existing, _)| *existing == name) {
+            Some((_, existing)) => {
+                existing.push(';');
+                existing.push_str(&value);
+            }
+            None => labels.push((name, value.into_owned())),
+        }
+    }
+    labels
+}
+
+fn scope_labels(scope: &InstrumentationScope, config: &EncoderConfig) -> Vec<(String, String)> {
+    if config.without_scope_info || scope.name().is_empty() {
+        return Vec::new();
+    }
+    let mut labels = vec![(SCOPE_NAME_LABEL.to_string(), scope.name().to_string())];
+    if let Some(version) = scope.version() {
+        labels.push((SCOPE_VERSION_LABEL.to_string(), version.to_string()));
+    }
+    labels
+}
+
+fn write_target_info(out: &mut String, resource: &Resource, format: ExpositionFormat) {
+    match format {
+        ExpositionFormat::PrometheusText => {
+            let _ = writeln!(out, "# HELP {TARGET_INFO_NAME} {TARGET_INFO_DESCRIPTION}");
+            let _ = writeln!(out, "# TYPE {TARGET_INFO_NAME} gauge");
+        }
+        ExpositionFormat::OpenMetrics => {
+            let _ = writeln!(out, "# TYPE target info");
+            let _ = writeln!(out, "# HELP target {TARGET_INFO_DESCRIPTION}");
+        }
+    }
+    out.push_str(TARGET_INFO_NAME);
+    write_labels(out, &labels(resource.iter()), &[]);
+    out.push_str(" 1\n");
+}
+
+fn write_family(out: &mut String, family: &Family, format: ExpositionFormat) {
+    if !family.help.is_empty() {
+        let _ = writeln!(out, "# HELP {} {}", family.name, escape_help(&family.help));
+    }
+    let _ = writeln!(out, "# TYPE {} {}", family.name, family.kind.as_str());
+    if format == ExpositionFormat::OpenMetrics {
+        if let Some(unit) = &family.unit {
+            let _ = writeln!(out, "# UNIT {} {}", family.name, unit);
+        }
+    }
+    out.push_str(&family.samples);
+}
+
+/// Writes the samples of a metric to the samples of its family.
+struct SampleWriter<'a> {
+    out: &'a mut String,
+    name: &'a str,
+    scope_labels: &'a [(String, String)],
+    format: ExpositionFormat,
+}
+
+impl SampleWriter<'_> {
+    fn write_data<T: Number + Display>(&mut self, data: &MetricData<T>) {
+        match data {
+            MetricData::Gauge(gauge) => self.write_gauge(gauge),
+            MetricData::Sum(sum) => self.write_sum(sum),
+            MetricData::Histogram(histogram) => self.write_histogram(histogram),
+            MetricData::ExponentialHistogram(_) => {}
+        }
+    }
+
+    fn write_gauge<T: Number + Display>(&mut self, gauge: &Gauge<T>) {
+        for data_point in gauge.data_points() {
+            let labels = labels(data_point.attributes().map(|kv| (&kv.key, &kv.value)));
+            self.write_sample("", &labels, &[], &format_number(data_point.value()));
+            self.out.push('\n');
+        }
+    }
+
+    fn write_sum<T: Number + Display>(&mut self, sum: &Sum<T>) {
+        let is_counter = sum.is_monotonic();
+        for data_point in sum.data_points() {
+            let labels = labels(data_point.attributes().map(|kv| (&kv.key, &kv.value)));
+            self.write_sample("", &labels, &[], &format_number(data_point.value()));
+            if is_counter {
+                if let Some(exemplar) = data_point.exemplars().last() {
+                    self.write_exemplar(exemplar);
+                }
+            }
+            self.out.push('\n');
+
+            if is_counter && self.format == ExpositionFormat::OpenMetrics {
+                let start_time = data_point.start_time().unwrap_or(sum.start_time());
+                self.write_created(&labels, start_time);
+            }
+        }
+    }
+
+    fn write_histogram<T: Number + Display>(&mut self, histogram: &Histogram<T>) {
+        for data_point in histogram.data_points() {
+            let labels = labels(data_point.attributes().map(|kv| (&kv.key, &kv.value)));
+            let bounds: Vec<f64> = data_point.bounds().collect();
+
+            // the exemplar of each bucket, the latest one falling into it
+            let mut exemplars: Vec<Option<&Exemplar<T>>> = vec![None; bounds.len() + 1];
+            for exemplar in data_point.exemplars() {
+                let value = exemplar.value.into_float();
+                let bucket = bounds.partition_point(|bound| *bound < value);
+                exemplars[bucket] = Some(exemplar);
+            }
+
+            let mut cumulative_count = 0;
+            for (bucket, count) in data_point.bucket_counts().enumerate() {
+                cumulative_count += count;
+                let le = match bounds.get(bucket) {
+                    Some(bound) => format_bound(*bound, self.format),
+                    None => "+Inf".to_string(),
+                };
+                self.write_sample(
+                    "_bucket",
+                    &labels,
+                    &[("le", &le)],
+                    &cumulative_count.to_string(),
+                );
+                if let Some(exemplar) = exemplars.get(bucket).copied().flatten() {
+                    self.write_exemplar(exemplar);
+                }
+                self.out.push('\n');
+            }
+
+            self.write_sample("_sum", &labels, &[], &format_number(data_point.sum()));
+            self.out.push('\n');
+            self.write_sample("_count", &labels, &[], &data_point.count().to_string());
+            self.out.push('\n');
+
+            if self.format == ExpositionFormat::OpenMetrics {
+                let start_time = data_point.start_time().unwrap_or(histogram.start_time());
+                self.write_created(&labels, start_time);
+            }
+        }
+    }
+
+    /// Writes a sample without the trailing newline.
+    fn write_sample(
+        &mut self,
+        suffix: &str,
+        labels: &[(String, String)],
+        extra_labels: &[(&str, &str)],
+        value: &str,
+    ) {
+        self.out.push_str(self.name);
+        self.out.push_str(suffix);
+        let mut extra: Vec<(String, String)> = self.scope_labels.to_vec();
+        extra.extend(
+            extra_labels
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string())),
+        );
+        write_labels(self.out, labels, &extra);
+        self.out.push(' ');
+        self.out.push_str(value);
+    }
+
+    fn write_created(&mut self, labels: &[(String, String)], start_time: SystemTime) {
+        let created = format_timestamp(start_time);
+        let name = self.name.strip_suffix(COUNTER_SUFFIX).unwrap_or(self.name);
+        self.out.push_str(name);
+        self.out.push_str("_created");
+        write_labels(self.out, labels, self.scope_labels);
+        let _ = writeln!(self.out, " {created}");
+    }
+
+    /// Appends an exemplar to the current sample, OpenMetrics only.
+    fn write_exemplar<T: Number + Display>(&mut self, exemplar: &Exemplar<T>) {
+        if self.format != ExpositionFormat::OpenMetrics {
+            return;
+        }
+
+        let mut exemplar_labels: Vec<(String, String)> = Vec::new();
+        if exemplar.trace_id() != &[0; 16] {
+            exemplar_labels.push(("trace_id".to_string(), hex(exemplar.trace_id())));
+            exemplar_labels.push(("span_id".to_string(), hex(exemplar.span_id())));
+        }
+        let mut length: usize = exemplar_labels
+            .iter()
+            .map(|(name, value)| name.chars().count() + value.chars().count())
+            .sum();
+        for (name, value) in labels(
+            exemplar
+                .filtered_attributes()
+                .map(|kv: &KeyValue| (&kv.key, &kv.value)),
+        ) {
+            length += name.chars().count() + value.chars().count();
+            if length > MAX_EXEMPLAR_LABELS_LENGTH {
+                break;
+            }
+            exemplar_labels.push((name, value));
+        }
+
+        self.out.push_str(" # {");
+        write_label_pairs(self.out, exemplar_labels.iter());
+        let _ = write!(
+            self.out,
+            "}} {} {}",
+            format_number(exemplar.value),
+            format_timestamp(exemplar.time())
+        );
+    }
+}
+
+fn write_labels(out: &mut String, labels: &[(String, String)], extra: &[(String, String)]) {
+    if labels.is_empty() && extra.is_empty() {
+        return;
+    }
+    out.push('{');
+    write_label_pairs(out, labels.iter().chain(extra));
+    out.push('}');
+}
+
+fn write_label_pairs<'a>(out: &mut String, labels: impl Iterator<Item = &'a (String, String)>) {
+    for (i, (name, value)) in labels.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        let _ = write!(out, "{name}=\"{}\"", escape_label_value(value));
+    }
+}
+
+fn escape_label_value(value: &str) -> Cow<'_, str> {
+    if !value.contains(['\\', '"', '\n']) {
+        return Cow::Borrowed(value);
+    }
+    Cow::Owned(
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n"),
+    )
+}
+
+fn escape_help(help: &str) -> Cow<'_, str> {
+    if !help.contains(['\\', '\n']) {
+        return Cow::Borrowed(help);
+    }
+    Cow::Owned(help.replace('\\', "\\\\").replace('\n', "\\n"))
+}
+
+fn format_number<T: Number + Display>(value: T) -> String {
+    let float = value.into_float();
+    if float.is_nan() {
+        "NaN".to_string()
+    } else if float == f64::INFINITY {
+        "+Inf".to_string()
+    } else if float == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Formats a histogram bucket bound, in the canonical float form required by
+/// OpenMetrics, e.g. `1.0` instead of `1`.
+fn format_bound(bound: f64, format: ExpositionFormat) -> String {
+    let formatted = format_number(bound);
+    if format == ExpositionFormat::OpenMetrics && bound.is_finite() && bound.fract() == 0.0 {
+        format!("{formatted}.0")
+    } else {
+        formatted
+    }
+}
+
+/// Formats a time as seconds since the Unix epoch.
+fn format_timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    format!(
+        "{}.{:03}",
+        since_epoch.as_secs(),
+        since_epoch.subsec_millis()
+    )
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut out, byte| {
+        let _ = write!(out, "{byte:02x}");
+        out
+    })
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn name_sanitization() {
+        let tests = vec![
+            ("nam€_with_3_width_rune", "nam__with_3_width_rune"),
+            ("`", "_"),
+            ("Avalid_23name", "Avalid_23name"),
+            ("_Avalid_23name", "_Avalid_23name"),
+            ("1valid_23name", "_1valid_23name"),
+            ("Ava:lid_23name", "Ava:lid_23name"),
+            ("a lid_23name", "a_lid_23name"),
+            ("http.server.duration", "http_server_duration"),
+            ("", ""),
+        ];
+
+        for (input, want) in tests {
+            assert_eq!(want, sanitize_name(input), "input: {input}")
+        }
+    }
+
+    #[test]
+    fn label_name_sanitization() {
+        assert_eq!(sanitize_label_name("http.method"), "http_method");
+        assert_eq!(sanitize_label_name("0key"), "key_0key");
+        assert_eq!(sanitize_label_name("valid_name"), "valid_name");
+    }
+
+    #[test]
+    fn test_get_unit_suffixes() {
+        let test_cases = vec![
+            // Direct match
+            ("g", Some(Cow::Borrowed("grams"))),
+            // Per unit
+            ("test/y", Some(Cow::Owned("per_year".to_owned()))),
+            ("1/y", Some(Cow::Owned("per_year".to_owned()))),
+            ("m/s", Some(Cow::Owned("meters_per_second".to_owned()))),
+            // No match
+            ("invalid", None),
+            ("invalid/invalid", None),
+            ("seconds", None),
+            ("", None),
+            // annotations
+            ("{request}", None),
+        ];
+        for (unit, expected_suffix) in test_cases {
+            assert_eq!(get_unit_suffixes(unit), expected_suffix);
+        }
+    }
+
+    #[test]
+    fn family_names() {
+        let config = EncoderConfig::default();
+        let text = ExpositionFormat::PrometheusText;
+        let open_metrics = ExpositionFormat::OpenMetrics;
+
+        assert_eq!(
+            family_name("requests", None, FamilyKind::Counter, &config, text),
+            "requests_total"
+        );
+        assert_eq!(
+            family_name("requests_total", None, FamilyKind::Counter, &config, text),
+            "requests_total"
+        );
+        assert_eq!(
+            family_name(
+                "requests_total",
+                None,
+                FamilyKind::Counter,
+                &config,
+                open_metrics
+            ),
+            "requests"
+        );
+        assert_eq!(
+            family_name(
+                "http.server.duration",
+                Some("seconds"),
+                FamilyKind::Histogram,
+                &config,
+                text
+            ),
+            "http_server_duration_seconds"
+        );
+        assert_eq!(
+            family_name(
+                "duration_seconds",
+                Some("seconds"),
+                FamilyKind::Gauge,
+                &config,
+                text
+            ),
+            "duration_seconds"
+        );
+
+        let config = EncoderConfig {
+            without_counter_suffixes: true,
+            namespace: Some("app".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            family_name(
+                "requests",
+                Some("bytes"),
+                FamilyKind::Counter,
+                &config,
+                text
+            ),
+            "app_requests_bytes"
+        );
+    }
+
+    #[test]
+    fn label_values_are_escaped() {
+        assert_eq!(escape_label_value("plain"), "plain");
+        assert_eq!(
+            escape_label_value("a \"quoted\"\\path\nline"),
+            "a \\\"quoted\\\"\\\\path\\nline"
+        );
+        assert_eq!(escape_help("multi\nline"), "multi\\nline");
+    }
+
+    #[test]
+    fn colliding_label_names_are_joined() {
+        let attributes = [KeyValue::new("foo.bar", "a"), KeyValue::new("foo_bar", "b")];
+        assert_eq!(
+            labels(attributes.iter().map(|kv| (&kv.key, &kv.value))),
+            vec![("foo_bar".to_string(), "a;b".to_string())]
+        );
+    }
+
+    #[test]
+    fn special_values_are_formatted() {
+        assert_eq!(format_number(f64::NAN), "NaN");
+        assert_eq!(format_number(f64::INFINITY), "+Inf");
+        assert_eq!(format_number(f64::NEG_INFINITY), "-Inf");
+        assert_eq!(format_number(1.5), "1.5");
+        assert_eq!(format_number(3u64), "3");
+        assert_eq!(format_bound(5.0, ExpositionFormat::OpenMetrics), "5.0");
+        assert_eq!(format_bound(5.0, ExpositionFormat::PrometheusText), "5");
+    }
+}
@@ -0,0 +1,256 @@
+//! A minimal HTTP/1.1 server answering Prometheus scrapes on `/metrics`.
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use opentelemetry::{otel_debug, otel_warn, Context};
+
+use super::{ExpositionFormat, PrometheusReaderInner};
+
+const METRICS_PATH: &str = "/metrics";
+/// The time a client has to send the request line and headers.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+/// The maximum size of the request line and headers.
+const MAX_REQUEST_HEAD_SIZE: u64 = 8 * 1024;
+/// The maximum number of connections served at once, further connections are
+/// closed without a response.
+const MAX_CONNECTIONS: usize = 8;
+
+/// Accepts connections on a dedicated thread, serving each of them the metrics
+/// of a reader on a thread of its own.
+///
+/// The threads only hold a weak reference to the reader, and the accepting
+/// thread exits once the listener is stopped or dropped.
+#[derive(Debug)]
+pub(super) struct HttpListener {
+    local_addr: SocketAddr,
+    is_stopped: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl HttpListener {
+    pub(super) fn start(
+        addr: SocketAddr,
+        reader: Weak<PrometheusReaderInner>,
+    ) -> io::Result<HttpListener> {
+        let listener = TcpListener::bind(addr)?;
+        let local_addr = listener.local_addr()?;
+        let is_stopped = Arc::new(AtomicBool::new(false));
+        let is_stopped_clone = is_stopped.clone();
+
+        let handle = thread::Builder::new()
+            .name("OpenTelemetry.Metrics.PrometheusListener".to_string())
+            .spawn(move || {
+                let _suppress_guard = Context::enter_telemetry_suppressed_scope();
+                let connections = Arc::new(AtomicUsize::new(0));
+                for stream in listener.incoming() {
+                    if is_stopped_clone.load(Ordering::Acquire) || reader.strong_count() == 0 {
+                        break;
+                    }
+                    match stream {
+                        Ok(stream) => spawn_connection(stream, reader.clone(), &connections),
+                        Err(err) => {
+                            otel_debug!(
+                                name: "PrometheusReader.Listener.AcceptFailed",
+                                error = format!("{err}")
+                            );
+                        }
+                    }
+                }
+                otel_debug!(name: "PrometheusReader.Listener.Stopped");
+            })?;
+
+        Ok(HttpListener {
+            local_addr,
+            is_stopped,
+            handle: Some(handle),
+        })
+    }
+
+    pub(super) fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Stops the listener and waits for its thread to exit.
+    pub(super) fn stop(mut self) {
+        self.signal_stop();
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                otel_warn!(
+                    name: "PrometheusReader.Listener.ThreadPanicked",
+                    message = "The Prometheus listener thread panicked."
+                );
+            }
+        }
+    }
+
+    fn signal_stop(&self) {
+        if self.is_stopped.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        // unblock the pending `accept`, the thread exits on the next connection
+        let _ = TcpStream::connect_timeout(&self.wake_addr(), Duration::from_secs(1));
+    }
+
+    /// The address to connect to for waking up the listener thread, as the
+    /// unspecified address it may be bound to cannot be connected to.
+    fn wake_addr(&self) -> SocketAddr {
+        let mut addr = self.local_addr;
+        if addr.ip().is_unspecified() {
+            match addr {
+                SocketAddr::V4(_) => addr.set_ip([127, 0, 0, 1].into()),
+                SocketAddr::V6(_) => addr.set_ip(std::net::Ipv6Addr::LOCALHOST.into()),
+            }
+        }
+        addr
+    }
+}
+
+impl Drop for HttpListener {
+    fn drop(&mut self) {
+        // The last reference to the reader may be dropped by the listener
+        // thread itself, so the thread is not joined here.
+        self.signal_stop();
+    }
+}
+
+/// Serves `stream` on a new thread, unless [`MAX_CONNECTIONS`] are already
+/// being served.
+fn spawn_connection(
+    stream: TcpStream,
+    reader: Weak<PrometheusReaderInner>,
+    connections: &Arc<AtomicUsize>,
+) {
+    if connections.fetch_add(1, Ordering::AcqRel) >= MAX_CONNECTIONS {
+        connections.fetch_sub(1, Ordering::AcqRel);
+        otel_debug!(
+            name: "PrometheusReader.Listener.TooManyConnections",
+            max_connections = MAX_CONNECTIONS
+        );
+        return;
+    }
+    let connections_clone = connections.clone();
+    let spawned = thread::Builder::new()
+        .name("OpenTelemetry.Metrics.PrometheusConnection".to_string())
+        .spawn(move || {
+            let _suppress_guard = Context::enter_telemetry_suppressed_scope();
+            if let Err(err) = serve(stream, &reader) {
+                otel_debug!(
+                    name: "PrometheusReader.Listener.RequestFailed",
+                    error = format!("{err}")
+                );
+            }
+            connections_clone.fetch_sub(1, Ordering::AcqRel);
+        });
+    if let Err(err) = spawned {
+        connections.fetch_sub(1, Ordering::AcqRel);
+        otel_debug!(
+            name: "PrometheusReader.Listener.SpawnFailed",
+            error = format!("{err}")
+        );
+    }
+}
+
+/// Reads from a stream until a deadline, shared by all reads.
+struct DeadlineReader<'a> {
+    stream: &'a TcpStream,
+    deadline: Instant,
+}
+
+impl Read for DeadlineReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        self.stream.set_read_timeout(Some(remaining))?;
+        self.stream.read(buf)
+    }
+}
+
+fn serve(stream: TcpStream, reader: &Weak<PrometheusReaderInner>) -> io::Result<()> {
+    // The request head is read within `READ_TIMEOUT` and `MAX_REQUEST_HEAD_SIZE`,
+    // the lines cut short by the size limit are handled as they are.
+    let mut request = BufReader::new(
+        DeadlineReader {
+            stream: &stream,
+            deadline: Instant::now() + READ_TIMEOUT,
+        }
+        .take(MAX_REQUEST_HEAD_SIZE),
+    );
+
+    let mut request_line = String::new();
+    request.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (
+        parts.next().unwrap_or_default(),
+        parts.next().unwrap_or_default(),
+    );
+
+    let mut accept = String::new();
+    loop {
+        let mut header = String::new();
+        let read = request.read_line(&mut header)?;
+        if read == 0 || header.trim_end().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("accept") {
+                accept = value.trim().to_string();
+            }
+        }
+    }
+
+    let path = path.split('?').next().unwrap_or_default();
+    if path != METRICS_PATH {
+        return respond(&stream, "404 Not Found", "text/plain", "Not Found\n");
+    }
+    if method != "GET" && method != "HEAD" {
+        return respond(
+            &stream,
+            "405 Method Not Allowed",
+            "text/plain",
+            "Method Not Allowed\n",
+        );
+    }
+
+    let Some(reader) = reader.upgrade() else {
+        return respond(
+            &stream,
+            "503 Service Unavailable",
+            "text/plain",
+            "Service Unavailable\n",
+        );
+    };
+    let format = ExpositionFormat::from_accept_header(&accept);
+    match reader.render(format) {
+        Ok(body) if method == "HEAD" => respond_head(&stream, format.content_type(), body.len()),
+        Ok(body) => respond(&stream, "200 OK", format.content_type(), &body),
+        Err(err) => respond(
+            &stream,
+            "500 Internal Server Error",
+            "text/plain",
+            &format!("failed to collect metrics: {err}\n"),
+        ),
+    }
+}
+
+fn respond(mut stream: &TcpStream, status: &str, content_type: &str, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+fn respond_head(mut stream: &TcpStream, content_type: &str, length: usize) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: {content_type}\r\nContent-Length: {length}\r\nConnection: close\r\n\r\n"
+    )?;
+    stream.flush()
+}
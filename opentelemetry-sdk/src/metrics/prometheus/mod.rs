@@ -0,0 +1,473 @@
+//! A pull [MetricReader] exposing metrics in the Prometheus text and
+//! OpenMetrics exposition formats.
+//!
+//! Unlike the discontinued `opentelemetry-prometheus` crate, the reader does
+//! not depend on the `prometheus` crate: it renders the collected metrics
+//! itself, and can optionally serve them on a minimal HTTP `/metrics`
+//! endpoint.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use opentelemetry_sdk::metrics::{ExpositionFormat, PrometheusReader, SdkMeterProvider};
+//!
+//! let reader = PrometheusReader::builder()
+//!     .with_http_listener(([0, 0, 0, 0], 9464))
+//!     .build()
+//!     .expect("failed to bind the /metrics listener");
+//! let provider = SdkMeterProvider::builder()
+//!     .with_reader(reader.clone())
+//!     .build();
+//!
+//! // or render on demand, e.g. from an existing HTTP server
+//! let body = reader.render(ExpositionFormat::OpenMetrics).unwrap();
+//! # drop((provider, body));
+//! ```
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+
+use crate::error::{OTelSdkError, OTelSdkResult};
+use crate::metrics::data::ResourceMetrics;
+use crate::metrics::pipeline::Pipeline;
+use crate::metrics::reader::MetricReader;
+use crate::metrics::{InstrumentKind, ManualReader, Temporality};
+
+mod encoder;
+mod listener;
+
+use encoder::EncoderConfig;
+use listener::HttpListener;
+
+/// The format metrics are rendered in by a [PrometheusReader].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ExpositionFormat {
+    /// The Prometheus text exposition format, version 0.0.4.
+    #[default]
+    PrometheusText,
+    /// The OpenMetrics text format, version 1.0.0, which adds units, exemplars
+    /// and `_created` samples.
+    OpenMetrics,
+}
+
+impl ExpositionFormat {
+    /// The `Content-Type` of metrics rendered in this format.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExpositionFormat::PrometheusText => "text/plain; version=0.0.4; charset=utf-8",
+            ExpositionFormat::OpenMetrics => {
+                "application/openmetrics-text; version=1.0.0; charset=utf-8"
+            }
+        }
+    }
+
+    /// Selects the format requested by the `Accept` header of a scrape
+    /// request, falling back to the Prometheus text format.
+    pub fn from_accept_header(accept: &str) -> Self {
+        if accept.split(',').any(|media_range| {
+            media_range
+                .trim()
+                .starts_with("application/openmetrics-text")
+        }) {
+            ExpositionFormat::OpenMetrics
+        } else {
+            ExpositionFormat::PrometheusText
+        }
+    }
+}
+
+/// A [MetricReader] rendering metrics in the Prometheus exposition formats
+/// when scraped.
+///
+/// Metrics are collected with cumulative temporality on each call to
+/// [PrometheusReader::render], or on each request to the HTTP listener
+/// enabled with [PrometheusReaderBuilder::with_http_listener].
+///
+/// The reader is cheap to clone: clones share the same state, so one clone
+/// can be registered with the [SdkMeterProvider] while another is used to
+/// render the metrics.
+///
+/// [SdkMeterProvider]: crate::metrics::SdkMeterProvider
+#[derive(Clone)]
+pub struct PrometheusReader {
+    inner: Arc<PrometheusReaderInner>,
+}
+
+struct PrometheusReaderInner {
+    reader: ManualReader,
+    config: EncoderConfig,
+    listener: Mutex<Option<HttpListener>>,
+}
+
+impl fmt::Debug for PrometheusReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrometheusReader")
+            .field("config", &self.inner.config)
+            .field("local_addr", &self.local_addr())
+            .finish()
+    }
+}
+
+impl PrometheusReader {
+    /// Configuration for this reader.
+    pub fn builder() -> PrometheusReaderBuilder {
+        PrometheusReaderBuilder::default()
+    }
+
+    /// Collects the metrics of the meter provider and renders them in
+    /// `format`.
+    pub fn render(&self, format: ExpositionFormat) -> Result<String, OTelSdkError> {
+        self.inner.render(format)
+    }
+
+    /// The address the HTTP listener is bound to, if enabled.
+    ///
+    /// Useful when the listener is bound to port `0`.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.inner
+            .listener
+            .lock()
+            .ok()
+            .and_then(|listener| listener.as_ref().map(HttpListener::local_addr))
+    }
+}
+
+impl PrometheusReaderInner {
+    fn render(&self, format: ExpositionFormat) -> Result<String, OTelSdkError> {
+        let mut rm = ResourceMetrics::default();
+        self.reader.collect(&mut rm)?;
+        Ok(encoder::encode(&rm, &self.config, format))
+    }
+}
+
+impl MetricReader for PrometheusReader {
+    fn register_pipeline(&self, pipeline: Weak<Pipeline>) {
+        self.inner.reader.register_pipeline(pipeline)
+    }
+
+    fn collect(&self, rm: &mut ResourceMetrics) -> OTelSdkResult {
+        self.inner.reader.collect(rm)
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.inner.reader.force_flush()
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        let listener = self
+            .inner
+            .listener
+            .lock()
+            .map(|mut listener| listener.take())
+            .unwrap_or_default();
+        if let Some(listener) = listener {
+            listener.stop();
+        }
+        self.inner.reader.shutdown_with_timeout(timeout)
+    }
+
+    fn temporality(&self, kind: InstrumentKind) -> Temporality {
+        self.inner.reader.temporality(kind)
+    }
+}
+
+/// Configuration for [PrometheusReader].
+#[derive(Debug, Default)]
+pub struct PrometheusReaderBuilder {
+    config: EncoderConfig,
+    listen_addr: Option<SocketAddr>,
+}
+
+impl PrometheusReaderBuilder {
+    /// Disables the unit suffixes added to metric names, e.g. `_seconds`.
+    pub fn without_units(mut self) -> Self {
+        self.config.without_units = true;
+        self
+    }
+
+    /// Disables the `_total` suffix added to counter names in the Prometheus
+    /// text format.
+    ///
+    /// OpenMetrics requires the suffix, so it is always added in that format.
+    pub fn without_counter_suffixes(mut self) -> Self {
+        self.config.without_counter_suffixes = true;
+        self
+    }
+
+    /// Disables the `target_info` metric carrying the resource attributes.
+    pub fn without_target_info(mut self) -> Self {
+        self.config.without_target_info = true;
+        self
+    }
+
+    /// Disables the `otel_scope_name` and `otel_scope_version` labels added
+    /// to every sample.
+    pub fn without_scope_info(mut self) -> Self {
+        self.config.without_scope_info = true;
+        self
+    }
+
+    /// Prefixes every metric name with `namespace`.
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        let namespace = namespace.into();
+        self.config.namespace = (!namespace.is_empty()).then_some(namespace);
+        self
+    }
+
+    /// Serves the metrics on `GET /metrics` on `addr`, conventionally port
+    /// `9464`.
+    ///
+    /// The listener accepts connections on a dedicated thread until the reader
+    /// is shut down, and serves up to 8 of them at once, each on a thread of
+    /// its own. It is a minimal HTTP/1.1 server meant for scrapes only; use
+    /// [PrometheusReader::render] to serve the metrics from an existing
+    /// server instead.
+    pub fn with_http_listener(mut self, addr: impl Into<SocketAddr>) -> Self {
+        self.listen_addr = Some(addr.into());
+        self
+    }
+
+    /// Creates a new [PrometheusReader], binding the HTTP listener if
+    /// enabled.
+    pub fn build(self) -> Result<PrometheusReader, OTelSdkError> {
+        let inner = Arc::new(PrometheusReaderInner {
+            reader: ManualReader::builder()
+                .with_temporality(Temporality::Cumulative)
+                .build(),
+            config: self.config,
+            listener: Mutex::new(None),
+        });
+
+        if let Some(addr) = self.listen_addr {
+            let listener = HttpListener::start(addr, Arc::downgrade(&inner)).map_err(|err| {
+                OTelSdkError::InternalFailure(format!(
+                    "failed to start the Prometheus HTTP listener on {addr}: {err}"
+                ))
+            })?;
+            if let Ok(mut guard) = inner.listener.lock() {
+                *guard = Some(listener);
+            }
+        }
+
+        Ok(PrometheusReader { inner })
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    use opentelemetry::metrics::MeterProvider;
+    use opentelemetry::{InstrumentationScope, KeyValue};
+
+    use super::*;
+    use crate::metrics::SdkMeterProvider;
+    use crate::Resource;
+
+    fn setup(builder: PrometheusReaderBuilder) -> (PrometheusReader, SdkMeterProvider) {
+        let reader = builder.build().unwrap();
+        let provider = SdkMeterProvider::builder()
+            .with_resource(
+                Resource::builder_empty()
+                    .with_attribute(KeyValue::new("service.name", "test"))
+                    .build(),
+            )
+            .with_reader(reader.clone())
+            .build();
+        (reader, provider)
+    }
+
+    fn record(provider: &SdkMeterProvider) {
+        let scope = InstrumentationScope::builder("test-scope")
+            .with_version("1.0")
+            .build();
+        let meter = provider.meter_with_scope(scope);
+        meter
+            .u64_counter("http.requests")
+            .with_description("Total requests")
+            .build()
+            .add(3, &[KeyValue::new("http.method", "GET")]);
+        meter
+            .f64_histogram("http.duration")
+            .with_unit("s")
+            .with_boundaries(vec![0.1, 1.0])
+            .build()
+            .record(0.5, &[]);
+        meter
+            .i64_up_down_counter("queue.items")
+            .build()
+            .add(-2, &[]);
+    }
+
+    #[test]
+    fn render_prometheus_text() {
+        // Arrange
+        let (reader, provider) = setup(PrometheusReader::builder());
+        record(&provider);
+
+        // Act
+        let body = reader.render(ExpositionFormat::PrometheusText).unwrap();
+
+        // Assert
+        let scope = r#"otel_scope_name="test-scope",otel_scope_version="1.0""#;
+        assert!(body.starts_with(
+            "# HELP target_info Target metadata\n# TYPE target_info gauge\ntarget_info{service_name=\"test\"} 1\n"
+        ));
+        assert!(body.contains("# HELP http_requests_total Total requests\n"));
+        assert!(body.contains("# TYPE http_requests_total counter\n"));
+        assert!(body.contains(&format!(
+            "http_requests_total{{http_method=\"GET\",{scope}}} 3\n"
+        )));
+        assert!(body.contains("# TYPE http_duration_seconds histogram\n"));
+        assert!(body.contains(&format!(
+            "http_duration_seconds_bucket{{{scope},le=\"0.1\"}} 0\n"
+        )));
+        assert!(body.contains(&format!(
+            "http_duration_seconds_bucket{{{scope},le=\"1\"}} 1\n"
+        )));
+        assert!(body.contains(&format!(
+            "http_duration_seconds_bucket{{{scope},le=\"+Inf\"}} 1\n"
+        )));
+        assert!(body.contains(&format!("http_duration_seconds_sum{{{scope}}} 0.5\n")));
+        assert!(body.contains(&format!("http_duration_seconds_count{{{scope}}} 1\n")));
+        assert!(body.contains("# TYPE queue_items gauge\n"));
+        assert!(body.contains(&format!("queue_items{{{scope}}} -2\n")));
+        assert!(!body.contains("# UNIT"));
+        assert!(!body.contains("_created"));
+        assert!(!body.contains("# EOF"));
+    }
+
+    #[test]
+    fn render_open_metrics() {
+        // Arrange
+        let (reader, provider) = setup(PrometheusReader::builder().without_scope_info());
+        record(&provider);
+
+        // Act
+        let body = reader.render(ExpositionFormat::OpenMetrics).unwrap();
+
+        // Assert
+        assert!(body.starts_with("# TYPE target info\n"));
+        assert!(body.contains("# TYPE http_requests counter\n"));
+        assert!(body.contains("http_requests_total{http_method=\"GET\"} 3\n"));
+        assert!(body.contains("http_requests_created{http_method=\"GET\"} "));
+        assert!(body.contains("# UNIT http_duration_seconds seconds\n"));
+        assert!(body.contains("http_duration_seconds_bucket{le=\"1.0\"} 1\n"));
+        assert!(body.contains("http_duration_seconds_created "));
+        assert!(!body.contains("otel_scope_name"));
+        assert!(body.ends_with("# EOF\n"));
+    }
+
+    #[test]
+    fn render_with_options() {
+        // Arrange
+        let (reader, provider) = setup(
+            PrometheusReader::builder()
+                .with_namespace("app")
+                .without_units()
+                .without_counter_suffixes()
+                .without_target_info(),
+        );
+        record(&provider);
+
+        // Act
+        let body = reader.render(ExpositionFormat::PrometheusText).unwrap();
+
+        // Assert
+        assert!(!body.contains("target_info"));
+        assert!(body.contains("# TYPE app_http_requests counter\n"));
+        assert!(body.contains("# TYPE app_http_duration histogram\n"));
+    }
+
+    #[test]
+    fn render_after_shutdown_fails() {
+        let (reader, provider) = setup(PrometheusReader::builder());
+        provider.shutdown().unwrap();
+
+        assert!(reader.render(ExpositionFormat::PrometheusText).is_err());
+    }
+
+    #[test]
+    fn exposition_format_from_accept_header() {
+        assert_eq!(
+            ExpositionFormat::from_accept_header(
+                "application/openmetrics-text;version=1.0.0,text/plain;version=0.0.4;q=0.5"
+            ),
+            ExpositionFormat::OpenMetrics
+        );
+        assert_eq!(
+            ExpositionFormat::from_accept_header("text/plain"),
+            ExpositionFormat::PrometheusText
+        );
+        assert_eq!(
+            ExpositionFormat::from_accept_header("*/*"),
+            ExpositionFormat::PrometheusText
+        );
+    }
+
+    fn get(addr: SocketAddr, path: &str, accept: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "GET {path} HTTP/1.1\r\nHost: localhost\r\nAccept: {accept}\r\n\r\n"
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn http_listener_serves_metrics() {
+        // Arrange
+        let (reader, provider) =
+            setup(PrometheusReader::builder().with_http_listener(([127, 0, 0, 1], 0)));
+        record(&provider);
+        let addr = reader.local_addr().expect("listener should be bound");
+
+        // Act
+        let text = get(addr, "/metrics", "text/plain");
+        let open_metrics = get(addr, "/metrics", "application/openmetrics-text");
+        let not_found = get(addr, "/", "*/*");
+
+        // Assert
+        assert!(text.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(text.contains("Content-Type: text/plain; version=0.0.4; charset=utf-8\r\n"));
+        assert!(text.contains("http_requests_total"));
+        assert!(open_metrics.contains("Content-Type: application/openmetrics-text"));
+        assert!(open_metrics.ends_with("# EOF\n"));
+        assert!(not_found.starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+        provider.shutdown().unwrap();
+        assert!(reader.local_addr().is_none());
+        assert!(TcpStream::connect(addr).is_err());
+    }
+
+    #[test]
+    fn http_listener_is_not_blocked_by_slow_clients() {
+        // Arrange
+        let (reader, provider) =
+            setup(PrometheusReader::builder().with_http_listener(([127, 0, 0, 1], 0)));
+        record(&provider);
+        let addr = reader.local_addr().expect("listener should be bound");
+        let mut slow = TcpStream::connect(addr).unwrap();
+        write!(slow, "GET /metr").unwrap();
+        // The request head is cut off at 8 KiB.
+        let mut oversized = TcpStream::connect(addr).unwrap();
+        oversized.write_all(&[b'a'; 8 * 1024]).unwrap();
+
+        // Act
+        let text = get(addr, "/metrics", "text/plain");
+        let mut response = String::new();
+        oversized.read_to_string(&mut response).unwrap();
+
+        // Assert
+        assert!(text.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        drop(slow);
+        provider.shutdown().unwrap();
+    }
+}
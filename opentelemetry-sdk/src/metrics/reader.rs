@@ -1,9 +1,14 @@
 //! Interfaces for reading and producing metrics
 use crate::error::OTelSdkResult;
+#[cfg(feature = "experimental_self_observability")]
+use opentelemetry::metrics::Meter;
 use std::time::Duration;
 use std::{fmt, sync::Weak};
 
-use super::{data::ResourceMetrics, instrument::InstrumentKind, pipeline::Pipeline, Temporality};
+use super::{
+    aggregation::Aggregation, data::ResourceMetrics, instrument::InstrumentKind,
+    pipeline::Pipeline, Temporality,
+};
 
 /// The interface used between the SDK and an exporter.
 ///
@@ -58,6 +63,22 @@ pub trait MetricReader: fmt::Debug + Send + Sync + 'static {
     ///
     /// If not configured, the Cumulative temporality SHOULD be used.
     fn temporality(&self, kind: InstrumentKind) -> Temporality;
+
+    /// The default aggregation, a function of instrument kind.
+    /// This SHOULD be obtained from the exporter.
+    ///
+    /// It is used for the streams of instruments whose view does not set an
+    /// aggregation, or sets [Aggregation::Default]. If this returns
+    /// [Aggregation::Default], the default aggregation of the instrument kind is
+    /// used.
+    fn aggregation(&self, _kind: InstrumentKind) -> Aggregation {
+        Aggregation::Default
+    }
+
+    /// Set the meter the reader reports its
+    /// [self-observability](crate::self_observability) metrics with.
+    #[cfg(feature = "experimental_self_observability")]
+    fn set_meter(&self, _meter: &Meter) {}
 }
 
 /// Produces metrics for a [MetricReader].
@@ -0,0 +1,1365 @@
+//! Conversion of metric data between temporalities.
+//!
+//! The [TemporalityConverter] sits in front of a [PushMetricExporter] and
+//! converts the metrics it receives into the [Temporality] of that exporter.
+//! It allows a single [SdkMeterProvider] pipeline to feed backends that
+//! disagree on temporality, or an exporter to consume data produced with a
+//! temporality it does not support.
+//!
+//! [SdkMeterProvider]: crate::metrics::SdkMeterProvider
+use std::borrow::Cow;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use opentelemetry::{time::now, InstrumentationScope, KeyValue};
+
+use crate::error::OTelSdkResult;
+
+use super::aggregation::Aggregation;
+use super::data::{
+    AggregatedMetrics, ExponentialBucket, ExponentialHistogram, ExponentialHistogramDataPoint,
+    Gauge, Histogram, HistogramDataPoint, Metric, MetricData, ResourceMetrics, ScopeMetrics, Sum,
+    SumDataPoint,
+};
+use super::exporter::PushMetricExporter;
+use super::internal::Number;
+use super::{InstrumentKind, Temporality};
+
+/// The default time after which a series that stopped reporting is forgotten.
+const DEFAULT_STALE_SERIES_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// A [PushMetricExporter] converting metrics to the [Temporality] of the
+/// exporter it wraps.
+///
+/// The converter requests the configured source temporality from the reader it
+/// is registered with, see [TemporalityConverterBuilder::with_source_temporality],
+/// and converts every sum and histogram whose temporality differs from the one
+/// expected by the wrapped exporter:
+///
+/// * Delta to cumulative: the deltas of each series are accumulated since the
+///   series was first seen, which is reported as the start time of its points.
+///   Series without new measurements keep being reported with their
+///   accumulated value.
+/// * Cumulative to delta: each series reports the difference to its previous
+///   cumulative value, starting at the time of the previous collection. A
+///   decreasing monotonic value or a new start time is treated as a reset and
+///   the cumulative value is reported as is.
+///
+/// Following the SDK behavior, non-monotonic sums are kept cumulative when the
+/// exporter prefers [Temporality::Delta] or [Temporality::LowMemory]. Gauges
+/// are passed through unchanged. Minimum and maximum values cannot be derived
+/// from cumulative histograms and are omitted from the converted deltas.
+///
+/// State is kept per series, identified by the instrumentation scope, the
+/// metric name and the attributes. Series that have not reported for the stale
+/// series timeout are evicted, see
+/// [TemporalityConverterBuilder::with_stale_series_timeout].
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry_sdk::metrics::exporter::PushMetricExporter;
+/// use opentelemetry_sdk::metrics::{
+///     PeriodicReader, SdkMeterProvider, Temporality, TemporalityConverter,
+/// };
+///
+/// // The SDK aggregates deltas, the exporter receives cumulative sums.
+/// fn meter_provider(exporter: impl PushMetricExporter) -> SdkMeterProvider {
+///     let exporter = TemporalityConverter::builder(exporter)
+///         .with_source_temporality(Temporality::Delta)
+///         .build();
+///     SdkMeterProvider::builder()
+///         .with_reader(PeriodicReader::builder(exporter).build())
+///         .build()
+/// }
+/// ```
+pub struct TemporalityConverter<E> {
+    exporter: E,
+    source_temporality: Temporality,
+    stale_series_timeout: Duration,
+    state: Mutex<ConversionState>,
+}
+
+impl<E: PushMetricExporter> TemporalityConverter<E> {
+    /// Configuration for a converter wrapping `exporter`.
+    pub fn builder(exporter: E) -> TemporalityConverterBuilder<E> {
+        TemporalityConverterBuilder::new(exporter)
+    }
+
+    /// Converts `metrics` to the temporality of the wrapped exporter, updating
+    /// the state of each series.
+    fn convert(&self, metrics: &ResourceMetrics) -> ResourceMetrics {
+        let target = self.exporter.temporality();
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        let ConversionState { f64, u64, i64 } = &mut *state;
+
+        let mut scope_metrics: Vec<ScopeMetrics> = Vec::with_capacity(metrics.scope_metrics.len());
+        let mut converted = HashSet::new();
+        for sm in &metrics.scope_metrics {
+            let mut out = Vec::with_capacity(sm.metrics.len());
+            for metric in &sm.metrics {
+                let id = StreamId {
+                    scope: sm.scope.clone(),
+                    name: metric.name.clone(),
+                };
+                let data = match &metric.data {
+                    AggregatedMetrics::F64(data) => {
+                        convert_data(f64, &id, metric, data, target).map(AggregatedMetrics::F64)
+                    }
+                    AggregatedMetrics::U64(data) => {
+                        convert_data(u64, &id, metric, data, target).map(AggregatedMetrics::U64)
+                    }
+                    AggregatedMetrics::I64(data) => {
+                        convert_data(i64, &id, metric, data, target).map(AggregatedMetrics::I64)
+                    }
+                };
+                if let Some(data) = data {
+                    out.push(Metric {
+                        name: metric.name.clone(),
+                        description: metric.description.clone(),
+                        unit: metric.unit.clone(),
+                        data,
+                    });
+                }
+                converted.insert(id);
+            }
+            push_scope_metrics(&mut scope_metrics, &sm.scope, out);
+        }
+
+        // Accumulated series keep being reported while their instrument is
+        // idle, which removes it from the collected delta metrics.
+        let time = now();
+        for (id, metric) in accumulated(f64, &converted, time, AggregatedMetrics::F64)
+            .chain(accumulated(u64, &converted, time, AggregatedMetrics::U64))
+            .chain(accumulated(i64, &converted, time, AggregatedMetrics::I64))
+        {
+            push_scope_metrics(&mut scope_metrics, &id.scope, vec![metric]);
+        }
+
+        evict_stale(f64, time, self.stale_series_timeout);
+        evict_stale(u64, time, self.stale_series_timeout);
+        evict_stale(i64, time, self.stale_series_timeout);
+
+        ResourceMetrics {
+            resource: metrics.resource.clone(),
+            scope_metrics,
+        }
+    }
+}
+
+impl<E: PushMetricExporter> PushMetricExporter for TemporalityConverter<E> {
+    async fn export(&self, metrics: &ResourceMetrics) -> OTelSdkResult {
+        let converted = self.convert(metrics);
+        self.exporter.export(&converted).await
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.exporter.force_flush()
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        self.exporter.shutdown_with_timeout(timeout)
+    }
+
+    fn temporality(&self) -> Temporality {
+        self.source_temporality
+    }
+
+    fn aggregation(&self, kind: InstrumentKind) -> Aggregation {
+        self.exporter.aggregation(kind)
+    }
+}
+
+impl<E: fmt::Debug> fmt::Debug for TemporalityConverter<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TemporalityConverter")
+            .field("exporter", &self.exporter)
+            .field("source_temporality", &self.source_temporality)
+            .field("stale_series_timeout", &self.stale_series_timeout)
+            .finish()
+    }
+}
+
+/// Configuration for a [TemporalityConverter].
+#[derive(Debug)]
+pub struct TemporalityConverterBuilder<E> {
+    exporter: E,
+    source_temporality: Temporality,
+    stale_series_timeout: Duration,
+}
+
+impl<E: PushMetricExporter> TemporalityConverterBuilder<E> {
+    fn new(exporter: E) -> Self {
+        TemporalityConverterBuilder {
+            exporter,
+            source_temporality: Temporality::default(),
+            stale_series_timeout: DEFAULT_STALE_SERIES_TIMEOUT,
+        }
+    }
+
+    /// The [Temporality] the converter requests from the reader it is
+    /// registered with.
+    ///
+    /// Defaults to [Temporality::Cumulative].
+    pub fn with_source_temporality(mut self, temporality: Temporality) -> Self {
+        self.source_temporality = temporality;
+        self
+    }
+
+    /// The time after which the state of a series that stopped reporting is
+    /// dropped.
+    ///
+    /// An accumulated cumulative series is no longer exported once evicted, and
+    /// restarts from zero if it reports again.
+    ///
+    /// Defaults to 5 minutes.
+    pub fn with_stale_series_timeout(mut self, timeout: Duration) -> Self {
+        self.stale_series_timeout = timeout;
+        self
+    }
+
+    /// Create a [TemporalityConverter] with the configuration of this builder.
+    pub fn build(self) -> TemporalityConverter<E> {
+        TemporalityConverter {
+            exporter: self.exporter,
+            source_temporality: self.source_temporality,
+            stale_series_timeout: self.stale_series_timeout,
+            state: Mutex::default(),
+        }
+    }
+}
+
+/// Appends `metrics` to the [ScopeMetrics] of `scope`, creating it if needed.
+fn push_scope_metrics(
+    scope_metrics: &mut Vec<ScopeMetrics>,
+    scope: &InstrumentationScope,
+    mut metrics: Vec<Metric>,
+) {
+    if metrics.is_empty() {
+        return;
+    }
+    match scope_metrics.iter_mut().find(|sm| &sm.scope == scope) {
+        Some(sm) => sm.metrics.append(&mut metrics),
+        None => scope_metrics.push(ScopeMetrics {
+            scope: scope.clone(),
+            metrics,
+        }),
+    }
+}
+
+/// Identifies a metric stream across collections.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct StreamId {
+    scope: InstrumentationScope,
+    name: Cow<'static, str>,
+}
+
+type Streams<T> = HashMap<StreamId, StreamState<T>>;
+
+#[derive(Default)]
+struct ConversionState {
+    f64: Streams<f64>,
+    u64: Streams<u64>,
+    i64: Streams<i64>,
+}
+
+/// The conversion state of a metric stream.
+struct StreamState<T> {
+    description: Cow<'static, str>,
+    unit: Cow<'static, str>,
+    data: StreamData<T>,
+}
+
+enum StreamData<T> {
+    Sum {
+        is_monotonic: bool,
+        state: SeriesState<SumDataPoint<T>>,
+    },
+    Histogram(SeriesState<HistogramDataPoint<T>>),
+    ExponentialHistogram(SeriesState<ExponentialHistogramDataPoint<T>>),
+}
+
+impl<T> StreamData<T> {
+    fn evict_stale(&mut self, time: SystemTime, timeout: Duration) -> bool {
+        match self {
+            StreamData::Sum { state, .. } => state.evict_stale(time, timeout),
+            StreamData::Histogram(state) => state.evict_stale(time, timeout),
+            StreamData::ExponentialHistogram(state) => state.evict_stale(time, timeout),
+        }
+    }
+}
+
+/// The direction a stream is converted in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Conversion {
+    ToCumulative,
+    ToDelta,
+}
+
+/// The series of a converted stream.
+struct SeriesState<P> {
+    conversion: Conversion,
+    /// The start time of the converted stream, the start time of the series
+    /// that do not have their own.
+    start_time: SystemTime,
+    /// The time of the last converted collection.
+    time: SystemTime,
+    series: HashMap<Vec<KeyValue>, Series<P>>,
+}
+
+struct Series<P> {
+    /// The cumulative value of the series.
+    point: P,
+    /// The time the series was created or last restarted.
+    start_time: SystemTime,
+    last_seen: SystemTime,
+}
+
+impl<P> SeriesState<P> {
+    fn new(conversion: Conversion, start_time: SystemTime) -> Self {
+        SeriesState {
+            conversion,
+            start_time,
+            time: start_time,
+            series: HashMap::new(),
+        }
+    }
+
+    /// Drops the series that have not reported for `timeout`, returning
+    /// whether no series is left.
+    fn evict_stale(&mut self, time: SystemTime, timeout: Duration) -> bool {
+        self.series.retain(|_, series| {
+            time.duration_since(series.last_seen).unwrap_or_default() <= timeout
+        });
+        self.series.is_empty()
+    }
+}
+
+impl<P: DataPoint> SeriesState<P> {
+    /// Adds the delta points of a collection, covering `start_time` to
+    /// `time`, to their series, returning the cumulative points of all series.
+    fn accumulate(&mut self, points: &[P], start_time: SystemTime, time: SystemTime) -> Vec<P> {
+        for point in points {
+            let point_start_time = point.start_time().unwrap_or(start_time);
+            match self.series.entry(series_key(point.attributes())) {
+                Entry::Occupied(mut entry) => {
+                    let series = entry.get_mut();
+                    if !series.point.accumulate(point) {
+                        // Incompatible points, e.g. after a change of histogram
+                        // boundaries, restart the series.
+                        series.point = point.clone();
+                        series.start_time = point_start_time;
+                    }
+                    series.last_seen = time;
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(Series {
+                        point: point.clone(),
+                        start_time: point_start_time,
+                        last_seen: time,
+                    });
+                }
+            }
+        }
+        self.time = time;
+        self.snapshot()
+    }
+
+    /// Returns the cumulative points of all series, handing out the exemplars
+    /// sampled since the last collection only once.
+    fn snapshot(&mut self) -> Vec<P> {
+        self.series
+            .values_mut()
+            .map(|series| {
+                let mut point = series.point.clone();
+                point.set_start_time(series.start_time);
+                series.point.clear_exemplars();
+                point
+            })
+            .collect()
+    }
+
+    /// Replaces the cumulative points of each series with the given ones,
+    /// returning the difference to the previous values.
+    fn difference(&mut self, points: &[P], is_monotonic: bool, time: SystemTime) -> Vec<P> {
+        let deltas = points
+            .iter()
+            .map(|point| {
+                let key = series_key(point.attributes());
+                let previous = self.series.insert(
+                    key,
+                    Series {
+                        point: point.clone(),
+                        start_time: point.start_time().unwrap_or(self.start_time),
+                        last_seen: time,
+                    },
+                );
+                previous
+                    // A series with a new start time was reset.
+                    .filter(|previous| previous.point.start_time() == point.start_time())
+                    .and_then(|previous| point.delta_from(&previous.point, is_monotonic))
+                    .unwrap_or_else(|| point.clone())
+            })
+            .collect();
+        self.time = time;
+        deltas
+    }
+}
+
+/// Returns the attributes in a canonical order.
+fn series_key(attributes: &[KeyValue]) -> Vec<KeyValue> {
+    let mut key = attributes.to_vec();
+    key.sort_unstable_by(|a, b| a.key.cmp(&b.key));
+    key
+}
+
+/// A data point that can be converted between temporalities.
+trait DataPoint: Clone {
+    fn attributes(&self) -> &[KeyValue];
+
+    fn start_time(&self) -> Option<SystemTime>;
+
+    fn set_start_time(&mut self, start_time: SystemTime);
+
+    fn clear_exemplars(&mut self);
+
+    /// Adds the delta point `delta` to this cumulative point.
+    ///
+    /// Returns `false` if the points cannot be combined.
+    fn accumulate(&mut self, delta: &Self) -> bool;
+
+    /// Returns the change since the `previous` cumulative point, or `None` if
+    /// the series was reset in between.
+    fn delta_from(&self, previous: &Self, is_monotonic: bool) -> Option<Self>;
+}
+
+impl<T: Number> DataPoint for SumDataPoint<T> {
+    fn attributes(&self) -> &[KeyValue] {
+        &self.attributes
+    }
+
+    fn start_time(&self) -> Option<SystemTime> {
+        self.start_time
+    }
+
+    fn set_start_time(&mut self, start_time: SystemTime) {
+        self.start_time = Some(start_time);
+    }
+
+    fn clear_exemplars(&mut self) {
+        self.exemplars.clear();
+    }
+
+    fn accumulate(&mut self, delta: &Self) -> bool {
+        self.value += delta.value;
+        self.exemplars.clone_from(&delta.exemplars);
+        true
+    }
+
+    fn delta_from(&self, previous: &Self, is_monotonic: bool) -> Option<Self> {
+        // Unsigned values cannot represent a decrease.
+        let is_unsigned = T::min() == T::default();
+        if (is_monotonic || is_unsigned) && self.value < previous.value {
+            return None;
+        }
+        Some(SumDataPoint {
+            attributes: self.attributes.clone(),
+            start_time: None,
+            value: self.value - previous.value,
+            exemplars: self.exemplars.clone(),
+        })
+    }
+}
+
+impl<T: Number> DataPoint for HistogramDataPoint<T> {
+    fn attributes(&self) -> &[KeyValue] {
+        &self.attributes
+    }
+
+    fn start_time(&self) -> Option<SystemTime> {
+        self.start_time
+    }
+
+    fn set_start_time(&mut self, start_time: SystemTime) {
+        self.start_time = Some(start_time);
+    }
+
+    fn clear_exemplars(&mut self) {
+        self.exemplars.clear();
+    }
+
+    fn accumulate(&mut self, delta: &Self) -> bool {
+        if self.bounds != delta.bounds {
+            return false;
+        }
+        self.count += delta.count;
+        for (count, delta) in self.bucket_counts.iter_mut().zip(&delta.bucket_counts) {
+            *count += delta;
+        }
+        self.sum += delta.sum;
+        self.min = min_of(self.min, delta.min);
+        self.max = max_of(self.max, delta.max);
+        self.exemplars.clone_from(&delta.exemplars);
+        true
+    }
+
+    fn delta_from(&self, previous: &Self, _is_monotonic: bool) -> Option<Self> {
+        if self.bounds != previous.bounds || self.count < previous.count {
+            return None;
+        }
+        let bucket_counts = self
+            .bucket_counts
+            .iter()
+            .zip(&previous.bucket_counts)
+            .map(|(count, previous)| count.checked_sub(*previous))
+            .collect::<Option<Vec<_>>>()?;
+        Some(HistogramDataPoint {
+            attributes: self.attributes.clone(),
+            start_time: None,
+            count: self.count - previous.count,
+            bounds: self.bounds.clone(),
+            bucket_counts,
+            min: None,
+            max: None,
+            sum: self.sum - previous.sum,
+            exemplars: self.exemplars.clone(),
+        })
+    }
+}
+
+impl<T: Number> DataPoint for ExponentialHistogramDataPoint<T> {
+    fn attributes(&self) -> &[KeyValue] {
+        &self.attributes
+    }
+
+    fn start_time(&self) -> Option<SystemTime> {
+        self.start_time
+    }
+
+    fn set_start_time(&mut self, start_time: SystemTime) {
+        self.start_time = Some(start_time);
+    }
+
+    fn clear_exemplars(&mut self) {
+        self.exemplars.clear();
+    }
+
+    fn accumulate(&mut self, delta: &Self) -> bool {
+        if self.zero_threshold != delta.zero_threshold {
+            return false;
+        }
+        let scale = self.scale.min(delta.scale);
+        let combine = |a: &ExponentialBucket, a_scale, b: &ExponentialBucket, b_scale| {
+            combine_buckets(
+                &downscale(a, a_scale, scale),
+                &downscale(b, b_scale, scale),
+                |a, b| Some(a + b),
+            )
+        };
+        let (Some(positive_bucket), Some(negative_bucket)) = (
+            combine(
+                &self.positive_bucket,
+                self.scale,
+                &delta.positive_bucket,
+                delta.scale,
+            ),
+            combine(
+                &self.negative_bucket,
+                self.scale,
+                &delta.negative_bucket,
+                delta.scale,
+            ),
+        ) else {
+            return false;
+        };
+        self.scale = scale;
+        self.positive_bucket = positive_bucket;
+        self.negative_bucket = negative_bucket;
+        self.count += delta.count;
+        self.zero_count += delta.zero_count;
+        self.sum += delta.sum;
+        self.min = min_of(self.min, delta.min);
+        self.max = max_of(self.max, delta.max);
+        self.exemplars.clone_from(&delta.exemplars);
+        true
+    }
+
+    fn delta_from(&self, previous: &Self, _is_monotonic: bool) -> Option<Self> {
+        if self.zero_threshold != previous.zero_threshold
+            || self.count < previous.count
+            || self.zero_count < previous.zero_count
+        {
+            return None;
+        }
+        let (current_scale, previous_scale) = (self.scale, previous.scale);
+        let scale = current_scale.min(previous_scale);
+        let subtract = |current: &ExponentialBucket, previous: &ExponentialBucket| {
+            combine_buckets(
+                &downscale(current, current_scale, scale),
+                &downscale(previous, previous_scale, scale),
+                u64::checked_sub,
+            )
+        };
+        Some(ExponentialHistogramDataPoint {
+            attributes: self.attributes.clone(),
+            start_time: None,
+            count: self.count - previous.count,
+            min: None,
+            max: None,
+            sum: self.sum - previous.sum,
+            scale,
+            zero_count: self.zero_count - previous.zero_count,
+            positive_bucket: subtract(&self.positive_bucket, &previous.positive_bucket)?,
+            negative_bucket: subtract(&self.negative_bucket, &previous.negative_bucket)?,
+            zero_threshold: self.zero_threshold,
+            exemplars: self.exemplars.clone(),
+        })
+    }
+}
+
+fn min_of<T: Number>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(if b < a { b } else { a }),
+        (a, b) => a.or(b),
+    }
+}
+
+fn max_of<T: Number>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(if b > a { b } else { a }),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Merges the buckets of `bucket` for a histogram of scale `from` into the
+/// buckets of the lower scale `to`.
+fn downscale(bucket: &ExponentialBucket, from: i8, to: i8) -> ExponentialBucket {
+    let shift = (from - to) as u32;
+    if shift == 0 || bucket.counts.is_empty() {
+        return bucket.clone();
+    }
+    let offset = bucket.offset >> shift;
+    let last = (bucket.offset + bucket.counts.len() as i32 - 1) >> shift;
+    let mut counts = vec![0; (last - offset + 1) as usize];
+    for (i, count) in bucket.counts.iter().enumerate() {
+        let index = (bucket.offset + i as i32) >> shift;
+        counts[(index - offset) as usize] += count;
+    }
+    ExponentialBucket { offset, counts }
+}
+
+/// Combines the counts of two buckets of the same scale index by index.
+///
+/// Returns `None` if `op` fails for any index.
+fn combine_buckets(
+    a: &ExponentialBucket,
+    b: &ExponentialBucket,
+    op: impl Fn(u64, u64) -> Option<u64>,
+) -> Option<ExponentialBucket> {
+    let range = |bucket: &ExponentialBucket| {
+        (!bucket.counts.is_empty())
+            .then(|| (bucket.offset, bucket.offset + bucket.counts.len() as i32))
+    };
+    let (start, end) = match (range(a), range(b)) {
+        (Some((a_start, a_end)), Some((b_start, b_end))) => {
+            (a_start.min(b_start), a_end.max(b_end))
+        }
+        (Some(range), None) | (None, Some(range)) => range,
+        (None, None) => return Some(a.clone()),
+    };
+    let count_at = |bucket: &ExponentialBucket, index: i32| {
+        usize::try_from(index - bucket.offset)
+            .ok()
+            .and_then(|i| bucket.counts.get(i))
+            .copied()
+            .unwrap_or_default()
+    };
+    let counts = (start..end)
+        .map(|index| op(count_at(a, index), count_at(b, index)))
+        .collect::<Option<Vec<_>>>()?;
+    Some(ExponentialBucket {
+        offset: start,
+        counts,
+    })
+}
+
+/// Returns the temporality `data` is exported with.
+fn target_temporality(target: Temporality, is_monotonic_sum_or_histogram: bool) -> Temporality {
+    match target {
+        Temporality::Cumulative => Temporality::Cumulative,
+        _ if is_monotonic_sum_or_histogram => Temporality::Delta,
+        _ => Temporality::Cumulative,
+    }
+}
+
+/// Converts the data of `metric` to the `target` temporality, returning `None`
+/// if there is nothing to export.
+fn convert_data<T: Number>(
+    streams: &mut Streams<T>,
+    id: &StreamId,
+    metric: &Metric,
+    data: &MetricData<T>,
+    target: Temporality,
+) -> Option<MetricData<T>> {
+    let (conversion, start_time, time) = match data {
+        MetricData::Gauge(gauge) => {
+            return Some(
+                Gauge {
+                    data_points: gauge.data_points.clone(),
+                    start_time: gauge.start_time,
+                    time: gauge.time,
+                }
+                .into(),
+            )
+        }
+        MetricData::Sum(sum) => {
+            let target = target_temporality(target, sum.is_monotonic);
+            (
+                conversion(sum.temporality, target),
+                sum.start_time,
+                sum.time,
+            )
+        }
+        MetricData::Histogram(histogram) => {
+            let target = target_temporality(target, true);
+            let conversion = conversion(histogram.temporality, target);
+            (conversion, histogram.start_time, histogram.time)
+        }
+        MetricData::ExponentialHistogram(histogram) => {
+            let target = target_temporality(target, true);
+            let conversion = conversion(histogram.temporality, target);
+            (conversion, histogram.start_time, histogram.time)
+        }
+    };
+
+    let Some(conversion) = conversion else {
+        // Already in the target temporality.
+        streams.remove(id);
+        return Some(clone_data(data));
+    };
+
+    let stream = streams.entry(id.clone()).or_insert_with(|| StreamState {
+        description: metric.description.clone(),
+        unit: metric.unit.clone(),
+        data: new_stream_data(data, conversion, start_time),
+    });
+    stream.description.clone_from(&metric.description);
+    stream.unit.clone_from(&metric.unit);
+    if !stream.data.matches(data, conversion) {
+        // The instrument changed, e.g. a different aggregation, start over.
+        stream.data = new_stream_data(data, conversion, start_time);
+    }
+
+    let converted = match (&mut stream.data, data) {
+        (
+            StreamData::Sum {
+                is_monotonic,
+                state,
+            },
+            MetricData::Sum(sum),
+        ) => {
+            let (start_time, data_points) =
+                convert_points(state, &sum.data_points, start_time, time, *is_monotonic);
+            Sum {
+                data_points,
+                start_time,
+                time,
+                temporality: conversion.temporality(),
+                is_monotonic: *is_monotonic,
+            }
+            .into()
+        }
+        (StreamData::Histogram(state), MetricData::Histogram(histogram)) => {
+            let (start_time, data_points) =
+                convert_points(state, &histogram.data_points, start_time, time, true);
+            Histogram {
+                data_points,
+                start_time,
+                time,
+                temporality: conversion.temporality(),
+            }
+            .into()
+        }
+        (StreamData::ExponentialHistogram(state), MetricData::ExponentialHistogram(histogram)) => {
+            let (start_time, data_points) =
+                convert_points(state, &histogram.data_points, start_time, time, true);
+            ExponentialHistogram {
+                data_points,
+                start_time,
+                time,
+                temporality: conversion.temporality(),
+            }
+            .into()
+        }
+        _ => return None,
+    };
+    Some(converted)
+}
+
+/// Converts the points of a collection, returning the start time of the
+/// converted points along with them.
+fn convert_points<P: DataPoint>(
+    state: &mut SeriesState<P>,
+    points: &[P],
+    start_time: SystemTime,
+    time: SystemTime,
+    is_monotonic: bool,
+) -> (SystemTime, Vec<P>) {
+    match state.conversion {
+        Conversion::ToCumulative => (state.start_time, state.accumulate(points, start_time, time)),
+        Conversion::ToDelta => {
+            if start_time != state.start_time {
+                // The source restarted, all previous values are obsolete.
+                state.start_time = start_time;
+                state.time = start_time;
+                state.series.clear();
+            }
+            let previous_time = state.time;
+            (previous_time, state.difference(points, is_monotonic, time))
+        }
+    }
+}
+
+/// Returns the conversion needed to go from `source` to `target`.
+fn conversion(source: Temporality, target: Temporality) -> Option<Conversion> {
+    match (source, target) {
+        (Temporality::Cumulative, Temporality::Delta) => Some(Conversion::ToDelta),
+        (Temporality::Delta, Temporality::Cumulative) => Some(Conversion::ToCumulative),
+        _ => None,
+    }
+}
+
+impl Conversion {
+    fn temporality(self) -> Temporality {
+        match self {
+            Conversion::ToCumulative => Temporality::Cumulative,
+            Conversion::ToDelta => Temporality::Delta,
+        }
+    }
+}
+
+fn new_stream_data<T>(
+    data: &MetricData<T>,
+    conversion: Conversion,
+    start_time: SystemTime,
+) -> StreamData<T> {
+    match data {
+        MetricData::Sum(sum) => StreamData::Sum {
+            is_monotonic: sum.is_monotonic,
+            state: SeriesState::new(conversion, start_time),
+        },
+        MetricData::ExponentialHistogram(_) => {
+            StreamData::ExponentialHistogram(SeriesState::new(conversion, start_time))
+        }
+        _ => StreamData::Histogram(SeriesState::new(conversion, start_time)),
+    }
+}
+
+impl<T> StreamData<T> {
+    /// Whether the state was built for `data` converted with `conversion`.
+    fn matches(&self, data: &MetricData<T>, conversion: Conversion) -> bool {
+        match (self, data) {
+            (
+                StreamData::Sum {
+                    is_monotonic,
+                    state,
+                },
+                MetricData::Sum(sum),
+            ) => *is_monotonic == sum.is_monotonic && state.conversion == conversion,
+            (StreamData::Histogram(state), MetricData::Histogram(_)) => {
+                state.conversion == conversion
+            }
+            (StreamData::ExponentialHistogram(state), MetricData::ExponentialHistogram(_)) => {
+                state.conversion == conversion
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Returns the cumulative metrics of the accumulated streams that were not part
+/// of the last collection.
+fn accumulated<'a, T: Number>(
+    streams: &'a mut Streams<T>,
+    converted: &'a HashSet<StreamId>,
+    time: SystemTime,
+    wrap: fn(MetricData<T>) -> AggregatedMetrics,
+) -> impl Iterator<Item = (StreamId, Metric)> + 'a {
+    streams
+        .iter_mut()
+        .filter(move |(id, _)| !converted.contains(id))
+        .filter_map(move |(id, stream)| {
+            let data: MetricData<T> = match &mut stream.data {
+                StreamData::Sum {
+                    is_monotonic,
+                    state,
+                } if state.conversion == Conversion::ToCumulative => Sum {
+                    data_points: state.snapshot(),
+                    start_time: state.start_time,
+                    time,
+                    temporality: Temporality::Cumulative,
+                    is_monotonic: *is_monotonic,
+                }
+                .into(),
+                StreamData::Histogram(state) if state.conversion == Conversion::ToCumulative => {
+                    Histogram {
+                        data_points: state.snapshot(),
+                        start_time: state.start_time,
+                        time,
+                        temporality: Temporality::Cumulative,
+                    }
+                    .into()
+                }
+                StreamData::ExponentialHistogram(state)
+                    if state.conversion == Conversion::ToCumulative =>
+                {
+                    ExponentialHistogram {
+                        data_points: state.snapshot(),
+                        start_time: state.start_time,
+                        time,
+                        temporality: Temporality::Cumulative,
+                    }
+                    .into()
+                }
+                _ => return None,
+            };
+            let metric = Metric {
+                name: id.name.clone(),
+                description: stream.description.clone(),
+                unit: stream.unit.clone(),
+                data: wrap(data),
+            };
+            Some((id.clone(), metric))
+        })
+}
+
+fn evict_stale<T>(streams: &mut Streams<T>, time: SystemTime, timeout: Duration) {
+    streams.retain(|_, stream| !stream.data.evict_stale(time, timeout));
+}
+
+fn clone_data<T: Clone>(data: &MetricData<T>) -> MetricData<T> {
+    match data {
+        MetricData::Gauge(gauge) => Gauge {
+            data_points: gauge.data_points.clone(),
+            start_time: gauge.start_time,
+            time: gauge.time,
+        }
+        .into(),
+        MetricData::Sum(sum) => Sum {
+            data_points: sum.data_points.clone(),
+            start_time: sum.start_time,
+            time: sum.time,
+            temporality: sum.temporality,
+            is_monotonic: sum.is_monotonic,
+        }
+        .into(),
+        MetricData::Histogram(histogram) => Histogram {
+            data_points: histogram.data_points.clone(),
+            start_time: histogram.start_time,
+            time: histogram.time,
+            temporality: histogram.temporality,
+        }
+        .into(),
+        MetricData::ExponentialHistogram(histogram) => ExponentialHistogram {
+            data_points: histogram.data_points.clone(),
+            start_time: histogram.start_time,
+            time: histogram.time,
+            temporality: histogram.temporality,
+        }
+        .into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{InMemoryMetricExporter, InMemoryMetricExporterBuilder};
+    use crate::Resource;
+
+    fn converter(
+        source: Temporality,
+        target: Temporality,
+    ) -> TemporalityConverter<InMemoryMetricExporter> {
+        TemporalityConverter::builder(
+            InMemoryMetricExporterBuilder::new()
+                .with_temporality(target)
+                .build(),
+        )
+        .with_source_temporality(source)
+        .build()
+    }
+
+    fn resource_metrics(data: Vec<(&'static str, AggregatedMetrics)>) -> ResourceMetrics {
+        ResourceMetrics {
+            resource: Resource::builder_empty().build(),
+            scope_metrics: vec![ScopeMetrics {
+                scope: InstrumentationScope::builder("test").build(),
+                metrics: data
+                    .into_iter()
+                    .map(|(name, data)| Metric {
+                        name: name.into(),
+                        description: "".into(),
+                        unit: "".into(),
+                        data,
+                    })
+                    .collect(),
+            }],
+        }
+    }
+
+    fn sum(
+        points: &[(&'static str, u64)],
+        temporality: Temporality,
+        is_monotonic: bool,
+        start_time: SystemTime,
+        time: SystemTime,
+    ) -> AggregatedMetrics {
+        AggregatedMetrics::U64(
+            Sum {
+                data_points: points
+                    .iter()
+                    .map(|(key, value)| SumDataPoint {
+                        attributes: vec![KeyValue::new("key", *key)],
+                        start_time: None,
+                        value: *value,
+                        exemplars: vec![],
+                    })
+                    .collect(),
+                start_time,
+                time,
+                temporality,
+                is_monotonic,
+            }
+            .into(),
+        )
+    }
+
+    fn sum_values(metrics: &ResourceMetrics) -> Vec<(String, u64)> {
+        let mut values = vec![];
+        for metric in metrics.scope_metrics().flat_map(|sm| sm.metrics()) {
+            if let AggregatedMetrics::U64(MetricData::Sum(sum)) = metric.data() {
+                for point in sum.data_points() {
+                    values.push((point.attributes[0].value.to_string(), point.value));
+                }
+            }
+        }
+        values.sort();
+        values
+    }
+
+    fn sum_of(metrics: &ResourceMetrics) -> &Sum<u64> {
+        match metrics.scope_metrics[0].metrics[0].data() {
+            AggregatedMetrics::U64(MetricData::Sum(sum)) => sum,
+            data => panic!("unexpected data {data:?}"),
+        }
+    }
+
+    fn values(points: &[(&str, u64)]) -> Vec<(String, u64)> {
+        points.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+    }
+
+    #[test]
+    fn delta_sums_to_cumulative() {
+        let converter = converter(Temporality::Delta, Temporality::Cumulative);
+        let t0 = now() - Duration::from_secs(30);
+        let (t1, t2) = (t0 + Duration::from_secs(10), t0 + Duration::from_secs(20));
+
+        let out = converter.convert(&resource_metrics(vec![(
+            "requests",
+            sum(&[("a", 5)], Temporality::Delta, true, t0, t1),
+        )]));
+        assert_eq!(sum_values(&out), values(&[("a", 5)]));
+        assert_eq!(sum_of(&out).temporality(), Temporality::Cumulative);
+
+        let out = converter.convert(&resource_metrics(vec![(
+            "requests",
+            sum(&[("a", 3), ("b", 1)], Temporality::Delta, true, t1, t2),
+        )]));
+        assert_eq!(sum_values(&out), values(&[("a", 8), ("b", 1)]));
+        let cumulative = sum_of(&out);
+        assert_eq!(cumulative.start_time(), t0);
+        assert_eq!(cumulative.time(), t2);
+
+        // Idle instruments are missing from the delta collection, their
+        // cumulative values are still reported.
+        let out = converter.convert(&resource_metrics(vec![]));
+        assert_eq!(sum_values(&out), values(&[("a", 8), ("b", 1)]));
+        assert_eq!(sum_of(&out).start_time(), t0);
+    }
+
+    #[test]
+    fn cumulative_sums_to_delta() {
+        let converter = converter(Temporality::Cumulative, Temporality::Delta);
+        let t0 = now() - Duration::from_secs(30);
+        let (t1, t2, t3) = (
+            t0 + Duration::from_secs(10),
+            t0 + Duration::from_secs(20),
+            t0 + Duration::from_secs(30),
+        );
+
+        let out = converter.convert(&resource_metrics(vec![(
+            "requests",
+            sum(&[("a", 10)], Temporality::Cumulative, true, t0, t1),
+        )]));
+        assert_eq!(sum_values(&out), values(&[("a", 10)]));
+        let delta = sum_of(&out);
+        assert_eq!(delta.temporality(), Temporality::Delta);
+        assert_eq!(delta.start_time(), t0);
+
+        let out = converter.convert(&resource_metrics(vec![(
+            "requests",
+            sum(
+                &[("a", 15), ("b", 2)],
+                Temporality::Cumulative,
+                true,
+                t0,
+                t2,
+            ),
+        )]));
+        assert_eq!(sum_values(&out), values(&[("a", 5), ("b", 2)]));
+        let delta = sum_of(&out);
+        assert_eq!(delta.start_time(), t1);
+        assert_eq!(delta.time(), t2);
+
+        // A decreasing monotonic sum was reset.
+        let out = converter.convert(&resource_metrics(vec![(
+            "requests",
+            sum(&[("a", 3), ("b", 2)], Temporality::Cumulative, true, t0, t3),
+        )]));
+        assert_eq!(sum_values(&out), values(&[("a", 3), ("b", 0)]));
+    }
+
+    #[test]
+    fn non_monotonic_sums_stay_cumulative() {
+        let converter = converter(Temporality::Cumulative, Temporality::Delta);
+        let t0 = now();
+        let input = resource_metrics(vec![(
+            "queue_size",
+            sum(&[("a", 4)], Temporality::Cumulative, false, t0, t0),
+        )]);
+
+        for _ in 0..2 {
+            let out = converter.convert(&input);
+            assert_eq!(sum_values(&out), values(&[("a", 4)]));
+            assert_eq!(sum_of(&out).temporality(), Temporality::Cumulative);
+        }
+    }
+
+    #[test]
+    fn cumulative_histograms_to_delta() {
+        let converter = converter(Temporality::Cumulative, Temporality::Delta);
+        let t0 = now();
+        let histogram = |count, bucket_counts: Vec<u64>, sum: f64| {
+            resource_metrics(vec![(
+                "latency",
+                AggregatedMetrics::F64(
+                    Histogram {
+                        data_points: vec![HistogramDataPoint {
+                            attributes: vec![],
+                            start_time: None,
+                            count,
+                            bounds: vec![1.0, 10.0],
+                            bucket_counts,
+                            min: Some(0.5),
+                            max: Some(20.0),
+                            sum,
+                            exemplars: vec![],
+                        }],
+                        start_time: t0,
+                        time: t0,
+                        temporality: Temporality::Cumulative,
+                    }
+                    .into(),
+                ),
+            )])
+        };
+
+        converter.convert(&histogram(3, vec![1, 1, 1], 25.5));
+        let out = converter.convert(&histogram(5, vec![1, 2, 2], 50.5));
+        let AggregatedMetrics::F64(MetricData::Histogram(histogram)) =
+            out.scope_metrics[0].metrics[0].data()
+        else {
+            panic!("expected a histogram");
+        };
+        assert_eq!(histogram.temporality(), Temporality::Delta);
+        let point = &histogram.data_points[0];
+        assert_eq!(point.count(), 2);
+        assert_eq!(point.bucket_counts().collect::<Vec<_>>(), vec![0, 1, 1]);
+        assert_eq!(point.sum(), 25.0);
+        assert_eq!((point.min(), point.max()), (None, None));
+    }
+
+    #[test]
+    fn delta_exponential_histograms_to_cumulative() {
+        let converter = converter(Temporality::Delta, Temporality::Cumulative);
+        let t0 = now();
+        let histogram = |scale, offset, counts: Vec<u64>| {
+            resource_metrics(vec![(
+                "latency",
+                AggregatedMetrics::F64(
+                    ExponentialHistogram {
+                        data_points: vec![ExponentialHistogramDataPoint {
+                            attributes: vec![],
+                            start_time: None,
+                            count: counts.iter().sum::<u64>() as usize,
+                            min: None,
+                            max: None,
+                            sum: 1.0,
+                            scale,
+                            zero_count: 0,
+                            positive_bucket: ExponentialBucket { offset, counts },
+                            negative_bucket: ExponentialBucket {
+                                offset: 0,
+                                counts: vec![],
+                            },
+                            zero_threshold: 0.0,
+                            exemplars: vec![],
+                        }],
+                        start_time: t0,
+                        time: t0,
+                        temporality: Temporality::Delta,
+                    }
+                    .into(),
+                ),
+            )])
+        };
+
+        converter.convert(&histogram(2, 4, vec![1, 1, 1, 1]));
+        let out = converter.convert(&histogram(1, 2, vec![3]));
+        let AggregatedMetrics::F64(MetricData::ExponentialHistogram(histogram)) =
+            out.scope_metrics[0].metrics[0].data()
+        else {
+            panic!("expected an exponential histogram");
+        };
+        assert_eq!(histogram.temporality(), Temporality::Cumulative);
+        let point = &histogram.data_points[0];
+        assert_eq!(point.scale, 1);
+        assert_eq!(point.count, 7);
+        assert_eq!(point.positive_bucket.offset, 2);
+        assert_eq!(point.positive_bucket.counts, vec![5, 2]);
+    }
+
+    #[test]
+    fn stale_series_are_evicted() {
+        let converter = TemporalityConverter::builder(
+            InMemoryMetricExporterBuilder::new()
+                .with_temporality(Temporality::Cumulative)
+                .build(),
+        )
+        .with_source_temporality(Temporality::Delta)
+        .with_stale_series_timeout(Duration::from_secs(5))
+        .build();
+        let t0 = now() - Duration::from_secs(60);
+
+        let out = converter.convert(&resource_metrics(vec![(
+            "requests",
+            sum(&[("a", 5)], Temporality::Delta, true, t0, t0),
+        )]));
+        assert_eq!(sum_values(&out), values(&[("a", 5)]));
+
+        // The series was last seen more than 5 seconds ago.
+        let out = converter.convert(&resource_metrics(vec![]));
+        assert!(sum_values(&out).is_empty());
+
+        let time = now();
+        let out = converter.convert(&resource_metrics(vec![(
+            "requests",
+            sum(&[("a", 2)], Temporality::Delta, true, time, time),
+        )]));
+        assert_eq!(sum_values(&out), values(&[("a", 2)]));
+        assert_eq!(sum_of(&out).start_time(), time);
+    }
+
+    #[test]
+    fn returning_series_get_a_new_start_time() {
+        let converter = TemporalityConverter::builder(
+            InMemoryMetricExporterBuilder::new()
+                .with_temporality(Temporality::Cumulative)
+                .build(),
+        )
+        .with_source_temporality(Temporality::Delta)
+        .with_stale_series_timeout(Duration::from_millis(100))
+        .build();
+        let start_times = |metrics: &ResourceMetrics| {
+            let mut start_times = sum_of(metrics)
+                .data_points()
+                .map(|point| (point.attributes[0].value.to_string(), point.start_time))
+                .collect::<Vec<_>>();
+            start_times.sort();
+            start_times
+        };
+
+        let (t0, t1) = (now() - Duration::from_millis(10), now());
+        converter.convert(&resource_metrics(vec![(
+            "requests",
+            sum(&[("a", 5)], Temporality::Delta, true, t0, t1),
+        )]));
+        std::thread::sleep(Duration::from_millis(200));
+
+        // "a" is reported a last time then evicted, "b" keeps the stream alive.
+        let t2 = now();
+        let out = converter.convert(&resource_metrics(vec![(
+            "requests",
+            sum(&[("b", 1)], Temporality::Delta, true, t1, t2),
+        )]));
+        assert_eq!(
+            start_times(&out),
+            vec![("a".into(), Some(t0)), ("b".into(), Some(t1))]
+        );
+
+        let t3 = now();
+        let out = converter.convert(&resource_metrics(vec![(
+            "requests",
+            sum(&[("a", 2)], Temporality::Delta, true, t2, t3),
+        )]));
+        assert_eq!(sum_values(&out), values(&[("a", 2), ("b", 1)]));
+        assert_eq!(
+            start_times(&out),
+            vec![("a".into(), Some(t2)), ("b".into(), Some(t1))]
+        );
+        assert_eq!(sum_of(&out).start_time(), t0);
+    }
+
+    #[test]
+    fn gauges_pass_through() {
+        let converter = converter(Temporality::Delta, Temporality::Cumulative);
+        let t0 = now();
+        let out = converter.convert(&resource_metrics(vec![(
+            "temperature",
+            AggregatedMetrics::I64(
+                Gauge {
+                    data_points: vec![crate::metrics::data::GaugeDataPoint {
+                        attributes: vec![],
+                        start_time: None,
+                        value: -3,
+                        exemplars: vec![],
+                    }],
+                    start_time: None,
+                    time: t0,
+                }
+                .into(),
+            ),
+        )]));
+        let AggregatedMetrics::I64(MetricData::Gauge(gauge)) =
+            out.scope_metrics[0].metrics[0].data()
+        else {
+            panic!("expected a gauge");
+        };
+        assert_eq!(gauge.data_points[0].value, -3);
+    }
+
+    #[test]
+    fn downscale_merges_buckets() {
+        let bucket = ExponentialBucket {
+            offset: -3,
+            counts: vec![1, 2, 3, 4, 5],
+        };
+        // Indexes -3..=1 map to -2, -1, -1, 0, 0 at one scale lower.
+        let downscaled = downscale(&bucket, 3, 2);
+        assert_eq!(downscaled.offset, -2);
+        assert_eq!(downscaled.counts, vec![1, 5, 9]);
+    }
+}
@@ -0,0 +1,383 @@
+//! # AWS X-Ray Propagator
+//!
+
+use opentelemetry::{
+    baggage::BaggageExt,
+    propagation::{text_map_propagator::FieldIter, Extractor, Injector, TextMapPropagator},
+    trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState},
+    Context,
+};
+use std::sync::OnceLock;
+
+const AWS_XRAY_TRACE_HEADER: &str = "x-amzn-trace-id";
+const AWS_XRAY_VERSION_KEY: &str = "1";
+const HEADER_ROOT_KEY: &str = "Root";
+const HEADER_PARENT_KEY: &str = "Parent";
+const HEADER_SAMPLED_KEY: &str = "Sampled";
+const HEADER_LINEAGE_KEY: &str = "Lineage";
+
+const SAMPLED: &str = "1";
+const NOT_SAMPLED: &str = "0";
+const REQUESTED_SAMPLING_DECISION: &str = "?";
+const DEBUG_SAMPLED: &str = "d";
+
+/// The baggage key under which the extracted `Lineage` field is stored.
+const LINEAGE_BAGGAGE_KEY: &str = "Lineage";
+
+// TODO Replace this with LazyLock once it is stable.
+static XRAY_HEADER_FIELDS: OnceLock<[String; 1]> = OnceLock::new();
+
+fn xray_header_fields() -> &'static [String; 1] {
+    XRAY_HEADER_FIELDS.get_or_init(|| [AWS_XRAY_TRACE_HEADER.to_owned()])
+}
+
+/// Propagates `SpanContext`s in the [AWS X-Ray] format under the
+/// `X-Amzn-Trace-Id` header.
+///
+/// Here's an example of a `X-Amzn-Trace-Id` header.
+///
+/// `X-Amzn-Trace-Id: Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1`
+///
+/// The `Root` field holds the trace id, where the first 8 hex digits are the
+/// seconds since the Unix epoch at which the trace started, see
+/// [`XrayIdGenerator`] to generate such trace ids. The `Parent` field holds
+/// the span id and `Sampled` the sampling decision.
+///
+/// An optional `Lineage` field is stored in the [`Baggage`] of the extracted
+/// context under the `Lineage` key and injected back from it.
+///
+/// [AWS X-Ray]: https://docs.aws.amazon.com/xray/latest/devguide/xray-concepts.html#xray-concepts-tracingheader
+/// [`XrayIdGenerator`]: crate::trace::XrayIdGenerator
+/// [`Baggage`]: opentelemetry::baggage::Baggage
+#[derive(Clone, Debug, Default)]
+pub struct XrayPropagator {
+    _private: (),
+}
+
+impl XrayPropagator {
+    /// Create a new `XrayPropagator`.
+    pub fn new() -> Self {
+        XrayPropagator { _private: () }
+    }
+
+    /// Extract span context and lineage from the `X-Amzn-Trace-Id` header.
+    fn extract_span_context(
+        &self,
+        extractor: &dyn Extractor,
+    ) -> Result<(SpanContext, Option<String>), ()> {
+        let header_value = extractor.get(AWS_XRAY_TRACE_HEADER).ok_or(())?.trim();
+
+        let mut trace_id = None;
+        let mut span_id = None;
+        let mut trace_flags = TraceFlags::default();
+        let mut lineage = None;
+
+        for part in header_value.split(';') {
+            let part = part.trim();
+            if part.is_empty() {
+                continue;
+            }
+            let (key, value) = part.split_once('=').ok_or(())?;
+            match key.trim() {
+                HEADER_ROOT_KEY => trace_id = Some(parse_trace_id(value.trim())?),
+                HEADER_PARENT_KEY => span_id = Some(parse_span_id(value.trim())?),
+                HEADER_SAMPLED_KEY => {
+                    trace_flags = match value.trim() {
+                        SAMPLED | DEBUG_SAMPLED => TraceFlags::SAMPLED,
+                        NOT_SAMPLED | REQUESTED_SAMPLING_DECISION => TraceFlags::default(),
+                        _ => return Err(()),
+                    }
+                }
+                HEADER_LINEAGE_KEY => {
+                    let value = value.trim();
+                    if is_valid_lineage(value) {
+                        lineage = Some(value.to_owned());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let span_context = SpanContext::new(
+            trace_id.ok_or(())?,
+            span_id.ok_or(())?,
+            trace_flags,
+            true,
+            TraceState::default(),
+        );
+
+        // Ensure span is valid
+        if !span_context.is_valid() {
+            return Err(());
+        }
+
+        Ok((span_context, lineage))
+    }
+}
+
+/// Parse a trace id of the form `1-{8 hex digits}-{24 hex digits}`.
+fn parse_trace_id(value: &str) -> Result<TraceId, ()> {
+    let mut parts = value.split('-');
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(AWS_XRAY_VERSION_KEY), Some(epoch), Some(unique), None)
+            if epoch.len() == 8
+                && unique.len() == 24
+                && is_lower_hex(epoch)
+                && is_lower_hex(unique) =>
+        {
+            TraceId::from_hex(&format!("{epoch}{unique}")).map_err(|_| ())
+        }
+        _ => Err(()),
+    }
+}
+
+fn parse_span_id(value: &str) -> Result<SpanId, ()> {
+    if value.len() != 16 || !is_lower_hex(value) {
+        return Err(());
+    }
+    SpanId::from_hex(value).map_err(|_| ())
+}
+
+fn is_lower_hex(value: &str) -> bool {
+    value
+        .bytes()
+        .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+}
+
+/// A lineage is `{request counter}:{hashed resource id}:{loop counter}`, with
+/// the request counter at most 32767, the hash 8 hex digits and the loop
+/// counter at most 255.
+fn is_valid_lineage(value: &str) -> bool {
+    let mut parts = value.split(':');
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(request_counter), Some(hash), Some(loop_counter), None) => {
+            request_counter
+                .parse::<u16>()
+                .is_ok_and(|counter| counter <= 32767)
+                && hash.len() == 8
+                && is_lower_hex(hash)
+                && loop_counter.parse::<u8>().is_ok()
+        }
+        _ => false,
+    }
+}
+
+impl TextMapPropagator for XrayPropagator {
+    /// Properly encodes the values of the `SpanContext` and injects them
+    /// into the `Injector`.
+    fn inject_context(&self, cx: &Context, injector: &mut dyn Injector) {
+        let span = cx.span();
+        let span_context = span.span_context();
+        if span_context.is_valid() {
+            let trace_id = span_context.trace_id().to_string();
+            let sampled = if span_context.is_sampled() {
+                SAMPLED
+            } else {
+                NOT_SAMPLED
+            };
+            let mut header_value = format!(
+                "{HEADER_ROOT_KEY}={AWS_XRAY_VERSION_KEY}-{}-{};{HEADER_PARENT_KEY}={};{HEADER_SAMPLED_KEY}={sampled}",
+                &trace_id[..8],
+                &trace_id[8..],
+                span_context.span_id(),
+            );
+            if let Some(lineage) = cx.baggage().get(LINEAGE_BAGGAGE_KEY) {
+                if is_valid_lineage(lineage.as_str()) {
+                    header_value.push_str(&format!(";{HEADER_LINEAGE_KEY}={}", lineage.as_str()));
+                }
+            }
+            injector.set(AWS_XRAY_TRACE_HEADER, header_value);
+        }
+    }
+
+    /// Retrieves encoded `SpanContext`s using the `Extractor`. It decodes
+    /// the `SpanContext` and returns it. If no `SpanContext` was retrieved
+    /// OR if the retrieved SpanContext is invalid then an empty `SpanContext`
+    /// is returned.
+    fn extract_with_context(&self, cx: &Context, extractor: &dyn Extractor) -> Context {
+        match self.extract_span_context(extractor) {
+            Ok((span_context, lineage)) => {
+                let cx = cx.with_remote_span_context(span_context);
+                match lineage {
+                    Some(lineage) => {
+                        let baggage = cx
+                            .baggage()
+                            .iter()
+                            .map(|(key, value)| (key.clone(), value.clone()))
+                            .chain(std::iter::once((
+                                LINEAGE_BAGGAGE_KEY.into(),
+                                (lineage.into(), Default::default()),
+                            )))
+                            .collect::<opentelemetry::baggage::Baggage>();
+                        cx.with_baggage(baggage)
+                    }
+                    None => cx,
+                }
+            }
+            Err(_) => cx.clone(),
+        }
+    }
+
+    fn fields(&self) -> FieldIter<'_> {
+        FieldIter::new(xray_header_fields())
+    }
+}
+
+#[cfg(all(test, feature = "testing", feature = "trace"))]
+mod tests {
+    use super::*;
+    use crate::testing::trace::TestSpan;
+    use opentelemetry::baggage::Baggage;
+    use std::collections::HashMap;
+
+    #[rustfmt::skip]
+    fn extract_data() -> Vec<(&'static str, SpanContext)> {
+        vec![
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1", SpanContext::new(TraceId::from(0x5759_e988_bd86_2e3f_e1be_46a9_9427_2793), SpanId::from(0x5399_5c3f_42cd_8ad8), TraceFlags::SAMPLED, true, TraceState::default())),
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=0", SpanContext::new(TraceId::from(0x5759_e988_bd86_2e3f_e1be_46a9_9427_2793), SpanId::from(0x5399_5c3f_42cd_8ad8), TraceFlags::default(), true, TraceState::default())),
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=d", SpanContext::new(TraceId::from(0x5759_e988_bd86_2e3f_e1be_46a9_9427_2793), SpanId::from(0x5399_5c3f_42cd_8ad8), TraceFlags::SAMPLED, true, TraceState::default())),
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=?", SpanContext::new(TraceId::from(0x5759_e988_bd86_2e3f_e1be_46a9_9427_2793), SpanId::from(0x5399_5c3f_42cd_8ad8), TraceFlags::default(), true, TraceState::default())),
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8", SpanContext::new(TraceId::from(0x5759_e988_bd86_2e3f_e1be_46a9_9427_2793), SpanId::from(0x5399_5c3f_42cd_8ad8), TraceFlags::default(), true, TraceState::default())),
+            ("Parent=53995c3f42cd8ad8;Sampled=1;Root=1-5759e988-bd862e3fe1be46a994272793;Self=1-5759e988-bd862e3fe1be46a994272794", SpanContext::new(TraceId::from(0x5759_e988_bd86_2e3f_e1be_46a9_9427_2793), SpanId::from(0x5399_5c3f_42cd_8ad8), TraceFlags::SAMPLED, true, TraceState::default())),
+            ("Root = 1-5759e988-bd862e3fe1be46a994272793 ; Parent = 53995c3f42cd8ad8 ; Sampled = 1 ;", SpanContext::new(TraceId::from(0x5759_e988_bd86_2e3f_e1be_46a9_9427_2793), SpanId::from(0x5399_5c3f_42cd_8ad8), TraceFlags::SAMPLED, true, TraceState::default())),
+        ]
+    }
+
+    #[rustfmt::skip]
+    fn extract_data_invalid() -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("", "empty header"),
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Sampled=1", "missing parent"),
+            ("Parent=53995c3f42cd8ad8;Sampled=1", "missing root"),
+            ("Root=2-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1", "wrong version"),
+            ("Root=1-5759e98-bd862e3fe1be46a9942727930;Parent=53995c3f42cd8ad8;Sampled=1", "wrong epoch length"),
+            ("Root=1-5759e988-bd862e3fe1be46a99427279;Parent=53995c3f42cd8ad8;Sampled=1", "wrong trace ID length"),
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad;Sampled=1", "wrong span ID length"),
+            ("Root=1-5759e988-qd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1", "bogus trace ID"),
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=q3995c3f42cd8ad8;Sampled=1", "bogus span ID"),
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=2", "bogus sampled flag"),
+            ("Root=1-5759E988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1", "upper case trace ID"),
+            ("Root=1-00000000-000000000000000000000000;Parent=0000000000000000;Sampled=1", "zero trace ID and span ID"),
+            ("Root;Parent=53995c3f42cd8ad8;Sampled=1", "missing value"),
+        ]
+    }
+
+    #[rustfmt::skip]
+    fn inject_data() -> Vec<(&'static str, SpanContext)> {
+        vec![
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1", SpanContext::new(TraceId::from(0x5759_e988_bd86_2e3f_e1be_46a9_9427_2793), SpanId::from(0x5399_5c3f_42cd_8ad8), TraceFlags::SAMPLED, true, TraceState::default())),
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=0", SpanContext::new(TraceId::from(0x5759_e988_bd86_2e3f_e1be_46a9_9427_2793), SpanId::from(0x5399_5c3f_42cd_8ad8), TraceFlags::default(), true, TraceState::default())),
+            ("", SpanContext::empty_context()),
+        ]
+    }
+
+    #[test]
+    fn extract_xray() {
+        let propagator = XrayPropagator::new();
+
+        for (header, expected_context) in extract_data() {
+            let mut extractor = HashMap::new();
+            extractor.insert(AWS_XRAY_TRACE_HEADER.to_string(), header.to_string());
+
+            assert_eq!(
+                propagator.extract(&extractor).span().span_context(),
+                &expected_context,
+                "{header}"
+            )
+        }
+    }
+
+    #[test]
+    fn extract_xray_reject_invalid() {
+        let propagator = XrayPropagator::new();
+
+        for (invalid_header, reason) in extract_data_invalid() {
+            let mut extractor = HashMap::new();
+            extractor.insert(
+                AWS_XRAY_TRACE_HEADER.to_string(),
+                invalid_header.to_string(),
+            );
+
+            assert_eq!(
+                propagator.extract(&extractor).span().span_context(),
+                &SpanContext::empty_context(),
+                "{reason}"
+            )
+        }
+    }
+
+    #[test]
+    fn inject_xray() {
+        let propagator = XrayPropagator::new();
+
+        for (expected_header, context) in inject_data() {
+            let mut injector = HashMap::new();
+            propagator.inject_context(
+                &Context::current_with_span(TestSpan(context)),
+                &mut injector,
+            );
+
+            assert_eq!(
+                Extractor::get(&injector, AWS_XRAY_TRACE_HEADER).unwrap_or(""),
+                expected_header
+            );
+        }
+    }
+
+    #[test]
+    fn lineage_round_trip() {
+        let propagator = XrayPropagator::new();
+        let header = "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1;Lineage=10:1234abcd:3";
+
+        let mut extractor = HashMap::new();
+        extractor.insert(AWS_XRAY_TRACE_HEADER.to_string(), header.to_string());
+        let cx = Context::new().with_baggage(Baggage::from_iter([opentelemetry::KeyValue::new(
+            "user", "alice",
+        )]));
+        let cx = propagator.extract_with_context(&cx, &extractor);
+
+        assert_eq!(
+            cx.baggage().get(LINEAGE_BAGGAGE_KEY).map(|v| v.as_str()),
+            Some("10:1234abcd:3")
+        );
+        assert_eq!(cx.baggage().get("user").map(|v| v.as_str()), Some("alice"));
+
+        let mut injector = HashMap::new();
+        propagator.inject_context(&cx, &mut injector);
+        assert_eq!(
+            Extractor::get(&injector, AWS_XRAY_TRACE_HEADER),
+            Some(header)
+        );
+    }
+
+    #[test]
+    fn invalid_lineage_is_ignored() {
+        let propagator = XrayPropagator::new();
+
+        for lineage in [
+            "40000:1234abcd:3",
+            "10:1234abc:3",
+            "10:1234abcd:256",
+            "10:1234abcd",
+        ] {
+            let header = format!(
+                "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1;Lineage={lineage}"
+            );
+            let mut extractor = HashMap::new();
+            extractor.insert(AWS_XRAY_TRACE_HEADER.to_string(), header);
+            let cx = propagator.extract(&extractor);
+
+            assert!(cx.span().span_context().is_valid(), "{lineage}");
+            assert_eq!(cx.baggage().get(LINEAGE_BAGGAGE_KEY), None, "{lineage}");
+        }
+    }
+
+    #[test]
+    fn fields_xray() {
+        let propagator = XrayPropagator::new();
+        assert_eq!(
+            propagator.fields().collect::<Vec<_>>(),
+            vec![AWS_XRAY_TRACE_HEADER]
+        );
+    }
+}
@@ -0,0 +1,664 @@
+//! # Self-observability
+//!
+//! Metrics the SDK reports about its own telemetry pipelines, following the
+//! [semantic conventions for OpenTelemetry SDK metrics][semconv], e.g.
+//! `otel.sdk.span.live`, `otel.sdk.processor.span.queue.size` or
+//! `otel.sdk.exporter.span.exported`. They help alerting on dropped telemetry.
+//!
+//! Reporting is opt-in: pass the [MeterProvider] the metrics are reported
+//! through to `with_self_observability` of the tracer, logger or meter
+//! provider builder. The provider hands the resulting [Meter] to its
+//! processors, readers and exporters, which create their instruments when
+//! receiving it.
+//!
+//! [ExporterMetrics] implements the exporter metrics, so exporters outside of
+//! this crate report them the same way.
+//!
+//! [MeterProvider]: opentelemetry::metrics::MeterProvider
+//! [semconv]: https://opentelemetry.io/docs/specs/semconv/otel/sdk-metrics/
+use std::collections::HashMap;
+use std::fmt;
+#[cfg(any(feature = "trace", feature = "logs"))]
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(any(feature = "trace", feature = "logs"))]
+use std::sync::Arc;
+use std::sync::{Mutex, OnceLock};
+#[cfg(feature = "metrics")]
+use std::time::Duration;
+
+#[cfg(feature = "metrics")]
+use opentelemetry::metrics::Histogram;
+use opentelemetry::metrics::{Counter, Meter, UpDownCounter};
+use opentelemetry::KeyValue;
+
+use crate::error::{OTelSdkError, OTelSdkResult};
+
+const COMPONENT_TYPE: &str = "otel.component.type";
+const COMPONENT_NAME: &str = "otel.component.name";
+const ERROR_TYPE: &str = "error.type";
+
+/// Returns the meter reporting the SDK metrics through `meter_provider`.
+#[cfg(any(feature = "trace", feature = "logs", feature = "metrics"))]
+pub(crate) fn meter(meter_provider: &impl opentelemetry::metrics::MeterProvider) -> Meter {
+    meter_provider.meter_with_scope(crate::util::sdk_scope())
+}
+
+/// Returns the attributes identifying a new instance of `component_type`,
+/// named after the number of instances of the type created before, e.g.
+/// `batching_span_processor/0`.
+fn component_attributes(component_type: &'static str) -> [KeyValue; 2] {
+    static INSTANCES: OnceLock<Mutex<HashMap<&'static str, usize>>> = OnceLock::new();
+    let mut instances = INSTANCES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|err| err.into_inner());
+    let instance = instances.entry(component_type).or_default();
+    let name = format!("{component_type}/{instance}");
+    *instance += 1;
+
+    [
+        KeyValue::new(COMPONENT_TYPE, component_type),
+        KeyValue::new(COMPONENT_NAME, name),
+    ]
+}
+
+/// The low-cardinality `error.type` of a failed operation.
+fn error_type(err: &OTelSdkError) -> &'static str {
+    match err {
+        OTelSdkError::AlreadyShutdown => "already_shutdown",
+        OTelSdkError::Timeout(_) => "timeout",
+        OTelSdkError::InternalFailure(_) => "internal_failure",
+    }
+}
+
+/// The telemetry signal of a processor or exporter.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Signal {
+    Span,
+    Log,
+    #[cfg(feature = "metrics")]
+    MetricDataPoint,
+}
+
+impl Signal {
+    fn name(self) -> &'static str {
+        match self {
+            Signal::Span => "span",
+            Signal::Log => "log",
+            #[cfg(feature = "metrics")]
+            Signal::MetricDataPoint => "metric_data_point",
+        }
+    }
+
+    fn unit(self) -> &'static str {
+        match self {
+            Signal::Span => "{span}",
+            Signal::Log => "{log_record}",
+            #[cfg(feature = "metrics")]
+            Signal::MetricDataPoint => "{data_point}",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Signal::Span => "spans",
+            Signal::Log => "log records",
+            #[cfg(feature = "metrics")]
+            Signal::MetricDataPoint => "metric data points",
+        }
+    }
+}
+
+/// The `otel.sdk.span.live` metric of a tracer provider.
+#[derive(Debug)]
+#[cfg(feature = "trace")]
+pub(crate) struct TracerMetrics {
+    live: UpDownCounter<i64>,
+}
+
+#[cfg(feature = "trace")]
+impl TracerMetrics {
+    pub(crate) fn new(meter: &Meter) -> Self {
+        TracerMetrics {
+            live: meter
+                .i64_up_down_counter("otel.sdk.span.live")
+                .with_description("The number of created spans that have not ended yet.")
+                .with_unit("{span}")
+                .build(),
+        }
+    }
+
+    /// Records that a recording span was started.
+    pub(crate) fn span_started(&self, sampled: bool) {
+        self.live.add(1, &[Self::sampling_result(sampled)]);
+    }
+
+    /// Records that a recording span ended.
+    pub(crate) fn span_ended(&self, sampled: bool) {
+        self.live.add(-1, &[Self::sampling_result(sampled)]);
+    }
+
+    fn sampling_result(sampled: bool) -> KeyValue {
+        let result = if sampled {
+            "RECORD_AND_SAMPLE"
+        } else {
+            "RECORD_ONLY"
+        };
+        KeyValue::new("otel.span.sampling_result", result)
+    }
+}
+
+/// The `otel.sdk.log.created` metric of a logger provider.
+#[derive(Debug)]
+#[cfg(feature = "logs")]
+pub(crate) struct LoggerMetrics {
+    created: Counter<u64>,
+}
+
+#[cfg(feature = "logs")]
+impl LoggerMetrics {
+    pub(crate) fn new(meter: &Meter) -> Self {
+        LoggerMetrics {
+            created: meter
+                .u64_counter("otel.sdk.log.created")
+                .with_description("The number of log records submitted to enabled SDK loggers.")
+                .with_unit("{log_record}")
+                .build(),
+        }
+    }
+
+    /// Records that a log record was emitted.
+    pub(crate) fn log_created(&self) {
+        self.created.add(1, &[]);
+    }
+}
+
+/// The metrics of a processor queueing telemetry for export.
+#[derive(Debug)]
+#[cfg(any(feature = "trace", feature = "logs"))]
+pub(crate) struct ProcessorMetrics {
+    processed: Counter<u64>,
+    attributes: [KeyValue; 2],
+    queue_full_attributes: [KeyValue; 3],
+}
+
+#[cfg(any(feature = "trace", feature = "logs"))]
+impl ProcessorMetrics {
+    /// Creates the metrics of a new `component_type` processor, observing the
+    /// number of items in its queue from `queue_size`.
+    pub(crate) fn new(
+        meter: &Meter,
+        signal: Signal,
+        component_type: &'static str,
+        queue_size: Arc<AtomicUsize>,
+        queue_capacity: usize,
+    ) -> Self {
+        let attributes = component_attributes(component_type);
+
+        let queue_size_attributes = attributes.clone();
+        meter
+            .i64_observable_up_down_counter(format!(
+                "otel.sdk.processor.{}.queue.size",
+                signal.name()
+            ))
+            .with_description(format!(
+                "The number of {} in the queue of the processor.",
+                signal.description()
+            ))
+            .with_unit(signal.unit())
+            .with_callback(move |observer| {
+                let size = queue_size.load(Ordering::Relaxed);
+                observer.observe(size as i64, &queue_size_attributes);
+            })
+            .build();
+
+        let queue_capacity_attributes = attributes.clone();
+        meter
+            .i64_observable_up_down_counter(format!(
+                "otel.sdk.processor.{}.queue.capacity",
+                signal.name()
+            ))
+            .with_description(format!(
+                "The maximum number of {} the queue of the processor can hold.",
+                signal.description()
+            ))
+            .with_unit(signal.unit())
+            .with_callback(move |observer| {
+                observer.observe(queue_capacity as i64, &queue_capacity_attributes);
+            })
+            .build();
+
+        let processed = meter
+            .u64_counter(format!("otel.sdk.processor.{}.processed", signal.name()))
+            .with_description(format!(
+                "The number of {} handed to the exporter or dropped by the processor.",
+                signal.description()
+            ))
+            .with_unit(signal.unit())
+            .build();
+
+        let [component_type, component_name] = attributes.clone();
+        ProcessorMetrics {
+            processed,
+            attributes,
+            queue_full_attributes: [
+                component_type,
+                component_name,
+                KeyValue::new(ERROR_TYPE, "queue_full"),
+            ],
+        }
+    }
+
+    /// Records that `count` items were handed to the exporter.
+    pub(crate) fn processed(&self, count: usize) {
+        if count > 0 {
+            self.processed.add(count as u64, &self.attributes);
+        }
+    }
+
+    /// Records that an item was dropped because the queue was full.
+    pub(crate) fn dropped(&self) {
+        self.processed.add(1, &self.queue_full_attributes);
+    }
+}
+
+/// The `otel.sdk.metric_reader.collection.duration` metric of a metric reader.
+#[derive(Debug)]
+#[cfg(feature = "metrics")]
+pub(crate) struct ReaderMetrics {
+    collection_duration: Histogram<f64>,
+    attributes: [KeyValue; 2],
+}
+
+#[cfg(feature = "metrics")]
+impl ReaderMetrics {
+    pub(crate) fn new(meter: &Meter, component_type: &'static str) -> Self {
+        ReaderMetrics {
+            collection_duration: meter
+                .f64_histogram("otel.sdk.metric_reader.collection.duration")
+                .with_description("The duration of the collections of the metric reader.")
+                .with_unit("s")
+                .with_boundaries(vec![
+                    0.005, 0.01, 0.025, 0.05, 0.075, 0.1, 0.25, 0.5, 0.75, 1.0, 2.5, 5.0, 7.5, 10.0,
+                ])
+                .build(),
+            attributes: component_attributes(component_type),
+        }
+    }
+
+    /// Records a collection that took `duration`.
+    pub(crate) fn collected(&self, duration: Duration, result: &OTelSdkResult) {
+        let duration = duration.as_secs_f64();
+        match result {
+            Ok(()) => self.collection_duration.record(duration, &self.attributes),
+            Err(err) => {
+                let [component_type, component_name] = self.attributes.clone();
+                self.collection_duration.record(
+                    duration,
+                    &[
+                        component_type,
+                        component_name,
+                        KeyValue::new(ERROR_TYPE, error_type(err)),
+                    ],
+                );
+            }
+        }
+    }
+}
+
+/// The metrics of an exporter: the number of exported items,
+/// `otel.sdk.exporter.{signal}.exported`, and of the items being exported,
+/// `otel.sdk.exporter.{signal}.inflight`.
+///
+/// # Example
+///
+/// ```
+/// use opentelemetry::metrics::Meter;
+/// use opentelemetry_sdk::error::OTelSdkResult;
+/// use opentelemetry_sdk::self_observability::ExporterMetrics;
+/// use opentelemetry_sdk::trace::SpanData;
+///
+/// #[derive(Debug, Default)]
+/// struct MyExporter {
+///     metrics: Option<ExporterMetrics>,
+/// }
+///
+/// impl opentelemetry_sdk::trace::SpanExporter for MyExporter {
+///     async fn export(&self, batch: Vec<SpanData>) -> OTelSdkResult {
+///         let count = batch.len();
+///         if let Some(metrics) = &self.metrics {
+///             metrics.export_started(count);
+///         }
+///         let result = Ok(()); // send the batch
+///         if let Some(metrics) = &self.metrics {
+///             metrics.export_finished(count, &result);
+///         }
+///         result
+///     }
+///
+///     fn set_meter(&mut self, meter: &Meter) {
+///         self.metrics = Some(ExporterMetrics::spans(meter, "my_span_exporter"));
+///     }
+/// }
+/// ```
+pub struct ExporterMetrics {
+    exported: Counter<u64>,
+    inflight: UpDownCounter<i64>,
+    attributes: [KeyValue; 2],
+}
+
+impl ExporterMetrics {
+    /// Creates the metrics of a new span exporter of type `component_type`,
+    /// e.g. `otlp_grpc_span_exporter`.
+    pub fn spans(meter: &Meter, component_type: &'static str) -> Self {
+        ExporterMetrics::new(meter, Signal::Span, component_type)
+    }
+
+    /// Creates the metrics of a new log exporter of type `component_type`,
+    /// e.g. `otlp_grpc_log_exporter`.
+    pub fn logs(meter: &Meter, component_type: &'static str) -> Self {
+        ExporterMetrics::new(meter, Signal::Log, component_type)
+    }
+
+    /// Creates the metrics of a new metric exporter of type `component_type`,
+    /// e.g. `otlp_grpc_metric_exporter`, counting metric data points.
+    #[cfg(feature = "metrics")]
+    pub fn metric_data_points(meter: &Meter, component_type: &'static str) -> Self {
+        ExporterMetrics::new(meter, Signal::MetricDataPoint, component_type)
+    }
+
+    fn new(meter: &Meter, signal: Signal, component_type: &'static str) -> Self {
+        ExporterMetrics {
+            exported: meter
+                .u64_counter(format!("otel.sdk.exporter.{}.exported", signal.name()))
+                .with_description(format!(
+                    "The number of {} for which the export has finished, either successful or failed.",
+                    signal.description()
+                ))
+                .with_unit(signal.unit())
+                .build(),
+            inflight: meter
+                .i64_up_down_counter(format!("otel.sdk.exporter.{}.inflight", signal.name()))
+                .with_description(format!(
+                    "The number of {} which were passed to the exporter, but that have not been exported yet.",
+                    signal.description()
+                ))
+                .with_unit(signal.unit())
+                .build(),
+            attributes: component_attributes(component_type),
+        }
+    }
+
+    /// Records that the export of `count` items started.
+    pub fn export_started(&self, count: usize) {
+        self.inflight.add(count as i64, &self.attributes);
+    }
+
+    /// Records that the export of `count` items finished with `result`.
+    pub fn export_finished(&self, count: usize, result: &OTelSdkResult) {
+        self.inflight.add(-(count as i64), &self.attributes);
+        match result {
+            Ok(()) => self.exported.add(count as u64, &self.attributes),
+            Err(err) => {
+                let [component_type, component_name] = self.attributes.clone();
+                self.exported.add(
+                    count as u64,
+                    &[
+                        component_type,
+                        component_name,
+                        KeyValue::new(ERROR_TYPE, error_type(err)),
+                    ],
+                );
+            }
+        }
+    }
+}
+
+impl fmt::Debug for ExporterMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExporterMetrics")
+            .field("attributes", &self.attributes)
+            .finish()
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::ExporterMetrics;
+    use crate::error::OTelSdkError;
+    use crate::logs::{InMemoryLogExporter, SdkLoggerProvider};
+    use crate::metrics::data::{AggregatedMetrics, Metric, MetricData, ResourceMetrics};
+    use crate::metrics::{InMemoryMetricExporter, SdkMeterProvider};
+    use crate::trace::{InMemorySpanExporter, Sampler, SdkTracerProvider};
+    use opentelemetry::logs::{Logger, LoggerProvider};
+    use opentelemetry::metrics::MeterProvider;
+    use opentelemetry::trace::{Span, Tracer, TracerProvider};
+    use opentelemetry::KeyValue;
+
+    fn self_observability_provider() -> (SdkMeterProvider, InMemoryMetricExporter) {
+        let exporter = InMemoryMetricExporter::default();
+        let meter_provider = SdkMeterProvider::builder()
+            .with_periodic_exporter(exporter.clone())
+            .build();
+        (meter_provider, exporter)
+    }
+
+    fn collect(
+        meter_provider: &SdkMeterProvider,
+        exporter: &InMemoryMetricExporter,
+    ) -> Vec<ResourceMetrics> {
+        exporter.reset();
+        meter_provider.force_flush().unwrap();
+        exporter.get_finished_metrics().unwrap()
+    }
+
+    fn find_metric<'a>(resource_metrics: &'a [ResourceMetrics], name: &str) -> &'a Metric {
+        resource_metrics
+            .iter()
+            .flat_map(|rm| rm.scope_metrics())
+            .flat_map(|sm| sm.metrics())
+            .find(|metric| metric.name() == name)
+            .unwrap_or_else(|| panic!("metric {name} expected"))
+    }
+
+    /// Returns the values of the sum data points with their attributes.
+    fn sum_values(metric: &Metric) -> Vec<(i64, Vec<KeyValue>)> {
+        match metric.data() {
+            AggregatedMetrics::I64(MetricData::Sum(sum)) => sum
+                .data_points()
+                .map(|dp| (dp.value(), dp.attributes().cloned().collect()))
+                .collect(),
+            AggregatedMetrics::U64(MetricData::Sum(sum)) => sum
+                .data_points()
+                .map(|dp| (dp.value() as i64, dp.attributes().cloned().collect()))
+                .collect(),
+            data => panic!("sum expected, got {data:?}"),
+        }
+    }
+
+    #[test]
+    fn tracer_provider_reports_live_spans() {
+        // Arrange
+        let (meter_provider, metric_exporter) = self_observability_provider();
+        let tracer_provider = SdkTracerProvider::builder()
+            .with_sampler(Sampler::AlwaysOn)
+            .with_self_observability(&meter_provider)
+            .build();
+        let tracer = tracer_provider.tracer("test");
+
+        // Act
+        let mut span = tracer.start("span");
+        let live_while_started = collect(&meter_provider, &metric_exporter);
+        span.end();
+        let live_after_ended = collect(&meter_provider, &metric_exporter);
+
+        // Assert
+        let sampled = vec![KeyValue::new(
+            "otel.span.sampling_result",
+            "RECORD_AND_SAMPLE",
+        )];
+        assert_eq!(
+            sum_values(find_metric(&live_while_started, "otel.sdk.span.live")),
+            vec![(1, sampled.clone())]
+        );
+        assert_eq!(
+            sum_values(find_metric(&live_after_ended, "otel.sdk.span.live")),
+            vec![(0, sampled)]
+        );
+    }
+
+    #[test]
+    fn batch_span_processor_reports_processed_spans() {
+        // Arrange
+        let (meter_provider, metric_exporter) = self_observability_provider();
+        let span_exporter = InMemorySpanExporter::default();
+        let tracer_provider = SdkTracerProvider::builder()
+            .with_batch_exporter(span_exporter.clone())
+            .with_self_observability(&meter_provider)
+            .build();
+        let tracer = tracer_provider.tracer("test");
+
+        // Act
+        for _ in 0..3 {
+            tracer.start("span").end();
+        }
+        tracer_provider.force_flush().unwrap();
+        let resource_metrics = collect(&meter_provider, &metric_exporter);
+
+        // Assert
+        assert_eq!(span_exporter.get_finished_spans().unwrap().len(), 3);
+        let processed = sum_values(find_metric(
+            &resource_metrics,
+            "otel.sdk.processor.span.processed",
+        ));
+        assert_eq!(processed.len(), 1);
+        let (count, attributes) = &processed[0];
+        assert_eq!(*count, 3);
+        assert!(attributes.contains(&KeyValue::new(
+            "otel.component.type",
+            "batching_span_processor"
+        )));
+        assert!(attributes
+            .iter()
+            .any(|kv| kv.key.as_str() == "otel.component.name"));
+
+        let queue_size = sum_values(find_metric(
+            &resource_metrics,
+            "otel.sdk.processor.span.queue.size",
+        ));
+        assert_eq!(queue_size[0].0, 0);
+        let queue_capacity = sum_values(find_metric(
+            &resource_metrics,
+            "otel.sdk.processor.span.queue.capacity",
+        ));
+        assert_eq!(queue_capacity[0].0, 2048);
+    }
+
+    #[test]
+    fn batch_log_processor_reports_created_and_processed_logs() {
+        // Arrange
+        let (meter_provider, metric_exporter) = self_observability_provider();
+        let log_exporter = InMemoryLogExporter::default();
+        let logger_provider = SdkLoggerProvider::builder()
+            .with_batch_exporter(log_exporter.clone())
+            .with_self_observability(&meter_provider)
+            .build();
+        let logger = logger_provider.logger("test");
+
+        // Act
+        for _ in 0..2 {
+            logger.emit(logger.create_log_record());
+        }
+        logger_provider.force_flush().unwrap();
+        let resource_metrics = collect(&meter_provider, &metric_exporter);
+
+        // Assert
+        assert_eq!(log_exporter.get_emitted_logs().unwrap().len(), 2);
+        assert_eq!(
+            sum_values(find_metric(&resource_metrics, "otel.sdk.log.created")),
+            vec![(2, vec![])]
+        );
+        let processed = sum_values(find_metric(
+            &resource_metrics,
+            "otel.sdk.processor.log.processed",
+        ));
+        assert_eq!(processed.len(), 1);
+        assert_eq!(processed[0].0, 2);
+        assert!(processed[0].1.contains(&KeyValue::new(
+            "otel.component.type",
+            "batching_log_processor"
+        )));
+    }
+
+    #[test]
+    fn periodic_reader_reports_collection_duration() {
+        // Arrange
+        let (meter_provider, metric_exporter) = self_observability_provider();
+        let observed_provider = SdkMeterProvider::builder()
+            .with_periodic_exporter(InMemoryMetricExporter::default())
+            .with_self_observability(&meter_provider)
+            .build();
+        observed_provider
+            .meter("test")
+            .u64_counter("counter")
+            .build()
+            .add(1, &[]);
+
+        // Act
+        observed_provider.force_flush().unwrap();
+        observed_provider.force_flush().unwrap();
+        let resource_metrics = collect(&meter_provider, &metric_exporter);
+
+        // Assert
+        let metric = find_metric(
+            &resource_metrics,
+            "otel.sdk.metric_reader.collection.duration",
+        );
+        assert_eq!(metric.unit(), "s");
+        let AggregatedMetrics::F64(MetricData::Histogram(histogram)) = metric.data() else {
+            panic!("f64 histogram expected");
+        };
+        let data_point = histogram.data_points().next().unwrap();
+        assert_eq!(data_point.count(), 2);
+        assert!(data_point
+            .attributes()
+            .any(|kv| *kv == KeyValue::new("otel.component.type", "periodic_metric_reader")));
+    }
+
+    #[test]
+    fn exporter_metrics_report_exported_and_inflight() {
+        // Arrange
+        let (meter_provider, metric_exporter) = self_observability_provider();
+        let metrics = ExporterMetrics::spans(&meter_provider.meter("test"), "test_span_exporter");
+
+        // Act
+        metrics.export_started(3);
+        let inflight = collect(&meter_provider, &metric_exporter);
+        metrics.export_finished(3, &Ok(()));
+        metrics.export_started(2);
+        metrics.export_finished(2, &Err(OTelSdkError::Timeout(Default::default())));
+        let finished = collect(&meter_provider, &metric_exporter);
+
+        // Assert
+        assert_eq!(
+            sum_values(find_metric(&inflight, "otel.sdk.exporter.span.inflight"))[0].0,
+            3
+        );
+        assert_eq!(
+            sum_values(find_metric(&finished, "otel.sdk.exporter.span.inflight"))[0].0,
+            0
+        );
+        let mut exported = sum_values(find_metric(&finished, "otel.sdk.exporter.span.exported"));
+        exported.sort_by_key(|(count, _)| *count);
+        assert_eq!(exported.len(), 2);
+        assert_eq!(exported[0].0, 2);
+        assert!(exported[0]
+            .1
+            .contains(&KeyValue::new("error.type", "timeout")));
+        assert_eq!(exported[1].0, 3);
+        assert!(!exported[1]
+            .1
+            .iter()
+            .any(|kv| kv.key.as_str() == "error.type"));
+    }
+}
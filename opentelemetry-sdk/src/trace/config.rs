@@ -3,6 +3,7 @@
 //! Configuration represents the global tracing configuration, overrides
 //! can be set for the default OpenTelemetry limits and Sampler.
 use crate::trace::{span_limit::SpanLimits, IdGenerator, RandomIdGenerator, Sampler, ShouldSample};
+use crate::util::attribute_value_length_limit_from_env;
 use crate::Resource;
 use opentelemetry::otel_warn;
 use std::borrow::Cow;
@@ -22,6 +23,21 @@ pub struct Config {
     /// span limits
     pub span_limits: SpanLimits,
 
+    /// The max length, in characters, of string attribute values of spans,
+    /// their events and links, including the elements of string arrays.
+    /// Longer values are truncated.
+    ///
+    /// `None` means values are not truncated.
+    pub max_attribute_value_length: Option<u32>,
+
+    /// Whether root spans are flagged with [`TraceFlags::RANDOM`] when the
+    /// [`IdGenerator`] generates random trace ids, see
+    /// [`TracerProviderBuilder::with_random_trace_flag`].
+    ///
+    /// [`TraceFlags::RANDOM`]: opentelemetry::trace::TraceFlags::RANDOM
+    /// [`TracerProviderBuilder::with_random_trace_flag`]: crate::trace::TracerProviderBuilder::with_random_trace_flag
+    pub random_trace_flag: bool,
+
     /// Contains attributes representing an entity that produces telemetry.
     pub resource: Cow<'static, Resource>,
 }
@@ -33,6 +49,8 @@ impl Default for Config {
             sampler: Box::new(Sampler::ParentBased(Box::new(Sampler::AlwaysOn))),
             id_generator: Box::<RandomIdGenerator>::default(),
             span_limits: SpanLimits::default(),
+            max_attribute_value_length: None,
+            random_trace_flag: false,
             resource: Cow::Owned(Resource::builder().build()),
         };
 
@@ -57,6 +75,9 @@ impl Default for Config {
             config.span_limits.max_links_per_span = max_links_per_span;
         }
 
+        config.max_attribute_value_length =
+            attribute_value_length_limit_from_env("OTEL_SPAN_ATTRIBUTE_VALUE_LENGTH_LIMIT");
+
         let sampler_arg = env::var("OTEL_TRACES_SAMPLER_ARG").ok();
         if let Ok(sampler) = env::var("OTEL_TRACES_SAMPLER") {
             config.sampler = match sampler.as_str() {
@@ -115,7 +136,7 @@ impl Default for Config {
                 "xray" => {
                     otel_warn!(
                         name: "TracerProvider.Config.UnsupportedSampler",
-                        message = "OTEL_TRACES_SAMPLER is set to 'xray'. AWS X-Ray sampler is not implemented in this SDK version. Using fallback sampler: ParentBased(AlwaysOn). Configure an alternative sampler using OTEL_TRACES_SAMPLER"
+                        message = "OTEL_TRACES_SAMPLER is set to 'xray'. The AWS X-Ray remote sampler requires a runtime and an HTTP client and cannot be configured from the environment; create it with Sampler::xray_remote, available with the xray_remote_sampler feature. Using fallback sampler: ParentBased(AlwaysOn). Configure an alternative sampler using OTEL_TRACES_SAMPLER"
                     );
                     Box::new(Sampler::ParentBased(Box::new(Sampler::AlwaysOn)))
                 }
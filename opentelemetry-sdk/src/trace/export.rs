@@ -72,6 +72,12 @@ pub trait SpanExporter: Send + Sync + Debug {
 
     /// Set the resource for the exporter.
     fn set_resource(&mut self, _resource: &Resource) {}
+
+    /// Set the meter the exporter reports its
+    /// [self-observability](crate::self_observability) metrics with, see
+    /// [ExporterMetrics](crate::self_observability::ExporterMetrics).
+    #[cfg(feature = "experimental_self_observability")]
+    fn set_meter(&mut self, _meter: &opentelemetry::metrics::Meter) {}
 }
 
 /// `SpanData` contains all the information collected by a `Span` and can be used
@@ -3,6 +3,10 @@ use rand::{rngs, Rng, SeedableRng};
 use std::cell::RefCell;
 use std::fmt;
 
+mod xray;
+
+pub use xray::XrayIdGenerator;
+
 /// Interface for generating IDs
 pub trait IdGenerator: Send + Sync + fmt::Debug {
     /// Generate a new `TraceId`
@@ -10,6 +14,20 @@ pub trait IdGenerator: Send + Sync + fmt::Debug {
 
     /// Generate a new `SpanId`
     fn new_span_id(&self) -> SpanId;
+
+    /// Whether the 7 rightmost bytes of the generated trace ids are random, as
+    /// defined by the W3C Trace Context Level 2 specification.
+    ///
+    /// Root spans with such trace ids are flagged with [`TraceFlags::RANDOM`]
+    /// if enabled with [`TracerProviderBuilder::with_random_trace_flag`],
+    /// allowing downstream consistent probability samplers to rely on them.
+    /// Defaults to `false`.
+    ///
+    /// [`TraceFlags::RANDOM`]: opentelemetry::trace::TraceFlags::RANDOM
+    /// [`TracerProviderBuilder::with_random_trace_flag`]: crate::trace::TracerProviderBuilder::with_random_trace_flag
+    fn trace_id_is_random(&self) -> bool {
+        false
+    }
 }
 
 /// Default [`IdGenerator`] implementation.
@@ -28,6 +46,10 @@ impl IdGenerator for RandomIdGenerator {
     fn new_span_id(&self) -> SpanId {
         CURRENT_RNG.with(|rng| SpanId::from(rng.borrow_mut().random::<u64>()))
     }
+
+    fn trace_id_is_random(&self) -> bool {
+        true
+    }
 }
 
 thread_local! {
@@ -0,0 +1,55 @@
+use opentelemetry::time::now;
+use opentelemetry::trace::{SpanId, TraceId};
+use rand::Rng;
+use std::time::UNIX_EPOCH;
+
+use super::{IdGenerator, CURRENT_RNG};
+
+/// [`IdGenerator`] generating trace ids compatible with [AWS X-Ray].
+///
+/// The first 32 bits of the trace ids are the seconds since the Unix epoch at
+/// which they are generated, the remaining 96 bits and span ids are random.
+///
+/// [AWS X-Ray]: https://docs.aws.amazon.com/xray/latest/devguide/xray-api-sendingdata.html#xray-api-traceids
+#[derive(Clone, Debug, Default)]
+pub struct XrayIdGenerator {
+    _private: (),
+}
+
+impl IdGenerator for XrayIdGenerator {
+    fn new_trace_id(&self) -> TraceId {
+        let epoch_secs = now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as u32)
+            .unwrap_or_default();
+        let random = CURRENT_RNG.with(|rng| rng.borrow_mut().random::<u128>());
+        TraceId::from((u128::from(epoch_secs) << 96) | (random >> 32))
+    }
+
+    fn new_span_id(&self) -> SpanId {
+        CURRENT_RNG.with(|rng| SpanId::from(rng.borrow_mut().random::<u64>()))
+    }
+
+    fn trace_id_is_random(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trace_id_starts_with_epoch_secs() {
+        let generator = XrayIdGenerator::default();
+        let before = now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let trace_id = generator.new_trace_id();
+        let after = now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+
+        let bytes = trace_id.to_bytes();
+        let epoch_secs = u64::from(u32::from_be_bytes(bytes[..4].try_into().unwrap()));
+        assert!((before..=after).contains(&epoch_secs));
+        assert_ne!(trace_id, generator.new_trace_id());
+        assert_ne!(generator.new_span_id(), SpanId::INVALID);
+    }
+}
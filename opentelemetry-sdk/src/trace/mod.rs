@@ -20,6 +20,8 @@ mod span_processor;
 #[cfg(feature = "experimental_trace_batch_span_processor_with_async_runtime")]
 /// Experimental feature to use async runtime with batch span processor.
 pub mod span_processor_with_async_runtime;
+#[cfg(feature = "experimental_trace_tail_sampling")]
+mod tail_sampling;
 mod tracer;
 
 pub use config::Config;
@@ -35,10 +37,16 @@ pub mod in_memory_exporter;
 #[cfg_attr(docsrs, doc(cfg(any(feature = "testing", test))))]
 pub use in_memory_exporter::{InMemorySpanExporter, InMemorySpanExporterBuilder};
 
-pub use id_generator::{IdGenerator, RandomIdGenerator};
+pub use id_generator::{IdGenerator, RandomIdGenerator, XrayIdGenerator};
 pub use links::SpanLinks;
 pub use provider::{SdkTracerProvider, TracerProviderBuilder};
-pub use sampler::{Sampler, SamplingDecision, SamplingResult, ShouldSample};
+pub use sampler::{
+    AnyOf, AnyOfBuilder, ComposableSampler, ConsistentParentBased, ConsistentProbabilityBased,
+    ConsistentRuleBased, ConsistentRuleBasedBuilder, RateLimitingSampler, RuleBasedSampler,
+    RuleBasedSamplerBuilder, RuleBasedSamplerConfig, Sampler, SamplerConfig, SamplingDecision,
+    SamplingIntent, SamplingParameters, SamplingResult, SamplingRule, SamplingRuleConfig,
+    ShouldSample,
+};
 pub use span::Span;
 pub use span_limit::SpanLimits;
 pub use span_processor::{
@@ -46,11 +54,17 @@ pub use span_processor::{
     SimpleSpanProcessor, SpanProcessor,
 };
 
+#[cfg(feature = "experimental_trace_tail_sampling")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental_trace_tail_sampling")))]
+pub use tail_sampling::{TailSamplingPolicy, TailSamplingProcessor, TailSamplingProcessorBuilder};
+
 pub use tracer::SdkTracer;
 pub use tracer::SdkTracer as Tracer; // for back-compat else tracing-opentelemetry won't build
 
 #[cfg(feature = "jaeger_remote_sampler")]
 pub use sampler::{JaegerRemoteSampler, JaegerRemoteSamplerBuilder};
+#[cfg(feature = "xray_remote_sampler")]
+pub use sampler::{XrayRemoteSampler, XrayRemoteSamplerBuilder};
 
 #[cfg(feature = "experimental_trace_batch_span_processor_with_async_runtime")]
 #[cfg(test)]
@@ -220,6 +234,82 @@ mod tests {
         );
     }
 
+    #[derive(Debug)]
+    struct EnrichingSpanProcessor;
+    impl SpanProcessor for EnrichingSpanProcessor {
+        fn on_start(&self, _span: &mut crate::trace::Span, _cx: &Context) {}
+
+        fn on_ending(&self, span: &mut crate::trace::Span) {
+            span.set_attribute(KeyValue::new("queue.depth", 3));
+            // Ending the span again from `on_ending` is ignored.
+            span.end();
+        }
+
+        fn on_end(&self, _span: SpanData) {}
+
+        fn force_flush(&self) -> OTelSdkResult {
+            Ok(())
+        }
+
+        fn shutdown_with_timeout(&self, _timeout: Duration) -> OTelSdkResult {
+            Ok(())
+        }
+    }
+
+    #[derive(Debug)]
+    struct RedactingSpanProcessor;
+    impl SpanProcessor for RedactingSpanProcessor {
+        fn on_start(&self, _span: &mut crate::trace::Span, _cx: &Context) {}
+
+        fn on_ending(&self, span: &mut crate::trace::Span) {
+            let attributes = span
+                .attributes_mut()
+                .expect("span should still be recording");
+            // Sees the attribute added by the previous processor.
+            assert!(attributes.contains(&KeyValue::new("queue.depth", 3)));
+            for kv in attributes.iter_mut() {
+                if kv.key.as_str() == "password" {
+                    kv.value = "<redacted>".into();
+                }
+            }
+        }
+
+        fn on_end(&self, _span: SpanData) {}
+
+        fn force_flush(&self) -> OTelSdkResult {
+            Ok(())
+        }
+
+        fn shutdown_with_timeout(&self, _timeout: Duration) -> OTelSdkResult {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn span_processor_on_ending() {
+        // Arrange
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let provider = SdkTracerProvider::builder()
+            .with_span_processor(EnrichingSpanProcessor)
+            .with_span_processor(RedactingSpanProcessor)
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let tracer = provider.tracer("test");
+
+        // Act
+        let mut span = tracer.start("span");
+        span.set_attribute(KeyValue::new("password", "hunter2"));
+        span.end();
+
+        // Assert
+        let exported_spans = exporter.get_finished_spans().unwrap();
+        assert_eq!(exported_spans.len(), 1);
+        let attributes = &exported_spans[0].attributes;
+        assert!(attributes.contains(&KeyValue::new("queue.depth", 3)));
+        assert!(attributes.contains(&KeyValue::new("password", "<redacted>")));
+        assert!(!attributes.contains(&KeyValue::new("password", "hunter2")));
+    }
+
     #[test]
     fn tracer_in_span() {
         // Arrange
@@ -1,5 +1,7 @@
 use super::IdGenerator;
 use crate::error::{OTelSdkError, OTelSdkResult};
+#[cfg(feature = "experimental_self_observability")]
+use crate::self_observability::{self, TracerMetrics};
 /// # Trace Provider SDK
 ///
 /// The `TracerProvider` handles the creation and management of [`Tracer`] instances and coordinates
@@ -70,6 +72,8 @@ use crate::trace::{
 };
 use crate::Resource;
 use crate::{trace::SpanExporter, trace::SpanProcessor};
+#[cfg(feature = "experimental_self_observability")]
+use opentelemetry::metrics::{Meter, MeterProvider};
 use opentelemetry::otel_debug;
 use opentelemetry::{otel_info, InstrumentationScope};
 use std::borrow::Cow;
@@ -93,9 +97,13 @@ fn noop_tracer_provider() -> &'static SdkTracerProvider {
                     sampler: Box::new(Sampler::ParentBased(Box::new(Sampler::AlwaysOn))),
                     id_generator: Box::<RandomIdGenerator>::default(),
                     span_limits: SpanLimits::default(),
+                    max_attribute_value_length: None,
+                    random_trace_flag: false,
                     resource: Cow::Owned(Resource::empty()),
                 },
                 is_shutdown: AtomicBool::new(true),
+                #[cfg(feature = "experimental_self_observability")]
+                metrics: None,
             }),
         }
     })
@@ -107,6 +115,8 @@ pub(crate) struct TracerProviderInner {
     processors: Vec<Box<dyn SpanProcessor>>,
     config: crate::trace::Config,
     is_shutdown: AtomicBool,
+    #[cfg(feature = "experimental_self_observability")]
+    metrics: Option<TracerMetrics>,
 }
 
 impl TracerProviderInner {
@@ -194,6 +204,12 @@ impl SdkTracerProvider {
         self.inner.is_shutdown.load(Ordering::Relaxed)
     }
 
+    /// Self-observability metrics of this provider, if enabled
+    #[cfg(feature = "experimental_self_observability")]
+    pub(crate) fn metrics(&self) -> Option<&TracerMetrics> {
+        self.inner.metrics.as_ref()
+    }
+
     /// Force flush all remaining spans in span processors and return results.
     ///
     /// # Examples
@@ -301,6 +317,8 @@ pub struct TracerProviderBuilder {
     processors: Vec<Box<dyn SpanProcessor>>,
     config: crate::trace::Config,
     resource: Option<Resource>,
+    #[cfg(feature = "experimental_self_observability")]
+    meter: Option<Meter>,
 }
 
 impl TracerProviderBuilder {
@@ -366,6 +384,23 @@ impl TracerProviderBuilder {
         self
     }
 
+    /// Flag root spans with [`TraceFlags::RANDOM`] when the id generator
+    /// generates random trace ids, as reported by
+    /// [`IdGenerator::trace_id_is_random`]. Disabled by default.
+    ///
+    /// The flag lets downstream consistent probability samplers rely on the
+    /// randomness of trace ids. [`TraceContextPropagator`] then sends the
+    /// `03` or `02` flags in `traceparent`, which previous releases of this
+    /// crate reject, starting a new trace instead of continuing the incoming
+    /// one; only enable it once all services parse the flag.
+    ///
+    /// [`TraceFlags::RANDOM`]: opentelemetry::trace::TraceFlags::RANDOM
+    /// [`TraceContextPropagator`]: crate::propagation::TraceContextPropagator
+    pub fn with_random_trace_flag(mut self, enabled: bool) -> Self {
+        self.config.random_trace_flag = enabled;
+        self
+    }
+
     /// Specify the number of events to be recorded per span.
     pub fn with_max_events_per_span(mut self, max_events: u32) -> Self {
         self.config.span_limits.max_events_per_span = max_events;
@@ -396,6 +431,13 @@ impl TracerProviderBuilder {
         self
     }
 
+    /// Specify the max length, in characters, of string attribute values.
+    /// Longer values, including the elements of string arrays, are truncated.
+    pub fn with_max_attribute_value_length(mut self, max_length: u32) -> Self {
+        self.config.max_attribute_value_length = Some(max_length);
+        self
+    }
+
     /// Specify all limit via the span_limits
     pub fn with_span_limits(mut self, span_limits: SpanLimits) -> Self {
         self.config.span_limits = span_limits;
@@ -422,6 +464,20 @@ impl TracerProviderBuilder {
         TracerProviderBuilder { resource, ..self }
     }
 
+    /// Reports the [self-observability](crate::self_observability) metrics of
+    /// the provider, e.g. `otel.sdk.span.live`, and of its span processors and
+    /// exporters through `meter_provider`.
+    ///
+    /// The metrics must not be reported through a pipeline exporting to the
+    /// same provider's processors, as this would create a feedback loop.
+    #[cfg(feature = "experimental_self_observability")]
+    pub fn with_self_observability(self, meter_provider: &impl MeterProvider) -> Self {
+        TracerProviderBuilder {
+            meter: Some(self_observability::meter(meter_provider)),
+            ..self
+        }
+    }
+
     /// Create a new provider from this configuration.
     pub fn build(self) -> SdkTracerProvider {
         let mut config = self.config;
@@ -455,11 +511,21 @@ impl TracerProviderBuilder {
             p.set_resource(config.resource.as_ref());
         }
 
+        #[cfg(feature = "experimental_self_observability")]
+        let metrics = self.meter.map(|meter| {
+            for p in &mut processors {
+                p.set_meter(&meter);
+            }
+            TracerMetrics::new(&meter)
+        });
+
         let is_shutdown = AtomicBool::new(false);
         SdkTracerProvider::new(TracerProviderInner {
             processors,
             config,
             is_shutdown,
+            #[cfg(feature = "experimental_self_observability")]
+            metrics,
         })
     }
 }
@@ -562,6 +628,8 @@ mod tests {
             ],
             config: Default::default(),
             is_shutdown: AtomicBool::new(false),
+            #[cfg(feature = "experimental_self_observability")]
+            metrics: None,
         });
 
         let results = tracer_provider.force_flush();
@@ -697,6 +765,48 @@ mod tests {
         assert_eq!(no_service_name.config().resource.len(), 0)
     }
 
+    #[test]
+    fn test_attribute_value_length_limit_from_env() {
+        temp_env::with_vars(
+            [
+                ("OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT", Some("16")),
+                ("OTEL_SPAN_ATTRIBUTE_VALUE_LENGTH_LIMIT", None::<&str>),
+            ],
+            || {
+                let provider = super::SdkTracerProvider::builder().build();
+                assert_eq!(provider.config().max_attribute_value_length, Some(16));
+            },
+        );
+
+        temp_env::with_vars(
+            [
+                ("OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT", Some("16")),
+                ("OTEL_SPAN_ATTRIBUTE_VALUE_LENGTH_LIMIT", Some("8")),
+            ],
+            || {
+                let provider = super::SdkTracerProvider::builder().build();
+                assert_eq!(provider.config().max_attribute_value_length, Some(8));
+
+                // Builder configuration takes precedence over the environment.
+                let provider = super::SdkTracerProvider::builder()
+                    .with_max_attribute_value_length(4)
+                    .build();
+                assert_eq!(provider.config().max_attribute_value_length, Some(4));
+            },
+        );
+
+        temp_env::with_vars_unset(
+            [
+                "OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT",
+                "OTEL_SPAN_ATTRIBUTE_VALUE_LENGTH_LIMIT",
+            ],
+            || {
+                let provider = super::SdkTracerProvider::builder().build();
+                assert_eq!(provider.config().max_attribute_value_length, None);
+            },
+        );
+    }
+
     #[test]
     fn test_shutdown_noops() {
         let processor = TestSpanProcessor::new(false);
@@ -705,6 +815,8 @@ mod tests {
             processors: vec![Box::from(processor)],
             config: Default::default(),
             is_shutdown: AtomicBool::new(false),
+            #[cfg(feature = "experimental_self_observability")]
+            metrics: None,
         });
 
         let test_tracer_1 = tracer_provider.tracer("test1");
@@ -815,6 +927,8 @@ mod tests {
                 ))],
                 config: Config::default(),
                 is_shutdown: AtomicBool::new(false),
+                #[cfg(feature = "experimental_self_observability")]
+                metrics: None,
             });
 
             {
@@ -853,6 +967,8 @@ mod tests {
             ))],
             config: Config::default(),
             is_shutdown: AtomicBool::new(false),
+            #[cfg(feature = "experimental_self_observability")]
+            metrics: None,
         });
 
         // Create a scope to test behavior when providers are dropped
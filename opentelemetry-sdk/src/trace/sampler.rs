@@ -1,10 +1,15 @@
 use opentelemetry::{
     trace::{Link, SpanKind, TraceContextExt, TraceId, TraceState},
-    Context, KeyValue,
+    Context, InstrumentationScope, KeyValue,
 };
 
+mod consistent;
 #[cfg(feature = "jaeger_remote_sampler")]
 mod jaeger_remote;
+mod rate_limit;
+mod rule_based;
+#[cfg(feature = "xray_remote_sampler")]
+mod xray_remote;
 
 /// The result of sampling logic for a given span.
 #[derive(Clone, Debug, PartialEq)]
@@ -32,10 +37,21 @@ pub enum SamplingDecision {
     RecordAndSample,
 }
 
+pub use consistent::{
+    AnyOf, AnyOfBuilder, ComposableSampler, ConsistentParentBased, ConsistentProbabilityBased,
+    ConsistentRuleBased, ConsistentRuleBasedBuilder, SamplingIntent, SamplingParameters,
+};
 #[cfg(feature = "jaeger_remote_sampler")]
 pub use jaeger_remote::{JaegerRemoteSampler, JaegerRemoteSamplerBuilder};
-#[cfg(feature = "jaeger_remote_sampler")]
+#[cfg(any(feature = "jaeger_remote_sampler", feature = "xray_remote_sampler"))]
 use opentelemetry_http::HttpClient;
+pub use rate_limit::RateLimitingSampler;
+pub use rule_based::{
+    RuleBasedSampler, RuleBasedSamplerBuilder, RuleBasedSamplerConfig, SamplerConfig, SamplingRule,
+    SamplingRuleConfig,
+};
+#[cfg(feature = "xray_remote_sampler")]
+pub use xray_remote::{XrayRemoteSampler, XrayRemoteSamplerBuilder};
 
 /// The [`ShouldSample`] interface allows implementations to provide samplers
 /// which will return a sampling [`SamplingResult`] based on information that
@@ -104,6 +120,30 @@ pub trait ShouldSample: CloneShouldSample + Send + Sync + std::fmt::Debug {
         attributes: &[KeyValue],
         links: &[Link],
     ) -> SamplingResult;
+
+    /// Returns the [`SamplingDecision`] for a [`Span`] to be created by a tracer
+    /// with the given instrumentation scope.
+    ///
+    /// This is the method called by the SDK tracer. The default implementation
+    /// ignores the scope and calls [`should_sample`]. Samplers delegating to
+    /// other samplers should override it to pass the scope on.
+    ///
+    /// [`Span`]: opentelemetry::trace::Span
+    /// [`should_sample`]: ShouldSample::should_sample
+    #[allow(clippy::too_many_arguments)]
+    fn should_sample_with_scope(
+        &self,
+        scope: &InstrumentationScope,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+        links: &[Link],
+    ) -> SamplingResult {
+        let _ = scope;
+        self.should_sample(parent_context, trace_id, name, span_kind, attributes, links)
+    }
 }
 
 /// This trait should not be used directly instead users should use [`ShouldSample`].
@@ -141,6 +181,21 @@ pub enum Sampler {
     AlwaysOff,
     /// Respects the parent span's sampling decision or delegates a delegate sampler for root spans.
     ParentBased(Box<dyn ShouldSample>),
+    /// Samples the span if its parent or any of its linked spans is sampled, and otherwise
+    /// delegates to the fallback sampler.
+    ///
+    /// This keeps the spans of consumers processing batches of messages from sampled producers,
+    /// e.g. a consumer span linked to the spans which produced the messages. Use a
+    /// [`Sampler::ParentBased`] fallback to also respect unsampled parents:
+    ///
+    /// ```
+    /// use opentelemetry_sdk::trace::Sampler;
+    ///
+    /// let sampler = Sampler::LinkBased(Box::new(Sampler::ParentBased(Box::new(
+    ///     Sampler::TraceIdRatioBased(0.1),
+    /// ))));
+    /// ```
+    LinkBased(Box<dyn ShouldSample>),
     /// Sample a given fraction of traces. Fractions >= 1 will always sample. If the parent span is
     /// sampled, then it's child spans will automatically be sampled. Fractions < 0 are treated as
     /// zero, but spans may still be sampled if their parent is.
@@ -157,9 +212,37 @@ pub enum Sampler {
     /// given service (a.k.a per operation).
     #[cfg(feature = "jaeger_remote_sampler")]
     JaegerRemote(JaegerRemoteSampler),
+    /// AWS X-Ray remote sampler, sampling according to the centralized sampling rules of
+    /// [AWS X-Ray](https://docs.aws.amazon.com/xray/latest/devguide/xray-console-sampling.html).
+    ///
+    /// The sampler polls the sampling rules and the reservoir quotas of this instance, and
+    /// reports its sampling statistics. Rules match on the service name and the HTTP method,
+    /// URL path and host of the span.
+    #[cfg(feature = "xray_remote_sampler")]
+    XrayRemote(XrayRemoteSampler),
+    /// Sample at most the given number of traces per second, see
+    /// [`RateLimitingSampler`]. Use [`Sampler::rate_limited`] to create it.
+    RateLimited(RateLimitingSampler),
+    /// Delegates to the sampler of the first matching rule, matching on the span
+    /// name, kind, instrumentation scope and initial attributes.
+    RuleBased(RuleBasedSampler),
 }
 
 impl Sampler {
+    /// Create a sampler sampling at most `traces_per_second` traces per second.
+    ///
+    /// Wrap it in [`Sampler::ParentBased`] to limit the number of traces rather
+    /// than the number of spans:
+    ///
+    /// ```
+    /// use opentelemetry_sdk::trace::Sampler;
+    ///
+    /// let sampler = Sampler::ParentBased(Box::new(Sampler::rate_limited(100.0)));
+    /// ```
+    pub fn rate_limited(traces_per_second: f64) -> Self {
+        Sampler::RateLimited(RateLimitingSampler::new(traces_per_second))
+    }
+
     /// Create a jaeger remote sampler builder.
     ///
     /// ### Arguments
@@ -184,11 +267,36 @@ impl Sampler {
     {
         JaegerRemoteSamplerBuilder::new(runtime, http_client, default_sampler, service_name)
     }
+
+    /// Create an AWS X-Ray remote sampler builder.
+    ///
+    /// ### Arguments
+    /// * `runtime` - A runtime to run the HTTP client.
+    /// * `http_client` - An HTTP client to query the sampling API of the X-Ray daemon or proxy.
+    /// * `default_sampler` - A default sampler to make a sampling decision before the SDK receives the first sampling rules.
+    /// * `service_name` - The name of the service, matched against the service name of the sampling rules.
+    #[cfg(feature = "xray_remote_sampler")]
+    pub fn xray_remote<C, Sampler, R, Svc>(
+        runtime: R,
+        http_client: C,
+        default_sampler: Sampler,
+        service_name: Svc,
+    ) -> XrayRemoteSamplerBuilder<C, Sampler, R>
+    where
+        C: HttpClient + 'static,
+        Sampler: ShouldSample,
+        R: crate::runtime::RuntimeChannel,
+        Svc: Into<String>,
+    {
+        XrayRemoteSamplerBuilder::new(runtime, http_client, default_sampler, service_name)
+    }
 }
 
-impl ShouldSample for Sampler {
-    fn should_sample(
+impl Sampler {
+    #[allow(clippy::too_many_arguments)]
+    fn sample(
         &self,
+        scope: Option<&InstrumentationScope>,
         parent_context: Option<&Context>,
         trace_id: TraceId,
         name: &str,
@@ -206,16 +314,17 @@ impl ShouldSample for Sampler {
                 .filter(|cx| cx.has_active_span())
                 .map_or_else(
                     || {
-                        delegate_sampler
-                            .should_sample(
-                                parent_context,
-                                trace_id,
-                                name,
-                                span_kind,
-                                attributes,
-                                links,
-                            )
-                            .decision
+                        sample_with_scope(
+                            delegate_sampler.as_ref(),
+                            scope,
+                            parent_context,
+                            trace_id,
+                            name,
+                            span_kind,
+                            attributes,
+                            links,
+                        )
+                        .decision
                     },
                     |ctx| {
                         let span = ctx.span();
@@ -227,6 +336,27 @@ impl ShouldSample for Sampler {
                         }
                     },
                 ),
+            // Sampled if the parent or a linked span is; otherwise the decision of fallback_sampler
+            Sampler::LinkBased(fallback_sampler) => {
+                let parent_sampled = parent_context
+                    .filter(|cx| cx.has_active_span())
+                    .is_some_and(|cx| cx.span().span_context().is_sampled());
+                if parent_sampled || links.iter().any(|link| link.span_context.is_sampled()) {
+                    SamplingDecision::RecordAndSample
+                } else {
+                    sample_with_scope(
+                        fallback_sampler.as_ref(),
+                        scope,
+                        parent_context,
+                        trace_id,
+                        name,
+                        span_kind,
+                        attributes,
+                        links,
+                    )
+                    .decision
+                }
+            }
             // Probabilistically sample the trace.
             Sampler::TraceIdRatioBased(prob) => sample_based_on_probability(prob, trace_id),
             #[cfg(feature = "jaeger_remote_sampler")]
@@ -235,6 +365,28 @@ impl ShouldSample for Sampler {
                     .should_sample(parent_context, trace_id, name, span_kind, attributes, links)
                     .decision
             }
+            #[cfg(feature = "xray_remote_sampler")]
+            Sampler::XrayRemote(remote_sampler) => {
+                remote_sampler
+                    .should_sample(parent_context, trace_id, name, span_kind, attributes, links)
+                    .decision
+            }
+            Sampler::RateLimited(rate_limiting_sampler) => {
+                rate_limiting_sampler
+                    .should_sample(parent_context, trace_id, name, span_kind, attributes, links)
+                    .decision
+            }
+            Sampler::RuleBased(rule_based_sampler) => {
+                return rule_based_sampler.sample(
+                    scope,
+                    parent_context,
+                    trace_id,
+                    name,
+                    span_kind,
+                    attributes,
+                    links,
+                );
+            }
         };
         SamplingResult {
             decision,
@@ -249,6 +401,76 @@ impl ShouldSample for Sampler {
     }
 }
 
+impl ShouldSample for Sampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+        links: &[Link],
+    ) -> SamplingResult {
+        self.sample(
+            None,
+            parent_context,
+            trace_id,
+            name,
+            span_kind,
+            attributes,
+            links,
+        )
+    }
+
+    fn should_sample_with_scope(
+        &self,
+        scope: &InstrumentationScope,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+        links: &[Link],
+    ) -> SamplingResult {
+        self.sample(
+            Some(scope),
+            parent_context,
+            trace_id,
+            name,
+            span_kind,
+            attributes,
+            links,
+        )
+    }
+}
+
+/// Calls [`ShouldSample::should_sample_with_scope`] if the scope is known, and
+/// [`ShouldSample::should_sample`] otherwise.
+#[allow(clippy::too_many_arguments)]
+fn sample_with_scope(
+    sampler: &dyn ShouldSample,
+    scope: Option<&InstrumentationScope>,
+    parent_context: Option<&Context>,
+    trace_id: TraceId,
+    name: &str,
+    span_kind: &SpanKind,
+    attributes: &[KeyValue],
+    links: &[Link],
+) -> SamplingResult {
+    match scope {
+        Some(scope) => sampler.should_sample_with_scope(
+            scope,
+            parent_context,
+            trace_id,
+            name,
+            span_kind,
+            attributes,
+            links,
+        ),
+        None => sampler.should_sample(parent_context, trace_id, name, span_kind, attributes, links),
+    }
+}
+
 pub(crate) fn sample_based_on_probability(prob: &f64, trace_id: TraceId) -> SamplingDecision {
     if *prob >= 1.0 {
         SamplingDecision::RecordAndSample
@@ -459,4 +681,85 @@ mod tests {
             assert_eq!(result.decision, expected);
         }
     }
+
+    #[test]
+    fn link_sampler() {
+        let span_context = |trace_flags| {
+            SpanContext::new(
+                TraceId::from(1),
+                SpanId::from(1),
+                trace_flags,
+                true,
+                TraceState::default(),
+            )
+        };
+        let sampled_link = Link::with_context(span_context(TraceFlags::SAMPLED));
+        let unsampled_link = Link::with_context(span_context(TraceFlags::default()));
+        let sampled_parent_cx =
+            Context::new().with_remote_span_context(span_context(TraceFlags::SAMPLED));
+        let unsampled_parent_cx =
+            Context::new().with_remote_span_context(span_context(TraceFlags::default()));
+
+        // name, fallback, parent context, links, expected decision
+        #[rustfmt::skip]
+        let test_cases = vec![
+            ("root without links uses fallback", Sampler::AlwaysOn, Context::new(), vec![], SamplingDecision::RecordAndSample),
+            ("root without links uses fallback, always off", Sampler::AlwaysOff, Context::new(), vec![], SamplingDecision::Drop),
+            ("sampled parent", Sampler::AlwaysOff, sampled_parent_cx.clone(), vec![], SamplingDecision::RecordAndSample),
+            ("unsampled parent uses fallback", Sampler::AlwaysOn, unsampled_parent_cx.clone(), vec![], SamplingDecision::RecordAndSample),
+            ("unsampled parent with parent based fallback", Sampler::ParentBased(Box::new(Sampler::AlwaysOn)), unsampled_parent_cx.clone(), vec![], SamplingDecision::Drop),
+            ("any sampled link", Sampler::AlwaysOff, Context::new(), vec![unsampled_link.clone(), sampled_link.clone()], SamplingDecision::RecordAndSample),
+            ("sampled link overrides unsampled parent", Sampler::ParentBased(Box::new(Sampler::AlwaysOff)), unsampled_parent_cx.clone(), vec![sampled_link.clone()], SamplingDecision::RecordAndSample),
+            ("unsampled links use fallback", Sampler::AlwaysOff, Context::new(), vec![unsampled_link.clone()], SamplingDecision::Drop),
+        ];
+
+        for (name, fallback, parent_cx, links, expected) in test_cases {
+            let sampler = Sampler::LinkBased(Box::new(fallback));
+            let result = sampler.should_sample(
+                Some(&parent_cx),
+                TraceId::from(1),
+                name,
+                &SpanKind::Consumer,
+                &[],
+                &links,
+            );
+
+            assert_eq!(result.decision, expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn parent_based_rate_limited_sampler() {
+        let sampler = Sampler::ParentBased(Box::new(Sampler::rate_limited(1.0)));
+        let sample = |parent_cx: &Context| {
+            sampler
+                .should_sample(
+                    Some(parent_cx),
+                    TraceId::from(1),
+                    "span",
+                    &SpanKind::Internal,
+                    &[],
+                    &[],
+                )
+                .decision
+        };
+        let sampled_parent_cx = Context::current_with_span(TestSpan(SpanContext::new(
+            TraceId::from(1),
+            SpanId::from(1),
+            TraceFlags::SAMPLED,
+            false,
+            TraceState::default(),
+        )));
+
+        // the first root span consumes the allowance of the current second
+        assert_eq!(sample(&Context::new()), SamplingDecision::RecordAndSample);
+        assert_eq!(sample(&Context::new()), SamplingDecision::Drop);
+        // spans of sampled traces are not limited
+        for _ in 0..10 {
+            assert_eq!(
+                sample(&sampled_parent_cx),
+                SamplingDecision::RecordAndSample
+            );
+        }
+    }
 }
@@ -0,0 +1,857 @@
+//! Consistent probability sampling.
+//!
+//! Consistent probability samplers make their decisions by comparing a 56 bit
+//! randomness value of the trace against a rejection threshold, and record the
+//! threshold of sampled spans in the `th` sub-key of the `ot` trace state entry.
+//! As all samplers of a trace share the same randomness, a span sampled with
+//! some probability implies that all spans of the trace sampled with a higher
+//! probability are sampled as well, and downstream components can use the
+//! recorded threshold to extrapolate span counts.
+//!
+//! The randomness is taken from the `rv` sub-key of the `ot` trace state entry
+//! when present, and from the 56 least significant bits of the trace id
+//! otherwise.
+//!
+//! See the [specification] for details.
+//!
+//! [specification]: https://opentelemetry.io/docs/specs/otel/trace/tracestate-probability-sampling/
+use std::fmt;
+use std::sync::Arc;
+
+use opentelemetry::{
+    trace::{Link, SpanContext, SpanKind, TraceContextExt, TraceId, TraceState},
+    Context, InstrumentationScope, KeyValue,
+};
+
+use super::{SamplingDecision, SamplingResult, ShouldSample};
+
+/// The key of the OpenTelemetry trace state entry.
+const OT_KEY: &str = "ot";
+/// The sub-key of the rejection threshold in the `ot` trace state entry.
+const THRESHOLD_KEY: &str = "th";
+/// The sub-key of the explicit randomness value in the `ot` trace state entry.
+const RANDOMNESS_KEY: &str = "rv";
+/// The number of hex digits of a threshold or randomness value.
+const HEX_DIGITS: usize = 14;
+/// The exclusive upper bound of thresholds and randomness values.
+const MAX_THRESHOLD: u64 = 1 << 56;
+
+/// The information available to a [`ComposableSampler`] about a span to be
+/// created.
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub struct SamplingParameters<'a> {
+    /// The instrumentation scope of the tracer creating the span, if known.
+    pub scope: Option<&'a InstrumentationScope>,
+    /// The parent context of the span, if any.
+    pub parent_context: Option<&'a Context>,
+    /// The trace id of the span.
+    pub trace_id: TraceId,
+    /// The name of the span.
+    pub name: &'a str,
+    /// The kind of the span.
+    pub span_kind: &'a SpanKind,
+    /// The initial attributes of the span.
+    pub attributes: &'a [KeyValue],
+    /// The initial links of the span.
+    pub links: &'a [Link],
+}
+
+impl<'a> SamplingParameters<'a> {
+    /// Returns the span context of the parent span, if the parent context has
+    /// an active span.
+    fn parent_span_context(&self) -> Option<SpanContext> {
+        self.parent_context
+            .filter(|cx| cx.has_active_span())
+            .map(|cx| cx.span().span_context().clone())
+    }
+}
+
+/// The sampling decision of a [`ComposableSampler`], expressed as a rejection
+/// threshold.
+///
+/// Spans are sampled if the randomness value of their trace is greater than or
+/// equal to the threshold, so a threshold of `0` samples all spans and no
+/// threshold samples none.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SamplingIntent {
+    threshold: Option<u64>,
+    threshold_reliable: bool,
+    attributes: Vec<KeyValue>,
+}
+
+impl SamplingIntent {
+    /// An intent sampling spans whose randomness value is greater than or
+    /// equal to `threshold`.
+    ///
+    /// Thresholds of `2^56` and above sample no spans.
+    pub fn with_threshold(threshold: u64) -> Self {
+        SamplingIntent {
+            threshold: (threshold < MAX_THRESHOLD).then_some(threshold),
+            threshold_reliable: true,
+            attributes: Vec::new(),
+        }
+    }
+
+    /// An intent sampling no spans.
+    pub fn drop() -> Self {
+        SamplingIntent {
+            threshold: None,
+            threshold_reliable: false,
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Marks the threshold as unsuitable for extrapolating span counts, in
+    /// which case it is not recorded in the trace state of sampled spans.
+    pub fn unreliable(mut self) -> Self {
+        self.threshold_reliable = false;
+        self
+    }
+
+    /// Adds attributes to the spans sampled with this intent.
+    pub fn with_attributes<T: IntoIterator<Item = KeyValue>>(mut self, attributes: T) -> Self {
+        self.attributes.extend(attributes);
+        self
+    }
+
+    /// The rejection threshold, or `None` if no spans are sampled.
+    pub fn threshold(&self) -> Option<u64> {
+        self.threshold
+    }
+
+    /// Whether the threshold can be used for extrapolating span counts.
+    pub fn is_threshold_reliable(&self) -> bool {
+        self.threshold_reliable
+    }
+
+    /// The attributes added to the spans sampled with this intent.
+    pub fn attributes(&self) -> &[KeyValue] {
+        &self.attributes
+    }
+}
+
+/// A sampler expressing its decisions as a [`SamplingIntent`], so it can be
+/// combined with other consistent samplers.
+///
+/// The samplers of this module implement both [`ComposableSampler`] and
+/// [`ShouldSample`]. Custom implementations can be used as the delegates of
+/// [`ConsistentParentBased`], [`ConsistentRuleBased`] and [`AnyOf`].
+pub trait ComposableSampler: Send + Sync + fmt::Debug {
+    /// Returns the [`SamplingIntent`] for a span to be created.
+    fn sampling_intent(&self, params: &SamplingParameters<'_>) -> SamplingIntent;
+}
+
+/// Samples a given fraction of traces, consistently with all other consistent
+/// probability samplers of the trace.
+///
+/// Unlike [`Sampler::TraceIdRatioBased`], the threshold of sampled spans is
+/// recorded in the `ot` trace state entry. Fractions >= 1 sample all spans,
+/// fractions <= 0 none.
+///
+/// [`Sampler::TraceIdRatioBased`]: super::Sampler::TraceIdRatioBased
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConsistentProbabilityBased {
+    threshold: Option<u64>,
+}
+
+impl ConsistentProbabilityBased {
+    /// Create a sampler sampling the given fraction of traces.
+    pub fn new(ratio: f64) -> Self {
+        ConsistentProbabilityBased {
+            threshold: ratio_to_threshold(ratio),
+        }
+    }
+}
+
+impl ComposableSampler for ConsistentProbabilityBased {
+    fn sampling_intent(&self, _params: &SamplingParameters<'_>) -> SamplingIntent {
+        match self.threshold {
+            Some(threshold) => SamplingIntent::with_threshold(threshold),
+            None => SamplingIntent::drop(),
+        }
+    }
+}
+
+/// Respects the sampling decision of the parent span, and delegates to a root
+/// sampler for spans without a parent.
+///
+/// Spans with a sampled parent reuse the threshold recorded in the trace state
+/// of the parent. If it is missing or inconsistent with the randomness of the
+/// trace, the spans are sampled without recording a threshold.
+#[derive(Clone, Debug)]
+pub struct ConsistentParentBased {
+    root: Arc<dyn ComposableSampler>,
+}
+
+impl ConsistentParentBased {
+    /// Create a sampler delegating to `root` for spans without a parent.
+    pub fn new<T: ComposableSampler + 'static>(root: T) -> Self {
+        ConsistentParentBased {
+            root: Arc::new(root),
+        }
+    }
+}
+
+impl ComposableSampler for ConsistentParentBased {
+    fn sampling_intent(&self, params: &SamplingParameters<'_>) -> SamplingIntent {
+        let Some(parent) = params.parent_span_context().filter(SpanContext::is_valid) else {
+            return self.root.sampling_intent(params);
+        };
+        if !parent.is_sampled() {
+            return SamplingIntent::drop();
+        }
+        let ot = OtTraceState::parse(parent.trace_state());
+        let randomness = ot
+            .randomness()
+            .unwrap_or_else(|| trace_id_randomness(params.trace_id));
+        match ot.threshold() {
+            Some(threshold) if randomness >= threshold => SamplingIntent::with_threshold(threshold),
+            _ => SamplingIntent::with_threshold(0).unreliable(),
+        }
+    }
+}
+
+type RulePredicate = dyn Fn(&SamplingParameters<'_>) -> bool + Send + Sync;
+type Rule = (Box<RulePredicate>, Box<dyn ComposableSampler>);
+
+/// Delegates to the composable sampler of the first rule whose predicate
+/// matches the span, and samples no spans if no rule matches.
+///
+/// Unlike [`RuleBasedSampler`], whose rules match declarative conditions and
+/// delegate to any [`ShouldSample`], the rules of this sampler are arbitrary
+/// predicates delegating to [`ComposableSampler`]s, so that the resulting
+/// threshold is recorded for consistent sampling.
+///
+/// [`RuleBasedSampler`]: crate::trace::RuleBasedSampler
+#[derive(Clone)]
+pub struct ConsistentRuleBased {
+    rules: Arc<[Rule]>,
+}
+
+impl ConsistentRuleBased {
+    /// Create a builder for a rule based sampler.
+    pub fn builder() -> ConsistentRuleBasedBuilder {
+        ConsistentRuleBasedBuilder::default()
+    }
+}
+
+impl fmt::Debug for ConsistentRuleBased {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConsistentRuleBased")
+            .field(
+                "samplers",
+                &self
+                    .rules
+                    .iter()
+                    .map(|(_, sampler)| sampler)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl ComposableSampler for ConsistentRuleBased {
+    fn sampling_intent(&self, params: &SamplingParameters<'_>) -> SamplingIntent {
+        self.rules
+            .iter()
+            .find(|(predicate, _)| predicate(params))
+            .map_or_else(SamplingIntent::drop, |(_, sampler)| {
+                sampler.sampling_intent(params)
+            })
+    }
+}
+
+/// Builder for [`ConsistentRuleBased`] samplers.
+#[derive(Default)]
+pub struct ConsistentRuleBasedBuilder {
+    rules: Vec<Rule>,
+}
+
+impl fmt::Debug for ConsistentRuleBasedBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConsistentRuleBasedBuilder")
+            .field("rules", &self.rules.len())
+            .finish()
+    }
+}
+
+impl ConsistentRuleBasedBuilder {
+    /// Add a rule delegating to `sampler` for the spans matched by
+    /// `predicate`. Rules are evaluated in the order they are added.
+    pub fn with_rule<P, S>(mut self, predicate: P, sampler: S) -> Self
+    where
+        P: Fn(&SamplingParameters<'_>) -> bool + Send + Sync + 'static,
+        S: ComposableSampler + 'static,
+    {
+        self.rules.push((Box::new(predicate), Box::new(sampler)));
+        self
+    }
+
+    /// Create the rule based sampler.
+    pub fn build(self) -> ConsistentRuleBased {
+        ConsistentRuleBased {
+            rules: self.rules.into(),
+        }
+    }
+}
+
+/// Samples the spans sampled by any of its delegates, using the lowest of
+/// their thresholds. Attributes of all delegates are added to sampled spans.
+#[derive(Clone, Debug)]
+pub struct AnyOf {
+    samplers: Arc<[Box<dyn ComposableSampler>]>,
+}
+
+impl AnyOf {
+    /// Create a builder for an any of sampler.
+    pub fn builder() -> AnyOfBuilder {
+        AnyOfBuilder::default()
+    }
+}
+
+impl ComposableSampler for AnyOf {
+    fn sampling_intent(&self, params: &SamplingParameters<'_>) -> SamplingIntent {
+        let mut result = SamplingIntent::drop();
+        for sampler in self.samplers.iter() {
+            let intent = sampler.sampling_intent(params);
+            match (intent.threshold, result.threshold) {
+                (Some(threshold), Some(min)) if threshold == min => {
+                    result.threshold_reliable |= intent.threshold_reliable;
+                }
+                (Some(threshold), Some(min)) if threshold > min => {}
+                (Some(threshold), _) => {
+                    result.threshold = Some(threshold);
+                    result.threshold_reliable = intent.threshold_reliable;
+                }
+                (None, _) => {}
+            }
+            result.attributes.extend(intent.attributes);
+        }
+        result
+    }
+}
+
+/// Builder for [`AnyOf`] samplers.
+#[derive(Debug, Default)]
+pub struct AnyOfBuilder {
+    samplers: Vec<Box<dyn ComposableSampler>>,
+}
+
+impl AnyOfBuilder {
+    /// Add a delegate sampler.
+    pub fn with_sampler<S: ComposableSampler + 'static>(mut self, sampler: S) -> Self {
+        self.samplers.push(Box::new(sampler));
+        self
+    }
+
+    /// Create the any of sampler.
+    pub fn build(self) -> AnyOf {
+        AnyOf {
+            samplers: self.samplers.into(),
+        }
+    }
+}
+
+macro_rules! impl_should_sample {
+    ($($sampler:ty),*) => {
+        $(
+            impl ShouldSample for $sampler {
+                fn should_sample(
+                    &self,
+                    parent_context: Option<&Context>,
+                    trace_id: TraceId,
+                    name: &str,
+                    span_kind: &SpanKind,
+                    attributes: &[KeyValue],
+                    links: &[Link],
+                ) -> SamplingResult {
+                    let params = SamplingParameters {
+                        scope: None,
+                        parent_context,
+                        trace_id,
+                        name,
+                        span_kind,
+                        attributes,
+                        links,
+                    };
+                    should_sample(self, &params)
+                }
+
+                fn should_sample_with_scope(
+                    &self,
+                    scope: &InstrumentationScope,
+                    parent_context: Option<&Context>,
+                    trace_id: TraceId,
+                    name: &str,
+                    span_kind: &SpanKind,
+                    attributes: &[KeyValue],
+                    links: &[Link],
+                ) -> SamplingResult {
+                    let params = SamplingParameters {
+                        scope: Some(scope),
+                        parent_context,
+                        trace_id,
+                        name,
+                        span_kind,
+                        attributes,
+                        links,
+                    };
+                    should_sample(self, &params)
+                }
+            }
+        )*
+    };
+}
+
+impl_should_sample!(
+    ConsistentProbabilityBased,
+    ConsistentParentBased,
+    ConsistentRuleBased,
+    AnyOf
+);
+
+/// Turns the intent of `sampler` into a sampling result, updating the
+/// threshold recorded in the `ot` trace state entry.
+fn should_sample<S: ComposableSampler>(
+    sampler: &S,
+    params: &SamplingParameters<'_>,
+) -> SamplingResult {
+    let parent = params.parent_span_context();
+    let trace_state = parent
+        .as_ref()
+        .map(|parent| parent.trace_state().clone())
+        .unwrap_or_default();
+    let mut ot = OtTraceState::parse(&trace_state);
+
+    let intent = sampler.sampling_intent(params);
+    let explicit_randomness = ot.randomness();
+    let randomness = explicit_randomness.unwrap_or_else(|| trace_id_randomness(params.trace_id));
+    // A remote parent not flagging its trace id as random may have propagated
+    // a trace id which is not suitable for extrapolation.
+    let randomness_reliable = explicit_randomness.is_some()
+        || parent.as_ref().map_or(true, |parent| {
+            !parent.is_remote() || parent.trace_flags().is_random()
+        });
+
+    let sampled = intent
+        .threshold
+        .is_some_and(|threshold| randomness >= threshold);
+    match intent.threshold {
+        Some(threshold) if sampled && intent.threshold_reliable && randomness_reliable => {
+            ot.set(THRESHOLD_KEY, encode_threshold(threshold))
+        }
+        _ => ot.remove(THRESHOLD_KEY),
+    }
+
+    SamplingResult {
+        decision: if sampled {
+            SamplingDecision::RecordAndSample
+        } else {
+            SamplingDecision::Drop
+        },
+        attributes: if sampled {
+            intent.attributes
+        } else {
+            Vec::new()
+        },
+        trace_state: ot.apply(trace_state),
+    }
+}
+
+/// The sub-keys of the `ot` trace state entry.
+#[derive(Debug, Default)]
+struct OtTraceState {
+    entries: Vec<(String, String)>,
+    modified: bool,
+}
+
+impl OtTraceState {
+    fn parse(trace_state: &TraceState) -> Self {
+        let entries = trace_state
+            .get(OT_KEY)
+            .map(|value| {
+                value
+                    .split(';')
+                    .filter_map(|entry| entry.split_once(':'))
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        OtTraceState {
+            entries,
+            modified: false,
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn threshold(&self) -> Option<u64> {
+        self.get(THRESHOLD_KEY).and_then(decode_threshold)
+    }
+
+    fn randomness(&self) -> Option<u64> {
+        self.get(RANDOMNESS_KEY)
+            .filter(|value| value.len() == HEX_DIGITS)
+            .and_then(|value| u64::from_str_radix(value, 16).ok())
+    }
+
+    fn set(&mut self, key: &str, value: String) {
+        match self.entries.iter_mut().find(|(k, _)| k == key) {
+            Some((_, v)) if *v == value => {}
+            Some((_, v)) => {
+                *v = value;
+                self.modified = true;
+            }
+            None => {
+                self.entries.insert(0, (key.to_string(), value));
+                self.modified = true;
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        let len = self.entries.len();
+        self.entries.retain(|(k, _)| k != key);
+        self.modified |= self.entries.len() != len;
+    }
+
+    /// Writes the sub-keys back into `trace_state` if they were modified.
+    fn apply(self, trace_state: TraceState) -> TraceState {
+        if !self.modified {
+            return trace_state;
+        }
+        let result = if self.entries.is_empty() {
+            trace_state.delete(OT_KEY)
+        } else {
+            let value = self
+                .entries
+                .iter()
+                .map(|(k, v)| format!("{k}:{v}"))
+                .collect::<Vec<_>>()
+                .join(";");
+            trace_state.insert(OT_KEY, value)
+        };
+        result.unwrap_or(trace_state)
+    }
+}
+
+/// The randomness value carried by the 56 least significant bits of the trace
+/// id.
+fn trace_id_randomness(trace_id: TraceId) -> u64 {
+    let bytes = trace_id.to_bytes();
+    let low = u64::from_be_bytes(bytes[8..].try_into().unwrap());
+    low & (MAX_THRESHOLD - 1)
+}
+
+fn ratio_to_threshold(ratio: f64) -> Option<u64> {
+    if ratio >= 1.0 {
+        Some(0)
+    } else if ratio > 0.0 {
+        let threshold = ((1.0 - ratio) * MAX_THRESHOLD as f64).round() as u64;
+        (threshold < MAX_THRESHOLD).then_some(threshold)
+    } else {
+        // also covers NaN
+        None
+    }
+}
+
+/// Encodes a threshold as hex digits, omitting trailing zeros.
+fn encode_threshold(threshold: u64) -> String {
+    let encoded = format!("{threshold:0width$x}", width = HEX_DIGITS);
+    match encoded.trim_end_matches('0') {
+        "" => "0".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+fn decode_threshold(value: &str) -> Option<u64> {
+    if value.is_empty() || value.len() > HEX_DIGITS {
+        return None;
+    }
+    let threshold = u64::from_str_radix(value, 16).ok()?;
+    Some(threshold << (4 * (HEX_DIGITS - value.len())))
+}
+
+#[cfg(all(test, feature = "testing", feature = "trace"))]
+mod tests {
+    use super::*;
+    use crate::testing::trace::TestSpan;
+    use opentelemetry::trace::{SpanId, TraceFlags};
+    use std::str::FromStr;
+
+    fn trace_id_with_randomness(randomness: u64) -> TraceId {
+        TraceId::from((0x1234_5678_u128 << 64) | u128::from(randomness))
+    }
+
+    fn parent_context(trace_id: TraceId, flags: TraceFlags, trace_state: &str) -> Context {
+        Context::current_with_span(TestSpan(SpanContext::new(
+            trace_id,
+            SpanId::from(1),
+            flags,
+            true,
+            TraceState::from_str(trace_state).unwrap(),
+        )))
+    }
+
+    fn sample<S: ShouldSample>(
+        sampler: &S,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+    ) -> SamplingResult {
+        sampler.should_sample(
+            parent_context,
+            trace_id,
+            "span",
+            &SpanKind::Internal,
+            &[],
+            &[],
+        )
+    }
+
+    #[test]
+    fn threshold_encoding() {
+        for (ratio, expected) in [
+            (1.0, "0"),
+            (0.5, "8"),
+            (0.25, "c"),
+            (0.125, "e"),
+            (0.75, "4"),
+            (1.0 / 1024.0, "ffc"),
+        ] {
+            let threshold = ratio_to_threshold(ratio).unwrap();
+            assert_eq!(encode_threshold(threshold), expected, "ratio {ratio}");
+            assert_eq!(decode_threshold(expected), Some(threshold));
+        }
+        assert_eq!(ratio_to_threshold(0.0), None);
+        assert_eq!(ratio_to_threshold(-1.0), None);
+        assert_eq!(ratio_to_threshold(f64::NAN), None);
+        assert_eq!(ratio_to_threshold(2.0), Some(0));
+
+        assert_eq!(decode_threshold(""), None);
+        assert_eq!(decode_threshold("xyz"), None);
+        assert_eq!(decode_threshold("000000000000000"), None);
+    }
+
+    #[test]
+    fn probability_based_sampling() {
+        let sampler = ConsistentProbabilityBased::new(0.25);
+
+        let result = sample(
+            &sampler,
+            None,
+            trace_id_with_randomness(0xc0_0000_0000_0000),
+        );
+        assert_eq!(result.decision, SamplingDecision::RecordAndSample);
+        assert_eq!(result.trace_state.get(OT_KEY), Some("th:c"));
+
+        let result = sample(
+            &sampler,
+            None,
+            trace_id_with_randomness(0xbf_ffff_ffff_ffff),
+        );
+        assert_eq!(result.decision, SamplingDecision::Drop);
+        assert_eq!(result.trace_state.get(OT_KEY), None);
+
+        let result = sample(
+            &ConsistentProbabilityBased::new(1.0),
+            None,
+            trace_id_with_randomness(0),
+        );
+        assert_eq!(result.decision, SamplingDecision::RecordAndSample);
+        assert_eq!(result.trace_state.get(OT_KEY), Some("th:0"));
+
+        let result = sample(
+            &ConsistentProbabilityBased::new(0.0),
+            None,
+            trace_id_with_randomness(MAX_THRESHOLD - 1),
+        );
+        assert_eq!(result.decision, SamplingDecision::Drop);
+    }
+
+    #[test]
+    fn probability_based_sampling_ratio() {
+        let sampler = ConsistentProbabilityBased::new(0.25);
+        let total = 10_000;
+        let sampled = (0..total)
+            .filter(|_| {
+                sample(&sampler, None, TraceId::from(rand::random::<u128>())).decision
+                    == SamplingDecision::RecordAndSample
+            })
+            .count();
+        let got = sampled as f64 / total as f64;
+        assert!((got - 0.25).abs() < 0.03, "sampled ratio {got}");
+    }
+
+    #[test]
+    fn explicit_randomness() {
+        let sampler = ConsistentProbabilityBased::new(0.5);
+        // the trace id randomness would sample the span, rv does not
+        let trace_id = trace_id_with_randomness(MAX_THRESHOLD - 1);
+        let cx = parent_context(trace_id, TraceFlags::default(), "ot=rv:10000000000000");
+        let result = sample(&sampler, Some(&cx), trace_id);
+        assert_eq!(result.decision, SamplingDecision::Drop);
+        assert_eq!(result.trace_state.get(OT_KEY), Some("rv:10000000000000"));
+
+        let cx = parent_context(
+            trace_id_with_randomness(0),
+            TraceFlags::default(),
+            "ot=rv:90000000000000",
+        );
+        let result = sample(&sampler, Some(&cx), trace_id_with_randomness(0));
+        assert_eq!(result.decision, SamplingDecision::RecordAndSample);
+        assert_eq!(
+            result.trace_state.get(OT_KEY),
+            Some("th:8;rv:90000000000000")
+        );
+    }
+
+    #[test]
+    fn remote_parent_without_random_flag() {
+        let sampler = ConsistentProbabilityBased::new(1.0);
+        let trace_id = trace_id_with_randomness(0);
+
+        let cx = parent_context(trace_id, TraceFlags::SAMPLED, "");
+        let result = sample(&sampler, Some(&cx), trace_id);
+        assert_eq!(result.decision, SamplingDecision::RecordAndSample);
+        assert_eq!(result.trace_state.get(OT_KEY), None);
+
+        let cx = parent_context(trace_id, TraceFlags::SAMPLED | TraceFlags::RANDOM, "");
+        let result = sample(&sampler, Some(&cx), trace_id);
+        assert_eq!(result.trace_state.get(OT_KEY), Some("th:0"));
+    }
+
+    #[test]
+    fn parent_based_sampling() {
+        let sampler = ConsistentParentBased::new(ConsistentProbabilityBased::new(0.5));
+        let trace_id = trace_id_with_randomness(0xc0_0000_0000_0000);
+        let flags = TraceFlags::SAMPLED | TraceFlags::RANDOM;
+
+        // root spans delegate to the root sampler
+        let result = sample(&sampler, None, trace_id);
+        assert_eq!(result.decision, SamplingDecision::RecordAndSample);
+        assert_eq!(result.trace_state.get(OT_KEY), Some("th:8"));
+
+        // the threshold of the parent is kept
+        let cx = parent_context(trace_id, flags, "ot=th:c");
+        let result = sample(&sampler, Some(&cx), trace_id);
+        assert_eq!(result.decision, SamplingDecision::RecordAndSample);
+        assert_eq!(result.trace_state.get(OT_KEY), Some("th:c"));
+
+        // a missing threshold is not made up
+        let cx = parent_context(trace_id, flags, "");
+        let result = sample(&sampler, Some(&cx), trace_id);
+        assert_eq!(result.decision, SamplingDecision::RecordAndSample);
+        assert_eq!(result.trace_state.get(OT_KEY), None);
+
+        // an inconsistent threshold is erased
+        let cx = parent_context(trace_id, flags, "ot=th:e");
+        let result = sample(&sampler, Some(&cx), trace_id);
+        assert_eq!(result.decision, SamplingDecision::RecordAndSample);
+        assert_eq!(result.trace_state.get(OT_KEY), None);
+
+        // unsampled parents are respected
+        let cx = parent_context(trace_id, TraceFlags::RANDOM, "ot=th:8");
+        let result = sample(&sampler, Some(&cx), trace_id);
+        assert_eq!(result.decision, SamplingDecision::Drop);
+        assert_eq!(result.trace_state.get(OT_KEY), None);
+    }
+
+    #[test]
+    fn rule_based_sampling() {
+        let sampler = ConsistentRuleBased::builder()
+            .with_rule(
+                |params| params.name == "health",
+                ConsistentProbabilityBased::new(0.0),
+            )
+            .with_rule(
+                |params| params.span_kind == &SpanKind::Server,
+                ConsistentProbabilityBased::new(1.0),
+            )
+            .build();
+        let trace_id = trace_id_with_randomness(0);
+
+        let decision = |name, kind| {
+            sampler
+                .should_sample(None, trace_id, name, &kind, &[], &[])
+                .decision
+        };
+        assert_eq!(decision("health", SpanKind::Server), SamplingDecision::Drop);
+        assert_eq!(
+            decision("request", SpanKind::Server),
+            SamplingDecision::RecordAndSample
+        );
+        assert_eq!(
+            decision("request", SpanKind::Client),
+            SamplingDecision::Drop
+        );
+    }
+
+    #[derive(Debug)]
+    struct Annotating(Option<u64>, KeyValue);
+
+    impl ComposableSampler for Annotating {
+        fn sampling_intent(&self, _params: &SamplingParameters<'_>) -> SamplingIntent {
+            match self.0 {
+                Some(threshold) => SamplingIntent::with_threshold(threshold),
+                None => SamplingIntent::drop(),
+            }
+            .with_attributes([self.1.clone()])
+        }
+    }
+
+    #[test]
+    fn any_of_sampling() {
+        let sampler = AnyOf::builder()
+            .with_sampler(Annotating(None, KeyValue::new("a", 1)))
+            .with_sampler(Annotating(Some(0xc0_0000_0000_0000), KeyValue::new("b", 2)))
+            .with_sampler(Annotating(Some(0x80_0000_0000_0000), KeyValue::new("c", 3)))
+            .build();
+        let trace_id = trace_id_with_randomness(0x80_0000_0000_0000);
+
+        let result = sample(&sampler, None, trace_id);
+        assert_eq!(result.decision, SamplingDecision::RecordAndSample);
+        assert_eq!(result.trace_state.get(OT_KEY), Some("th:8"));
+        assert_eq!(
+            result.attributes,
+            vec![
+                KeyValue::new("a", 1),
+                KeyValue::new("b", 2),
+                KeyValue::new("c", 3)
+            ]
+        );
+
+        let result = sample(&AnyOf::builder().build(), None, trace_id);
+        assert_eq!(result.decision, SamplingDecision::Drop);
+        assert!(result.attributes.is_empty());
+    }
+
+    #[test]
+    fn trace_state_is_preserved() {
+        let sampler = ConsistentProbabilityBased::new(0.5);
+        let trace_id = trace_id_with_randomness(MAX_THRESHOLD - 1);
+        let cx = parent_context(
+            trace_id,
+            TraceFlags::SAMPLED | TraceFlags::RANDOM,
+            "vendor=value,ot=th:c;foo:bar",
+        );
+
+        let result = sample(&sampler, Some(&cx), trace_id);
+        assert_eq!(result.decision, SamplingDecision::RecordAndSample);
+        assert_eq!(result.trace_state.header(), "ot=th:8;foo:bar,vendor=value");
+
+        // an unchanged trace state is passed through as is
+        let cx = parent_context(
+            trace_id,
+            TraceFlags::SAMPLED | TraceFlags::RANDOM,
+            "vendor=value,ot=th:8",
+        );
+        let result = sample(&sampler, Some(&cx), trace_id);
+        assert_eq!(result.trace_state.header(), "vendor=value,ot=th:8");
+    }
+}
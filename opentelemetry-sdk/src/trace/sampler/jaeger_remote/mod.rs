@@ -1,4 +1,3 @@
-mod rate_limit;
 #[allow(dead_code)]
 mod remote;
 mod sampler;
@@ -10,7 +10,7 @@ use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
 use std::sync::Mutex;
 
-use super::rate_limit::LeakyBucket;
+use crate::trace::sampler::rate_limit::LeakyBucket;
 
 // todo: remove the mutex as probabilistic doesn't require mutable ref
 // sampling strategy that sent by remote agents or collectors.
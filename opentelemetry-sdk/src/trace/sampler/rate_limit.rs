@@ -0,0 +1,208 @@
+use opentelemetry::trace::{Link, SpanKind, TraceContextExt, TraceId, TraceState};
+use opentelemetry::{Context, KeyValue};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use super::{SamplingDecision, SamplingResult, ShouldSample};
+
+/// Samples at most the given number of traces per second, allowing bursts of
+/// up to one second worth of traces.
+///
+/// Each call to the sampler consumes from the allowance, so the sampler is
+/// usually the root sampler of [`Sampler::ParentBased`] to limit the number of
+/// traces rather than spans. Clones of the sampler share the allowance.
+///
+/// [`Sampler::ParentBased`]: super::Sampler::ParentBased
+#[derive(Clone, Debug)]
+pub struct RateLimitingSampler {
+    leaky_bucket: Arc<Mutex<LeakyBucket>>,
+}
+
+impl RateLimitingSampler {
+    /// Create a sampler sampling at most `traces_per_second` traces per
+    /// second. Rates <= 0 sample no traces.
+    pub fn new(traces_per_second: f64) -> Self {
+        let bucket_size = if traces_per_second > 0.0 {
+            traces_per_second.max(1.0)
+        } else {
+            0.0
+        };
+        RateLimitingSampler {
+            leaky_bucket: Arc::new(Mutex::new(LeakyBucket::new(
+                bucket_size,
+                traces_per_second.max(0.0),
+            ))),
+        }
+    }
+}
+
+impl ShouldSample for RateLimitingSampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&Context>,
+        _trace_id: TraceId,
+        _name: &str,
+        _span_kind: &SpanKind,
+        _attributes: &[KeyValue],
+        _links: &[Link],
+    ) -> SamplingResult {
+        let sampled = self
+            .leaky_bucket
+            .lock()
+            .map(|mut leaky_bucket| leaky_bucket.should_sample())
+            .unwrap_or(false);
+        SamplingResult {
+            decision: if sampled {
+                SamplingDecision::RecordAndSample
+            } else {
+                SamplingDecision::Drop
+            },
+            attributes: Vec::new(),
+            trace_state: match parent_context {
+                Some(ctx) => ctx.span().span_context().trace_state().clone(),
+                None => TraceState::default(),
+            },
+        }
+    }
+}
+
+// leaky bucket based rate limit
+// should be Send+Sync
+#[derive(Debug)]
+pub(crate) struct LeakyBucket {
+    span_per_sec: f64,
+    available: f64,
+    bucket_size: f64,
+    last_time: Instant,
+}
+
+impl LeakyBucket {
+    pub(crate) fn new(bucket_size: f64, span_per_sec: f64) -> LeakyBucket {
+        LeakyBucket {
+            span_per_sec,
+            available: bucket_size,
+            bucket_size,
+            last_time: Instant::now(),
+        }
+    }
+
+    #[cfg(feature = "jaeger_remote_sampler")]
+    pub(crate) fn update(&mut self, span_per_sec: f64) {
+        self.span_per_sec = span_per_sec;
+    }
+
+    pub(crate) fn should_sample(&mut self) -> bool {
+        self.check_availability(Instant::now)
+    }
+
+    fn check_availability<F>(&mut self, now: F) -> bool
+    where
+        F: Fn() -> Instant,
+    {
+        if self.available >= 1.0 {
+            self.available -= 1.0;
+            true
+        } else {
+            let cur_time = now();
+            let elapsed = cur_time.duration_since(self.last_time);
+            self.last_time = cur_time;
+            self.available = f64::min(
+                elapsed.as_secs_f64() * self.span_per_sec + self.available,
+                self.bucket_size,
+            );
+
+            if self.available >= 1.0 {
+                self.available -= 1.0;
+                true
+            } else {
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::trace::sampler::rate_limit::{LeakyBucket, RateLimitingSampler};
+    use crate::trace::{SamplingDecision, ShouldSample};
+    use opentelemetry::trace::{SpanKind, TraceId};
+    use std::ops::Add;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_leaky_bucket() {
+        // maximum bucket size 2, add 1 allowance every 10 seconds
+        let mut leaky_bucket = LeakyBucket::new(2.0, 0.1);
+        let current_time = Instant::now();
+        leaky_bucket.last_time = current_time;
+
+        let test_cases = vec![
+            (0, vec![true, true, false]),
+            (1, vec![false]),
+            (5, vec![false]),
+            (10, vec![true, false]),
+            (60, vec![true, true, false]), // maximum allowance is 2
+        ];
+
+        for (elapsed_sec, cases) in test_cases.into_iter() {
+            for should_pass in cases {
+                assert_eq!(
+                    should_pass,
+                    leaky_bucket.check_availability(|| {
+                        current_time.add(Duration::from_secs(elapsed_sec))
+                    })
+                )
+            }
+        }
+    }
+
+    #[test]
+    fn test_rate_limiting_sampler() {
+        let sample = |sampler: &RateLimitingSampler| {
+            sampler
+                .should_sample(
+                    None,
+                    TraceId::from(1),
+                    "span",
+                    &SpanKind::Internal,
+                    &[],
+                    &[],
+                )
+                .decision
+        };
+
+        // bursts of up to one second worth of traces
+        let sampler = RateLimitingSampler::new(3.0);
+        let clone = sampler.clone();
+        assert_eq!(sample(&sampler), SamplingDecision::RecordAndSample);
+        assert_eq!(sample(&clone), SamplingDecision::RecordAndSample);
+        assert_eq!(sample(&sampler), SamplingDecision::RecordAndSample);
+        assert_eq!(sample(&clone), SamplingDecision::Drop);
+
+        // rates below one trace per second still allow a first trace
+        let sampler = RateLimitingSampler::new(0.5);
+        assert_eq!(sample(&sampler), SamplingDecision::RecordAndSample);
+        assert_eq!(sample(&sampler), SamplingDecision::Drop);
+
+        for traces_per_second in [0.0, -1.0, f64::NAN] {
+            let sampler = RateLimitingSampler::new(traces_per_second);
+            assert_eq!(sample(&sampler), SamplingDecision::Drop);
+        }
+    }
+
+    #[test]
+    fn test_leaky_bucket_sub_second_refill() {
+        let mut leaky_bucket = LeakyBucket::new(1.0, 4.0);
+        let current_time = Instant::now();
+        leaky_bucket.last_time = current_time;
+
+        assert!(leaky_bucket.check_availability(|| current_time));
+        assert!(!leaky_bucket.check_availability(|| current_time));
+        assert!(
+            !leaky_bucket.check_availability(|| { current_time.add(Duration::from_millis(100)) })
+        );
+        assert!(
+            leaky_bucket.check_availability(|| { current_time.add(Duration::from_millis(300)) })
+        );
+    }
+}
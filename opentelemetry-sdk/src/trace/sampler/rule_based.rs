@@ -0,0 +1,754 @@
+use std::sync::Arc;
+
+use opentelemetry::{
+    trace::{Link, SpanKind, TraceId},
+    Context, InstrumentationScope, KeyValue,
+};
+
+use super::{sample_with_scope, Sampler, SamplingResult, ShouldSample};
+
+/// A rule of a [`RuleBasedSampler`], delegating to a sampler for the spans it
+/// matches.
+///
+/// A rule matches the spans satisfying all of its conditions, a rule without
+/// conditions matches all spans.
+#[derive(Clone, Debug)]
+pub struct SamplingRule {
+    span_name: Option<String>,
+    span_kinds: Vec<SpanKind>,
+    scope_name: Option<String>,
+    attributes: Vec<KeyValue>,
+    sampler: Box<dyn ShouldSample>,
+}
+
+impl SamplingRule {
+    /// Create a rule delegating to `sampler` for the spans it matches.
+    pub fn new<T: ShouldSample + 'static>(sampler: T) -> Self {
+        SamplingRule {
+            span_name: None,
+            span_kinds: Vec::new(),
+            scope_name: None,
+            attributes: Vec::new(),
+            sampler: Box::new(sampler),
+        }
+    }
+
+    /// Only match spans whose name matches the given glob pattern, where `*`
+    /// matches any sequence of characters and `?` any single character.
+    pub fn with_span_name<T: Into<String>>(mut self, pattern: T) -> Self {
+        self.span_name = Some(pattern.into());
+        self
+    }
+
+    /// Only match spans of the given kind. Calling this several times matches
+    /// spans of any of the given kinds.
+    pub fn with_span_kind(mut self, span_kind: SpanKind) -> Self {
+        self.span_kinds.push(span_kind);
+        self
+    }
+
+    /// Only match spans created by tracers whose instrumentation scope name
+    /// matches the given glob pattern.
+    ///
+    /// Spans are only matched if the scope is known, that is if the sampler is
+    /// called through [`ShouldSample::should_sample_with_scope`].
+    pub fn with_scope_name<T: Into<String>>(mut self, pattern: T) -> Self {
+        self.scope_name = Some(pattern.into());
+        self
+    }
+
+    /// Only match spans created with the given attribute. Calling this several
+    /// times matches spans having all the given attributes.
+    pub fn with_attribute(mut self, attribute: KeyValue) -> Self {
+        self.attributes.push(attribute);
+        self
+    }
+
+    fn matches(
+        &self,
+        scope: Option<&InstrumentationScope>,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+    ) -> bool {
+        self.span_name
+            .as_ref()
+            .map_or(true, |pattern| glob_matches(pattern, name))
+            && (self.span_kinds.is_empty() || self.span_kinds.contains(span_kind))
+            && self.scope_name.as_ref().map_or(true, |pattern| {
+                scope.is_some_and(|scope| glob_matches(pattern, scope.name()))
+            })
+            && self
+                .attributes
+                .iter()
+                .all(|expected| attributes.contains(expected))
+    }
+}
+
+/// Delegates to the sampler of the first [`SamplingRule`] matching the span,
+/// or to a fallback sampler if no rule matches.
+///
+/// Rules delegate to any [`ShouldSample`] and can be described declaratively
+/// with a [`SamplerConfig`]. To combine consistent probability samplers while
+/// recording their threshold, use [`ConsistentRuleBased`] instead.
+///
+/// [`ConsistentRuleBased`]: crate::trace::ConsistentRuleBased
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::trace::SpanKind;
+/// use opentelemetry_sdk::trace::{RuleBasedSampler, Sampler, SamplingRule};
+///
+/// let sampler = RuleBasedSampler::builder()
+///     .with_rule(SamplingRule::new(Sampler::AlwaysOn).with_span_name("/checkout*"))
+///     .with_rule(
+///         SamplingRule::new(Sampler::TraceIdRatioBased(0.01))
+///             .with_span_name("/healthz")
+///             .with_span_kind(SpanKind::Server),
+///     )
+///     .with_rule(SamplingRule::new(Sampler::AlwaysOff).with_scope_name("hyper*"))
+///     .build();
+/// ```
+#[derive(Clone, Debug)]
+pub struct RuleBasedSampler {
+    rules: Arc<[SamplingRule]>,
+    fallback: Box<dyn ShouldSample>,
+}
+
+impl RuleBasedSampler {
+    /// Create a builder for a rule based sampler.
+    pub fn builder() -> RuleBasedSamplerBuilder {
+        RuleBasedSamplerBuilder::default()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn sample(
+        &self,
+        scope: Option<&InstrumentationScope>,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+        links: &[Link],
+    ) -> SamplingResult {
+        let sampler = self
+            .rules
+            .iter()
+            .find(|rule| rule.matches(scope, name, span_kind, attributes))
+            .map_or(self.fallback.as_ref(), |rule| rule.sampler.as_ref());
+        sample_with_scope(
+            sampler,
+            scope,
+            parent_context,
+            trace_id,
+            name,
+            span_kind,
+            attributes,
+            links,
+        )
+    }
+}
+
+impl ShouldSample for RuleBasedSampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+        links: &[Link],
+    ) -> SamplingResult {
+        self.sample(
+            None,
+            parent_context,
+            trace_id,
+            name,
+            span_kind,
+            attributes,
+            links,
+        )
+    }
+
+    fn should_sample_with_scope(
+        &self,
+        scope: &InstrumentationScope,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+        links: &[Link],
+    ) -> SamplingResult {
+        self.sample(
+            Some(scope),
+            parent_context,
+            trace_id,
+            name,
+            span_kind,
+            attributes,
+            links,
+        )
+    }
+}
+
+/// Builder for [`RuleBasedSampler`].
+#[derive(Debug)]
+pub struct RuleBasedSamplerBuilder {
+    rules: Vec<SamplingRule>,
+    fallback: Box<dyn ShouldSample>,
+}
+
+impl Default for RuleBasedSamplerBuilder {
+    fn default() -> Self {
+        RuleBasedSamplerBuilder {
+            rules: Vec::new(),
+            fallback: Box::new(Sampler::ParentBased(Box::new(Sampler::AlwaysOn))),
+        }
+    }
+}
+
+impl RuleBasedSamplerBuilder {
+    /// Add a rule. Rules are evaluated in the order they are added.
+    pub fn with_rule(mut self, rule: SamplingRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Set the sampler used for spans not matched by any rule.
+    ///
+    /// Defaults to `Sampler::ParentBased(Box::new(Sampler::AlwaysOn))`.
+    pub fn with_fallback<T: ShouldSample + 'static>(mut self, sampler: T) -> Self {
+        self.fallback = Box::new(sampler);
+        self
+    }
+
+    /// Create the rule based sampler.
+    pub fn build(self) -> RuleBasedSampler {
+        RuleBasedSampler {
+            rules: self.rules.into(),
+            fallback: self.fallback,
+        }
+    }
+}
+
+/// The declarative configuration of a [`Sampler`].
+///
+/// With the `experimental_trace_sampler_config` feature, the configuration can
+/// be deserialized with serde, for instance from the following YAML:
+///
+/// ```yaml
+/// rule_based:
+///   rules:
+///     - span_name: /checkout*
+///       sampler: always_on
+///     - span_name: /healthz
+///       span_kinds: [server]
+///       sampler:
+///         trace_id_ratio_based:
+///           ratio: 0.01
+///     - scope_name: hyper*
+///       sampler: always_off
+///   fallback:
+///     parent_based:
+///       root:
+///         rate_limited:
+///           traces_per_second: 100
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "experimental_trace_sampler_config",
+    derive(serde::Deserialize),
+    serde(rename_all = "snake_case", deny_unknown_fields)
+)]
+#[non_exhaustive]
+pub enum SamplerConfig {
+    /// See [`Sampler::AlwaysOn`].
+    AlwaysOn,
+    /// See [`Sampler::AlwaysOff`].
+    AlwaysOff,
+    /// See [`Sampler::TraceIdRatioBased`].
+    TraceIdRatioBased {
+        /// The fraction of traces to sample.
+        ratio: f64,
+    },
+    /// See [`Sampler::RateLimited`].
+    RateLimited {
+        /// The maximum number of traces sampled per second.
+        traces_per_second: f64,
+    },
+    /// See [`Sampler::ParentBased`].
+    ParentBased {
+        /// The sampler for spans without a parent.
+        root: Box<SamplerConfig>,
+    },
+    /// See [`Sampler::LinkBased`].
+    LinkBased {
+        /// The sampler for spans without a sampled parent or link.
+        fallback: Box<SamplerConfig>,
+    },
+    /// See [`Sampler::RuleBased`].
+    RuleBased(RuleBasedSamplerConfig),
+}
+
+impl From<SamplerConfig> for Sampler {
+    fn from(config: SamplerConfig) -> Self {
+        match config {
+            SamplerConfig::AlwaysOn => Sampler::AlwaysOn,
+            SamplerConfig::AlwaysOff => Sampler::AlwaysOff,
+            SamplerConfig::TraceIdRatioBased { ratio } => Sampler::TraceIdRatioBased(ratio),
+            SamplerConfig::RateLimited { traces_per_second } => {
+                Sampler::rate_limited(traces_per_second)
+            }
+            SamplerConfig::ParentBased { root } => {
+                Sampler::ParentBased(Box::new(Sampler::from(*root)))
+            }
+            SamplerConfig::LinkBased { fallback } => {
+                Sampler::LinkBased(Box::new(Sampler::from(*fallback)))
+            }
+            SamplerConfig::RuleBased(config) => Sampler::RuleBased(config.into()),
+        }
+    }
+}
+
+/// The declarative configuration of a [`RuleBasedSampler`].
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "experimental_trace_sampler_config",
+    derive(serde::Deserialize),
+    serde(default, deny_unknown_fields)
+)]
+pub struct RuleBasedSamplerConfig {
+    /// The rules, in evaluation order.
+    pub rules: Vec<SamplingRuleConfig>,
+    /// The sampler for spans not matched by any rule, see
+    /// [`RuleBasedSamplerBuilder::with_fallback`].
+    pub fallback: Option<Box<SamplerConfig>>,
+}
+
+impl From<RuleBasedSamplerConfig> for RuleBasedSampler {
+    fn from(config: RuleBasedSamplerConfig) -> Self {
+        let mut builder = config
+            .rules
+            .into_iter()
+            .fold(RuleBasedSampler::builder(), |builder, rule| {
+                builder.with_rule(rule.into())
+            });
+        if let Some(fallback) = config.fallback {
+            builder = builder.with_fallback(Sampler::from(*fallback));
+        }
+        builder.build()
+    }
+}
+
+/// The declarative configuration of a [`SamplingRule`].
+///
+/// Attributes are configured as a map of attribute keys to boolean, integer,
+/// float or string values.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "experimental_trace_sampler_config",
+    derive(serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
+pub struct SamplingRuleConfig {
+    /// See [`SamplingRule::with_span_name`].
+    #[cfg_attr(feature = "experimental_trace_sampler_config", serde(default))]
+    pub span_name: Option<String>,
+    /// See [`SamplingRule::with_span_kind`].
+    #[cfg_attr(
+        feature = "experimental_trace_sampler_config",
+        serde(default, deserialize_with = "serde_config::span_kinds")
+    )]
+    pub span_kinds: Vec<SpanKind>,
+    /// See [`SamplingRule::with_scope_name`].
+    #[cfg_attr(feature = "experimental_trace_sampler_config", serde(default))]
+    pub scope_name: Option<String>,
+    /// See [`SamplingRule::with_attribute`].
+    #[cfg_attr(
+        feature = "experimental_trace_sampler_config",
+        serde(default, deserialize_with = "serde_config::attributes")
+    )]
+    pub attributes: Vec<KeyValue>,
+    /// The sampler for the spans matched by the rule.
+    pub sampler: SamplerConfig,
+}
+
+impl From<SamplingRuleConfig> for SamplingRule {
+    fn from(config: SamplingRuleConfig) -> Self {
+        SamplingRule {
+            span_name: config.span_name,
+            span_kinds: config.span_kinds,
+            scope_name: config.scope_name,
+            attributes: config.attributes,
+            sampler: Box::new(Sampler::from(config.sampler)),
+        }
+    }
+}
+
+#[cfg(feature = "experimental_trace_sampler_config")]
+mod serde_config {
+    use std::collections::BTreeMap;
+
+    use opentelemetry::{trace::SpanKind, KeyValue, Value};
+    use serde::{Deserialize, Deserializer};
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum SpanKindConfig {
+        Client,
+        Server,
+        Producer,
+        Consumer,
+        Internal,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ValueConfig {
+        Bool(bool),
+        I64(i64),
+        F64(f64),
+        String(String),
+    }
+
+    pub(super) fn span_kinds<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<SpanKind>, D::Error> {
+        let kinds = Vec::<SpanKindConfig>::deserialize(deserializer)?;
+        Ok(kinds
+            .into_iter()
+            .map(|kind| match kind {
+                SpanKindConfig::Client => SpanKind::Client,
+                SpanKindConfig::Server => SpanKind::Server,
+                SpanKindConfig::Producer => SpanKind::Producer,
+                SpanKindConfig::Consumer => SpanKind::Consumer,
+                SpanKindConfig::Internal => SpanKind::Internal,
+            })
+            .collect())
+    }
+
+    pub(super) fn attributes<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<KeyValue>, D::Error> {
+        let attributes = BTreeMap::<String, ValueConfig>::deserialize(deserializer)?;
+        Ok(attributes
+            .into_iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    ValueConfig::Bool(value) => Value::from(value),
+                    ValueConfig::I64(value) => Value::from(value),
+                    ValueConfig::F64(value) => Value::from(value),
+                    ValueConfig::String(value) => Value::from(value),
+                };
+                KeyValue::new(key, value)
+            })
+            .collect())
+    }
+}
+
+/// Returns whether `text` matches the glob `pattern`, where `*` matches any
+/// sequence of characters and `?` any single character.
+pub(super) fn glob_matches(pattern: &str, text: &str) -> bool {
+    let next_char = |s: &str, offset: usize| s[offset..].chars().next();
+    // byte offsets in the pattern and the text
+    let (mut p, mut t) = (0, 0);
+    // the offset following the last `*` in the pattern, and of the text it
+    // matched up to
+    let mut backtrack = None;
+    while let Some(c) = next_char(text, t) {
+        match next_char(pattern, p) {
+            Some('*') => {
+                p += 1;
+                backtrack = Some((p, t));
+            }
+            Some(pc) if pc == '?' || pc == c => {
+                p += pc.len_utf8();
+                t += c.len_utf8();
+            }
+            _ => match backtrack {
+                Some((after_star, matched)) => {
+                    // let the `*` match one more character
+                    let matched = matched + next_char(text, matched).map_or(0, char::len_utf8);
+                    p = after_star;
+                    t = matched;
+                    backtrack = Some((after_star, matched));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].chars().all(|c| c == '*')
+}
+
+#[cfg(all(test, feature = "testing", feature = "trace"))]
+mod tests {
+    use super::*;
+    use crate::trace::SamplingDecision;
+
+    fn decision(
+        sampler: &RuleBasedSampler,
+        scope: &str,
+        name: &str,
+        span_kind: SpanKind,
+        attributes: &[KeyValue],
+    ) -> SamplingDecision {
+        sampler
+            .should_sample_with_scope(
+                &InstrumentationScope::builder(scope.to_string()).build(),
+                None,
+                TraceId::from(1),
+                name,
+                &span_kind,
+                attributes,
+                &[],
+            )
+            .decision
+    }
+
+    #[test]
+    fn glob_matching() {
+        for (pattern, text, expected) in [
+            ("", "", true),
+            ("", "a", false),
+            ("*", "", true),
+            ("*", "anything", true),
+            ("/checkout", "/checkout", true),
+            ("/checkout", "/checkout/cart", false),
+            ("/checkout*", "/checkout/cart", true),
+            ("*/cart", "/checkout/cart", true),
+            ("/c?eckout", "/checkout", true),
+            ("/c?eckout", "/cheeckout", false),
+            ("a*b*c", "aXbYbZc", true),
+            ("a*b*c", "aXbYbZ", false),
+            ("hyper*", "hyper-util", true),
+            ("h?e*", "hyper", false),
+            ("caf?", "café", true),
+            ("*é*", "crème brûlée", true),
+            ("?", "é", true),
+            ("??", "é", false),
+        ] {
+            assert_eq!(
+                glob_matches(pattern, text),
+                expected,
+                "pattern {pattern:?} on {text:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let sampler = RuleBasedSampler::builder()
+            .with_rule(SamplingRule::new(Sampler::AlwaysOn).with_span_name("/checkout*"))
+            .with_rule(
+                SamplingRule::new(Sampler::AlwaysOff)
+                    .with_span_name("/healthz")
+                    .with_span_kind(SpanKind::Server),
+            )
+            .with_rule(SamplingRule::new(Sampler::AlwaysOff).with_scope_name("hyper*"))
+            .with_rule(
+                SamplingRule::new(Sampler::AlwaysOff)
+                    .with_attribute(KeyValue::new("http.method", "OPTIONS")),
+            )
+            .with_fallback(Sampler::AlwaysOn)
+            .build();
+
+        let server = SpanKind::Server;
+        let sample = SamplingDecision::RecordAndSample;
+        let drop = SamplingDecision::Drop;
+        assert_eq!(
+            decision(&sampler, "hyper", "/checkout", server.clone(), &[]),
+            sample
+        );
+        assert_eq!(
+            decision(&sampler, "app", "/healthz", server.clone(), &[]),
+            drop
+        );
+        assert_eq!(
+            decision(&sampler, "app", "/healthz", SpanKind::Client, &[]),
+            sample
+        );
+        assert_eq!(
+            decision(&sampler, "hyper-util", "GET", server.clone(), &[]),
+            drop
+        );
+        let options = [KeyValue::new("http.method", "OPTIONS")];
+        assert_eq!(
+            decision(&sampler, "app", "/", server.clone(), &options),
+            drop
+        );
+        let get = [KeyValue::new("http.method", "GET")];
+        assert_eq!(decision(&sampler, "app", "/", server, &get), sample);
+    }
+
+    #[test]
+    fn scope_rules_require_scope() {
+        let sampler = RuleBasedSampler::builder()
+            .with_rule(SamplingRule::new(Sampler::AlwaysOff).with_scope_name("*"))
+            .with_fallback(Sampler::AlwaysOn)
+            .build();
+        let result = sampler.should_sample(
+            None,
+            TraceId::from(1),
+            "span",
+            &SpanKind::Internal,
+            &[],
+            &[],
+        );
+        assert_eq!(result.decision, SamplingDecision::RecordAndSample);
+        assert_eq!(
+            decision(&sampler, "app", "span", SpanKind::Internal, &[]),
+            SamplingDecision::Drop
+        );
+    }
+
+    #[test]
+    fn tracer_passes_scope() {
+        use crate::trace::{InMemorySpanExporter, SdkTracerProvider};
+        use opentelemetry::trace::{Tracer, TracerProvider};
+
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .with_sampler(
+                RuleBasedSampler::builder()
+                    .with_rule(SamplingRule::new(Sampler::AlwaysOff).with_scope_name("hyper"))
+                    .with_fallback(Sampler::AlwaysOn)
+                    .build(),
+            )
+            .build();
+
+        provider.tracer("hyper").in_span("dropped", |_| {});
+        provider.tracer("app").in_span("sampled", |_| {});
+
+        let spans = exporter.get_finished_spans().unwrap();
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].name, "sampled");
+    }
+
+    #[test]
+    fn nested_samplers_receive_scope() {
+        let sampler = Sampler::ParentBased(Box::new(Sampler::RuleBased(
+            RuleBasedSampler::builder()
+                .with_rule(SamplingRule::new(Sampler::AlwaysOff).with_scope_name("hyper"))
+                .with_fallback(Sampler::AlwaysOn)
+                .build(),
+        )));
+        let result = sampler.should_sample_with_scope(
+            &InstrumentationScope::builder("hyper").build(),
+            None,
+            TraceId::from(1),
+            "span",
+            &SpanKind::Internal,
+            &[],
+            &[],
+        );
+        assert_eq!(result.decision, SamplingDecision::Drop);
+    }
+
+    #[test]
+    fn sampler_from_config() {
+        let config = SamplerConfig::RuleBased(RuleBasedSamplerConfig {
+            rules: vec![SamplingRuleConfig {
+                span_name: Some("/healthz".to_string()),
+                span_kinds: vec![SpanKind::Server],
+                scope_name: None,
+                attributes: vec![],
+                sampler: SamplerConfig::AlwaysOff,
+            }],
+            fallback: Some(Box::new(SamplerConfig::AlwaysOn)),
+        });
+        let Sampler::RuleBased(sampler) = Sampler::from(config) else {
+            panic!("expected a rule based sampler");
+        };
+        assert_eq!(
+            decision(&sampler, "app", "/healthz", SpanKind::Server, &[]),
+            SamplingDecision::Drop
+        );
+        assert_eq!(
+            decision(&sampler, "app", "/", SpanKind::Server, &[]),
+            SamplingDecision::RecordAndSample
+        );
+    }
+
+    #[cfg(feature = "experimental_trace_sampler_config")]
+    #[test]
+    fn deserialize_config() {
+        let config: SamplerConfig = serde_json::from_str(
+            r#"{
+                "rule_based": {
+                    "rules": [
+                        {"span_name": "/checkout*", "sampler": "always_on"},
+                        {
+                            "span_name": "/healthz",
+                            "span_kinds": ["server"],
+                            "attributes": {"http.method": "GET", "retry": false, "port": 8080},
+                            "sampler": {"trace_id_ratio_based": {"ratio": 0.01}}
+                        },
+                        {"scope_name": "hyper*", "sampler": "always_off"}
+                    ],
+                    "fallback": {"parent_based": {"root": {"rate_limited": {"traces_per_second": 100}}}}
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config,
+            SamplerConfig::RuleBased(RuleBasedSamplerConfig {
+                rules: vec![
+                    SamplingRuleConfig {
+                        span_name: Some("/checkout*".to_string()),
+                        span_kinds: vec![],
+                        scope_name: None,
+                        attributes: vec![],
+                        sampler: SamplerConfig::AlwaysOn,
+                    },
+                    SamplingRuleConfig {
+                        span_name: Some("/healthz".to_string()),
+                        span_kinds: vec![SpanKind::Server],
+                        scope_name: None,
+                        attributes: vec![
+                            KeyValue::new("http.method", "GET"),
+                            KeyValue::new("port", 8080),
+                            KeyValue::new("retry", false),
+                        ],
+                        sampler: SamplerConfig::TraceIdRatioBased { ratio: 0.01 },
+                    },
+                    SamplingRuleConfig {
+                        span_name: None,
+                        span_kinds: vec![],
+                        scope_name: Some("hyper*".to_string()),
+                        attributes: vec![],
+                        sampler: SamplerConfig::AlwaysOff,
+                    },
+                ],
+                fallback: Some(Box::new(SamplerConfig::ParentBased {
+                    root: Box::new(SamplerConfig::RateLimited {
+                        traces_per_second: 100.0
+                    })
+                })),
+            })
+        );
+
+        assert_eq!(
+            serde_json::from_str::<SamplerConfig>(
+                r#"{"link_based": {"fallback": {"parent_based": {"root": "always_off"}}}}"#
+            )
+            .unwrap(),
+            SamplerConfig::LinkBased {
+                fallback: Box::new(SamplerConfig::ParentBased {
+                    root: Box::new(SamplerConfig::AlwaysOff)
+                })
+            }
+        );
+
+        assert!(serde_json::from_str::<SamplerConfig>(r#""always_maybe""#).is_err());
+        assert!(serde_json::from_str::<SamplerConfig>(
+            r#"{"rule_based": {"rules": [{"span_kinds": ["server"]}]}}"#
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,5 @@
+mod remote;
+mod rule;
+mod sampler;
+
+pub use sampler::{XrayRemoteSampler, XrayRemoteSamplerBuilder};
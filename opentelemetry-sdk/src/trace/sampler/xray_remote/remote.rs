@@ -0,0 +1,157 @@
+//! Types of the X-Ray sampling API, see the [`GetSamplingRules`] and
+//! [`GetSamplingTargets`] references.
+//!
+//! [`GetSamplingRules`]: https://docs.aws.amazon.com/xray/latest/api/API_GetSamplingRules.html
+//! [`GetSamplingTargets`]: https://docs.aws.amazon.com/xray/latest/api/API_GetSamplingTargets.html
+use std::collections::HashMap;
+
+/// The request body of `GetSamplingRules`.
+#[derive(serde::Serialize, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct GetSamplingRulesRequest {
+    pub(crate) next_token: Option<String>,
+}
+
+/// The response body of `GetSamplingRules`.
+#[derive(serde::Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct GetSamplingRulesResponse {
+    #[serde(default)]
+    pub(crate) sampling_rule_records: Vec<SamplingRuleRecord>,
+    pub(crate) next_token: Option<String>,
+}
+
+#[derive(serde::Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct SamplingRuleRecord {
+    pub(crate) sampling_rule: SamplingRule,
+}
+
+/// A sampling rule, matching requests on the service and HTTP properties of
+/// the span. Matchers accept `*` and `?` wildcards.
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct SamplingRule {
+    pub(crate) rule_name: String,
+    /// Rules are evaluated by ascending priority.
+    pub(crate) priority: i32,
+    /// The fraction of requests sampled once the reservoir is exhausted.
+    pub(crate) fixed_rate: f64,
+    /// The number of requests per second sampled before applying the fixed
+    /// rate, shared by all instances of the service.
+    pub(crate) reservoir_size: u32,
+    pub(crate) service_name: String,
+    pub(crate) service_type: String,
+    pub(crate) host: String,
+    #[serde(rename = "HTTPMethod")]
+    pub(crate) http_method: String,
+    #[serde(rename = "URLPath")]
+    pub(crate) url_path: String,
+    #[serde(rename = "ResourceARN")]
+    pub(crate) resource_arn: String,
+    pub(crate) version: i32,
+    #[serde(default)]
+    pub(crate) attributes: HashMap<String, String>,
+}
+
+/// The request body of `GetSamplingTargets`.
+#[derive(serde::Serialize, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct GetSamplingTargetsRequest {
+    pub(crate) sampling_statistics_documents: Vec<SamplingStatisticsDocument>,
+}
+
+/// The sampling statistics of a rule since the last report.
+#[derive(serde::Serialize, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct SamplingStatisticsDocument {
+    pub(crate) rule_name: String,
+    #[serde(rename = "ClientID")]
+    pub(crate) client_id: String,
+    /// Seconds since the Unix epoch.
+    pub(crate) timestamp: f64,
+    pub(crate) request_count: u64,
+    pub(crate) sampled_count: u64,
+    pub(crate) borrow_count: u64,
+}
+
+/// The response body of `GetSamplingTargets`.
+#[derive(serde::Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct GetSamplingTargetsResponse {
+    #[serde(default)]
+    pub(crate) sampling_target_documents: Vec<SamplingTargetDocument>,
+    /// Seconds since the Unix epoch of the last change of the rules.
+    pub(crate) last_rule_modification: Option<f64>,
+}
+
+/// The reservoir quota and fixed rate assigned to this instance for a rule.
+#[derive(serde::Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct SamplingTargetDocument {
+    pub(crate) rule_name: String,
+    pub(crate) fixed_rate: Option<f64>,
+    pub(crate) reservoir_quota: Option<u32>,
+    /// Seconds since the Unix epoch until which the quota is valid.
+    #[serde(rename = "ReservoirQuotaTTL")]
+    pub(crate) reservoir_quota_ttl: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_sampling_rules_response() {
+        let json = r#"{
+            "SamplingRuleRecords": [
+                {
+                    "SamplingRule": {
+                        "RuleName": "Default",
+                        "RuleARN": "arn:aws:xray:us-east-1:123456789012:sampling-rule/Default",
+                        "ResourceARN": "*",
+                        "Priority": 10000,
+                        "FixedRate": 0.05,
+                        "ReservoirSize": 1,
+                        "ServiceName": "*",
+                        "ServiceType": "*",
+                        "Host": "*",
+                        "HTTPMethod": "*",
+                        "URLPath": "*",
+                        "Version": 1,
+                        "Attributes": {}
+                    },
+                    "CreatedAt": 0.0,
+                    "ModifiedAt": 1681856516.0
+                }
+            ],
+            "NextToken": null
+        }"#;
+        let resp: GetSamplingRulesResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(resp.next_token, None);
+        let rule = &resp.sampling_rule_records[0].sampling_rule;
+        assert_eq!(rule.rule_name, "Default");
+        assert_eq!(rule.priority, 10000);
+        assert_eq!(rule.fixed_rate, 0.05);
+        assert_eq!(rule.reservoir_size, 1);
+        assert_eq!(rule.http_method, "*");
+    }
+
+    #[test]
+    fn serialize_sampling_targets_request() {
+        let request = GetSamplingTargetsRequest {
+            sampling_statistics_documents: vec![SamplingStatisticsDocument {
+                rule_name: "Default".to_string(),
+                client_id: "0123456789abcdef01234567".to_string(),
+                timestamp: 1681856516.0,
+                request_count: 10,
+                sampled_count: 2,
+                borrow_count: 1,
+            }],
+        };
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"SamplingStatisticsDocuments":[{"RuleName":"Default","ClientID":"0123456789abcdef01234567","Timestamp":1681856516.0,"RequestCount":10,"SampledCount":2,"BorrowCount":1}]}"#
+        );
+    }
+}
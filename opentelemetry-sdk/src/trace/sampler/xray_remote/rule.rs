@@ -0,0 +1,546 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use opentelemetry::trace::TraceId;
+use opentelemetry::KeyValue;
+
+use crate::trace::sampler::rule_based::glob_matches;
+use crate::trace::sampler::sample_based_on_probability;
+use crate::trace::sampler::xray_remote::remote::{
+    GetSamplingTargetsResponse, SamplingRule, SamplingStatisticsDocument,
+};
+use crate::trace::SamplingDecision;
+
+/// The only version of sampling rules supported.
+const SUPPORTED_RULE_VERSION: i32 = 1;
+
+const HTTP_METHOD_KEYS: [&str; 2] = ["http.request.method", "http.method"];
+const URL_PATH_KEYS: [&str; 2] = ["url.path", "http.target"];
+const URL_FULL_KEYS: [&str; 2] = ["url.full", "http.url"];
+const HOST_KEYS: [&str; 3] = ["server.address", "http.host", "net.host.name"];
+
+/// The sampling rules of the service, ordered by priority, with their
+/// reservoir and statistics.
+#[derive(Debug, Default)]
+pub(crate) struct RuleCache {
+    rules: Vec<Rule>,
+    /// When the rules were last fetched.
+    fetched_at: Option<SystemTime>,
+}
+
+impl RuleCache {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Replaces the rules, keeping the reservoir and statistics of rules
+    /// whose name is unchanged.
+    pub(crate) fn update_rules(&mut self, rules: Vec<SamplingRule>, now: SystemTime) {
+        let mut previous = std::mem::take(&mut self.rules);
+        self.rules = rules
+            .into_iter()
+            .filter(|rule| rule.version == SUPPORTED_RULE_VERSION)
+            .map(|rule| {
+                let existing = previous
+                    .iter()
+                    .position(|existing| existing.config.rule_name == rule.rule_name)
+                    .map(|index| previous.swap_remove(index));
+                match existing {
+                    Some(mut existing) => {
+                        if existing.config.reservoir_size != rule.reservoir_size {
+                            existing.reservoir = Reservoir::default();
+                        }
+                        existing.fixed_rate = rule.fixed_rate;
+                        existing.config = rule;
+                        existing
+                    }
+                    None => Rule::new(rule),
+                }
+            })
+            .collect();
+        self.rules.sort_by(|a, b| {
+            a.config
+                .priority
+                .cmp(&b.config.priority)
+                .then_with(|| a.config.rule_name.cmp(&b.config.rule_name))
+        });
+        self.fetched_at = Some(now);
+    }
+
+    /// Applies the targets assigned to this instance. Returns whether the
+    /// rules changed since they were last fetched.
+    pub(crate) fn update_targets(&mut self, targets: GetSamplingTargetsResponse) -> bool {
+        for target in targets.sampling_target_documents {
+            let Some(rule) = self
+                .rules
+                .iter_mut()
+                .find(|rule| rule.config.rule_name == target.rule_name)
+            else {
+                continue;
+            };
+            if let Some(fixed_rate) = target.fixed_rate {
+                rule.fixed_rate = fixed_rate;
+            }
+            if let Some(quota) = target.reservoir_quota {
+                rule.reservoir.quota = Some(quota);
+            }
+            if let Some(ttl) = target.reservoir_quota_ttl {
+                rule.reservoir.expires_at = Some(from_epoch_secs(ttl));
+            }
+        }
+        match (targets.last_rule_modification, self.fetched_at) {
+            (Some(modified_at), Some(fetched_at)) => from_epoch_secs(modified_at) > fetched_at,
+            _ => false,
+        }
+    }
+
+    /// Returns the statistics of all rules since the last call.
+    pub(crate) fn take_statistics(
+        &mut self,
+        client_id: &str,
+        now: SystemTime,
+    ) -> Vec<SamplingStatisticsDocument> {
+        let timestamp = epoch_secs(now);
+        self.rules
+            .iter_mut()
+            .map(|rule| {
+                let statistics = std::mem::take(&mut rule.statistics);
+                SamplingStatisticsDocument {
+                    rule_name: rule.config.rule_name.clone(),
+                    client_id: client_id.to_string(),
+                    timestamp,
+                    request_count: statistics.request_count,
+                    sampled_count: statistics.sampled_count,
+                    borrow_count: statistics.borrow_count,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the decision of the first rule matching the span, or `None` if
+    /// no rule matches.
+    pub(crate) fn should_sample(
+        &mut self,
+        service_name: &str,
+        trace_id: TraceId,
+        request: &Request<'_>,
+        now: SystemTime,
+    ) -> Option<SamplingDecision> {
+        self.rules
+            .iter_mut()
+            .find(|rule| rule.matches(service_name, request))
+            .map(|rule| rule.sample(trace_id, now))
+    }
+}
+
+/// The HTTP properties of a span matched by rules.
+///
+/// Built before taking the lock of the [RuleCache], the URL is only parsed if
+/// the path or the host are not recorded on their own.
+pub(crate) struct Request<'a> {
+    http_method: Option<String>,
+    url_path: Option<String>,
+    host: Option<String>,
+    attributes: &'a [KeyValue],
+}
+
+impl<'a> Request<'a> {
+    pub(crate) fn new(attributes: &'a [KeyValue]) -> Self {
+        let find = |keys: &[&str]| {
+            keys.iter().find_map(|key| {
+                attributes
+                    .iter()
+                    .find(|kv| kv.key.as_str() == *key)
+                    .map(|kv| kv.value.as_str().into_owned())
+            })
+        };
+        let mut url_path = find(&URL_PATH_KEYS).map(|mut path| {
+            if let Some(query) = path.find('?') {
+                path.truncate(query);
+            }
+            path
+        });
+        let mut host = find(&HOST_KEYS);
+        if url_path.is_none() || host.is_none() {
+            if let Some(url) = find(&URL_FULL_KEYS).and_then(|url| url::Url::parse(&url).ok()) {
+                url_path = url_path.or_else(|| Some(url.path().to_string()));
+                host = host.or_else(|| url.host_str().map(Into::into));
+            }
+        }
+        Request {
+            http_method: find(&HTTP_METHOD_KEYS),
+            url_path,
+            host,
+            attributes,
+        }
+    }
+
+    fn attribute(&self, key: &str) -> Option<String> {
+        self.attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == key)
+            .map(|kv| kv.value.as_str().into_owned())
+    }
+}
+
+#[derive(Debug)]
+struct Rule {
+    config: SamplingRule,
+    /// The fixed rate of the rule, possibly overridden by a target.
+    fixed_rate: f64,
+    reservoir: Reservoir,
+    statistics: Statistics,
+}
+
+impl Rule {
+    fn new(config: SamplingRule) -> Self {
+        Rule {
+            fixed_rate: config.fixed_rate,
+            config,
+            reservoir: Reservoir::default(),
+            statistics: Statistics::default(),
+        }
+    }
+
+    fn matches(&self, service_name: &str, request: &Request<'_>) -> bool {
+        let matches = |pattern: &str, value: Option<&str>| {
+            pattern == "*" || value.is_some_and(|value| glob_matches(pattern, value))
+        };
+        // like X-Ray, service names, hosts, methods and paths are matched
+        // case-insensitively
+        let matches_ignore_case = |pattern: &str, value: Option<&str>| {
+            pattern == "*"
+                || value.is_some_and(|value| {
+                    glob_matches(&pattern.to_lowercase(), &value.to_lowercase())
+                })
+        };
+        // the resource ARN and service type of the service are not known
+        matches(&self.config.resource_arn, None)
+            && matches(&self.config.service_type, None)
+            && matches_ignore_case(&self.config.service_name, Some(service_name))
+            && matches_ignore_case(&self.config.http_method, request.http_method.as_deref())
+            && matches_ignore_case(&self.config.url_path, request.url_path.as_deref())
+            && matches_ignore_case(&self.config.host, request.host.as_deref())
+            && self
+                .config
+                .attributes
+                .iter()
+                .all(|(key, pattern)| matches(pattern, request.attribute(key).as_deref()))
+    }
+
+    fn sample(&mut self, trace_id: TraceId, now: SystemTime) -> SamplingDecision {
+        self.statistics.request_count += 1;
+        let decision = match self.reservoir.take(self.config.reservoir_size, now) {
+            ReservoirDecision::Take => SamplingDecision::RecordAndSample,
+            ReservoirDecision::Borrow => {
+                self.statistics.borrow_count += 1;
+                SamplingDecision::RecordAndSample
+            }
+            ReservoirDecision::NoQuota => sample_based_on_probability(&self.fixed_rate, trace_id),
+        };
+        if decision == SamplingDecision::RecordAndSample {
+            self.statistics.sampled_count += 1;
+        }
+        decision
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ReservoirDecision {
+    Take,
+    Borrow,
+    NoQuota,
+}
+
+/// The number of requests per second sampled before applying the fixed rate.
+///
+/// Until a quota is assigned by the sampling targets, or once it expired, one
+/// request per second is borrowed from the reservoir of the rule.
+#[derive(Debug, Default)]
+struct Reservoir {
+    quota: Option<u32>,
+    expires_at: Option<SystemTime>,
+    current_second: u64,
+    used: u32,
+}
+
+impl Reservoir {
+    fn take(&mut self, reservoir_size: u32, now: SystemTime) -> ReservoirDecision {
+        let second = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        if second != self.current_second {
+            self.current_second = second;
+            self.used = 0;
+        }
+        let quota = match (self.quota, self.expires_at) {
+            (Some(quota), Some(expires_at)) if now < expires_at => Some(quota),
+            _ => None,
+        };
+        match quota {
+            Some(quota) if self.used < quota => {
+                self.used += 1;
+                ReservoirDecision::Take
+            }
+            Some(_) => ReservoirDecision::NoQuota,
+            None if reservoir_size > 0 && self.used < 1 => {
+                self.used += 1;
+                ReservoirDecision::Borrow
+            }
+            None => ReservoirDecision::NoQuota,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Statistics {
+    request_count: u64,
+    sampled_count: u64,
+    borrow_count: u64,
+}
+
+fn epoch_secs(time: SystemTime) -> f64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+fn from_epoch_secs(secs: f64) -> SystemTime {
+    UNIX_EPOCH + Duration::try_from_secs_f64(secs).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace::sampler::xray_remote::remote::SamplingTargetDocument;
+    use std::collections::HashMap;
+
+    fn rule(name: &str, priority: i32, fixed_rate: f64) -> SamplingRule {
+        SamplingRule {
+            rule_name: name.to_string(),
+            priority,
+            fixed_rate,
+            reservoir_size: 1,
+            service_name: "*".to_string(),
+            service_type: "*".to_string(),
+            host: "*".to_string(),
+            http_method: "*".to_string(),
+            url_path: "*".to_string(),
+            resource_arn: "*".to_string(),
+            version: 1,
+            attributes: HashMap::new(),
+        }
+    }
+
+    fn request_attributes(method: &str, url: &str) -> Vec<KeyValue> {
+        vec![
+            KeyValue::new("http.request.method", method.to_string()),
+            KeyValue::new("url.full", url.to_string()),
+        ]
+    }
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn request_prefers_recorded_path_and_host() {
+        let attributes = [
+            KeyValue::new("url.full", "https://shop.example.com/checkout/1?a=b"),
+            KeyValue::new("url.path", "/cart?id=1"),
+        ];
+        let request = Request::new(&attributes);
+        assert_eq!(request.url_path.as_deref(), Some("/cart"));
+        assert_eq!(request.host.as_deref(), Some("shop.example.com"));
+
+        let attributes = [
+            KeyValue::new("url.full", "not a url"),
+            KeyValue::new("http.target", "/cart"),
+            KeyValue::new("http.host", "api.example.com"),
+        ];
+        let request = Request::new(&attributes);
+        assert_eq!(request.url_path.as_deref(), Some("/cart"));
+        assert_eq!(request.host.as_deref(), Some("api.example.com"));
+    }
+
+    #[test]
+    fn rule_matching() {
+        let mut checkout = rule("checkout", 1, 1.0);
+        checkout.service_name = "shop-*".to_string();
+        checkout.http_method = "POST".to_string();
+        checkout.url_path = "/checkout*".to_string();
+        checkout.host = "*.example.com".to_string();
+        let mut tagged = rule("tagged", 2, 1.0);
+        tagged.attributes = HashMap::from([("tier".to_string(), "gold".to_string())]);
+        let mut unknown_arn = rule("arn", 0, 1.0);
+        unknown_arn.resource_arn = "arn:aws:ecs:*".to_string();
+
+        let mut cache = RuleCache::default();
+        cache.update_rules(
+            vec![
+                rule("Default", 10000, 0.0),
+                tagged.clone(),
+                checkout.clone(),
+                unknown_arn,
+            ],
+            at(0),
+        );
+        let names: Vec<_> = cache
+            .rules
+            .iter()
+            .map(|rule| rule.config.rule_name.as_str())
+            .collect();
+        assert_eq!(names, ["arn", "checkout", "tagged", "Default"]);
+
+        let matched = |service_name: &str, attributes: &[KeyValue]| {
+            let request = Request::new(attributes);
+            cache
+                .rules
+                .iter()
+                .find(|rule| rule.matches(service_name, &request))
+                .map(|rule| rule.config.rule_name.clone())
+                .unwrap()
+        };
+        let checkout_request =
+            request_attributes("POST", "https://shop.example.com/checkout/1?a=b");
+        assert_eq!(matched("shop-api", &checkout_request), "checkout");
+        assert_eq!(matched("inventory", &checkout_request), "Default");
+        assert_eq!(
+            matched(
+                "Shop-API",
+                &request_attributes("post", "https://Shop.Example.com/Checkout/1")
+            ),
+            "checkout"
+        );
+        assert_eq!(
+            matched(
+                "shop-api",
+                &request_attributes("GET", "https://shop.example.com/checkout")
+            ),
+            "Default"
+        );
+        assert_eq!(
+            matched(
+                "shop-api",
+                &[
+                    KeyValue::new("http.method", "POST"),
+                    KeyValue::new("http.target", "/checkout?id=1"),
+                    KeyValue::new("server.address", "api.example.com"),
+                ]
+            ),
+            "checkout"
+        );
+        assert_eq!(
+            matched("shop-api", &[KeyValue::new("tier", "gold")]),
+            "tagged"
+        );
+        assert_eq!(
+            matched("shop-api", &[KeyValue::new("tier", "silver")]),
+            "Default"
+        );
+    }
+
+    #[test]
+    fn unsupported_rule_versions_are_ignored() {
+        let mut cache = RuleCache::default();
+        let mut rule = rule("v2", 1, 1.0);
+        rule.version = 2;
+        cache.update_rules(vec![rule], at(0));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn reservoir_borrowing_and_fixed_rate() {
+        let mut cache = RuleCache::default();
+        cache.update_rules(vec![rule("Default", 10000, 0.0)], at(0));
+        let sample = |cache: &mut RuleCache, now| {
+            cache
+                .should_sample("svc", TraceId::from(1), &Request::new(&[]), now)
+                .unwrap()
+        };
+
+        // one request per second is borrowed until a quota is assigned
+        assert_eq!(sample(&mut cache, at(1)), SamplingDecision::RecordAndSample);
+        assert_eq!(sample(&mut cache, at(1)), SamplingDecision::Drop);
+        assert_eq!(sample(&mut cache, at(2)), SamplingDecision::RecordAndSample);
+
+        let statistics = cache.take_statistics("client", at(2));
+        assert_eq!(
+            statistics,
+            vec![SamplingStatisticsDocument {
+                rule_name: "Default".to_string(),
+                client_id: "client".to_string(),
+                timestamp: 2.0,
+                request_count: 3,
+                sampled_count: 2,
+                borrow_count: 2,
+            }]
+        );
+        assert_eq!(cache.take_statistics("client", at(2))[0].request_count, 0);
+    }
+
+    #[test]
+    fn sampling_targets() {
+        let mut cache = RuleCache::default();
+        cache.update_rules(vec![rule("Default", 10000, 0.0)], at(10));
+
+        let rules_changed = cache.update_targets(GetSamplingTargetsResponse {
+            sampling_target_documents: vec![SamplingTargetDocument {
+                rule_name: "Default".to_string(),
+                fixed_rate: Some(0.0),
+                reservoir_quota: Some(2),
+                reservoir_quota_ttl: Some(20.0),
+            }],
+            last_rule_modification: Some(5.0),
+        });
+        assert!(!rules_changed);
+
+        let mut sample = |now| {
+            cache
+                .should_sample("svc", TraceId::from(1), &Request::new(&[]), now)
+                .unwrap()
+        };
+        assert_eq!(sample(at(11)), SamplingDecision::RecordAndSample);
+        assert_eq!(sample(at(11)), SamplingDecision::RecordAndSample);
+        assert_eq!(sample(at(11)), SamplingDecision::Drop);
+        // the quota expired, fall back to borrowing
+        assert_eq!(sample(at(25)), SamplingDecision::RecordAndSample);
+        assert_eq!(sample(at(25)), SamplingDecision::Drop);
+
+        let statistics = cache.take_statistics("client", at(25));
+        assert_eq!(statistics[0].sampled_count, 3);
+        assert_eq!(statistics[0].borrow_count, 1);
+
+        // a fixed rate of 1 samples everything once the reservoir is exhausted
+        assert!(cache.update_targets(GetSamplingTargetsResponse {
+            sampling_target_documents: vec![SamplingTargetDocument {
+                rule_name: "Default".to_string(),
+                fixed_rate: Some(1.0),
+                reservoir_quota: None,
+                reservoir_quota_ttl: None,
+            }],
+            last_rule_modification: Some(30.0),
+        }));
+        for _ in 0..10 {
+            assert_eq!(
+                cache.should_sample("svc", TraceId::from(1), &Request::new(&[]), at(25)),
+                Some(SamplingDecision::RecordAndSample)
+            );
+        }
+    }
+
+    #[test]
+    fn rule_updates_keep_state() {
+        let mut cache = RuleCache::default();
+        cache.update_rules(vec![rule("Default", 10000, 0.0)], at(0));
+        assert_eq!(
+            cache.should_sample("svc", TraceId::from(1), &Request::new(&[]), at(1)),
+            Some(SamplingDecision::RecordAndSample)
+        );
+
+        cache.update_rules(vec![rule("Default", 10000, 0.0)], at(1));
+        // the borrowed request of this second is still accounted for
+        assert_eq!(
+            cache.should_sample("svc", TraceId::from(1), &Request::new(&[]), at(1)),
+            Some(SamplingDecision::Drop)
+        );
+        assert_eq!(cache.take_statistics("client", at(1))[0].request_count, 2);
+    }
+}
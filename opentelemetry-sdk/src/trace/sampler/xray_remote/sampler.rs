@@ -0,0 +1,618 @@
+use crate::runtime::{to_interval_stream, RuntimeChannel};
+use crate::trace::error::TraceError;
+use crate::trace::sampler::xray_remote::remote::{
+    GetSamplingRulesRequest, GetSamplingRulesResponse, GetSamplingTargetsRequest,
+    GetSamplingTargetsResponse, SamplingRule,
+};
+use crate::trace::sampler::xray_remote::rule::{Request, RuleCache};
+use crate::trace::{Sampler, SamplingResult, ShouldSample};
+use futures_util::{stream, StreamExt as _};
+use http::Uri;
+use opentelemetry::time::now;
+use opentelemetry::trace::{Link, SpanKind, TraceContextExt, TraceId, TraceState};
+use opentelemetry::{otel_debug, otel_warn, Context, KeyValue};
+use opentelemetry_http::HttpClient;
+use std::fmt::{Debug, Formatter};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const DEFAULT_REMOTE_SAMPLER_ENDPOINT: &str = "http://localhost:2000";
+const GET_SAMPLING_RULES_PATH: &str = "/GetSamplingRules";
+const GET_SAMPLING_TARGETS_PATH: &str = "/SamplingTargets";
+/// The maximum number of pages of sampling rules fetched at once.
+const MAX_SAMPLING_RULES_PAGES: usize = 100;
+
+/// Builder for [`XrayRemoteSampler`].
+/// See [Sampler::xray_remote] for details.
+#[derive(Debug)]
+pub struct XrayRemoteSamplerBuilder<C, S, R>
+where
+    R: RuntimeChannel,
+    C: HttpClient + 'static,
+    S: ShouldSample + 'static,
+{
+    pub(crate) rules_update_interval: Duration,
+    pub(crate) targets_update_interval: Duration,
+    pub(crate) client: C,
+    pub(crate) endpoint: String,
+    pub(crate) default_sampler: S,
+    pub(crate) runtime: R,
+    pub(crate) service_name: String,
+}
+
+impl<C, S, R> XrayRemoteSamplerBuilder<C, S, R>
+where
+    C: HttpClient + 'static,
+    S: ShouldSample + 'static,
+    R: RuntimeChannel,
+{
+    pub(crate) fn new<Svc>(
+        runtime: R,
+        http_client: C,
+        default_sampler: S,
+        service_name: Svc,
+    ) -> Self
+    where
+        Svc: Into<String>,
+    {
+        XrayRemoteSamplerBuilder {
+            runtime,
+            rules_update_interval: Duration::from_secs(60 * 5),
+            targets_update_interval: Duration::from_secs(10),
+            client: http_client,
+            endpoint: DEFAULT_REMOTE_SAMPLER_ENDPOINT.to_string(),
+            default_sampler,
+            service_name: service_name.into(),
+        }
+    }
+
+    /// Change how often the SDK should fetch the sampling rules.
+    ///
+    /// By default it fetches every 5 minutes. Rules are also fetched whenever
+    /// the sampling targets report that they changed.
+    pub fn with_rules_update_interval(self, interval: Duration) -> Self {
+        Self {
+            rules_update_interval: interval,
+            ..self
+        }
+    }
+
+    /// Change how often the SDK should report its sampling statistics and
+    /// fetch its reservoir quotas.
+    ///
+    /// By default it fetches every 10 seconds.
+    pub fn with_targets_update_interval(self, interval: Duration) -> Self {
+        Self {
+            targets_update_interval: interval,
+            ..self
+        }
+    }
+
+    /// The endpoint of the X-Ray daemon or proxy serving the sampling API.
+    ///
+    /// By default it's `http://localhost:2000`.
+    pub fn with_endpoint<Str: Into<String>>(self, endpoint: Str) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            ..self
+        }
+    }
+
+    /// Build a [XrayRemoteSampler] using provided configuration.
+    ///
+    /// Return errors if:
+    ///
+    /// - the endpoint provided is empty or invalid.
+    /// - the service name provided is empty.
+    pub fn build(self) -> Result<Sampler, TraceError> {
+        if self.endpoint.is_empty() || self.service_name.is_empty() {
+            return Err(TraceError::Other(
+                "endpoint and service name cannot be empty".into(),
+            ));
+        }
+        let endpoints = Endpoints::new(&self.endpoint)
+            .map_err(|_err| TraceError::Other("invalid endpoint".into()))?;
+
+        Ok(Sampler::XrayRemote(XrayRemoteSampler::new(
+            self.runtime,
+            self.rules_update_interval,
+            self.targets_update_interval,
+            self.client,
+            endpoints,
+            self.default_sampler,
+            self.service_name,
+        )))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Endpoints {
+    rules: Uri,
+    targets: Uri,
+}
+
+impl Endpoints {
+    fn new(endpoint: &str) -> Result<Self, http::uri::InvalidUri> {
+        let endpoint = endpoint.trim_end_matches('/');
+        Ok(Endpoints {
+            rules: Uri::from_str(&format!("{endpoint}{GET_SAMPLING_RULES_PATH}"))?,
+            targets: Uri::from_str(&format!("{endpoint}{GET_SAMPLING_TARGETS_PATH}"))?,
+        })
+    }
+}
+
+/// Sampler that fetches its sampling rules from the [AWS X-Ray] centralized
+/// sampling API.
+///
+/// Rules are matched by priority against the service name and the HTTP method,
+/// URL path and host of the span, taken from the `http.request.method`,
+/// `url.path`, `url.full` and `server.address` attributes or their deprecated
+/// equivalents, and against custom attributes. The service name and the HTTP
+/// properties are matched case-insensitively. Rules matching on a service
+/// type or resource ARN other than `*` are never matched.
+///
+/// The first matching rule samples a number of requests per second from its
+/// reservoir, whose quota is assigned by the sampling targets the SDK fetches
+/// periodically while reporting its sampling statistics, and a fixed rate of
+/// requests beyond. Until a quota is assigned, one request per second is
+/// borrowed from the reservoir.
+///
+/// The default sampler is used until the rules are fetched.
+///
+/// User can build a [`XrayRemoteSampler`] by getting a [`XrayRemoteSamplerBuilder`] from
+/// [`Sampler::xray_remote`].
+///
+/// [AWS X-Ray]: https://docs.aws.amazon.com/xray/latest/devguide/xray-console-sampling.html
+#[derive(Clone, Debug)]
+pub struct XrayRemoteSampler {
+    inner: Arc<Inner>,
+    default_sampler: Arc<dyn ShouldSample + 'static>,
+}
+
+pub(crate) struct Inner {
+    rules: Mutex<RuleCache>,
+    service_name: String,
+    client_id: String,
+    shut_down: futures_channel::mpsc::Sender<()>,
+}
+
+impl Debug for Inner {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("XrayRemoteSamplerInner")
+            .field("service_name", &self.service_name)
+            .field("client_id", &self.client_id)
+            .finish()
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        let _ = self.shut_down.try_send(());
+    }
+}
+
+enum UpdateEvent {
+    Rules,
+    Targets,
+    Shutdown,
+}
+
+impl XrayRemoteSampler {
+    fn new<C, R, S>(
+        runtime: R,
+        rules_update_interval: Duration,
+        targets_update_interval: Duration,
+        client: C,
+        endpoints: Endpoints,
+        default_sampler: S,
+        service_name: String,
+    ) -> Self
+    where
+        R: RuntimeChannel,
+        C: HttpClient + 'static,
+        S: ShouldSample + 'static,
+    {
+        let (shutdown_tx, shutdown_rx) = futures_channel::mpsc::channel(1);
+        let inner = Arc::new(Inner {
+            rules: Mutex::new(RuleCache::default()),
+            service_name,
+            client_id: format!("{:024x}", rand::random::<u128>() >> 32),
+            shut_down: shutdown_tx,
+        });
+        Self::run_update_task(
+            runtime,
+            Arc::downgrade(&inner),
+            rules_update_interval,
+            targets_update_interval,
+            client,
+            shutdown_rx,
+            endpoints,
+        );
+        XrayRemoteSampler {
+            inner,
+            default_sampler: Arc::new(default_sampler),
+        }
+    }
+
+    // start a updating task, fetching the rules right away
+    fn run_update_task<C, R>(
+        runtime: R,
+        inner: std::sync::Weak<Inner>,
+        rules_update_interval: Duration,
+        targets_update_interval: Duration,
+        client: C,
+        shutdown: futures_channel::mpsc::Receiver<()>,
+        endpoints: Endpoints,
+    ) where
+        R: RuntimeChannel,
+        C: HttpClient + 'static,
+    {
+        let rules_interval = to_interval_stream(runtime.clone(), rules_update_interval);
+        let targets_interval = to_interval_stream(runtime.clone(), targets_update_interval);
+
+        runtime.spawn(async move {
+            let mut updates = Box::pin(stream::select(
+                shutdown.map(|_| UpdateEvent::Shutdown),
+                stream::select(
+                    stream::once(async {})
+                        .chain(rules_interval)
+                        .map(|_| UpdateEvent::Rules),
+                    targets_interval.map(|_| UpdateEvent::Targets),
+                ),
+            ));
+
+            while let Some(event) = updates.next().await {
+                let Some(inner) = inner.upgrade() else {
+                    break;
+                };
+                let fetch_rules = match event {
+                    UpdateEvent::Rules => true,
+                    UpdateEvent::Targets => Self::update_targets(&client, &endpoints, &inner).await,
+                    UpdateEvent::Shutdown => break,
+                };
+                if fetch_rules {
+                    Self::update_rules(&client, &endpoints, &inner).await;
+                }
+            }
+        });
+    }
+
+    async fn update_rules<C: HttpClient>(client: &C, endpoints: &Endpoints, inner: &Inner) {
+        match Self::request_rules(client, endpoints).await {
+            Ok(rules) => {
+                if let Ok(mut cache) = inner.rules.lock() {
+                    cache.update_rules(rules, now());
+                }
+            }
+            Err(err_msg) => {
+                otel_warn!(
+                    name: "XrayRemoteSampler.FailedToFetchRules",
+                    message = "Failed to fetch the sampling rules from the remote endpoint. The last successfully fetched rules will be used if available; otherwise, the default sampler will be applied until a successful fetch.",
+                    reason = format!("{}", err_msg),
+                );
+            }
+        }
+    }
+
+    /// Reports the sampling statistics and applies the new targets. Returns
+    /// whether the rules need to be fetched again.
+    async fn update_targets<C: HttpClient>(
+        client: &C,
+        endpoints: &Endpoints,
+        inner: &Inner,
+    ) -> bool {
+        let statistics = match inner.rules.lock() {
+            Ok(mut cache) if !cache.is_empty() => cache.take_statistics(&inner.client_id, now()),
+            _ => return false,
+        };
+        let request = GetSamplingTargetsRequest {
+            sampling_statistics_documents: statistics,
+        };
+        match Self::post::<_, GetSamplingTargetsResponse, _>(client, &endpoints.targets, &request)
+            .await
+        {
+            Ok(targets) => inner
+                .rules
+                .lock()
+                .map(|mut cache| cache.update_targets(targets))
+                .unwrap_or(false),
+            Err(err_msg) => {
+                otel_debug!(
+                    name: "XrayRemoteSampler.FailedToFetchTargets",
+                    reason = format!("{}", err_msg),
+                );
+                false
+            }
+        }
+    }
+
+    async fn request_rules<C: HttpClient>(
+        client: &C,
+        endpoints: &Endpoints,
+    ) -> Result<Vec<SamplingRule>, String> {
+        let mut rules = Vec::new();
+        let mut next_token = None;
+        for _ in 0..MAX_SAMPLING_RULES_PAGES {
+            let request = GetSamplingRulesRequest { next_token };
+            let resp: GetSamplingRulesResponse =
+                Self::post(client, &endpoints.rules, &request).await?;
+            rules.extend(
+                resp.sampling_rule_records
+                    .into_iter()
+                    .map(|record| record.sampling_rule),
+            );
+            match resp.next_token {
+                Some(token) if request.next_token.as_ref() == Some(&token) => {
+                    return Err(format!("the next token {token} of the rules is repeated"));
+                }
+                Some(token) if !token.is_empty() => next_token = Some(token),
+                _ => return Ok(rules),
+            }
+        }
+        Err(format!(
+            "the rules span more than {MAX_SAMPLING_RULES_PAGES} pages"
+        ))
+    }
+
+    async fn post<C, T, B>(client: &C, endpoint: &Uri, body: &B) -> Result<T, String>
+    where
+        C: HttpClient,
+        T: serde::de::DeserializeOwned,
+        B: serde::Serialize,
+    {
+        let body = serde_json::to_vec(body)
+            .map_err(|err| format!("cannot serialize the request, {err}"))?;
+        let request = http::Request::post(endpoint.clone())
+            .header("Content-Type", "application/json")
+            .body(body.into())
+            .unwrap();
+
+        let resp = client
+            .send_bytes(request)
+            .await
+            .map_err(|err| format!("the request is failed to send {err}"))?;
+
+        // process failures
+        if resp.status() != http::StatusCode::OK {
+            return Err(format!(
+                "the http response code is not 200 but {}",
+                resp.status()
+            ));
+        }
+
+        // deserialize the response
+        serde_json::from_slice(&resp.body()[..])
+            .map_err(|err| format!("cannot deserialize the response, {err}"))
+    }
+}
+
+impl ShouldSample for XrayRemoteSampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+        links: &[Link],
+    ) -> SamplingResult {
+        let request = Request::new(attributes);
+        let decision = self.inner.rules.lock().ok().and_then(|mut cache| {
+            cache.should_sample(&self.inner.service_name, trace_id, &request, now())
+        });
+        match decision {
+            Some(decision) => SamplingResult {
+                decision,
+                attributes: Vec::new(),
+                trace_state: match parent_context {
+                    Some(ctx) => ctx.span().span_context().trace_state().clone(),
+                    None => TraceState::default(),
+                },
+            },
+            None => self.default_sampler.should_sample(
+                parent_context,
+                trace_id,
+                name,
+                span_kind,
+                attributes,
+                links,
+            ),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::runtime;
+    use crate::trace::SamplingDecision;
+    use opentelemetry_http::{Bytes, HttpError};
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// A mock of the X-Ray sampling API, recording the requests it receives.
+    #[derive(Debug, Clone, Default)]
+    struct MockXrayServer {
+        requests: Arc<Mutex<Vec<(String, serde_json::Value)>>>,
+        rules_changed: Arc<AtomicBool>,
+        /// Whether the rules are returned with a next token, always the same.
+        repeat_next_token: Arc<AtomicBool>,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpClient for MockXrayServer {
+        async fn send_bytes(
+            &self,
+            request: http::Request<Bytes>,
+        ) -> Result<http::Response<Bytes>, HttpError> {
+            let path = request.uri().path().to_string();
+            let body: serde_json::Value = serde_json::from_slice(request.body())?;
+            self.requests.lock().unwrap().push((path.clone(), body));
+
+            let body = match path.as_str() {
+                GET_SAMPLING_RULES_PATH => {
+                    let fixed_rate = if self.rules_changed.load(Ordering::Acquire) {
+                        1.0
+                    } else {
+                        0.0
+                    };
+                    serde_json::json!({
+                        "SamplingRuleRecords": [
+                            {"SamplingRule": {
+                                "RuleName": "Default", "Priority": 10000, "FixedRate": 0.0,
+                                "ReservoirSize": 0, "ServiceName": "*", "ServiceType": "*",
+                                "Host": "*", "HTTPMethod": "*", "URLPath": "*",
+                                "ResourceARN": "*", "Version": 1
+                            }},
+                            {"SamplingRule": {
+                                "RuleName": "Checkout", "Priority": 1, "FixedRate": fixed_rate,
+                                "ReservoirSize": 0, "ServiceName": "shop", "ServiceType": "*",
+                                "Host": "*", "HTTPMethod": "POST", "URLPath": "/checkout",
+                                "ResourceARN": "*", "Version": 1, "Attributes": {}
+                            }}
+                        ],
+                        "NextToken": self.repeat_next_token.load(Ordering::Acquire).then_some("page")
+                    })
+                }
+                GET_SAMPLING_TARGETS_PATH => serde_json::json!({
+                    "SamplingTargetDocuments": [],
+                    "LastRuleModification": if self.rules_changed.load(Ordering::Acquire) {
+                        4102444800.0
+                    } else {
+                        0.0
+                    },
+                    "UnprocessedStatistics": []
+                }),
+                _ => {
+                    return Ok(http::Response::builder()
+                        .status(404)
+                        .body(Bytes::new())
+                        .unwrap())
+                }
+            };
+            Ok(http::Response::builder()
+                .status(200)
+                .body(serde_json::to_vec(&body).unwrap().into())
+                .unwrap())
+        }
+    }
+
+    fn checkout_decision(sampler: &Sampler) -> SamplingDecision {
+        sampler
+            .should_sample(
+                None,
+                TraceId::from(1),
+                "POST /checkout",
+                &SpanKind::Server,
+                &[
+                    KeyValue::new("http.request.method", "POST"),
+                    KeyValue::new("url.path", "/checkout"),
+                ],
+                &[],
+            )
+            .decision
+    }
+
+    async fn wait_for(condition: impl Fn() -> bool) {
+        for _ in 0..500 {
+            if condition() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("condition not met in time");
+    }
+
+    #[test]
+    fn invalid_configuration() {
+        for (endpoint, service_name) in [("", "shop"), ("http://localhost:2000", "")] {
+            let result = Sampler::xray_remote(
+                runtime::Tokio,
+                MockXrayServer::default(),
+                Sampler::AlwaysOn,
+                service_name,
+            )
+            .with_endpoint(endpoint)
+            .build();
+            assert!(result.is_err());
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn fetches_rules_and_reports_statistics() {
+        let server = MockXrayServer::default();
+        let sampler =
+            Sampler::xray_remote(runtime::Tokio, server.clone(), Sampler::AlwaysOn, "shop")
+                .with_endpoint("http://localhost:2000/")
+                .with_targets_update_interval(Duration::from_millis(50))
+                .build()
+                .unwrap();
+
+        // the default sampler is used until the rules are fetched, the
+        // checkout rule drops everything
+        wait_for(|| checkout_decision(&sampler) == SamplingDecision::Drop).await;
+
+        wait_for(|| {
+            server.requests.lock().unwrap().iter().any(|(path, body)| {
+                path == GET_SAMPLING_TARGETS_PATH
+                    && body["SamplingStatisticsDocuments"]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .any(|doc| doc["RuleName"] == "Checkout" && doc["RequestCount"] != 0)
+            })
+        })
+        .await;
+        let requests = server.requests.lock().unwrap().clone();
+        assert_eq!(requests[0].0, GET_SAMPLING_RULES_PATH);
+        assert_eq!(requests[0].1, serde_json::json!({"NextToken": null}));
+        let (_, targets_request) = requests
+            .iter()
+            .find(|(path, _)| path == GET_SAMPLING_TARGETS_PATH)
+            .unwrap();
+        let documents = targets_request["SamplingStatisticsDocuments"]
+            .as_array()
+            .unwrap();
+        assert_eq!(documents.len(), 2);
+        assert_eq!(documents[0]["ClientID"].as_str().unwrap().len(), 24);
+
+        // rules are fetched again once the targets report that they changed
+        server.rules_changed.store(true, Ordering::Release);
+        wait_for(|| checkout_decision(&sampler) == SamplingDecision::RecordAndSample).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn repeated_next_token_stops_fetching_rules() {
+        let server = MockXrayServer::default();
+        server.repeat_next_token.store(true, Ordering::Release);
+        let endpoints = Endpoints::new(DEFAULT_REMOTE_SAMPLER_ENDPOINT).unwrap();
+
+        let result = XrayRemoteSampler::request_rules(&server, &endpoints).await;
+        assert_eq!(
+            result.unwrap_err(),
+            "the next token page of the rules is repeated"
+        );
+        let requests = server.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].1, serde_json::json!({"NextToken": "page"}));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn uses_default_sampler_without_rules() {
+        let sampler = Sampler::xray_remote(
+            runtime::Tokio,
+            MockXrayServer::default(),
+            Sampler::AlwaysOn,
+            "shop",
+        )
+        .with_endpoint("http://localhost:2000/unknown")
+        .build()
+        .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(
+            checkout_decision(&sampler),
+            SamplingDecision::RecordAndSample
+        );
+    }
+}
@@ -8,7 +8,9 @@
 //! start time is set to the current time on span creation. After the `Span` is created, it
 //! is possible to change its name, set its `Attributes`, and add `Links` and `Events`.
 //! These cannot be changed after the `Span`'s end time has been set.
+use crate::trace::span_limit::truncate_attributes;
 use crate::trace::SpanLimits;
+use opentelemetry::otel_debug;
 use opentelemetry::trace::{Event, Link, SpanContext, SpanId, SpanKind, Status};
 use opentelemetry::KeyValue;
 use std::borrow::Cow;
@@ -21,6 +23,8 @@ pub struct Span {
     data: Option<SpanData>,
     tracer: crate::trace::SdkTracer,
     span_limits: SpanLimits,
+    /// Whether the span processors' `on_ending` callbacks are running.
+    is_ending: bool,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -42,6 +46,9 @@ pub(crate) struct SpanData {
     /// The number of attributes that were above the configured limit, and thus
     /// dropped.
     pub(crate) dropped_attributes_count: u32,
+    /// The number of attribute values of the span, its events and links that
+    /// were longer than the configured limit, and thus truncated.
+    pub(crate) truncated_attributes_count: u32,
     /// Span events
     pub(crate) events: crate::trace::SpanEvents,
     /// Span Links
@@ -62,6 +69,7 @@ impl Span {
             data,
             tracer,
             span_limits: span_limit,
+            is_ending: false,
         }
     }
 
@@ -73,6 +81,17 @@ impl Span {
         self.data.as_mut().map(f)
     }
 
+    /// Mutable access to the attributes recorded so far, e.g. to redact values
+    /// from [`SpanProcessor::on_ending`]. Returns `None` if the span is not
+    /// recording.
+    ///
+    /// [`SpanProcessor::on_ending`]: crate::trace::SpanProcessor::on_ending
+    pub fn attributes_mut(&mut self) -> Option<&mut [KeyValue]> {
+        self.data
+            .as_mut()
+            .map(|data| data.attributes.as_mut_slice())
+    }
+
     /// Convert information in this span into `exporter::trace::SpanData`.
     /// This function copies all data from the current span, which will create a
     /// overhead.
@@ -101,11 +120,14 @@ impl opentelemetry::trace::Span for Span {
     {
         let span_events_limit = self.span_limits.max_events_per_span as usize;
         let event_attributes_limit = self.span_limits.max_attributes_per_event as usize;
+        let value_length_limit = self.tracer.provider().config().max_attribute_value_length;
         self.with_data(|data| {
             if data.events.len() < span_events_limit {
                 let dropped_attributes_count =
                     attributes.len().saturating_sub(event_attributes_limit);
                 attributes.truncate(event_attributes_limit);
+                data.truncated_attributes_count +=
+                    truncate_attributes(&mut attributes, value_length_limit);
 
                 data.events.add_event(Event::new(
                     name,
@@ -136,10 +158,13 @@ impl opentelemetry::trace::Span for Span {
     /// Note that the OpenTelemetry project documents certain ["standard
     /// attributes"](https://github.com/open-telemetry/opentelemetry-specification/tree/v0.5.0/specification/trace/semantic_conventions/README.md)
     /// that have prescribed semantic meanings.
-    fn set_attribute(&mut self, attribute: KeyValue) {
+    fn set_attribute(&mut self, mut attribute: KeyValue) {
         let span_attribute_limit = self.span_limits.max_attributes_per_span as usize;
+        let value_length_limit = self.tracer.provider().config().max_attribute_value_length;
         self.with_data(|data| {
             if data.attributes.len() < span_attribute_limit {
+                data.truncated_attributes_count +=
+                    truncate_attributes(std::slice::from_mut(&mut attribute), value_length_limit);
                 data.attributes.push(attribute);
             } else {
                 data.dropped_attributes_count += 1;
@@ -175,12 +200,15 @@ impl opentelemetry::trace::Span for Span {
     fn add_link(&mut self, span_context: SpanContext, attributes: Vec<KeyValue>) {
         let span_links_limit = self.span_limits.max_links_per_span as usize;
         let link_attributes_limit = self.span_limits.max_attributes_per_link as usize;
+        let value_length_limit = self.tracer.provider().config().max_attribute_value_length;
         self.with_data(|data| {
             if data.links.links.len() < span_links_limit {
                 let dropped_attributes_count =
                     attributes.len().saturating_sub(link_attributes_limit);
                 let mut attributes = attributes;
                 attributes.truncate(link_attributes_limit);
+                data.truncated_attributes_count +=
+                    truncate_attributes(&mut attributes, value_length_limit);
                 data.links.add_link(Link::new(
                     span_context,
                     attributes,
@@ -200,17 +228,14 @@ impl opentelemetry::trace::Span for Span {
 
 impl Span {
     fn ensure_ended_and_exported(&mut self, timestamp: Option<SystemTime>) {
-        // skip if data has already been exported
-        let mut data = match self.data.take() {
-            Some(data) => data,
-            None => return,
-        };
-
-        let provider = self.tracer.provider();
-        // skip if provider has been shut down
-        if provider.is_shutdown() {
+        // skip if the span is being ended from a span processor's `on_ending`
+        if self.is_ending {
             return;
         }
+        // skip if data has already been exported
+        let Some(data) = self.data.as_mut() else {
+            return;
+        };
 
         // ensure end time is set via explicit end or implicitly on drop
         if let Some(timestamp) = timestamp {
@@ -219,6 +244,37 @@ impl Span {
             data.end_time = opentelemetry::time::now();
         }
 
+        let provider = self.tracer.provider().clone();
+        if !provider.is_shutdown() {
+            // let processors mutate the span before it becomes immutable
+            self.is_ending = true;
+            for processor in provider.span_processors() {
+                processor.on_ending(self);
+            }
+            self.is_ending = false;
+        }
+
+        let Some(data) = self.data.take() else {
+            return;
+        };
+
+        if data.truncated_attributes_count > 0 {
+            otel_debug!(
+                name: "Span.AttributeValuesTruncated",
+                span_name = format!("{}", data.name),
+                truncated_attributes_count = data.truncated_attributes_count,
+            );
+        }
+
+        #[cfg(feature = "experimental_self_observability")]
+        if let Some(metrics) = provider.metrics() {
+            metrics.span_ended(self.span_context.is_sampled());
+        }
+        // skip if provider has been shut down
+        if provider.is_shutdown() {
+            return;
+        }
+
         match provider.span_processors() {
             [] => {}
             [processor] => {
@@ -279,6 +335,7 @@ mod tests {
         DEFAULT_MAX_ATTRIBUTES_PER_SPAN, DEFAULT_MAX_EVENT_PER_SPAN, DEFAULT_MAX_LINKS_PER_SPAN,
     };
     use crate::trace::{SpanEvents, SpanLinks};
+    use opentelemetry::testing::TestError;
     use opentelemetry::trace::{self, SpanBuilder, TraceFlags, TraceId, Tracer};
     use opentelemetry::{trace::Span as _, trace::TracerProvider};
     use std::time::Duration;
@@ -296,6 +353,7 @@ mod tests {
             end_time: opentelemetry::time::now(),
             attributes: Vec::new(),
             dropped_attributes_count: 0,
+            truncated_attributes_count: 0,
             events: SpanEvents::default(),
             links: SpanLinks::default(),
             status: Status::Unset,
@@ -389,6 +447,31 @@ mod tests {
         });
     }
 
+    #[test]
+    fn record_exception() {
+        let mut span = create_span();
+        let err = TestError::new("request failed").with_source(TestError::new("timed out"));
+        span.record_exception(&err, None);
+        span.with_data(|data| {
+            if let Some(event) = data.events.iter().next() {
+                assert_eq!(event.name, "exception");
+                assert_eq!(
+                    event.attributes,
+                    vec![
+                        KeyValue::new("exception.type", std::any::type_name::<TestError>()),
+                        KeyValue::new("exception.message", "request failed"),
+                        KeyValue::new(
+                            "exception.stacktrace",
+                            "request failed\n\nCaused by:\n    0: timed out"
+                        ),
+                    ]
+                );
+            } else {
+                panic!("no event");
+            }
+        });
+    }
+
     #[test]
     fn set_attribute() {
         let mut span = create_span();
@@ -507,6 +590,7 @@ mod tests {
         );
         let err = std::io::Error::from(std::io::ErrorKind::Other);
         span.record_error(&err);
+        span.record_exception(&err, None);
         span.set_attribute(KeyValue::new("k", "v"));
         span.set_status(Status::error("ERROR"));
         span.update_name("new_name");
@@ -570,6 +654,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn exceed_attribute_value_length_limit() {
+        let provider = crate::trace::SdkTracerProvider::builder()
+            .with_simple_exporter(NoopSpanExporter::new())
+            .with_max_attribute_value_length(4)
+            .build();
+        let tracer = provider.tracer("opentelemetry-test");
+
+        let mut link = Link::with_context(SpanContext::new(
+            TraceId::from(12),
+            SpanId::from(12),
+            TraceFlags::default(),
+            false,
+            Default::default(),
+        ));
+        link.attributes.push(KeyValue::new("link", "linkvalue"));
+        let span_builder = tracer
+            .span_builder("test")
+            .with_attributes(vec![
+                KeyValue::new("short", "abc"),
+                KeyValue::new("long", "abcdefgh"),
+                KeyValue::new("number", 123456789),
+            ])
+            .with_links(vec![link]);
+        let mut span = tracer.build(span_builder);
+        span.set_attribute(KeyValue::new("multibyte", "ñandú🦀"));
+        span.add_event("event", vec![KeyValue::new("event", "eventvalue")]);
+
+        let data = span.data.clone().expect("span should be recording");
+        assert_eq!(data.truncated_attributes_count, 4);
+        assert_eq!(
+            data.attributes,
+            vec![
+                KeyValue::new("short", "abc"),
+                KeyValue::new("long", "abcd"),
+                KeyValue::new("number", 123456789),
+                KeyValue::new("multibyte", "ñand"),
+            ]
+        );
+        assert_eq!(
+            data.links.links[0].attributes,
+            vec![KeyValue::new("link", "link")]
+        );
+        assert_eq!(
+            data.events.events[0].attributes,
+            vec![KeyValue::new("event", "even")]
+        );
+    }
+
     #[test]
     fn exceed_event_attributes_limit() {
         let exporter = NoopSpanExporter::new();
@@ -12,6 +12,10 @@
 ///
 /// If the limit has been breached. The attributes, events or links will be dropped based on their
 /// index in the collection. The one added to collections later will be dropped first.
+use opentelemetry::{Array, KeyValue, StringValue, Value};
+
+use crate::util::truncate_str;
+
 pub(crate) const DEFAULT_MAX_EVENT_PER_SPAN: u32 = 128;
 pub(crate) const DEFAULT_MAX_ATTRIBUTES_PER_SPAN: u32 = 128;
 pub(crate) const DEFAULT_MAX_LINKS_PER_SPAN: u32 = 128;
@@ -44,3 +48,78 @@ impl Default for SpanLimits {
         }
     }
 }
+
+/// Truncates the string values of `attributes` to `limit` characters, see
+/// [`Config::max_attribute_value_length`], returning the number of truncated
+/// attributes.
+///
+/// [`Config::max_attribute_value_length`]: crate::trace::Config::max_attribute_value_length
+pub(crate) fn truncate_attributes(attributes: &mut [KeyValue], limit: Option<u32>) -> u32 {
+    let Some(limit) = limit else {
+        return 0;
+    };
+    attributes
+        .iter_mut()
+        .map(|kv| truncate_value(&mut kv.value, limit as usize) as u32)
+        .sum()
+}
+
+/// Truncates the string value, or the elements of a string array, to `limit`
+/// characters. Returns `true` if anything was truncated.
+pub(crate) fn truncate_value(value: &mut Value, limit: usize) -> bool {
+    match value {
+        Value::String(s) => truncate_string_value(s, limit),
+        Value::Array(Array::String(values)) => values.iter_mut().fold(false, |truncated, s| {
+            truncate_string_value(s, limit) | truncated
+        }),
+        _ => false,
+    }
+}
+
+fn truncate_string_value(value: &mut StringValue, limit: usize) -> bool {
+    match truncate_str(value.as_str(), limit) {
+        Some(truncated) => {
+            *value = StringValue::from(truncated.to_owned());
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_attributes_respects_limit() {
+        let mut attributes = vec![
+            KeyValue::new("short", "abc"),
+            KeyValue::new("long", "abcdef"),
+            KeyValue::new("multibyte", "日本語です"),
+            KeyValue::new("int", 123456),
+            KeyValue::new(
+                "array",
+                Value::Array(vec![StringValue::from("a"), StringValue::from("abcd")].into()),
+            ),
+        ];
+
+        let truncated = truncate_attributes(&mut attributes, Some(3));
+
+        assert_eq!(truncated, 3);
+        assert_eq!(attributes[0].value, Value::from("abc"));
+        assert_eq!(attributes[1].value, Value::from("abc"));
+        assert_eq!(attributes[2].value, Value::from("日本語"));
+        assert_eq!(attributes[3].value, Value::I64(123456));
+        assert_eq!(
+            attributes[4].value,
+            Value::Array(vec![StringValue::from("a"), StringValue::from("abc")].into())
+        );
+    }
+
+    #[test]
+    fn truncate_attributes_without_limit() {
+        let mut attributes = vec![KeyValue::new("long", "abcdef")];
+        assert_eq!(truncate_attributes(&mut attributes, None), 0);
+        assert_eq!(attributes[0].value, Value::from("abcdef"));
+    }
+}
@@ -36,12 +36,18 @@
 
 use crate::error::{OTelSdkError, OTelSdkResult};
 use crate::resource::Resource;
+#[cfg(feature = "experimental_self_observability")]
+use crate::self_observability::{ProcessorMetrics, Signal};
 use crate::trace::Span;
 use crate::trace::{SpanData, SpanExporter};
+#[cfg(feature = "experimental_self_observability")]
+use opentelemetry::metrics::Meter;
 use opentelemetry::Context;
 use opentelemetry::{otel_debug, otel_error, otel_warn};
 use std::cmp::min;
 use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "experimental_self_observability")]
+use std::sync::OnceLock;
 use std::sync::{Arc, Mutex};
 use std::{env, str::FromStr, time::Duration};
 
@@ -79,6 +85,17 @@ pub trait SpanProcessor: Send + Sync + std::fmt::Debug {
     /// synchronously on the thread that started the span, therefore it should
     /// not block or throw exceptions.
     fn on_start(&self, span: &mut Span, cx: &Context);
+    /// `on_ending` is called when a `Span` is ending, after its end timestamp
+    /// is set but before it becomes immutable and is passed to
+    /// [`on_end`](SpanProcessor::on_end) of any processor. Changes made to the
+    /// span here, e.g. attributes added or redacted, are observed by all span
+    /// processors and exporters.
+    ///
+    /// This method is called synchronously within the `Span::end` API, in the
+    /// order the processors were registered, therefore it should not block or
+    /// throw exceptions. Calls to `Span::end` made from this method are
+    /// ignored.
+    fn on_ending(&self, _span: &mut Span) {}
     /// `on_end` is called after a `Span` is ended (i.e., the end timestamp is
     /// already set). This method is called synchronously within the `Span::end`
     /// API, therefore it should not block or throw an exception.
@@ -97,6 +114,10 @@ pub trait SpanProcessor: Send + Sync + std::fmt::Debug {
     }
     /// Set the resource for the span processor.
     fn set_resource(&mut self, _resource: &Resource) {}
+    /// Set the meter the span processor and its exporter report their
+    /// [self-observability](crate::self_observability) metrics with.
+    #[cfg(feature = "experimental_self_observability")]
+    fn set_meter(&mut self, _meter: &Meter) {}
 }
 
 /// A [SpanProcessor] that passes finished spans to the configured
@@ -173,6 +194,13 @@ impl<T: SpanExporter> SpanProcessor for SimpleSpanProcessor<T> {
             exporter.set_resource(resource);
         }
     }
+
+    #[cfg(feature = "experimental_self_observability")]
+    fn set_meter(&mut self, meter: &Meter) {
+        if let Ok(mut exporter) = self.exporter.lock() {
+            exporter.set_meter(meter);
+        }
+    }
 }
 
 /// The `BatchSpanProcessor` collects finished spans in a buffer and exports them
@@ -249,6 +277,8 @@ enum BatchMessage {
     ForceFlush(SyncSender<OTelSdkResult>),
     Shutdown(SyncSender<OTelSdkResult>),
     SetResource(Arc<Resource>),
+    #[cfg(feature = "experimental_self_observability")]
+    SetMeter(Meter),
 }
 
 /// The `BatchSpanProcessor` collects finished spans in a buffer and exports them
@@ -292,6 +322,8 @@ pub struct BatchSpanProcessor {
     max_export_batch_size: usize,
     dropped_spans_count: AtomicUsize,
     max_queue_size: usize,
+    #[cfg(feature = "experimental_self_observability")]
+    metrics: Arc<OnceLock<ProcessorMetrics>>,
 }
 
 impl BatchSpanProcessor {
@@ -312,6 +344,10 @@ impl BatchSpanProcessor {
         let max_export_batch_size = config.max_export_batch_size;
         let current_batch_size = Arc::new(AtomicUsize::new(0));
         let current_batch_size_for_thread = current_batch_size.clone();
+        #[cfg(feature = "experimental_self_observability")]
+        let metrics = Arc::new(OnceLock::new());
+        #[cfg(feature = "experimental_self_observability")]
+        let metrics_for_thread = Arc::clone(&metrics);
 
         let handle = thread::Builder::new()
             .name("OpenTelemetry.Traces.BatchProcessor".to_string())
@@ -326,6 +362,8 @@ impl BatchSpanProcessor {
                 let mut spans = Vec::with_capacity(config.max_export_batch_size);
                 let mut last_export_time = Instant::now();
                 let current_batch_size = current_batch_size_for_thread;
+                #[cfg(feature = "experimental_self_observability")]
+                let metrics = metrics_for_thread;
                 loop {
                     let remaining_time_option = config
                         .scheduled_delay
@@ -349,6 +387,8 @@ impl BatchSpanProcessor {
                                     &mut last_export_time,
                                     &current_batch_size,
                                     &config,
+                                    #[cfg(feature = "experimental_self_observability")]
+                                    &metrics,
                                 );
                             }
                             BatchMessage::ForceFlush(sender) => {
@@ -360,6 +400,8 @@ impl BatchSpanProcessor {
                                     &mut last_export_time,
                                     &current_batch_size,
                                     &config,
+                                    #[cfg(feature = "experimental_self_observability")]
+                                    &metrics,
                                 );
                                 let _ = sender.send(result);
                             }
@@ -372,6 +414,8 @@ impl BatchSpanProcessor {
                                     &mut last_export_time,
                                     &current_batch_size,
                                     &config,
+                                    #[cfg(feature = "experimental_self_observability")]
+                                    &metrics,
                                 );
                                 let _ = exporter.shutdown();
                                 let _ = sender.send(result);
@@ -388,6 +432,10 @@ impl BatchSpanProcessor {
                             BatchMessage::SetResource(resource) => {
                                 exporter.set_resource(&resource);
                             }
+                            #[cfg(feature = "experimental_self_observability")]
+                            BatchMessage::SetMeter(meter) => {
+                                exporter.set_meter(&meter);
+                            }
                         },
                         Err(RecvTimeoutError::Timeout) => {
                             otel_debug!(
@@ -401,6 +449,8 @@ impl BatchSpanProcessor {
                                 &mut last_export_time,
                                 &current_batch_size,
                                 &config,
+                                #[cfg(feature = "experimental_self_observability")]
+                                &metrics,
                             );
                         }
                         Err(RecvTimeoutError::Disconnected) => {
@@ -430,6 +480,8 @@ impl BatchSpanProcessor {
             export_span_message_sent: Arc::new(AtomicBool::new(false)),
             current_batch_size,
             max_export_batch_size,
+            #[cfg(feature = "experimental_self_observability")]
+            metrics,
         }
     }
 
@@ -455,6 +507,7 @@ impl BatchSpanProcessor {
         last_export_time: &mut Instant,
         current_batch_size: &AtomicUsize,
         config: &BatchConfig,
+        #[cfg(feature = "experimental_self_observability")] metrics: &OnceLock<ProcessorMetrics>,
     ) -> OTelSdkResult
     where
         E: SpanExporter + Send + Sync + 'static,
@@ -478,6 +531,10 @@ impl BatchSpanProcessor {
             result = Self::export_batch_sync(exporter, spans, last_export_time); // This method clears the spans vec after exporting
 
             current_batch_size.fetch_sub(count_of_spans, Ordering::Relaxed);
+            #[cfg(feature = "experimental_self_observability")]
+            if let Some(metrics) = metrics.get() {
+                metrics.processed(count_of_spans);
+            }
         }
         result
     }
@@ -574,6 +631,10 @@ impl SpanProcessor for BatchSpanProcessor {
                 }
             }
             Err(std::sync::mpsc::TrySendError::Full(_)) => {
+                #[cfg(feature = "experimental_self_observability")]
+                if let Some(metrics) = self.metrics.get() {
+                    metrics.dropped();
+                }
                 // Increment dropped spans count. The first time we have to drop
                 // a span, emit a warning.
                 if self.dropped_spans_count.fetch_add(1, Ordering::Relaxed) == 0 {
@@ -700,6 +761,21 @@ impl SpanProcessor for BatchSpanProcessor {
             .message_sender
             .try_send(BatchMessage::SetResource(resource));
     }
+
+    /// Set the meter for the processor and its exporter.
+    #[cfg(feature = "experimental_self_observability")]
+    fn set_meter(&mut self, meter: &Meter) {
+        let _ = self.metrics.set(ProcessorMetrics::new(
+            meter,
+            Signal::Span,
+            "batching_span_processor",
+            Arc::clone(&self.current_batch_size),
+            self.max_queue_size,
+        ));
+        let _ = self
+            .message_sender
+            .try_send(BatchMessage::SetMeter(meter.clone()));
+    }
 }
 
 /// Builder for `BatchSpanProcessorDedicatedThread`.
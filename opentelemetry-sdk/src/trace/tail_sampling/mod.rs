@@ -0,0 +1,964 @@
+//! # Tail Sampling Span Processor
+//!
+//! Head sampling with [`ShouldSample`] decides whether a trace is sampled
+//! when its first span starts, so it cannot keep e.g. all traces with an error
+//! or all traces slower than two seconds. The [`TailSamplingProcessor`]
+//! buffers the finished spans of each trace instead, and decides whether to
+//! keep the whole trace once its decision wait elapses.
+//!
+//! This is meant for single-process services. Traces spanning several
+//! services are only seen partially, and should be tail sampled by a collector
+//! instead.
+//!
+//! [`ShouldSample`]: crate::trace::ShouldSample
+mod policy;
+
+pub use policy::TailSamplingPolicy;
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, RecvTimeoutError, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "experimental_self_observability")]
+use opentelemetry::metrics::Meter;
+use opentelemetry::trace::TraceId;
+use opentelemetry::{otel_debug, otel_error, otel_warn, Context};
+
+use crate::error::{OTelSdkError, OTelSdkResult};
+use crate::resource::Resource;
+use crate::trace::{Span, SpanData, SpanExporter, SpanProcessor};
+use policy::PolicyEvaluator;
+
+/// Default time to wait after the first span of a trace ended before deciding
+/// whether to keep the trace.
+const DEFAULT_DECISION_WAIT: Duration = Duration::from_secs(10);
+/// Default maximum number of traces awaiting a decision.
+const DEFAULT_MAX_TRACES: usize = 10_000;
+/// Default maximum number of spans buffered per trace.
+const DEFAULT_MAX_SPANS_PER_TRACE: usize = 1_000;
+/// Longest interval between two checks for traces to decide on.
+const MAX_TICK_INTERVAL: Duration = Duration::from_secs(1);
+/// Shortest interval between two checks for traces to decide on.
+const MIN_TICK_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Messages sent from the processor to its background thread.
+#[derive(Debug)]
+enum TailSamplingMessage {
+    ForceFlush(SyncSender<OTelSdkResult>),
+    Shutdown(SyncSender<OTelSdkResult>),
+    SetResource(Arc<Resource>),
+    #[cfg(feature = "experimental_self_observability")]
+    SetMeter(Meter),
+}
+
+/// A [`SpanProcessor`] that buffers the finished spans of each trace and
+/// exports whole traces that are kept by its [`TailSamplingPolicy`]s.
+///
+/// The decision for a trace is made once its decision wait elapses, counted
+/// from the end of its first span. A trace is kept if any policy keeps it, so
+/// no trace is kept if no policy is configured. Spans of a trace ending after
+/// its decision was made follow that decision.
+///
+/// Memory is bounded by the maximum number of traces awaiting a decision and
+/// the maximum number of spans buffered per trace. When too many traces are
+/// buffered, the oldest one is dropped.
+///
+/// Only spans sampled by the head sampler reach this processor, so it is
+/// typically used with the default [`Sampler::AlwaysOn`] based sampler.
+/// Decided traces are exported from a dedicated background thread.
+///
+/// # Example
+///
+/// ```rust
+/// use opentelemetry_sdk::testing::trace::NoopSpanExporter;
+/// use opentelemetry_sdk::trace::{SdkTracerProvider, TailSamplingPolicy, TailSamplingProcessor};
+/// use std::time::Duration;
+///
+/// let processor = TailSamplingProcessor::builder(NoopSpanExporter::new())
+///     .with_policy(TailSamplingPolicy::ErrorStatus)
+///     .with_policy(TailSamplingPolicy::Latency(Duration::from_secs(2)))
+///     .with_policy(TailSamplingPolicy::Probabilistic(0.01))
+///     .with_decision_wait(Duration::from_secs(5))
+///     .build();
+///
+/// let provider = SdkTracerProvider::builder()
+///     .with_span_processor(processor)
+///     .build();
+/// # provider.shutdown().unwrap();
+/// ```
+///
+/// [`Sampler::AlwaysOn`]: crate::trace::Sampler::AlwaysOn
+#[derive(Debug)]
+pub struct TailSamplingProcessor {
+    buffer: Arc<Mutex<TraceBuffer>>,
+    message_sender: SyncSender<TailSamplingMessage>,
+    handle: Mutex<Option<thread::JoinHandle<()>>>,
+    forceflush_timeout: Duration,
+    is_shutdown: AtomicBool,
+    dropped_traces_count: AtomicUsize,
+    dropped_spans_count: AtomicUsize,
+    max_traces: usize,
+    max_spans_per_trace: usize,
+}
+
+impl TailSamplingProcessor {
+    /// Create a new [`TailSamplingProcessorBuilder`] exporting kept traces to
+    /// `exporter`.
+    pub fn builder<E>(exporter: E) -> TailSamplingProcessorBuilder<E>
+    where
+        E: SpanExporter + 'static,
+    {
+        TailSamplingProcessorBuilder {
+            exporter,
+            policies: Vec::new(),
+            decision_wait: DEFAULT_DECISION_WAIT,
+            max_traces: DEFAULT_MAX_TRACES,
+            max_spans_per_trace: DEFAULT_MAX_SPANS_PER_TRACE,
+        }
+    }
+
+    fn new<E>(exporter: E, policies: Vec<PolicyEvaluator>, config: TailSamplingConfig) -> Self
+    where
+        E: SpanExporter + 'static,
+    {
+        let buffer = Arc::new(Mutex::new(TraceBuffer::new(config.max_traces)));
+        let (message_sender, message_receiver) = sync_channel(64);
+
+        let mut worker = Worker {
+            exporter,
+            policies,
+            buffer: Arc::clone(&buffer),
+            decision_wait: config.decision_wait,
+        };
+        let tick = (config.decision_wait / 10).clamp(MIN_TICK_INTERVAL, MAX_TICK_INTERVAL);
+        let handle = thread::Builder::new()
+            .name("OpenTelemetry.Traces.TailSamplingProcessor".to_string())
+            .spawn(move || {
+                let _suppress_guard = Context::enter_telemetry_suppressed_scope();
+                otel_debug!(
+                    name: "TailSamplingProcessor.ThreadStarted",
+                    decision_wait_in_millisecs = config.decision_wait.as_millis(),
+                    max_traces = config.max_traces,
+                );
+                worker.run(message_receiver, tick);
+                otel_debug!(name: "TailSamplingProcessor.ThreadStopped");
+            })
+            .expect("Failed to spawn thread");
+
+        TailSamplingProcessor {
+            buffer,
+            message_sender,
+            handle: Mutex::new(Some(handle)),
+            forceflush_timeout: Duration::from_secs(5),
+            is_shutdown: AtomicBool::new(false),
+            dropped_traces_count: AtomicUsize::new(0),
+            dropped_spans_count: AtomicUsize::new(0),
+            max_traces: config.max_traces,
+            max_spans_per_trace: config.max_spans_per_trace,
+        }
+    }
+
+    fn send_and_wait(
+        &self,
+        message: impl FnOnce(SyncSender<OTelSdkResult>) -> TailSamplingMessage,
+        timeout: Duration,
+    ) -> OTelSdkResult {
+        let (sender, receiver) = sync_channel(1);
+        match self.message_sender.try_send(message(sender)) {
+            Ok(_) => receiver.recv_timeout(timeout).map_err(|err| match err {
+                RecvTimeoutError::Timeout => OTelSdkError::Timeout(timeout),
+                RecvTimeoutError::Disconnected => OTelSdkError::InternalFailure(format!("{err}")),
+            })?,
+            Err(TrySendError::Full(_)) => Err(OTelSdkError::InternalFailure(
+                "Control channel is full. This can occur if user repeatedly calls force_flush/shutdown without finishing the previous call.".into(),
+            )),
+            Err(TrySendError::Disconnected(_)) => Err(OTelSdkError::AlreadyShutdown),
+        }
+    }
+}
+
+impl SpanProcessor for TailSamplingProcessor {
+    fn on_start(&self, _span: &mut Span, _cx: &Context) {
+        // Ignored
+    }
+
+    fn on_end(&self, span: SpanData) {
+        if !span.span_context.is_sampled() {
+            return;
+        }
+        if self.is_shutdown.load(Ordering::Relaxed) {
+            otel_warn!(
+                name: "TailSamplingProcessor.OnEnd.AfterShutdown",
+                message = "Spans are being emitted even after Shutdown. This indicates incorrect lifecycle management of TracerProvider in application. Spans will not be exported."
+            );
+            return;
+        }
+
+        let Ok(mut buffer) = self.buffer.lock() else {
+            return;
+        };
+        match buffer.push(span, self.max_spans_per_trace) {
+            PushResult::Buffered => {}
+            PushResult::SpanDropped => {
+                if self.dropped_spans_count.fetch_add(1, Ordering::Relaxed) == 0 {
+                    otel_warn!(
+                        name: "TailSamplingProcessor.SpanDroppingStarted",
+                        message = "TailSamplingProcessor dropped a Span as its trace reached the maximum number of buffered spans. No further log will be emitted for further drops until Shutdown."
+                    );
+                }
+            }
+            PushResult::OldestTraceDropped => {
+                if self.dropped_traces_count.fetch_add(1, Ordering::Relaxed) == 0 {
+                    otel_warn!(
+                        name: "TailSamplingProcessor.TraceDroppingStarted",
+                        message = "TailSamplingProcessor dropped the oldest buffered trace as the maximum number of traces awaiting a decision was reached. No further log will be emitted for further drops until Shutdown."
+                    );
+                }
+            }
+        }
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.send_and_wait(TailSamplingMessage::ForceFlush, self.forceflush_timeout)
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        if self.is_shutdown.swap(true, Ordering::Relaxed) {
+            return Err(OTelSdkError::AlreadyShutdown);
+        }
+
+        let dropped_traces = self.dropped_traces_count.load(Ordering::Relaxed);
+        let dropped_spans = self.dropped_spans_count.load(Ordering::Relaxed);
+        if dropped_traces > 0 || dropped_spans > 0 {
+            otel_warn!(
+                name: "TailSamplingProcessor.SpansDropped",
+                dropped_trace_count = dropped_traces,
+                dropped_span_count = dropped_spans,
+                max_traces = self.max_traces,
+                max_spans_per_trace = self.max_spans_per_trace,
+                message = "Traces or spans were dropped as the buffer was full. Consider increasing the buffer limits or decreasing the decision wait."
+            );
+        }
+
+        let result = self.send_and_wait(TailSamplingMessage::Shutdown, timeout);
+        match &result {
+            Ok(()) => {
+                // join the background thread after receiving back the
+                // shutdown signal
+                if let Some(handle) = self.handle.lock().ok().and_then(|mut h| h.take()) {
+                    let _ = handle.join();
+                }
+            }
+            Err(err) => {
+                otel_error!(
+                    name: "TailSamplingProcessor.Shutdown.Error",
+                    error = format!("{err}")
+                );
+            }
+        }
+        result
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        let _ = self
+            .message_sender
+            .try_send(TailSamplingMessage::SetResource(Arc::new(resource.clone())));
+    }
+
+    #[cfg(feature = "experimental_self_observability")]
+    fn set_meter(&mut self, meter: &Meter) {
+        let _ = self
+            .message_sender
+            .try_send(TailSamplingMessage::SetMeter(meter.clone()));
+    }
+}
+
+/// Builder for [`TailSamplingProcessor`].
+#[derive(Debug)]
+pub struct TailSamplingProcessorBuilder<E> {
+    exporter: E,
+    policies: Vec<TailSamplingPolicy>,
+    decision_wait: Duration,
+    max_traces: usize,
+    max_spans_per_trace: usize,
+}
+
+impl<E> TailSamplingProcessorBuilder<E>
+where
+    E: SpanExporter + 'static,
+{
+    /// Add a policy. A trace is kept if any of the policies keeps it.
+    pub fn with_policy(mut self, policy: TailSamplingPolicy) -> Self {
+        self.policies.push(policy);
+        self
+    }
+
+    /// Set how long to wait after the first span of a trace ended before
+    /// deciding whether to keep the trace. The default is 10 seconds.
+    pub fn with_decision_wait(self, decision_wait: Duration) -> Self {
+        TailSamplingProcessorBuilder {
+            decision_wait,
+            ..self
+        }
+    }
+
+    /// Set the maximum number of traces awaiting a decision. When exceeded,
+    /// the oldest trace is dropped. The default is 10,000.
+    pub fn with_max_traces(self, max_traces: usize) -> Self {
+        TailSamplingProcessorBuilder {
+            max_traces: max_traces.max(1),
+            ..self
+        }
+    }
+
+    /// Set the maximum number of spans buffered per trace. Further spans of
+    /// the trace are dropped. The default is 1,000.
+    pub fn with_max_spans_per_trace(self, max_spans_per_trace: usize) -> Self {
+        TailSamplingProcessorBuilder {
+            max_spans_per_trace: max_spans_per_trace.max(1),
+            ..self
+        }
+    }
+
+    /// Build a new [`TailSamplingProcessor`].
+    ///
+    /// Policies containing an empty [`TailSamplingPolicy::And`] are ignored.
+    pub fn build(self) -> TailSamplingProcessor {
+        let policies = self
+            .policies
+            .into_iter()
+            .filter(|policy| {
+                let empty_and = policy.has_empty_and();
+                if empty_and {
+                    otel_warn!(
+                        name: "TailSamplingProcessor.EmptyAndPolicy",
+                        message = "Ignoring a tail sampling policy containing an And policy without any policies.",
+                        policy = format!("{policy:?}"),
+                    );
+                }
+                !empty_and
+            })
+            .map(Into::into)
+            .collect();
+        TailSamplingProcessor::new(
+            self.exporter,
+            policies,
+            TailSamplingConfig {
+                decision_wait: self.decision_wait,
+                max_traces: self.max_traces,
+                max_spans_per_trace: self.max_spans_per_trace,
+            },
+        )
+    }
+}
+
+#[derive(Debug)]
+struct TailSamplingConfig {
+    decision_wait: Duration,
+    max_traces: usize,
+    max_spans_per_trace: usize,
+}
+
+#[derive(Debug)]
+struct BufferedTrace {
+    first_seen: Instant,
+    spans: Vec<SpanData>,
+}
+
+#[derive(Debug, PartialEq)]
+enum PushResult {
+    Buffered,
+    SpanDropped,
+    OldestTraceDropped,
+}
+
+/// The traces awaiting a decision, and the recent decisions applied to spans
+/// ending late.
+#[derive(Debug)]
+struct TraceBuffer {
+    max_traces: usize,
+    traces: HashMap<TraceId, BufferedTrace>,
+    /// Trace ids in the order their first span was buffered.
+    arrival_order: VecDeque<TraceId>,
+    decisions: HashMap<TraceId, bool>,
+    /// Trace ids in the order they were decided, bounding `decisions`.
+    decision_order: VecDeque<TraceId>,
+    /// Spans of kept traces that ended after the decision.
+    late_spans: Vec<SpanData>,
+}
+
+impl TraceBuffer {
+    fn new(max_traces: usize) -> Self {
+        TraceBuffer {
+            max_traces,
+            traces: HashMap::new(),
+            arrival_order: VecDeque::new(),
+            decisions: HashMap::new(),
+            decision_order: VecDeque::new(),
+            late_spans: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, span: SpanData, max_spans_per_trace: usize) -> PushResult {
+        let trace_id = span.span_context.trace_id();
+        if let Some(keep) = self.decisions.get(&trace_id) {
+            if *keep {
+                self.late_spans.push(span);
+            }
+            return PushResult::Buffered;
+        }
+        if let Some(trace) = self.traces.get_mut(&trace_id) {
+            if trace.spans.len() >= max_spans_per_trace {
+                return PushResult::SpanDropped;
+            }
+            trace.spans.push(span);
+            return PushResult::Buffered;
+        }
+
+        let mut result = PushResult::Buffered;
+        if self.traces.len() >= self.max_traces {
+            if let Some(oldest) = self.arrival_order.pop_front() {
+                self.traces.remove(&oldest);
+                result = PushResult::OldestTraceDropped;
+            }
+        }
+        self.traces.insert(
+            trace_id,
+            BufferedTrace {
+                first_seen: Instant::now(),
+                spans: vec![span],
+            },
+        );
+        self.arrival_order.push_back(trace_id);
+        result
+    }
+
+    /// Removes the traces buffered for at least `decision_wait`, or all
+    /// traces if `decision_wait` is `None`.
+    fn take_ready(&mut self, decision_wait: Option<Duration>) -> Vec<(TraceId, Vec<SpanData>)> {
+        let mut ready = Vec::new();
+        while let Some(trace_id) = self.arrival_order.front() {
+            let is_ready = match (decision_wait, self.traces.get(trace_id)) {
+                (Some(wait), Some(trace)) => trace.first_seen.elapsed() >= wait,
+                _ => true,
+            };
+            if !is_ready {
+                break;
+            }
+            let trace_id = *trace_id;
+            self.arrival_order.pop_front();
+            if let Some(trace) = self.traces.remove(&trace_id) {
+                ready.push((trace_id, trace.spans));
+            }
+        }
+        ready
+    }
+
+    fn record_decision(&mut self, trace_id: TraceId, keep: bool) {
+        if self.decision_order.len() >= self.max_traces {
+            if let Some(oldest) = self.decision_order.pop_front() {
+                self.decisions.remove(&oldest);
+            }
+        }
+        self.decisions.insert(trace_id, keep);
+        self.decision_order.push_back(trace_id);
+    }
+}
+
+/// The state owned by the background thread.
+struct Worker<E> {
+    exporter: E,
+    policies: Vec<PolicyEvaluator>,
+    buffer: Arc<Mutex<TraceBuffer>>,
+    decision_wait: Duration,
+}
+
+impl<E: SpanExporter> Worker<E> {
+    fn run(&mut self, receiver: Receiver<TailSamplingMessage>, tick: Duration) {
+        loop {
+            match receiver.recv_timeout(tick) {
+                Ok(TailSamplingMessage::ForceFlush(sender)) => {
+                    otel_debug!(name: "TailSamplingProcessor.ExportingDueToForceFlush");
+                    let _ = sender.send(self.decide_and_export(None));
+                }
+                Ok(TailSamplingMessage::Shutdown(sender)) => {
+                    otel_debug!(name: "TailSamplingProcessor.ExportingDueToShutdown");
+                    let result = self.decide_and_export(None);
+                    let _ = self.exporter.shutdown();
+                    let _ = sender.send(result);
+                    break;
+                }
+                Ok(TailSamplingMessage::SetResource(resource)) => {
+                    self.exporter.set_resource(&resource);
+                }
+                #[cfg(feature = "experimental_self_observability")]
+                Ok(TailSamplingMessage::SetMeter(meter)) => {
+                    self.exporter.set_meter(&meter);
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    let _ = self.decide_and_export(Some(self.decision_wait));
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    otel_debug!(
+                        name: "TailSamplingProcessor.ThreadExiting",
+                        reason = "MessageSenderDisconnected"
+                    );
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Decides on the traces buffered for at least `decision_wait`, or on all
+    /// traces if `None`, and exports the kept ones.
+    fn decide_and_export(&mut self, decision_wait: Option<Duration>) -> OTelSdkResult {
+        let (ready, mut batch) = match self.buffer.lock() {
+            Ok(mut buffer) => (
+                buffer.take_ready(decision_wait),
+                std::mem::take(&mut buffer.late_spans),
+            ),
+            Err(_) => {
+                return Err(OTelSdkError::InternalFailure(
+                    "TailSamplingProcessor mutex poison".into(),
+                ))
+            }
+        };
+
+        let mut decisions = Vec::with_capacity(ready.len());
+        for (trace_id, spans) in ready {
+            let keep = self
+                .policies
+                .iter_mut()
+                .any(|policy| policy.should_keep(&spans));
+            if keep {
+                batch.extend(spans);
+            }
+            decisions.push((trace_id, keep));
+        }
+        if let Ok(mut buffer) = self.buffer.lock() {
+            for (trace_id, keep) in decisions {
+                buffer.record_decision(trace_id, keep);
+            }
+        }
+
+        if batch.is_empty() {
+            return Ok(());
+        }
+        let result = futures_executor::block_on(self.exporter.export(batch));
+        if let Err(err) = &result {
+            otel_error!(
+                name: "TailSamplingProcessor.ExportError",
+                error = format!("{err}")
+            );
+        }
+        result
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::trace::{InMemorySpanExporter, InMemorySpanExporterBuilder, SpanEvents, SpanLinks};
+    use opentelemetry::trace::{SpanContext, SpanId, SpanKind, Status, TraceFlags, TraceState};
+    use opentelemetry::KeyValue;
+    use std::time::SystemTime;
+
+    fn span(trace_id: u128, span_id: u64) -> SpanData {
+        let start_time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        SpanData {
+            span_context: SpanContext::new(
+                TraceId::from(trace_id),
+                SpanId::from(span_id),
+                TraceFlags::SAMPLED,
+                false,
+                TraceState::default(),
+            ),
+            parent_span_id: SpanId::INVALID,
+            parent_span_is_remote: false,
+            span_kind: SpanKind::Internal,
+            name: "span".into(),
+            start_time,
+            end_time: start_time + Duration::from_millis(10),
+            attributes: Vec::new(),
+            dropped_attributes_count: 0,
+            events: SpanEvents::default(),
+            links: SpanLinks::default(),
+            status: Status::Unset,
+            instrumentation_scope: Default::default(),
+        }
+    }
+
+    fn new_processor(
+        exporter: &InMemorySpanExporter,
+        policies: Vec<TailSamplingPolicy>,
+    ) -> TailSamplingProcessor {
+        policies
+            .into_iter()
+            .fold(
+                TailSamplingProcessor::builder(exporter.clone())
+                    .with_decision_wait(Duration::from_secs(60)),
+                |builder, policy| builder.with_policy(policy),
+            )
+            .build()
+    }
+
+    fn exported_traces(exporter: &InMemorySpanExporter) -> Vec<TraceId> {
+        let mut trace_ids: Vec<_> = exporter
+            .get_finished_spans()
+            .unwrap()
+            .iter()
+            .map(|span| span.span_context.trace_id())
+            .collect();
+        trace_ids.sort_by_key(|trace_id| trace_id.to_bytes());
+        trace_ids.dedup();
+        trace_ids
+    }
+
+    #[test]
+    fn error_status_policy_keeps_whole_trace() {
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor = new_processor(&exporter, vec![TailSamplingPolicy::ErrorStatus]);
+
+        processor.on_end(span(1, 1));
+        let mut failed = span(1, 2);
+        failed.status = Status::error("failed");
+        processor.on_end(failed);
+        processor.on_end(span(2, 3));
+        processor.force_flush().unwrap();
+
+        let exported = exporter.get_finished_spans().unwrap();
+        assert_eq!(exported.len(), 2);
+        assert_eq!(exported_traces(&exporter), vec![TraceId::from(1)]);
+        processor.shutdown().unwrap();
+    }
+
+    #[test]
+    fn latency_policy() {
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor = new_processor(
+            &exporter,
+            vec![TailSamplingPolicy::Latency(Duration::from_secs(2))],
+        );
+
+        // The trace lasts from the earliest start to the latest end.
+        let root = span(1, 1);
+        let mut child = span(1, 2);
+        child.start_time = root.start_time + Duration::from_secs(1);
+        child.end_time = root.start_time + Duration::from_secs(2);
+        processor.on_end(child);
+        processor.on_end(root);
+        processor.on_end(span(2, 3));
+        processor.force_flush().unwrap();
+
+        assert_eq!(exported_traces(&exporter), vec![TraceId::from(1)]);
+        processor.shutdown().unwrap();
+    }
+
+    #[test]
+    fn attribute_policy() {
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor = new_processor(
+            &exporter,
+            vec![
+                TailSamplingPolicy::Attribute {
+                    key: "http.route".into(),
+                    values: vec!["/checkout".into()],
+                },
+                TailSamplingPolicy::Attribute {
+                    key: "debug".into(),
+                    values: vec![],
+                },
+            ],
+        );
+
+        let mut checkout = span(1, 1);
+        checkout.attributes = vec![KeyValue::new("http.route", "/checkout")];
+        let mut other_route = span(2, 2);
+        other_route.attributes = vec![KeyValue::new("http.route", "/home")];
+        let mut debug = span(3, 3);
+        debug.attributes = vec![KeyValue::new("debug", false)];
+        processor.on_end(checkout);
+        processor.on_end(other_route);
+        processor.on_end(debug);
+        processor.force_flush().unwrap();
+
+        assert_eq!(
+            exported_traces(&exporter),
+            vec![TraceId::from(1), TraceId::from(3)]
+        );
+        processor.shutdown().unwrap();
+    }
+
+    #[test]
+    fn probabilistic_policy() {
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor = new_processor(&exporter, vec![TailSamplingPolicy::Probabilistic(0.0)]);
+        for trace_id in 1..=10 {
+            processor.on_end(span(trace_id, 1));
+        }
+        processor.force_flush().unwrap();
+        assert!(exported_traces(&exporter).is_empty());
+        processor.shutdown().unwrap();
+
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor = new_processor(&exporter, vec![TailSamplingPolicy::Probabilistic(1.0)]);
+        for trace_id in 1..=10 {
+            processor.on_end(span(trace_id, 1));
+        }
+        processor.force_flush().unwrap();
+        assert_eq!(exported_traces(&exporter).len(), 10);
+        processor.shutdown().unwrap();
+    }
+
+    #[test]
+    fn rate_limiting_policy() {
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor = new_processor(
+            &exporter,
+            vec![TailSamplingPolicy::RateLimiting {
+                spans_per_second: 3,
+            }],
+        );
+        for trace_id in 1..=3 {
+            processor.on_end(span(trace_id, 1));
+            processor.on_end(span(trace_id, 2));
+        }
+        processor.force_flush().unwrap();
+
+        // Only the first trace fits in the budget of 3 spans per second.
+        assert_eq!(exported_traces(&exporter), vec![TraceId::from(1)]);
+        processor.shutdown().unwrap();
+    }
+
+    #[test]
+    fn and_policy() {
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor = new_processor(
+            &exporter,
+            vec![TailSamplingPolicy::And(vec![
+                TailSamplingPolicy::ErrorStatus,
+                TailSamplingPolicy::Attribute {
+                    key: "tenant".into(),
+                    values: vec!["premium".into()],
+                },
+            ])],
+        );
+
+        let mut premium_error = span(1, 1);
+        premium_error.status = Status::error("failed");
+        premium_error.attributes = vec![KeyValue::new("tenant", "premium")];
+        let mut error = span(2, 2);
+        error.status = Status::error("failed");
+        let mut premium = span(3, 3);
+        premium.attributes = vec![KeyValue::new("tenant", "premium")];
+        processor.on_end(premium_error);
+        processor.on_end(error);
+        processor.on_end(premium);
+        processor.force_flush().unwrap();
+
+        assert_eq!(exported_traces(&exporter), vec![TraceId::from(1)]);
+        processor.shutdown().unwrap();
+    }
+
+    #[test]
+    fn and_policy_rate_limits_matched_traces_only() {
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor = new_processor(
+            &exporter,
+            vec![TailSamplingPolicy::And(vec![
+                TailSamplingPolicy::RateLimiting {
+                    spans_per_second: 1,
+                },
+                TailSamplingPolicy::ErrorStatus,
+            ])],
+        );
+
+        let mut error = span(2, 2);
+        error.status = Status::error("failed");
+        processor.on_end(span(1, 1));
+        processor.on_end(error);
+        processor.force_flush().unwrap();
+
+        // The first trace is rejected by the error status policy, so it does
+        // not use up the budget of the rate limiter.
+        assert_eq!(exported_traces(&exporter), vec![TraceId::from(2)]);
+        processor.shutdown().unwrap();
+    }
+
+    #[test]
+    fn empty_and_policy_is_ignored() {
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor = new_processor(
+            &exporter,
+            vec![
+                TailSamplingPolicy::And(vec![]),
+                TailSamplingPolicy::And(vec![
+                    TailSamplingPolicy::AlwaysSample,
+                    TailSamplingPolicy::And(vec![]),
+                ]),
+                TailSamplingPolicy::ErrorStatus,
+            ],
+        );
+
+        let mut failed = span(1, 1);
+        failed.status = Status::error("failed");
+        processor.on_end(failed);
+        processor.on_end(span(2, 2));
+        processor.force_flush().unwrap();
+
+        assert_eq!(exported_traces(&exporter), vec![TraceId::from(1)]);
+        processor.shutdown().unwrap();
+    }
+
+    #[test]
+    fn no_policy_keeps_nothing() {
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor = new_processor(&exporter, vec![]);
+        processor.on_end(span(1, 1));
+        processor.force_flush().unwrap();
+        assert!(exported_traces(&exporter).is_empty());
+        processor.shutdown().unwrap();
+    }
+
+    #[test]
+    fn unsampled_spans_are_ignored() {
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor = new_processor(&exporter, vec![TailSamplingPolicy::AlwaysSample]);
+        let mut unsampled = span(1, 1);
+        unsampled.span_context = SpanContext::new(
+            TraceId::from(1),
+            SpanId::from(1),
+            TraceFlags::default(),
+            false,
+            TraceState::default(),
+        );
+        processor.on_end(unsampled);
+        processor.force_flush().unwrap();
+        assert!(exported_traces(&exporter).is_empty());
+        processor.shutdown().unwrap();
+    }
+
+    #[test]
+    fn traces_are_decided_after_decision_wait() {
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor = TailSamplingProcessor::builder(exporter.clone())
+            .with_policy(TailSamplingPolicy::AlwaysSample)
+            .with_decision_wait(Duration::from_millis(50))
+            .build();
+
+        processor.on_end(span(1, 1));
+        assert!(exported_traces(&exporter).is_empty());
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while exported_traces(&exporter).is_empty() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(exported_traces(&exporter), vec![TraceId::from(1)]);
+        processor.shutdown().unwrap();
+    }
+
+    #[test]
+    fn late_spans_follow_the_decision() {
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor = new_processor(&exporter, vec![TailSamplingPolicy::ErrorStatus]);
+
+        let mut failed = span(1, 1);
+        failed.status = Status::error("failed");
+        processor.on_end(failed);
+        processor.on_end(span(2, 2));
+        processor.force_flush().unwrap();
+        exporter.reset();
+
+        processor.on_end(span(1, 3));
+        processor.on_end(span(2, 4));
+        processor.force_flush().unwrap();
+
+        let exported = exporter.get_finished_spans().unwrap();
+        assert_eq!(exported.len(), 1);
+        assert_eq!(exported[0].span_context.span_id(), SpanId::from(3));
+        processor.shutdown().unwrap();
+    }
+
+    #[test]
+    fn buffer_limits() {
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor = TailSamplingProcessor::builder(exporter.clone())
+            .with_policy(TailSamplingPolicy::AlwaysSample)
+            .with_decision_wait(Duration::from_secs(60))
+            .with_max_traces(2)
+            .with_max_spans_per_trace(2)
+            .build();
+
+        for span_id in 1..=3 {
+            processor.on_end(span(1, span_id));
+        }
+        processor.on_end(span(2, 4));
+        // Drops the oldest trace, 1.
+        processor.on_end(span(3, 5));
+        processor.force_flush().unwrap();
+
+        assert_eq!(
+            exported_traces(&exporter),
+            vec![TraceId::from(2), TraceId::from(3)]
+        );
+        assert_eq!(processor.dropped_spans_count.load(Ordering::Relaxed), 1);
+        assert_eq!(processor.dropped_traces_count.load(Ordering::Relaxed), 1);
+        processor.shutdown().unwrap();
+    }
+
+    #[test]
+    fn shutdown_exports_buffered_traces() {
+        let exporter = InMemorySpanExporterBuilder::new()
+            .keep_records_on_shutdown()
+            .build();
+        let processor = new_processor(&exporter, vec![TailSamplingPolicy::AlwaysSample]);
+        processor.on_end(span(1, 1));
+
+        processor.shutdown().unwrap();
+
+        assert_eq!(exported_traces(&exporter), vec![TraceId::from(1)]);
+        assert!(exporter.is_shutdown_called());
+        assert!(matches!(
+            processor.shutdown(),
+            Err(OTelSdkError::AlreadyShutdown)
+        ));
+    }
+
+    #[test]
+    fn with_tracer_provider() {
+        use crate::trace::SdkTracerProvider;
+        use opentelemetry::trace::{Span as _, Tracer, TracerProvider};
+
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let provider = SdkTracerProvider::builder()
+            .with_span_processor(new_processor(
+                &exporter,
+                vec![TailSamplingPolicy::ErrorStatus],
+            ))
+            .build();
+        let tracer = provider.tracer("test");
+
+        tracer.in_span("ok", |_| {
+            tracer.start("child").end();
+        });
+        tracer.in_span("failed", |_| {
+            let mut child = tracer.start("child");
+            child.set_status(Status::error("failed"));
+            child.end();
+        });
+        provider.force_flush().unwrap();
+
+        let mut names: Vec<_> = exporter
+            .get_finished_spans()
+            .unwrap()
+            .into_iter()
+            .map(|span| span.name)
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["child", "failed"]);
+        provider.shutdown().unwrap();
+    }
+}
@@ -0,0 +1,198 @@
+use std::time::{Duration, Instant};
+
+use opentelemetry::trace::Status;
+use opentelemetry::{Key, Value};
+
+use crate::trace::sampler::sample_based_on_probability;
+use crate::trace::{SamplingDecision, SpanData};
+
+/// A policy deciding whether a buffered trace is kept by the
+/// [`TailSamplingProcessor`].
+///
+/// Policies are evaluated once the decision wait of a trace elapses, against
+/// all spans of the trace that ended until then.
+///
+/// [`TailSamplingProcessor`]: super::TailSamplingProcessor
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum TailSamplingPolicy {
+    /// Keep every trace.
+    AlwaysSample,
+    /// Keep traces with at least one span whose status is [`Status::Error`].
+    ErrorStatus,
+    /// Keep traces lasting at least the given duration, measured from the
+    /// earliest span start to the latest span end.
+    Latency(Duration),
+    /// Keep traces with at least one span having the attribute `key` set to
+    /// one of `values`. Traces with a span having the attribute at all are
+    /// kept if `values` is empty.
+    Attribute {
+        /// The attribute key to match.
+        key: Key,
+        /// The attribute values to match.
+        values: Vec<Value>,
+    },
+    /// Keep the given fraction of traces, based on their trace id. Fractions
+    /// >= 1 keep all traces, fractions <= 0 none.
+    Probabilistic(f64),
+    /// Keep traces as long as the spans kept in the current second stay
+    /// within `spans_per_second`.
+    RateLimiting {
+        /// The maximum number of spans kept per second.
+        spans_per_second: u32,
+    },
+    /// Keep traces matched by all the given policies. The list must not be
+    /// empty, policies containing an empty `And` are ignored when the
+    /// [`TailSamplingProcessor`] is built.
+    ///
+    /// [`TailSamplingProcessor`]: super::TailSamplingProcessor
+    And(Vec<TailSamplingPolicy>),
+}
+
+impl TailSamplingPolicy {
+    /// Returns whether the policy, including its nested policies, contains
+    /// an empty [`And`](TailSamplingPolicy::And).
+    pub(super) fn has_empty_and(&self) -> bool {
+        match self {
+            TailSamplingPolicy::And(policies) => {
+                policies.is_empty() || policies.iter().any(TailSamplingPolicy::has_empty_and)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The evaluation state of a [`TailSamplingPolicy`].
+#[derive(Debug)]
+pub(super) enum PolicyEvaluator {
+    AlwaysSample,
+    ErrorStatus,
+    Latency(Duration),
+    Attribute { key: Key, values: Vec<Value> },
+    Probabilistic(f64),
+    RateLimiting(RateLimiter),
+    And(Vec<PolicyEvaluator>),
+}
+
+impl From<TailSamplingPolicy> for PolicyEvaluator {
+    fn from(policy: TailSamplingPolicy) -> Self {
+        match policy {
+            TailSamplingPolicy::AlwaysSample => PolicyEvaluator::AlwaysSample,
+            TailSamplingPolicy::ErrorStatus => PolicyEvaluator::ErrorStatus,
+            TailSamplingPolicy::Latency(threshold) => PolicyEvaluator::Latency(threshold),
+            TailSamplingPolicy::Attribute { key, values } => {
+                PolicyEvaluator::Attribute { key, values }
+            }
+            TailSamplingPolicy::Probabilistic(ratio) => PolicyEvaluator::Probabilistic(ratio),
+            TailSamplingPolicy::RateLimiting { spans_per_second } => {
+                PolicyEvaluator::RateLimiting(RateLimiter::new(spans_per_second))
+            }
+            TailSamplingPolicy::And(policies) => {
+                PolicyEvaluator::And(policies.into_iter().map(Into::into).collect())
+            }
+        }
+    }
+}
+
+impl PolicyEvaluator {
+    /// Returns whether the trace made of `spans` is kept. `spans` is never
+    /// empty.
+    ///
+    /// Rate limiters only take the spans of the trace once the whole policy
+    /// matched, so a rate limiter in an [`And`](PolicyEvaluator::And) does not
+    /// use up its allowance on traces rejected by the other policies.
+    pub(super) fn should_keep(&mut self, spans: &[SpanData]) -> bool {
+        if self.matches(spans) {
+            self.acquire(spans.len());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns whether the trace made of `spans` matches, without taking
+    /// from the rate limiters.
+    fn matches(&mut self, spans: &[SpanData]) -> bool {
+        match self {
+            PolicyEvaluator::AlwaysSample => true,
+            PolicyEvaluator::ErrorStatus => spans
+                .iter()
+                .any(|span| matches!(span.status, Status::Error { .. })),
+            PolicyEvaluator::Latency(threshold) => trace_duration(spans) >= *threshold,
+            PolicyEvaluator::Attribute { key, values } => spans.iter().any(|span| {
+                span.attributes
+                    .iter()
+                    .any(|kv| kv.key == *key && (values.is_empty() || values.contains(&kv.value)))
+            }),
+            PolicyEvaluator::Probabilistic(ratio) => {
+                sample_based_on_probability(ratio, spans[0].span_context.trace_id())
+                    == SamplingDecision::RecordAndSample
+            }
+            PolicyEvaluator::RateLimiting(limiter) => limiter.has_room(spans.len()),
+            PolicyEvaluator::And(policies) => {
+                policies.iter_mut().all(|policy| policy.matches(spans))
+            }
+        }
+    }
+
+    /// Takes `spans` spans of a matched trace from the rate limiters.
+    fn acquire(&mut self, spans: usize) {
+        match self {
+            PolicyEvaluator::RateLimiting(limiter) => limiter.acquire(spans),
+            PolicyEvaluator::And(policies) => {
+                for policy in policies {
+                    policy.acquire(spans);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn trace_duration(spans: &[SpanData]) -> Duration {
+    let start = spans.iter().map(|span| span.start_time).min();
+    let end = spans.iter().map(|span| span.end_time).max();
+    match (start, end) {
+        (Some(start), Some(end)) => end.duration_since(start).unwrap_or_default(),
+        _ => Duration::ZERO,
+    }
+}
+
+/// Limits the number of spans kept per one second window.
+#[derive(Debug)]
+pub(super) struct RateLimiter {
+    spans_per_second: u64,
+    window_start: Option<Instant>,
+    spans_in_window: u64,
+}
+
+impl RateLimiter {
+    fn new(spans_per_second: u32) -> Self {
+        RateLimiter {
+            spans_per_second: spans_per_second.into(),
+            window_start: None,
+            spans_in_window: 0,
+        }
+    }
+
+    /// Returns whether `spans` more spans fit in the current window.
+    fn has_room(&mut self, spans: usize) -> bool {
+        let now = Instant::now();
+        match self.window_start {
+            Some(start) if now.duration_since(start) < Duration::from_secs(1) => {}
+            _ => {
+                self.window_start = Some(now);
+                self.spans_in_window = 0;
+            }
+        }
+        self.spans_in_window + spans as u64 <= self.spans_per_second
+    }
+
+    /// Counts `spans` spans in the current window, which [`has_room`] checked
+    /// they fit in.
+    ///
+    /// [`has_room`]: RateLimiter::has_room
+    fn acquire(&mut self, spans: usize) {
+        self.spans_in_window += spans as u64;
+    }
+}
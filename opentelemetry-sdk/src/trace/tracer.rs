@@ -10,6 +10,7 @@
 use crate::trace::{
     provider::SdkTracerProvider,
     span::{Span, SpanData},
+    span_limit::truncate_attributes,
     SamplingDecision, SpanEvents, SpanLimits, SpanLinks,
 };
 use opentelemetry::{
@@ -70,6 +71,9 @@ impl SdkTracer {
             .saturating_sub(span_attributes_limit);
         attribute_options.truncate(span_attributes_limit);
         let dropped_attributes_count = dropped_attributes_count as u32;
+        let value_length_limit = self.provider().config().max_attribute_value_length;
+        let mut truncated_attributes_count =
+            truncate_attributes(&mut attribute_options, value_length_limit);
 
         // Links are available as Option<Vec<Link>> in the builder
         // If it is None, then there are no links to process.
@@ -90,6 +94,8 @@ impl SdkTracer {
                     link.attributes.len().saturating_sub(link_attributes_limit);
                 link.attributes.truncate(link_attributes_limit);
                 link.dropped_attributes_count = dropped_attributes_count as u32;
+                truncated_attributes_count +=
+                    truncate_attributes(&mut link.attributes, value_length_limit);
             }
             SpanLinks {
                 links,
@@ -119,6 +125,8 @@ impl SdkTracer {
                     .saturating_sub(event_attributes_limit);
                 event.attributes.truncate(event_attributes_limit);
                 event.dropped_attributes_count = dropped_attributes_count as u32;
+                truncated_attributes_count +=
+                    truncate_attributes(&mut event.attributes, value_length_limit);
             }
             SpanEvents {
                 events,
@@ -138,6 +146,7 @@ impl SdkTracer {
                 end_time: start_time,
                 attributes: attribute_options,
                 dropped_attributes_count,
+                truncated_attributes_count,
                 events: span_events,
                 links: span_links,
                 status: Status::default(),
@@ -199,7 +208,8 @@ impl opentelemetry::trace::Tracer for SdkTracer {
             trace_id = config.id_generator.new_trace_id();
         };
 
-        let samplings_result = config.sampler.should_sample(
+        let samplings_result = config.sampler.should_sample_with_scope(
+            &self.scope,
             Some(parent_cx),
             trace_id,
             &builder.name,
@@ -208,7 +218,13 @@ impl opentelemetry::trace::Tracer for SdkTracer {
             builder.links.as_deref().unwrap_or(&[]),
         );
 
-        let trace_flags = parent_cx.span().span_context().trace_flags();
+        // Children inherit the flags of their parent, root spans are flagged as
+        // random when enabled and their trace id is.
+        let trace_flags = match &parent_span {
+            Some(parent) => parent.span_context().trace_flags(),
+            None => TraceFlags::default()
+                .with_random(config.random_trace_flag && config.id_generator.trace_id_is_random()),
+        };
         let trace_state = samplings_result.trace_state;
         let span_limits = config.span_limits;
         // Build optional inner context, `None` if not recording.
@@ -246,13 +262,23 @@ impl opentelemetry::trace::Tracer for SdkTracer {
                 )
             }
             SamplingDecision::Drop => {
-                let span_context =
-                    SpanContext::new(trace_id, span_id, TraceFlags::default(), false, trace_state);
+                let span_context = SpanContext::new(
+                    trace_id,
+                    span_id,
+                    trace_flags.with_sampled(false),
+                    false,
+                    trace_state,
+                );
                 Span::new(span_context, None, self.clone(), span_limits)
             }
         };
 
         if span.is_recording() {
+            #[cfg(feature = "experimental_self_observability")]
+            if let Some(metrics) = provider.metrics() {
+                metrics.span_started(span.span_context().is_sampled());
+            }
+
             // Call `on_start` for all processors
             for processor in provider.span_processors() {
                 processor.on_start(&mut span, parent_cx)
@@ -266,16 +292,22 @@ impl opentelemetry::trace::Tracer for SdkTracer {
 #[cfg(all(test, feature = "testing", feature = "trace"))]
 mod tests {
     use crate::{
+        propagation::TraceContextPropagator,
         testing::trace::TestSpan,
-        trace::{Sampler, SamplingDecision, SamplingResult, ShouldSample},
+        trace::{
+            ConsistentParentBased, ConsistentProbabilityBased, Sampler, SamplingDecision,
+            SamplingResult, ShouldSample,
+        },
     };
     use opentelemetry::{
+        propagation::TextMapPropagator,
         trace::{
             Link, Span, SpanContext, SpanId, SpanKind, TraceContextExt, TraceFlags, TraceId,
             TraceState, Tracer, TracerProvider,
         },
         Context, KeyValue,
     };
+    use std::collections::HashMap;
 
     #[derive(Clone, Debug)]
     struct TestSampler {}
@@ -369,4 +401,58 @@ mod tests {
 
         assert!(!span.span_context().is_sampled());
     }
+
+    #[test]
+    fn random_trace_flag_is_opt_in() {
+        let root_flags = |builder: crate::trace::TracerProviderBuilder| {
+            builder
+                .build()
+                .tracer("test")
+                .start("root")
+                .span_context()
+                .trace_flags()
+        };
+
+        assert_eq!(
+            root_flags(crate::trace::SdkTracerProvider::builder()),
+            TraceFlags::SAMPLED
+        );
+        assert_eq!(
+            root_flags(crate::trace::SdkTracerProvider::builder().with_random_trace_flag(true)),
+            TraceFlags::SAMPLED | TraceFlags::RANDOM
+        );
+    }
+
+    #[test]
+    fn consistent_threshold_survives_propagation() {
+        let sampler = || ConsistentParentBased::new(ConsistentProbabilityBased::new(1.0));
+        let upstream = crate::trace::SdkTracerProvider::builder()
+            .with_sampler(sampler())
+            .with_random_trace_flag(true)
+            .build();
+        let downstream = crate::trace::SdkTracerProvider::builder()
+            .with_sampler(sampler())
+            .build();
+        let propagator = TraceContextPropagator::new();
+
+        let root = upstream.tracer("upstream").start("root");
+        assert!(root.span_context().trace_flags().is_random());
+        assert_eq!(root.span_context().trace_state().get("ot"), Some("th:0"));
+        let child = upstream
+            .tracer("upstream")
+            .start_with_context("child", &Context::new().with_span(root));
+        assert!(child.span_context().trace_flags().is_random());
+
+        let mut carrier = HashMap::new();
+        propagator.inject_context(
+            &Context::new().with_remote_span_context(child.span_context().clone()),
+            &mut carrier,
+        );
+        let remote_cx = propagator.extract(&carrier);
+        let span = downstream
+            .tracer("downstream")
+            .start_with_context("server", &remote_cx);
+        assert!(span.span_context().trace_flags().is_random());
+        assert_eq!(span.span_context().trace_state().get("ot"), Some("th:0"));
+    }
 }
@@ -7,3 +7,93 @@ pub fn tokio_interval_stream(
 ) -> tokio_stream::wrappers::IntervalStream {
     tokio_stream::wrappers::IntervalStream::new(tokio::time::interval(period))
 }
+
+/// The instrumentation scope of the telemetry the SDK reports about itself.
+#[cfg(any(
+    feature = "metrics",
+    all(
+        feature = "experimental_self_observability",
+        any(feature = "trace", feature = "logs")
+    )
+))]
+pub(crate) fn sdk_scope() -> opentelemetry::InstrumentationScope {
+    opentelemetry::InstrumentationScope::builder(env!("CARGO_PKG_NAME"))
+        .with_version(env!("CARGO_PKG_VERSION"))
+        .build()
+}
+
+/// Returns `value` truncated to at most `limit` characters, or `None` if it is
+/// within the limit. Characters are never split.
+#[cfg(any(feature = "trace", feature = "logs"))]
+pub(crate) fn truncate_str(value: &str, limit: usize) -> Option<&str> {
+    value
+        .char_indices()
+        .nth(limit)
+        .map(|(index, _)| &value[..index])
+}
+
+/// Reads the attribute value length limit of a signal from the environment
+/// variable `var`, falling back to `OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT` if `var`
+/// is unset or invalid.
+#[cfg(any(feature = "trace", feature = "logs"))]
+pub(crate) fn attribute_value_length_limit_from_env(var: &str) -> Option<u32> {
+    [var, "OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT"]
+        .into_iter()
+        .find_map(|var| std::env::var(var).ok()?.parse().ok())
+}
+
+#[cfg(all(test, any(feature = "trace", feature = "logs")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_str_keeps_characters_whole() {
+        assert_eq!(truncate_str("hello", 10), None);
+        assert_eq!(truncate_str("hello", 5), None);
+        assert_eq!(truncate_str("hello", 3), Some("hel"));
+        assert_eq!(truncate_str("héllo", 2), Some("hé"));
+        assert_eq!(truncate_str("日本語", 1), Some("日"));
+        assert_eq!(truncate_str("🦀🦀", 1), Some("🦀"));
+        assert_eq!(truncate_str("abc", 0), Some(""));
+    }
+
+    #[test]
+    fn attribute_value_length_limit_env_precedence() {
+        temp_env::with_vars(
+            [
+                ("OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT", Some("10")),
+                ("OTEL_SPAN_ATTRIBUTE_VALUE_LENGTH_LIMIT", Some("5")),
+            ],
+            || {
+                assert_eq!(
+                    attribute_value_length_limit_from_env("OTEL_SPAN_ATTRIBUTE_VALUE_LENGTH_LIMIT"),
+                    Some(5)
+                );
+                assert_eq!(
+                    attribute_value_length_limit_from_env(
+                        "OTEL_LOGRECORD_ATTRIBUTE_VALUE_LENGTH_LIMIT"
+                    ),
+                    Some(10)
+                );
+            },
+        );
+        temp_env::with_var("OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT", Some("invalid"), || {
+            assert_eq!(
+                attribute_value_length_limit_from_env("OTEL_SPAN_ATTRIBUTE_VALUE_LENGTH_LIMIT"),
+                None
+            );
+        });
+        temp_env::with_vars(
+            [
+                ("OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT", Some("10")),
+                ("OTEL_SPAN_ATTRIBUTE_VALUE_LENGTH_LIMIT", Some("invalid")),
+            ],
+            || {
+                assert_eq!(
+                    attribute_value_length_limit_from_env("OTEL_SPAN_ATTRIBUTE_VALUE_LENGTH_LIMIT"),
+                    Some(10)
+                );
+            },
+        );
+    }
+}
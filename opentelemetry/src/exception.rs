@@ -0,0 +1,118 @@
+//! Attributes describing errors, following the [exception semantic conventions].
+//!
+//! Shared by [`Span::record_exception`] and the log appenders recording errors,
+//! so spans and logs describe an error the same way.
+//!
+//! [exception semantic conventions]: https://opentelemetry.io/docs/specs/semconv/exceptions/
+//! [`Span::record_exception`]: crate::trace::Span::record_exception
+use crate::{Key, KeyValue};
+use std::backtrace::Backtrace;
+use std::error::Error;
+use std::fmt::Write;
+
+const EXCEPTION_TYPE: Key = Key::from_static_str("exception.type");
+const EXCEPTION_MESSAGE: Key = Key::from_static_str("exception.message");
+const EXCEPTION_STACKTRACE: Key = Key::from_static_str("exception.stacktrace");
+
+/// Returns the `exception.type`, `exception.message` and
+/// `exception.stacktrace` attributes of `err`.
+///
+/// `exception.type` is the static type name of `err`, it is omitted for trait
+/// objects such as `dyn Error`, whose concrete type is not known.
+/// `exception.stacktrace` is only present if `err` has a
+/// [source](Error::source) or a backtrace is given, and holds the message of
+/// `err`, the messages of its sources and the backtrace.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::exception;
+///
+/// if let Err(err) = "forty-two".parse::<u32>() {
+///     let attributes = exception::attributes(&err, None);
+///     assert_eq!(attributes[0].value.as_str(), "core::num::error::ParseIntError");
+/// }
+/// ```
+pub fn attributes<E>(err: &E, backtrace: Option<&Backtrace>) -> Vec<KeyValue>
+where
+    E: Error + ?Sized,
+{
+    let mut attributes = Vec::with_capacity(3);
+    let type_name = std::any::type_name::<E>();
+    if !type_name.starts_with("dyn ") {
+        attributes.push(KeyValue::new(EXCEPTION_TYPE, type_name));
+    }
+    attributes.push(KeyValue::new(EXCEPTION_MESSAGE, err.to_string()));
+    if let Some(stacktrace) = stacktrace(err, backtrace) {
+        attributes.push(KeyValue::new(EXCEPTION_STACKTRACE, stacktrace));
+    }
+    attributes
+}
+
+/// Formats the message and source chain of `err` followed by `backtrace`, if
+/// `err` has a source or a backtrace is given.
+fn stacktrace<E>(err: &E, backtrace: Option<&Backtrace>) -> Option<String>
+where
+    E: Error + ?Sized,
+{
+    if err.source().is_none() && backtrace.is_none() {
+        return None;
+    }
+
+    let mut stacktrace = err.to_string();
+    let mut source = err.source();
+    if source.is_some() {
+        stacktrace.push_str("\n\nCaused by:");
+    }
+    let mut index = 0;
+    while let Some(err) = source {
+        let _ = write!(stacktrace, "\n    {index}: {err}");
+        source = err.source();
+        index += 1;
+    }
+    if let Some(backtrace) = backtrace {
+        let _ = write!(stacktrace, "\n\nStack backtrace:\n{backtrace}");
+    }
+    Some(stacktrace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestError;
+
+    #[test]
+    fn stacktrace_formats_source_chain() {
+        let err = TestError::new("request failed").with_source(
+            TestError::new("connection reset").with_source(TestError::new("broken pipe")),
+        );
+
+        assert_eq!(
+            stacktrace(&err, None).as_deref(),
+            Some("request failed\n\nCaused by:\n    0: connection reset\n    1: broken pipe")
+        );
+        assert_eq!(stacktrace(&TestError::new("broken pipe"), None), None);
+
+        let backtrace = Backtrace::disabled();
+        assert_eq!(
+            stacktrace(&TestError::new("oops"), Some(&backtrace)),
+            Some(format!("oops\n\nStack backtrace:\n{backtrace}"))
+        );
+    }
+
+    #[test]
+    fn attributes_omit_unknown_type() {
+        let err = TestError::new("request failed");
+        assert_eq!(
+            attributes(&err, None),
+            vec![
+                KeyValue::new(EXCEPTION_TYPE, std::any::type_name::<TestError>()),
+                KeyValue::new(EXCEPTION_MESSAGE, "request failed"),
+            ]
+        );
+        assert_eq!(
+            attributes(&err as &dyn Error, None),
+            vec![KeyValue::new(EXCEPTION_MESSAGE, "request failed")]
+        );
+    }
+}
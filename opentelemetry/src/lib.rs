@@ -253,6 +253,10 @@ pub use trace_context::{SpanId, TraceFlags, TraceId};
 
 mod common;
 
+#[cfg(any(feature = "trace", feature = "logs"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "trace", feature = "logs"))))]
+pub mod exception;
+
 #[cfg(any(feature = "testing", test))]
 #[doc(hidden)]
 pub mod testing;
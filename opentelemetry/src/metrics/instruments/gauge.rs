@@ -2,7 +2,7 @@ use crate::KeyValue;
 use core::fmt;
 use std::sync::Arc;
 
-use super::SyncInstrument;
+use super::{BoundAttributes, SyncInstrument};
 
 /// An instrument that records independent values
 ///
@@ -32,6 +32,11 @@ impl<T> Gauge<T> {
     pub fn record(&self, value: T, attributes: &[KeyValue]) {
         self.0.measure(value, attributes)
     }
+
+    /// Records an independent value, with a pre-bound attribute set.
+    pub fn record_bound(&self, value: T, attributes: &BoundAttributes) {
+        self.0.measure_bound(value, attributes)
+    }
 }
 
 /// An async instrument that records independent readings.
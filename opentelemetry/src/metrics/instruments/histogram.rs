@@ -2,7 +2,7 @@ use crate::KeyValue;
 use core::fmt;
 use std::sync::Arc;
 
-use super::SyncInstrument;
+use super::{BoundAttributes, SyncInstrument};
 
 /// An instrument that records a distribution of values.
 ///
@@ -32,4 +32,9 @@ impl<T> Histogram<T> {
     pub fn record(&self, value: T, attributes: &[KeyValue]) {
         self.0.measure(value, attributes)
     }
+
+    /// Adds an additional value to the distribution, with a pre-bound attribute set.
+    pub fn record_bound(&self, value: T, attributes: &BoundAttributes) {
+        self.0.measure_bound(value, attributes)
+    }
 }
@@ -2,7 +2,7 @@ use crate::KeyValue;
 use core::fmt;
 use std::sync::Arc;
 
-use super::SyncInstrument;
+use super::{BoundAttributes, SyncInstrument};
 
 /// An instrument that records increasing or decreasing values.
 ///
@@ -35,6 +35,11 @@ impl<T> UpDownCounter<T> {
     pub fn add(&self, value: T, attributes: &[KeyValue]) {
         self.0.measure(value, attributes)
     }
+
+    /// Records an increment or decrement to the counter, with a pre-bound attribute set.
+    pub fn add_bound(&self, value: T, attributes: &BoundAttributes) {
+        self.0.measure_bound(value, attributes)
+    }
 }
 
 /// An async instrument that records increasing or decreasing values.
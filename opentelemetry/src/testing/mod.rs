@@ -1,3 +1,39 @@
 #[cfg(feature = "trace")]
 #[cfg_attr(docsrs, doc(cfg(feature = "trace")))]
 pub mod trace;
+
+use std::error::Error;
+use std::fmt;
+
+/// An error with an optional source, to test how errors are recorded.
+#[derive(Debug)]
+pub struct TestError {
+    message: &'static str,
+    source: Option<Box<TestError>>,
+}
+
+impl TestError {
+    pub fn new(message: &'static str) -> Self {
+        TestError {
+            message,
+            source: None,
+        }
+    }
+
+    pub fn with_source(mut self, source: TestError) -> Self {
+        self.source = Some(Box::new(source));
+        self
+    }
+}
+
+impl fmt::Display for TestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message)
+    }
+}
+
+impl Error for TestError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_deref().map(|err| err as _)
+    }
+}
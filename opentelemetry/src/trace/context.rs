@@ -4,7 +4,7 @@ use crate::{
     trace::{Span, SpanContext, Status},
     Context, ContextGuard, KeyValue,
 };
-use std::{borrow::Cow, error::Error, sync::Mutex};
+use std::{backtrace::Backtrace, borrow::Cow, error::Error, sync::Mutex};
 
 // Re-export for compatability. This used to be contained here.
 pub use crate::context::{FutureExt, WithContext};
@@ -93,6 +93,17 @@ impl SpanRef<'_> {
         self.with_inner_mut(|inner| inner.record_error(err))
     }
 
+    /// Record an exception as an event for this span, see
+    /// [`Span::record_exception`].
+    ///
+    /// If this span is not being recorded then this method does nothing.
+    pub fn record_exception<E>(&self, err: &E, backtrace: Option<&Backtrace>)
+    where
+        E: Error + ?Sized,
+    {
+        self.with_inner_mut(|inner| inner.record_exception(err, backtrace))
+    }
+
     /// Record an event with a timestamp in the context this span.
     ///
     /// Note that the OpenTelemetry project documents certain "[standard
@@ -1,4 +1,5 @@
-use crate::{trace::SpanContext, KeyValue};
+use crate::{exception, trace::SpanContext, KeyValue};
+use std::backtrace::Backtrace;
 use std::borrow::Cow;
 use std::error::Error;
 use std::time::SystemTime;
@@ -76,6 +77,46 @@ pub trait Span {
         }
     }
 
+    /// Record an exception as an event for this span, following the
+    /// [exception semantic conventions].
+    ///
+    /// The `exception` event carries the type name of `err` in `exception.type`
+    /// and its message in `exception.message`. If `err` has a
+    /// [source](Error::source) or a backtrace is given, `exception.stacktrace`
+    /// holds the message of `err`, the messages of its sources and the
+    /// backtrace.
+    ///
+    /// The type name is the static type of `err`, pass the concrete error type
+    /// rather than a `dyn Error`, for which `exception.type` is omitted. See
+    /// [`exception::attributes`].
+    ///
+    /// An additional call to [Span::set_status] is required if the status of the
+    /// span should be set to error, as this method does not change the span status.
+    ///
+    /// If this span is not being recorded then this method does nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use opentelemetry::{global, trace::{Span, Tracer}};
+    /// use std::backtrace::Backtrace;
+    ///
+    /// let mut span = global::tracer("example").start("parse");
+    /// if let Err(err) = "forty-two".parse::<u32>() {
+    ///     span.record_exception(&err, Some(&Backtrace::capture()));
+    /// }
+    /// ```
+    ///
+    /// [exception semantic conventions]: https://opentelemetry.io/docs/specs/semconv/exceptions/exceptions-spans/
+    fn record_exception<E>(&mut self, err: &E, backtrace: Option<&Backtrace>)
+    where
+        E: Error + ?Sized,
+    {
+        if self.is_recording() {
+            self.add_event("exception", exception::attributes(err, backtrace));
+        }
+    }
+
     /// Record an event with a timestamp in the context this span.
     ///
     /// Note that the OpenTelemetry project documents certain "[standard
@@ -32,6 +32,15 @@ impl TraceFlags {
     /// [W3C TraceContext specification]: https://www.w3.org/TR/trace-context/#sampled-flag
     pub const SAMPLED: TraceFlags = TraceFlags(0x01);
 
+    /// Trace flags with the `random` flag set to `1`.
+    ///
+    /// The flag indicates that at least the 56 least significant bits of the
+    /// trace id are random. See the `random-trace-id` section of the [W3C
+    /// TraceContext Level 2 specification] for details.
+    ///
+    /// [W3C TraceContext Level 2 specification]: https://www.w3.org/TR/trace-context-2/#random-trace-id-flag
+    pub const RANDOM: TraceFlags = TraceFlags(0x02);
+
     /// Construct new trace flags
     pub const fn new(flags: u8) -> Self {
         TraceFlags(flags)
@@ -51,6 +60,20 @@ impl TraceFlags {
         }
     }
 
+    /// Returns `true` if the `random` flag is set
+    pub fn is_random(&self) -> bool {
+        (*self & TraceFlags::RANDOM) == TraceFlags::RANDOM
+    }
+
+    /// Returns copy of the current flags with the `random` flag set.
+    pub fn with_random(&self, random: bool) -> Self {
+        if random {
+            *self | TraceFlags::RANDOM
+        } else {
+            *self & !TraceFlags::RANDOM
+        }
+    }
+
     /// Returns the flags as a `u8`
     pub fn to_u8(self) -> u8 {
         self.0
@@ -254,4 +277,17 @@ mod tests {
             assert_eq!(test_case.0, SpanId::from_bytes(test_case.2));
         }
     }
+
+    #[test]
+    fn test_trace_flags() {
+        let flags = TraceFlags::SAMPLED.with_random(true);
+        assert!(flags.is_sampled());
+        assert!(flags.is_random());
+        assert_eq!(flags.to_u8(), 0x03);
+
+        let flags = flags.with_random(false);
+        assert!(flags.is_sampled());
+        assert!(!flags.is_random());
+        assert!(!TraceFlags::default().is_random());
+    }
 }